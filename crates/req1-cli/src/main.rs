@@ -69,6 +69,9 @@ enum Command {
         /// Link ID
         #[arg(long)]
         link_id: String,
+        /// Why the change does not affect the link (recorded in the link history)
+        #[arg(long)]
+        justification: Option<String>,
     },
     /// Import a file into a project or module
     Import {
@@ -109,6 +112,12 @@ enum Command {
         #[arg(long, default_value = "reqif")]
         format: String,
     },
+    /// Serve MCP over stdio, forwarding each message to the server's `/api/v1/mcp`
+    Mcp {
+        /// Bearer token; tool calls run with this user's permissions
+        #[arg(long, env = "REQ1_TOKEN")]
+        token: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -291,8 +300,11 @@ async fn main() -> Result<()> {
             module_id,
             object_id,
         } => cmd_review(&client, base, &module_id, object_id.as_deref()).await?,
-        Command::ResolveSuspect { link_id } => {
-            cmd_resolve_suspect(&client, base, &link_id).await?;
+        Command::ResolveSuspect {
+            link_id,
+            justification,
+        } => {
+            cmd_resolve_suspect(&client, base, &link_id, justification.as_deref()).await?;
         }
         Command::Reorder {
             module_id,
@@ -320,6 +332,7 @@ async fn main() -> Result<()> {
             output,
             format,
         } => cmd_export(&client, base, &module_id, &output, &format).await?,
+        Command::Mcp { token } => cmd_mcp(&client, base, &token).await?,
     }
 
     Ok(())
@@ -856,18 +869,29 @@ async fn review_single(
     Ok(())
 }
 
-async fn cmd_resolve_suspect(client: &reqwest::Client, base: &str, link_id: &str) -> Result<()> {
-    let url = format!("{base}/api/v1/links/{link_id}");
-    let payload = serde_json::json!({"suspect": false});
-    let resp = client
-        .patch(&url)
-        .json(&payload)
-        .send()
-        .await
-        .context("request failed")?;
+async fn cmd_resolve_suspect(
+    client: &reqwest::Client,
+    base: &str,
+    link_id: &str,
+    justification: Option<&str>,
+) -> Result<()> {
+    let request = match justification {
+        Some(text) => client
+            .post(format!("{base}/api/v1/suspect-links/resolve"))
+            .json(&serde_json::json!({"link_ids": [link_id], "justification": text})),
+        None => client
+            .patch(format!("{base}/api/v1/links/{link_id}"))
+            .json(&serde_json::json!({"suspect": false})),
+    };
+    let resp = request.send().await.context("request failed")?;
 
     ensure_success(&resp)?;
-    let link: Link = resp.json().await.context("invalid json")?;
+    let link: Link = if justification.is_some() {
+        let mut links: Vec<Link> = resp.json().await.context("invalid json")?;
+        links.pop().context("empty response")?
+    } else {
+        resp.json().await.context("invalid json")?
+    };
     println!(
         "Resolved suspect link {} ({} -> {})",
         link.id, link.source_object_id, link.target_object_id
@@ -1048,3 +1072,70 @@ fn ensure_success(resp: &reqwest::Response) -> Result<()> {
     }
     Ok(())
 }
+
+/// Bridge newline-delimited JSON-RPC on stdin/stdout to the HTTP MCP endpoint.
+/// Diagnostics go to stderr so they never corrupt the protocol stream.
+async fn cmd_mcp(client: &reqwest::Client, base: &str, token: &str) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let url = format!("{base}/api/v1/mcp");
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await.context("read stdin")? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let id = serde_json::from_str::<serde_json::Value>(&line)
+            .ok()
+            .and_then(|m| m.get("id").cloned());
+
+        let reply = match client
+            .post(&url)
+            .bearer_auth(token)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream")
+            .body(line)
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status() == reqwest::StatusCode::ACCEPTED => None,
+            Ok(resp) if resp.status().is_success() => match resp.json().await {
+                Ok(body) => Some(body),
+                Err(e) => {
+                    eprintln!("req1 mcp: invalid response: {e}");
+                    id.map(|id| mcp_error(id, &format!("invalid response from server: {e}")))
+                }
+            },
+            Ok(resp) => {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                eprintln!("req1 mcp: server returned {status}: {body}");
+                id.map(|id| mcp_error(id, &format!("server returned {status}")))
+            }
+            Err(e) => {
+                eprintln!("req1 mcp: request failed: {e}");
+                id.map(|id| mcp_error(id, &format!("request failed: {e}")))
+            }
+        };
+
+        if let Some(reply) = reply {
+            let mut out = serde_json::to_string(&reply).context("serialize json")?;
+            out.push('\n');
+            stdout
+                .write_all(out.as_bytes())
+                .await
+                .context("write stdout")?;
+            stdout.flush().await.context("flush stdout")?;
+        }
+    }
+    Ok(())
+}
+
+fn mcp_error(id: serde_json::Value, message: &str) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": -32603, "message": message },
+    })
+}
@@ -1,7 +1,12 @@
-use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use std::collections::HashMap;
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, Order, QueryFilter,
+    QueryOrder, Set, Statement,
+};
 use uuid::Uuid;
 
-use entity::{baseline_entry, object};
+use entity::{baseline, baseline_entry, baseline_link, baseline_set, link, object};
 
 use crate::error::CoreError;
 
@@ -21,6 +26,9 @@ pub async fn snapshot_baseline(
             baseline_id: Set(baseline_id),
             object_id: Set(obj.id),
             version: Set(obj.current_version),
+            parent_id: Set(obj.parent_id),
+            position: Set(Some(obj.position)),
+            level: Set(Some(obj.level.clone())),
         };
         let inserted = entry.insert(db).await?;
         entries.push(inserted);
@@ -28,3 +36,205 @@ pub async fn snapshot_baseline(
 
     Ok(entries)
 }
+
+/// Freeze every link touching the given objects (in either direction), including its
+/// suspect flag.
+pub async fn snapshot_links(
+    db: &impl ConnectionTrait,
+    baseline_id: Uuid,
+    object_ids: Vec<Uuid>,
+) -> Result<Vec<baseline_link::Model>, CoreError> {
+    if object_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let links = link::Entity::find()
+        .filter(
+            Condition::any()
+                .add(link::Column::SourceObjectId.is_in(object_ids.clone()))
+                .add(link::Column::TargetObjectId.is_in(object_ids)),
+        )
+        .all(db)
+        .await?;
+
+    let mut snapshot = Vec::with_capacity(links.len());
+    for l in links {
+        let row = baseline_link::ActiveModel {
+            baseline_id: Set(baseline_id),
+            link_id: Set(l.id),
+            source_object_id: Set(l.source_object_id),
+            target_object_id: Set(l.target_object_id),
+            link_type_id: Set(l.link_type_id),
+            suspect: Set(l.suspect),
+            attributes: Set(l.attributes),
+        };
+        snapshot.push(row.insert(db).await?);
+    }
+
+    Ok(snapshot)
+}
+
+/// An object as frozen in a baseline. The heading is folded from the object's history up
+/// to the frozen version.
+#[derive(Debug, Clone)]
+pub struct FrozenObject {
+    pub id: Uuid,
+    pub module_id: Uuid,
+    pub version: i32,
+    pub heading: Option<String>,
+    pub position: i32,
+    pub level: String,
+}
+
+/// The frozen data a read-only query is evaluated against: a single baseline, or every
+/// baseline of a baseline set.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Covered module -> the baseline frozen for it.
+    modules: HashMap<Uuid, Uuid>,
+}
+
+impl Snapshot {
+    /// Resolve the optional `baseline_id` / `baseline_set_id` query parameters. Returns
+    /// `None` when neither is given, i.e. the query should run against live data.
+    pub async fn resolve(
+        db: &impl ConnectionTrait,
+        baseline_id: Option<Uuid>,
+        baseline_set_id: Option<Uuid>,
+    ) -> Result<Option<Self>, CoreError> {
+        let baselines = match (baseline_id, baseline_set_id) {
+            (None, None) => return Ok(None),
+            (Some(_), Some(_)) => {
+                return Err(CoreError::bad_request(
+                    "baseline_id and baseline_set_id are mutually exclusive".to_owned(),
+                ));
+            }
+            (Some(id), None) => vec![
+                baseline::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("baseline {id} not found")))?,
+            ],
+            (None, Some(set_id)) => {
+                let _set = baseline_set::Entity::find_by_id(set_id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| {
+                        CoreError::not_found(format!("baseline set {set_id} not found"))
+                    })?;
+                baseline::Entity::find()
+                    .filter(baseline::Column::BaselineSetId.eq(set_id))
+                    .order_by(baseline::Column::CreatedAt, Order::Asc)
+                    .all(db)
+                    .await?
+            }
+        };
+
+        // If a set holds several baselines of one module, the latest one wins
+        let modules = baselines.into_iter().map(|b| (b.module_id, b.id)).collect();
+        Ok(Some(Self { modules }))
+    }
+
+    pub fn covers(&self, module_id: Uuid) -> bool {
+        self.modules.contains_key(&module_id)
+    }
+
+    /// Baseline frozen for `module_id`, or a bad request if the snapshot does not cover it.
+    pub fn baseline_for(&self, module_id: Uuid) -> Result<Uuid, CoreError> {
+        self.modules.get(&module_id).copied().ok_or_else(|| {
+            CoreError::bad_request(format!(
+                "module {module_id} is not covered by the selected baseline"
+            ))
+        })
+    }
+
+    fn baseline_ids(&self) -> Vec<Uuid> {
+        self.modules.values().copied().collect()
+    }
+
+    /// Frozen objects of one covered module, ordered by position.
+    pub async fn objects(
+        &self,
+        db: &impl ConnectionTrait,
+        module_id: Uuid,
+    ) -> Result<Vec<FrozenObject>, CoreError> {
+        let baseline_id = self.baseline_for(module_id)?;
+        Self::load_objects(db, vec![baseline_id]).await
+    }
+
+    /// Frozen objects of every covered module.
+    pub async fn all_objects(
+        &self,
+        db: &impl ConnectionTrait,
+    ) -> Result<Vec<FrozenObject>, CoreError> {
+        Self::load_objects(db, self.baseline_ids()).await
+    }
+
+    async fn load_objects(
+        db: &impl ConnectionTrait,
+        baseline_ids: Vec<Uuid>,
+    ) -> Result<Vec<FrozenObject>, CoreError> {
+        // History rows only carry the fields a change set, so take the latest heading
+        // recorded up to the frozen version
+        let sql = r"
+            SELECT
+                e.object_id,
+                b.module_id,
+                e.version,
+                COALESCE(e.position, 0) AS position,
+                COALESCE(e.level, o.level, '') AS level,
+                h.heading
+            FROM baseline_entry e
+            JOIN baseline b ON b.id = e.baseline_id
+            LEFT JOIN object o ON o.id = e.object_id
+            LEFT JOIN LATERAL (
+                SELECT heading FROM object_history
+                WHERE object_id = e.object_id
+                    AND version <= e.version
+                    AND heading IS NOT NULL
+                ORDER BY version DESC, id DESC
+                LIMIT 1
+            ) h ON TRUE
+            WHERE e.baseline_id = $1
+        ";
+
+        let mut objects = Vec::new();
+        for baseline_id in baseline_ids {
+            let stmt = Statement::from_sql_and_values(
+                sea_orm::DatabaseBackend::Postgres,
+                sql,
+                [baseline_id.into()],
+            );
+            for row in &db.query_all(stmt).await? {
+                objects.push(FrozenObject {
+                    id: row.try_get("", "object_id")?,
+                    module_id: row.try_get("", "module_id")?,
+                    version: row.try_get("", "version")?,
+                    heading: row.try_get("", "heading")?,
+                    position: row.try_get("", "position")?,
+                    level: row.try_get("", "level")?,
+                });
+            }
+        }
+        objects.sort_by_key(|o| (o.module_id, o.position));
+        Ok(objects)
+    }
+
+    /// Links frozen by any covered baseline, deduplicated by link id (a link between two
+    /// covered modules is frozen by both baselines).
+    pub async fn links(
+        &self,
+        db: &impl ConnectionTrait,
+    ) -> Result<Vec<baseline_link::Model>, CoreError> {
+        let rows = baseline_link::Entity::find()
+            .filter(baseline_link::Column::BaselineId.is_in(self.baseline_ids()))
+            .all(db)
+            .await?;
+
+        let mut by_id: HashMap<Uuid, baseline_link::Model> = HashMap::new();
+        for row in rows {
+            let _ = by_id.insert(row.link_id, row);
+        }
+        Ok(by_id.into_values().collect())
+    }
+}
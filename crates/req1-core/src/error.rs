@@ -10,6 +10,10 @@ pub enum CoreError {
     Unauthorized(String),
     #[error("forbidden: {0}")]
     Forbidden(String),
+    #[error("script_timeout: {0}")]
+    ScriptTimeout(String),
+    #[error("script_memory_limit: {0}")]
+    ScriptMemoryLimit(String),
     #[error("internal: {0}")]
     Internal(String),
     #[error("db: {0}")]
@@ -37,6 +41,14 @@ impl CoreError {
         Self::Forbidden(msg.into())
     }
 
+    pub fn script_timeout(msg: impl Into<String>) -> Self {
+        Self::ScriptTimeout(msg.into())
+    }
+
+    pub fn script_memory_limit(msg: impl Into<String>) -> Self {
+        Self::ScriptMemoryLimit(msg.into())
+    }
+
     pub fn internal(msg: impl Into<String>) -> Self {
         Self::Internal(msg.into())
     }
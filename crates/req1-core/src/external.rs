@@ -0,0 +1,144 @@
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+
+/// Computes the current content hash of external resources it understands. Register
+/// further checkers (ticket systems, model repositories, ...) on [`ExternalCheckers`].
+pub trait UriChecker: Send + Sync {
+    fn handles(&self, uri: &str) -> bool;
+
+    /// Hash identifying the resource's current content.
+    fn content_hash(&self, uri: &str) -> Result<String, String>;
+}
+
+/// `file:///path` — SHA-256 of the file's bytes. Only paths below one of `roots` are read.
+pub struct FileChecker {
+    roots: Vec<PathBuf>,
+}
+
+/// `git+file:///repo#<rev>` — the object id `git rev-parse` gives for `rev` (default
+/// `HEAD`), e.g. `HEAD:src/lib.rs` for a file on the checked-out branch or a commit id.
+/// Only repositories below one of `roots` are inspected.
+pub struct GitChecker {
+    roots: Vec<PathBuf>,
+}
+
+const OUTSIDE_ROOTS: &str = "path is outside the configured external link roots";
+
+/// `path` with `.` and `..` resolved lexically; `None` for relative paths or
+/// ones climbing above the filesystem root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() {
+        return None;
+    }
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    return None;
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    Some(out)
+}
+
+/// Canonical `path` if it lies below one of `roots`. Containment is checked on
+/// the lexical path before the filesystem is touched, so paths outside the roots
+/// all fail alike, whether they exist or not; the canonical path is checked again
+/// to catch symlinks leading out.
+fn confined(path: &str, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let lexical = normalize(Path::new(path)).ok_or_else(|| OUTSIDE_ROOTS.to_owned())?;
+    if !roots
+        .iter()
+        .filter_map(|r| normalize(r))
+        .any(|r| lexical.starts_with(r))
+    {
+        return Err(OUTSIDE_ROOTS.to_owned());
+    }
+    let path = lexical
+        .canonicalize()
+        .map_err(|e| format!("{}: {e}", lexical.display()))?;
+    roots
+        .iter()
+        .filter_map(|r| r.canonicalize().ok())
+        .any(|r| path.starts_with(r))
+        .then_some(path)
+        .ok_or_else(|| OUTSIDE_ROOTS.to_owned())
+}
+
+impl UriChecker for FileChecker {
+    fn handles(&self, uri: &str) -> bool {
+        uri.starts_with("file://")
+    }
+
+    fn content_hash(&self, uri: &str) -> Result<String, String> {
+        let path = confined(uri.trim_start_matches("file://"), &self.roots)?;
+        let bytes = std::fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Ok(format!("{:x}", Sha256::digest(bytes)))
+    }
+}
+
+impl UriChecker for GitChecker {
+    fn handles(&self, uri: &str) -> bool {
+        uri.starts_with("git+file://")
+    }
+
+    fn content_hash(&self, uri: &str) -> Result<String, String> {
+        let rest = uri.trim_start_matches("git+file://");
+        let (repo, rev) = rest.split_once('#').unwrap_or((rest, "HEAD"));
+        let repo = confined(repo, &self.roots)?;
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&repo)
+            .args(["rev-parse", "--verify", "--quiet", "--end-of-options", rev])
+            .output()
+            .map_err(|e| format!("git: {e}"))?;
+        if !output.status.success() {
+            return Err(format!("git: cannot resolve '{rev}' in {}", repo.display()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+}
+
+/// Checkers consulted in registration order; the first that handles a URI wins.
+#[derive(Clone, Default)]
+pub struct ExternalCheckers {
+    checkers: Vec<Arc<dyn UriChecker>>,
+}
+
+impl ExternalCheckers {
+    /// File and git checkers confined to `roots`; none when `roots` is empty.
+    pub fn local(roots: &[PathBuf]) -> Self {
+        let mut checkers = Self::default();
+        if !roots.is_empty() {
+            checkers.register(Arc::new(FileChecker {
+                roots: roots.to_vec(),
+            }));
+            checkers.register(Arc::new(GitChecker {
+                roots: roots.to_vec(),
+            }));
+        }
+        checkers
+    }
+
+    pub fn register(&mut self, checker: Arc<dyn UriChecker>) {
+        self.checkers.push(checker);
+    }
+
+    /// Current hash of `uri`, or `None` when no checker handles it.
+    pub async fn content_hash(&self, uri: &str) -> Option<Result<String, String>> {
+        let checker = self.checkers.iter().find(|c| c.handles(uri))?.clone();
+        let uri = uri.to_owned();
+        Some(
+            tokio::task::spawn_blocking(move || checker.content_hash(&uri))
+                .await
+                .unwrap_or_else(|e| Err(format!("checker failed: {e}"))),
+        )
+    }
+}
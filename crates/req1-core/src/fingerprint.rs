@@ -1,19 +1,252 @@
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, sea_query::Expr};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use entity::{link, module, object};
+
+use crate::error::CoreError;
+
+/// Which attributes count as content when fingerprinting an object. Set on a module or
+/// an object type; an object type's policy takes precedence over its module's.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FingerprintPolicy {
+    /// Only these attributes are hashed; all attributes when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>,
+    /// Attributes never hashed, e.g. administrative or volatile fields.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl FingerprintPolicy {
+    /// Parse a stored policy; `null` means no policy.
+    pub fn from_json(value: Option<&serde_json::Value>) -> Result<Option<Self>, CoreError> {
+        match value {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(v) => serde_json::from_value(v.clone())
+                .map(Some)
+                .map_err(|e| CoreError::bad_request(format!("invalid fingerprint_policy: {e}"))),
+        }
+    }
+
+    /// Stored form of a policy; the default (every attribute counts) is stored as `null`
+    /// so an object type falls back to its module's policy.
+    pub fn to_json(&self) -> Option<serde_json::Value> {
+        (*self != Self::default()).then(|| serde_json::json!(self))
+    }
+
+    /// Policy for an object of the given module and (optional) object type.
+    pub async fn resolve(
+        db: &impl ConnectionTrait,
+        module_id: Uuid,
+        object_type_id: Option<Uuid>,
+    ) -> Result<Self, CoreError> {
+        if let Some(type_id) = object_type_id
+            && let Some(ot) = entity::object_type::Entity::find_by_id(type_id)
+                .one(db)
+                .await?
+            && let Some(policy) = Self::from_json(ot.fingerprint_policy.as_ref())?
+        {
+            return Ok(policy);
+        }
+        let module = entity::module::Entity::find_by_id(module_id)
+            .one(db)
+            .await?;
+        Ok(
+            Self::from_json(module.and_then(|m| m.fingerprint_policy).as_ref())?
+                .unwrap_or_default(),
+        )
+    }
+
+    pub(crate) fn counts(&self, key: &str) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|keys| keys.iter().any(|k| k == key))
+            && !self.exclude.iter().any(|k| k == key)
+    }
+}
 
 pub fn compute_content_fingerprint(
     heading: Option<&str>,
     body: Option<&str>,
     attributes: Option<&serde_json::Value>,
+    policy: &FingerprintPolicy,
 ) -> String {
     let mut hasher = Sha256::new();
     hasher.update(heading.unwrap_or(""));
     hasher.update(b"\0");
     hasher.update(body.unwrap_or(""));
     hasher.update(b"\0");
-    if let Some(attrs) = attributes {
-        // Canonical JSON serialization for deterministic hashing
-        let canonical = serde_json::to_string(attrs).unwrap_or_default();
-        hasher.update(canonical.as_bytes());
+    match attributes {
+        Some(serde_json::Value::Object(map)) => {
+            let content: serde_json::Map<String, serde_json::Value> = map
+                .iter()
+                .filter(|(k, _)| policy.counts(k))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            // An empty map hashes like no attributes, so excluded fields never matter
+            if !content.is_empty() {
+                hasher.update(canonical_json(&serde_json::Value::Object(content)).as_bytes());
+            }
+        }
+        Some(serde_json::Value::Null) | None => {}
+        Some(other) => hasher.update(canonical_json(other).as_bytes()),
     }
     format!("{:x}", hasher.finalize())
 }
+
+/// Recompute the fingerprints of a module's objects after a policy change. Links and
+/// reviews that matched the old fingerprint are moved to the new one, so changing a
+/// policy never marks links suspect. Returns the number of objects whose fingerprint
+/// changed.
+pub async fn refingerprint_module(
+    db: &impl ConnectionTrait,
+    module_id: Uuid,
+) -> Result<u64, CoreError> {
+    let objects = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .all(db)
+        .await?;
+
+    let mut policies: HashMap<Option<Uuid>, FingerprintPolicy> = HashMap::new();
+    let mut changed = 0;
+    for obj in objects {
+        let policy = match policies.get(&obj.object_type_id) {
+            Some(p) => p.clone(),
+            None => {
+                let p = FingerprintPolicy::resolve(db, module_id, obj.object_type_id).await?;
+                let _ = policies.insert(obj.object_type_id, p.clone());
+                p
+            }
+        };
+        let fp = compute_content_fingerprint(
+            obj.heading.as_deref(),
+            obj.body.as_deref(),
+            obj.attributes.as_ref(),
+            &policy,
+        );
+        if fp == obj.content_fingerprint {
+            continue;
+        }
+
+        let old = obj.content_fingerprint;
+        let _ = link::Entity::update_many()
+            .col_expr(link::Column::SourceFingerprint, Expr::value(fp.clone()))
+            .filter(link::Column::SourceObjectId.eq(obj.id))
+            .filter(link::Column::SourceFingerprint.eq(old.clone()))
+            .exec(db)
+            .await?;
+        let _ = link::Entity::update_many()
+            .col_expr(link::Column::TargetFingerprint, Expr::value(fp.clone()))
+            .filter(link::Column::TargetObjectId.eq(obj.id))
+            .filter(link::Column::TargetFingerprint.eq(old.clone()))
+            .exec(db)
+            .await?;
+        let _ = object::Entity::update_many()
+            .col_expr(object::Column::ReviewedFingerprint, Expr::value(fp.clone()))
+            .filter(object::Column::Id.eq(obj.id))
+            .filter(object::Column::ReviewedFingerprint.eq(old))
+            .exec(db)
+            .await?;
+        let _ = object::Entity::update_many()
+            .col_expr(object::Column::ContentFingerprint, Expr::value(fp))
+            .filter(object::Column::Id.eq(obj.id))
+            .exec(db)
+            .await?;
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+/// Recompute the fingerprints of all objects, as [`refingerprint_module`] does for one
+/// module. Run once after the migration that introduced canonical hashing.
+pub async fn refingerprint_all(db: &impl ConnectionTrait) -> Result<u64, CoreError> {
+    let modules = module::Entity::find().all(db).await?;
+    let mut changed = 0;
+    for m in modules {
+        changed += refingerprint_module(db, m.id).await?;
+    }
+    Ok(changed)
+}
+
+/// Compact JSON with object keys sorted bytewise at every level, independent of map
+/// ordering.
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            out.push('{');
+            for (i, (key, v)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(v, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, v) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(v, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fingerprint_ignores_key_order_and_excluded_attributes() {
+        let a: serde_json::Value =
+            serde_json::from_str(r#"{"priority": "high", "meta": {"y": 1, "x": [2, 3]}}"#).unwrap();
+        let b: serde_json::Value =
+            serde_json::from_str(r#"{"meta": {"x": [2, 3], "y": 1}, "priority": "high"}"#).unwrap();
+        assert_eq!(
+            canonical_json(&a),
+            r#"{"meta":{"x":[2,3],"y":1},"priority":"high"}"#
+        );
+
+        let all = FingerprintPolicy::default();
+        assert_eq!(
+            compute_content_fingerprint(Some("H"), None, Some(&a), &all),
+            compute_content_fingerprint(Some("H"), None, Some(&b), &all),
+        );
+
+        let policy = FingerprintPolicy {
+            include: None,
+            exclude: vec!["owner".to_owned()],
+        };
+        let with_owner = json!({"priority": "high", "owner": "alice"});
+        let without = json!({"priority": "high"});
+        assert_eq!(
+            compute_content_fingerprint(Some("H"), None, Some(&with_owner), &policy),
+            compute_content_fingerprint(Some("H"), None, Some(&without), &policy),
+        );
+        assert_eq!(
+            compute_content_fingerprint(Some("H"), None, Some(&json!({"owner": "bob"})), &policy),
+            compute_content_fingerprint(Some("H"), None, None, &policy),
+        );
+    }
+}
@@ -1,9 +1,23 @@
-use sea_orm::{ActiveModelTrait, ConnectionTrait, NotSet, Set};
+use std::collections::HashMap;
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, NotSet, QueryFilter, QueryOrder,
+    Set,
+};
 use uuid::Uuid;
 
-use entity::object_history;
+use entity::{object, object_history};
 
 use crate::error::CoreError;
+use crate::service::audit::AuditContext;
+
+/// Lifecycle state recorded alongside a history entry.
+pub struct StateChange {
+    pub from_state: Option<String>,
+    pub to_state: String,
+    pub lifecycle_model_id: Option<Uuid>,
+    pub e_signature_id: Option<Uuid>,
+}
 
 pub struct HistoryEntry {
     pub object_id: Uuid,
@@ -13,12 +27,18 @@ pub struct HistoryEntry {
     pub heading: Option<String>,
     pub body: Option<String>,
     pub change_type: String,
+    pub state: Option<StateChange>,
 }
 
+/// Write a history row. The object's parent, position and outline level are read from
+/// its current row, so callers should insert history after any structural change.
 pub async fn insert_history(
     db: &impl ConnectionTrait,
+    ctx: &AuditContext,
     entry: HistoryEntry,
 ) -> Result<(), CoreError> {
+    let state = entry.state;
+    let current = object::Entity::find_by_id(entry.object_id).one(db).await?;
     let record = object_history::ActiveModel {
         id: NotSet,
         object_id: Set(entry.object_id),
@@ -27,10 +47,87 @@ pub async fn insert_history(
         attribute_values: Set(entry.attribute_values),
         heading: Set(entry.heading),
         body: Set(entry.body),
-        changed_by: Set(None),
+        changed_by: Set(ctx.user_id),
+        actor: Set(ctx.actor()),
         changed_at: Set(chrono::Utc::now().fixed_offset()),
         change_type: Set(entry.change_type),
+        from_state: Set(state.as_ref().and_then(|s| s.from_state.clone())),
+        to_state: Set(state.as_ref().map(|s| s.to_state.clone())),
+        lifecycle_model_id: Set(state.as_ref().and_then(|s| s.lifecycle_model_id)),
+        e_signature_id: Set(state.and_then(|s| s.e_signature_id)),
+        parent_id: Set(current.as_ref().and_then(|o| o.parent_id)),
+        position: Set(current.as_ref().map(|o| o.position)),
+        level: Set(current.map(|o| o.level)),
     };
     let _ = record.insert(db).await?;
     Ok(())
 }
+
+/// Object content as of some version, folded from its history rows.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Content {
+    pub heading: Option<String>,
+    pub body: Option<String>,
+    pub attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Content {
+    /// Fold history rows given oldest first. Rows carry only the fields set by each
+    /// change; a row with attributes carries the whole map, replacing the previous one.
+    pub fn fold<'a>(rows: impl IntoIterator<Item = &'a object_history::Model>) -> Self {
+        let mut content = Self::default();
+        for h in rows {
+            if h.heading.is_some() {
+                content.heading.clone_from(&h.heading);
+            }
+            if h.body.is_some() {
+                content.body.clone_from(&h.body);
+            }
+            if let Some(serde_json::Value::Object(attrs)) = &h.attribute_values {
+                content.attributes.clone_from(attrs);
+            }
+        }
+        content
+    }
+
+    /// Current content of an object row.
+    pub fn of(obj: &object::Model) -> Self {
+        Self {
+            heading: obj.heading.clone(),
+            body: obj.body.clone(),
+            attributes: obj
+                .attributes
+                .as_ref()
+                .and_then(|a| a.as_object())
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Content of each object as of the given version, folded from its history.
+pub async fn contents_at(
+    db: &impl ConnectionTrait,
+    versions: &HashMap<Uuid, i32>,
+) -> Result<HashMap<Uuid, Content>, CoreError> {
+    if versions.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let rows = object_history::Entity::find()
+        .filter(object_history::Column::ObjectId.is_in(versions.keys().copied()))
+        .order_by_asc(object_history::Column::Version)
+        .order_by_asc(object_history::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut by_object: HashMap<Uuid, Vec<&object_history::Model>> = HashMap::new();
+    for h in &rows {
+        if versions.get(&h.object_id).is_some_and(|&v| h.version <= v) {
+            by_object.entry(h.object_id).or_default().push(h);
+        }
+    }
+    Ok(by_object
+        .into_iter()
+        .map(|(id, rows)| (id, Content::fold(rows)))
+        .collect())
+}
@@ -4,13 +4,18 @@ use utoipa::{IntoParams, ToSchema};
 pub mod auth;
 pub mod baseline;
 pub mod error;
+pub mod external;
 pub mod fingerprint;
 pub mod history;
 pub mod level;
+pub mod link_history;
+pub mod link_rules;
+pub mod oslc;
 pub mod reqif;
 pub mod scripting;
 pub mod service;
 pub mod suspect;
+pub mod sysml;
 pub mod validation;
 
 /// Common ID type used across the application.
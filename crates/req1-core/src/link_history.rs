@@ -0,0 +1,47 @@
+use sea_orm::{ActiveModelTrait, ConnectionTrait, NotSet, Set};
+use uuid::Uuid;
+
+use entity::{link, link_history};
+
+use crate::error::CoreError;
+use crate::service::audit::AuditContext;
+
+#[derive(Default)]
+pub struct LinkHistoryEntry<'a> {
+    pub change_type: &'a str,
+    pub justification: Option<String>,
+    pub suspect_reason: Option<serde_json::Value>,
+    pub old_attributes: Option<serde_json::Value>,
+    /// Previous type when the link was retyped.
+    pub old_link_type_id: Option<Uuid>,
+}
+
+/// Write a history row for `link` as it stands after the change.
+pub async fn insert_link_history(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    link: &link::Model,
+    entry: LinkHistoryEntry<'_>,
+) -> Result<(), CoreError> {
+    let record = link_history::ActiveModel {
+        id: NotSet,
+        link_id: Set(link.id),
+        version: Set(link.version),
+        change_type: Set(entry.change_type.to_owned()),
+        changed_by: Set(ctx.user_id),
+        actor: Set(ctx.actor()),
+        changed_at: Set(chrono::Utc::now().fixed_offset()),
+        justification: Set(entry.justification),
+        suspect_reason: Set(entry.suspect_reason),
+        source_fingerprint: Set(Some(link.source_fingerprint.clone())),
+        target_fingerprint: Set(Some(link.target_fingerprint.clone())),
+        source_object_id: Set(Some(link.source_object_id)),
+        target_object_id: Set(Some(link.target_object_id)),
+        link_type_id: Set(Some(link.link_type_id)),
+        old_link_type_id: Set(entry.old_link_type_id),
+        old_attributes: Set(entry.old_attributes),
+        new_attributes: Set(link.attributes.clone()),
+    };
+    let _ = record.insert(db).await?;
+    Ok(())
+}
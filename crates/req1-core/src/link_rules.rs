@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use entity::{link, link_type, object};
+
+use crate::error::CoreError;
+
+const fn default_allow_cycles() -> bool {
+    true
+}
+
+/// What links of a type may connect. Endpoint lists left out allow anything; minimum
+/// counts only apply to objects selected by an endpoint list on that side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LinkTypeRules {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_object_type_ids: Option<Vec<Uuid>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_module_ids: Option<Vec<Uuid>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_object_type_ids: Option<Vec<Uuid>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_module_ids: Option<Vec<Uuid>>,
+    /// Links of this type each source object needs at least / may have at most.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_outgoing: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_outgoing: Option<u32>,
+    /// Links of this type each target object needs at least / may have at most.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_incoming: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_incoming: Option<u32>,
+    #[serde(default = "default_allow_cycles")]
+    pub allow_cycles: bool,
+}
+
+impl Default for LinkTypeRules {
+    fn default() -> Self {
+        Self {
+            source_object_type_ids: None,
+            source_module_ids: None,
+            target_object_type_ids: None,
+            target_module_ids: None,
+            min_outgoing: None,
+            max_outgoing: None,
+            min_incoming: None,
+            max_incoming: None,
+            allow_cycles: true,
+        }
+    }
+}
+
+/// End of a link a rule applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkEnd {
+    Source,
+    Target,
+}
+
+impl LinkEnd {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Source => "source",
+            Self::Target => "target",
+        }
+    }
+
+    /// How links at this end are counted from the object's point of view.
+    pub const fn direction(self) -> &'static str {
+        match self {
+            Self::Source => "outgoing",
+            Self::Target => "incoming",
+        }
+    }
+}
+
+impl LinkTypeRules {
+    /// Parse stored rules; `null` means unrestricted.
+    pub fn from_json(value: Option<&serde_json::Value>) -> Result<Self, CoreError> {
+        match value {
+            None | Some(serde_json::Value::Null) => Ok(Self::default()),
+            Some(v) => serde_json::from_value(v.clone())
+                .map_err(|e| CoreError::bad_request(format!("invalid link type rules: {e}"))),
+        }
+    }
+
+    /// Stored form; unrestricted rules are stored as `null`.
+    pub fn to_json(&self) -> Option<serde_json::Value> {
+        (*self != Self::default()).then(|| serde_json::json!(self))
+    }
+
+    pub fn validate(&self) -> Result<(), CoreError> {
+        for (end, min, max) in [
+            ("outgoing", self.min_outgoing, self.max_outgoing),
+            ("incoming", self.min_incoming, self.max_incoming),
+        ] {
+            if let (Some(min), Some(max)) = (min, max)
+                && min > max
+            {
+                return Err(CoreError::bad_request(format!(
+                    "min_{end} ({min}) exceeds max_{end} ({max})"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the end's object type and module lists narrow which objects qualify.
+    pub const fn is_scoped(&self, end: LinkEnd) -> bool {
+        match end {
+            LinkEnd::Source => {
+                self.source_object_type_ids.is_some() || self.source_module_ids.is_some()
+            }
+            LinkEnd::Target => {
+                self.target_object_type_ids.is_some() || self.target_module_ids.is_some()
+            }
+        }
+    }
+
+    pub fn allows(&self, end: LinkEnd, obj: &object::Model) -> bool {
+        let (types, modules) = match end {
+            LinkEnd::Source => (&self.source_object_type_ids, &self.source_module_ids),
+            LinkEnd::Target => (&self.target_object_type_ids, &self.target_module_ids),
+        };
+        types
+            .as_ref()
+            .is_none_or(|t| obj.object_type_id.is_some_and(|id| t.contains(&id)))
+            && modules.as_ref().is_none_or(|m| m.contains(&obj.module_id))
+    }
+
+    pub const fn bounds(&self, end: LinkEnd) -> (Option<u32>, Option<u32>) {
+        match end {
+            LinkEnd::Source => (self.min_outgoing, self.max_outgoing),
+            LinkEnd::Target => (self.min_incoming, self.max_incoming),
+        }
+    }
+}
+
+/// Check that a new link of type `lt` from `source` to `target` obeys the type's rules.
+/// `ignore` excludes a link being retyped from the counts.
+pub async fn check_link(
+    db: &impl ConnectionTrait,
+    lt: &link_type::Model,
+    source: &object::Model,
+    target: &object::Model,
+    ignore: Option<Uuid>,
+) -> Result<(), CoreError> {
+    let rules = LinkTypeRules::from_json(lt.rules.as_ref())?;
+
+    for (end, obj) in [(LinkEnd::Source, source), (LinkEnd::Target, target)] {
+        if !rules.allows(end, obj) {
+            return Err(CoreError::bad_request(format!(
+                "object {} is not an allowed {} for link type '{}'",
+                obj.id,
+                end.as_str(),
+                lt.name
+            )));
+        }
+
+        let (_, Some(max)) = rules.bounds(end) else {
+            continue;
+        };
+        let column = match end {
+            LinkEnd::Source => link::Column::SourceObjectId,
+            LinkEnd::Target => link::Column::TargetObjectId,
+        };
+        let mut query = link::Entity::find()
+            .filter(column.eq(obj.id))
+            .filter(link::Column::LinkTypeId.eq(lt.id));
+        if let Some(id) = ignore {
+            query = query.filter(link::Column::Id.ne(id));
+        }
+        if query.count(db).await? >= u64::from(max) {
+            return Err(CoreError::bad_request(format!(
+                "object {} already has {max} {} link(s) of type '{}'",
+                obj.id,
+                end.direction(),
+                lt.name
+            )));
+        }
+    }
+
+    if !rules.allow_cycles {
+        let links = link::Entity::find()
+            .filter(link::Column::LinkTypeId.eq(lt.id))
+            .all(db)
+            .await?;
+        let graph = adjacency(links.iter().filter(|l| Some(l.id) != ignore));
+        if reaches(&graph, target.id, source.id) {
+            return Err(CoreError::bad_request(format!(
+                "link type '{}' does not allow cycles",
+                lt.name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Outgoing edges per object.
+pub fn adjacency<'a>(links: impl Iterator<Item = &'a link::Model>) -> HashMap<Uuid, Vec<Uuid>> {
+    let mut graph: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for l in links {
+        graph
+            .entry(l.source_object_id)
+            .or_default()
+            .push(l.target_object_id);
+    }
+    graph
+}
+
+/// Whether `to` can be reached from `from` along the edges of `graph`.
+pub fn reaches(graph: &HashMap<Uuid, Vec<Uuid>>, from: Uuid, to: Uuid) -> bool {
+    let mut seen = HashSet::from([from]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            return true;
+        }
+        for next in graph.get(&node).into_iter().flatten() {
+            if seen.insert(*next) {
+                queue.push_back(*next);
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,506 @@
+pub mod query;
+pub mod rdf;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use entity::{module, object, project};
+
+use crate::error::CoreError;
+use crate::service::audit::AuditContext;
+use crate::service::object::{CreateObjectInput, ObjectService};
+
+use self::query::{Prefixes, apply_select, parse_select, parse_where};
+use self::rdf::{Resource, Term, expand, is_local_name};
+
+const RM_DOMAIN: &str = "http://open-services.net/ns/rm#";
+const REQUIREMENT_TYPE: &str = "http://open-services.net/ns/rm#Requirement";
+
+/// Absolute URIs of the provider's resources under `base` (scheme and authority).
+#[derive(Debug, Clone)]
+pub struct Urls {
+    base: String,
+}
+
+impl Urls {
+    pub fn new(base: impl Into<String>) -> Self {
+        Self {
+            base: base.into().trim_end_matches('/').to_owned(),
+        }
+    }
+
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    fn api(&self, path: &str) -> String {
+        format!("{}/api/v1/oslc/{path}", self.base)
+    }
+
+    pub fn catalog(&self) -> String {
+        self.api("catalog")
+    }
+
+    pub fn provider(&self, project_id: Uuid) -> String {
+        self.api(&format!("projects/{project_id}"))
+    }
+
+    pub fn project_query(&self, project_id: Uuid) -> String {
+        self.api(&format!("projects/{project_id}/requirements"))
+    }
+
+    pub fn selection_dialog(&self, project_id: Uuid) -> String {
+        self.api(&format!("projects/{project_id}/selector"))
+    }
+
+    pub fn collection(&self, module_id: Uuid) -> String {
+        self.api(&format!("modules/{module_id}"))
+    }
+
+    pub fn creation_factory(&self, module_id: Uuid) -> String {
+        self.api(&format!("modules/{module_id}/requirements"))
+    }
+
+    pub fn creation_dialog(&self, module_id: Uuid) -> String {
+        self.api(&format!("modules/{module_id}/creator"))
+    }
+
+    pub fn requirement(&self, object_id: Uuid) -> String {
+        self.api(&format!("requirements/{object_id}"))
+    }
+}
+
+/// OSLC query parameters accepted by query capabilities.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct OslcQuery {
+    /// e.g. `dcterms:title="Braking" and req1:version>2`
+    #[serde(rename = "oslc.where")]
+    pub where_: Option<String>,
+    /// e.g. `dcterms:title,oslc:shortTitle`
+    #[serde(rename = "oslc.select")]
+    pub select: Option<String>,
+    /// e.g. `dc=<http://purl.org/dc/terms/>`
+    #[serde(rename = "oslc.prefix")]
+    pub prefix: Option<String>,
+    /// Page size; all results when omitted.
+    #[serde(rename = "oslc.pageSize")]
+    pub page_size: Option<u64>,
+    /// 1-based page number.
+    pub page: Option<u64>,
+}
+
+fn timestamp(value: &sea_orm::prelude::DateTimeWithTimeZone) -> Term {
+    Term::Typed(value.to_rfc3339(), "dateTime")
+}
+
+fn attribute_term(value: &serde_json::Value) -> Option<Term> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(Term::Literal(s.clone())),
+        serde_json::Value::Bool(b) => Some(Term::Typed(b.to_string(), "boolean")),
+        serde_json::Value::Number(n) if n.is_f64() => Some(Term::Typed(n.to_string(), "decimal")),
+        serde_json::Value::Number(n) => Some(Term::Typed(n.to_string(), "integer")),
+        other => Some(Term::Literal(other.to_string())),
+    }
+}
+
+/// An object as an `oslc_rm:Requirement`.
+pub fn requirement_resource(urls: &Urls, obj: &object::Model, project_id: Uuid) -> Resource {
+    let mut r = Resource::new(urls.requirement(obj.id), "oslc_rm:Requirement")
+        .literal("dcterms:identifier", obj.id.to_string())
+        .literal("oslc:shortTitle", obj.level.clone());
+    if let Some(heading) = &obj.heading {
+        r = r.literal("dcterms:title", heading.clone());
+    }
+    if let Some(body) = &obj.body {
+        r = r.literal("dcterms:description", body.clone());
+    }
+    r = r
+        .with("dcterms:created", timestamp(&obj.created_at))
+        .with("dcterms:modified", timestamp(&obj.updated_at))
+        .uri("oslc:serviceProvider", urls.provider(project_id))
+        .uri("req1:module", urls.collection(obj.module_id))
+        .with(
+            "req1:version",
+            Term::Typed(obj.current_version.to_string(), "integer"),
+        )
+        .literal("req1:classification", obj.classification.clone());
+    if let Some(state) = &obj.lifecycle_state {
+        r = r.literal("req1:lifecycleState", state.clone());
+    }
+    if let Some(attrs) = obj.attributes.as_ref().and_then(|a| a.as_object()) {
+        for (key, value) in attrs {
+            if is_local_name(key)
+                && let Some(term) = attribute_term(value)
+            {
+                r = r.with(&format!("req1_attr:{key}"), term);
+            }
+        }
+    }
+    r
+}
+
+async fn find_project(
+    db: &impl ConnectionTrait,
+    project_id: Uuid,
+) -> Result<project::Model, CoreError> {
+    project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("project {project_id} not found")))
+}
+
+async fn find_module(
+    db: &impl ConnectionTrait,
+    module_id: Uuid,
+) -> Result<module::Model, CoreError> {
+    module::Entity::find_by_id(module_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("module {module_id} not found")))
+}
+
+/// Service provider catalog listing one provider per project in `workspace_ids`
+/// (`None`: all projects).
+pub async fn catalog(
+    db: &impl ConnectionTrait,
+    urls: &Urls,
+    workspace_ids: Option<Vec<Uuid>>,
+) -> Result<Vec<Resource>, CoreError> {
+    let mut query = project::Entity::find().order_by_asc(project::Column::Name);
+    if let Some(ids) = workspace_ids {
+        query = query.filter(project::Column::WorkspaceId.is_in(ids));
+    }
+    let projects = query.all(db).await?;
+
+    let mut catalog = Resource::new(urls.catalog(), "oslc:ServiceProviderCatalog")
+        .literal("dcterms:title", "req1")
+        .uri("oslc:domain", RM_DOMAIN);
+    for p in &projects {
+        catalog = catalog.uri("oslc:serviceProvider", urls.provider(p.id));
+    }
+    let mut graph = vec![catalog];
+    graph.extend(projects.iter().map(|p| {
+        Resource::new(urls.provider(p.id), "oslc:ServiceProvider")
+            .literal("dcterms:title", p.name.clone())
+            .uri("oslc:details", urls.provider(p.id))
+    }));
+    Ok(graph)
+}
+
+fn dialog(title: String, label: &str, uri: String) -> Resource {
+    Resource::blank("oslc:Dialog")
+        .literal("dcterms:title", title)
+        .literal("oslc:label", label)
+        .uri("oslc:dialog", uri)
+        .literal("oslc:hintWidth", "640px")
+        .literal("oslc:hintHeight", "480px")
+        .uri("oslc:resourceType", REQUIREMENT_TYPE)
+}
+
+/// Service provider of a project: query capability and selection dialog over the whole
+/// project, creation factory and dialog per module. `visible` limits the modules listed.
+pub async fn service_provider(
+    db: &impl ConnectionTrait,
+    urls: &Urls,
+    project_id: Uuid,
+    visible: Option<Vec<Uuid>>,
+) -> Result<Resource, CoreError> {
+    let project = find_project(db, project_id).await?;
+    let modules: Vec<module::Model> = module::Entity::find()
+        .filter(module::Column::ProjectId.eq(project_id))
+        .order_by_asc(module::Column::Name)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|m| visible.as_ref().is_none_or(|v| v.contains(&m.id)))
+        .collect();
+
+    let mut service = Resource::blank("oslc:Service")
+        .uri("oslc:domain", RM_DOMAIN)
+        .node(
+            "oslc:queryCapability",
+            Resource::blank("oslc:QueryCapability")
+                .literal("dcterms:title", format!("Requirements in {}", project.name))
+                .literal("oslc:label", "Requirements")
+                .uri("oslc:queryBase", urls.project_query(project_id))
+                .uri("oslc:resourceType", REQUIREMENT_TYPE),
+        )
+        .node(
+            "oslc:selectionDialog",
+            dialog(
+                format!("Select requirements in {}", project.name),
+                "Requirements",
+                urls.selection_dialog(project_id),
+            ),
+        );
+    for m in &modules {
+        service = service
+            .node(
+                "oslc:creationFactory",
+                Resource::blank("oslc:CreationFactory")
+                    .literal("dcterms:title", format!("New requirement in {}", m.name))
+                    .literal("oslc:label", m.name.clone())
+                    .uri("oslc:creation", urls.creation_factory(m.id))
+                    .uri("oslc:resourceType", REQUIREMENT_TYPE),
+            )
+            .node(
+                "oslc:creationDialog",
+                dialog(
+                    format!("Create requirement in {}", m.name),
+                    &m.name,
+                    urls.creation_dialog(m.id),
+                ),
+            );
+    }
+
+    let mut provider = Resource::new(urls.provider(project_id), "oslc:ServiceProvider")
+        .literal("dcterms:title", project.name.clone());
+    if let Some(description) = &project.description {
+        provider = provider.literal("dcterms:description", description.clone());
+    }
+    for (prefix, ns) in rdf::PREFIXES {
+        provider = provider.node(
+            "oslc:prefixDefinition",
+            Resource::blank("oslc:PrefixDefinition")
+                .literal("oslc:prefix", *prefix)
+                .uri("oslc:prefixBase", *ns),
+        );
+    }
+    Ok(provider.node("oslc:service", service))
+}
+
+pub async fn requirement(
+    db: &impl ConnectionTrait,
+    urls: &Urls,
+    object_id: Uuid,
+) -> Result<Resource, CoreError> {
+    let obj = object::Entity::find_by_id(object_id)
+        .filter(object::Column::DeletedAt.is_null())
+        .one(db)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("requirement {object_id} not found")))?;
+    let module = find_module(db, obj.module_id).await?;
+    Ok(requirement_resource(urls, &obj, module.project_id))
+}
+
+/// A module as an `oslc_rm:RequirementCollection` of its objects.
+pub async fn collection(
+    db: &impl ConnectionTrait,
+    urls: &Urls,
+    module_id: Uuid,
+) -> Result<Resource, CoreError> {
+    let module = find_module(db, module_id).await?;
+    let objects = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .filter(object::Column::DeletedAt.is_null())
+        .order_by_asc(object::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut r = Resource::new(urls.collection(module_id), "oslc_rm:RequirementCollection")
+        .literal("dcterms:identifier", module.id.to_string())
+        .literal("dcterms:title", module.name.clone());
+    if let Some(description) = &module.description {
+        r = r.literal("dcterms:description", description.clone());
+    }
+    r = r
+        .with("dcterms:created", timestamp(&module.created_at))
+        .with("dcterms:modified", timestamp(&module.updated_at))
+        .uri("oslc:serviceProvider", urls.provider(module.project_id));
+    for o in &objects {
+        r = r.uri("oslc_rm:uses", urls.requirement(o.id));
+    }
+    Ok(r)
+}
+
+/// What a query capability searches.
+#[derive(Debug, Clone, Copy)]
+pub enum QueryScope {
+    Project(Uuid),
+    Module(Uuid),
+}
+
+/// Run an OSLC query. `visible` limits the modules searched; `request_uri` is the full
+/// URI the query was made with and identifies the response info.
+pub async fn query(
+    db: &impl ConnectionTrait,
+    urls: &Urls,
+    scope: QueryScope,
+    visible: Option<Vec<Uuid>>,
+    params: &OslcQuery,
+    request_uri: &str,
+) -> Result<Vec<Resource>, CoreError> {
+    let prefixes = Prefixes::parse(params.prefix.as_deref()).map_err(CoreError::bad_request)?;
+    let conditions = params
+        .where_
+        .as_deref()
+        .filter(|w| !w.trim().is_empty())
+        .map(|w| parse_where(w, &prefixes))
+        .transpose()
+        .map_err(CoreError::bad_request)?
+        .unwrap_or_default();
+    let select =
+        parse_select(params.select.as_deref(), &prefixes).map_err(CoreError::bad_request)?;
+
+    let (query_base, modules) = match scope {
+        QueryScope::Project(project_id) => {
+            let _ = find_project(db, project_id).await?;
+            let modules = module::Entity::find()
+                .filter(module::Column::ProjectId.eq(project_id))
+                .all(db)
+                .await?;
+            (urls.project_query(project_id), modules)
+        }
+        QueryScope::Module(module_id) => (
+            urls.creation_factory(module_id),
+            vec![find_module(db, module_id).await?],
+        ),
+    };
+    let modules: Vec<module::Model> = modules
+        .into_iter()
+        .filter(|m| visible.as_ref().is_none_or(|v| v.contains(&m.id)))
+        .collect();
+
+    let objects = object::Entity::find()
+        .filter(object::Column::ModuleId.is_in(modules.iter().map(|m| m.id)))
+        .filter(object::Column::DeletedAt.is_null())
+        .order_by_asc(object::Column::ModuleId)
+        .order_by_asc(object::Column::Id)
+        .all(db)
+        .await?;
+    let mut members: Vec<Resource> = objects
+        .iter()
+        .filter_map(|o| {
+            let project_id = modules.iter().find(|m| m.id == o.module_id)?.project_id;
+            let r = requirement_resource(urls, o, project_id);
+            conditions.iter().all(|c| c.matches(&r)).then_some(r)
+        })
+        .collect();
+
+    let total = members.len() as u64;
+    let mut next_page = None;
+    if let Some(size) = params.page_size.filter(|s| *s > 0) {
+        let page = params.page.unwrap_or(1).max(1);
+        let start = usize::try_from((page - 1).saturating_mul(size)).unwrap_or(usize::MAX);
+        members = members
+            .into_iter()
+            .skip(start)
+            .take(usize::try_from(size).unwrap_or(usize::MAX))
+            .collect();
+        if page.saturating_mul(size) < total {
+            next_page = Some(with_page(request_uri, page + 1));
+        }
+    }
+    if let Some(select) = &select {
+        for m in &mut members {
+            apply_select(m, select);
+        }
+    }
+
+    let mut info = Resource::new(request_uri, "oslc:ResponseInfo")
+        .with("oslc:totalCount", Term::Typed(total.to_string(), "integer"));
+    if let Some(next) = next_page {
+        info = info.uri("oslc:nextPage", next);
+    }
+    let mut results = Resource {
+        about: Some(query_base),
+        ..Resource::default()
+    };
+    for m in &members {
+        if let Some(about) = &m.about {
+            results = results.uri("rdfs:member", about.clone());
+        }
+    }
+
+    let mut graph = vec![info, results];
+    graph.extend(members);
+    Ok(graph)
+}
+
+fn with_page(uri: &str, page: u64) -> String {
+    let (path, query) = uri.split_once('?').unwrap_or((uri, ""));
+    let mut params: Vec<&str> = query
+        .split('&')
+        .filter(|p| !p.is_empty() && !p.starts_with("page="))
+        .collect();
+    let page = format!("page={page}");
+    params.push(&page);
+    format!("{path}?{}", params.join("&"))
+}
+
+/// Text of a JSON-LD property given by prefixed name, full URI, or bare local name.
+fn json_ld_text(body: &serde_json::Map<String, serde_json::Value>, name: &str) -> Option<String> {
+    let full = expand(name);
+    let local = name.split_once(':').map_or(name, |(_, l)| l);
+    let value = body
+        .get(name)
+        .or_else(|| body.get(&full))
+        .or_else(|| body.get(local))?;
+    let value = match value {
+        serde_json::Value::Array(values) => values.first()?,
+        v => v,
+    };
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Object(o) => o.get("@value").map(|v| match v {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// Creation factory: make an object in the module from a JSON-LD `oslc_rm:Requirement`.
+/// `dcterms:title` and `dcterms:description` become heading and body; `req1_attr:*`
+/// properties become attributes.
+pub async fn create_requirement(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    urls: &Urls,
+    module_id: Uuid,
+    body: &serde_json::Value,
+) -> Result<Resource, CoreError> {
+    let module = find_module(db, module_id).await?;
+    let body = body
+        .as_object()
+        .ok_or_else(|| CoreError::bad_request("expected a JSON-LD object"))?;
+
+    let attr_ns = expand("req1_attr:");
+    let mut attributes = serde_json::Map::new();
+    for (key, value) in body {
+        let name = key
+            .strip_prefix("req1_attr:")
+            .or_else(|| key.strip_prefix(attr_ns.as_str()));
+        if let Some(name) = name {
+            let value = value.get("@value").unwrap_or(value).clone();
+            let _ = attributes.insert(name.to_owned(), value);
+        }
+    }
+
+    let obj = ObjectService::create(
+        db,
+        ctx,
+        CreateObjectInput {
+            module_id,
+            parent_id: None,
+            position: None,
+            heading: json_ld_text(body, "dcterms:title"),
+            body: json_ld_text(body, "dcterms:description"),
+            attributes: (!attributes.is_empty()).then_some(serde_json::Value::Object(attributes)),
+            classification: json_ld_text(body, "req1:classification"),
+            references: None,
+            object_type_id: None,
+            lifecycle_state: None,
+            lifecycle_model_id: None,
+            source_object_id: None,
+            source_module_id: None,
+            is_placeholder: None,
+        },
+    )
+    .await?;
+    Ok(requirement_resource(urls, &obj, module.project_id))
+}
@@ -0,0 +1,376 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use super::rdf::{PREFIXES, Resource, Term};
+
+/// Prefix bindings for `oslc.where` / `oslc.select`: the provider's own plus any given
+/// in `oslc.prefix`.
+#[derive(Debug, Clone)]
+pub struct Prefixes(HashMap<String, String>);
+
+impl Default for Prefixes {
+    fn default() -> Self {
+        Self(
+            PREFIXES
+                .iter()
+                .map(|(p, ns)| ((*p).to_owned(), (*ns).to_owned()))
+                .collect(),
+        )
+    }
+}
+
+impl Prefixes {
+    /// Parse `oslc.prefix`, e.g. `dc=<http://purl.org/dc/terms/>,rm=<...>`.
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        let mut prefixes = Self::default();
+        for binding in value.unwrap_or_default().split(',').map(str::trim) {
+            if binding.is_empty() {
+                continue;
+            }
+            let (prefix, uri) = binding
+                .split_once('=')
+                .ok_or_else(|| format!("invalid oslc.prefix binding '{binding}'"))?;
+            let uri = uri
+                .trim()
+                .strip_prefix('<')
+                .and_then(|u| u.strip_suffix('>'))
+                .ok_or_else(|| format!("oslc.prefix URI for '{prefix}' must be in <>"))?;
+            let _ = prefixes.0.insert(prefix.trim().to_owned(), uri.to_owned());
+        }
+        Ok(prefixes)
+    }
+
+    fn expand(&self, name: &str) -> Result<String, String> {
+        let (prefix, local) = name
+            .split_once(':')
+            .ok_or_else(|| format!("'{name}' is not a prefixed name"))?;
+        self.0
+            .get(prefix)
+            .map(|ns| format!("{ns}{local}"))
+            .ok_or_else(|| format!("unknown prefix '{prefix}'"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Uri(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+/// One `property op value` or `property in [...]` term of an `oslc.where` clause.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    /// Full property URI.
+    pub property: String,
+    pub op: Op,
+    /// More than one value only for `in`.
+    pub values: Vec<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Str(String),
+    Num(f64),
+    Uri(String),
+    Op(Op),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while let Some(&c) = chars.get(i) {
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '[' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err("unterminated string in oslc.where".to_owned()),
+                        Some('"') => break,
+                        Some('\\') => {
+                            s.extend(chars.get(i + 1));
+                            i += 2;
+                        }
+                        Some(c) => {
+                            s.push(*c);
+                            i += 1;
+                        }
+                    }
+                }
+                i += 1;
+                // Datatype and language tags carry no meaning for the comparison
+                if chars.get(i) == Some(&'^') || chars.get(i) == Some(&'@') {
+                    while chars
+                        .get(i)
+                        .is_some_and(|c| !c.is_whitespace() && *c != ']')
+                    {
+                        i += 1;
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '<' if chars.get(i + 1).is_some_and(|c| c.is_ascii_alphabetic()) => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|c| *c == '>')
+                    .ok_or_else(|| "unterminated URI in oslc.where".to_owned())?;
+                tokens.push(Token::Uri(chars[i + 1..i + end].iter().collect()));
+                i += end + 1;
+            }
+            '=' | '!' | '<' | '>' => {
+                let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+                let (op, len) = match two.as_str() {
+                    "!=" => (Op::Ne, 2),
+                    "<=" => (Op::Le, 2),
+                    ">=" => (Op::Ge, 2),
+                    _ => match c {
+                        '=' => (Op::Eq, 1),
+                        '<' => (Op::Lt, 1),
+                        '>' => (Op::Gt, 1),
+                        _ => return Err("expected '!='".to_owned()),
+                    },
+                };
+                tokens.push(Token::Op(op));
+                i += len;
+            }
+            _ => {
+                let start = i;
+                while chars.get(i).is_some_and(|c| {
+                    c.is_alphanumeric() || matches!(c, ':' | '_' | '-' | '.' | '+')
+                }) {
+                    i += 1;
+                }
+                if i == start {
+                    return Err(format!("unexpected '{c}' in oslc.where"));
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.parse::<f64>() {
+                    Ok(n) => Token::Num(n),
+                    Err(_) => Token::Name(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn literal(token: Option<Token>) -> Result<Value, String> {
+    match token {
+        Some(Token::Str(s)) => Ok(Value::Str(s)),
+        Some(Token::Num(n)) => Ok(Value::Num(n)),
+        Some(Token::Uri(u)) => Ok(Value::Uri(u)),
+        Some(Token::Name(n)) if n == "true" || n == "false" => Ok(Value::Bool(n == "true")),
+        other => Err(format!("expected a value in oslc.where, found {other:?}")),
+    }
+}
+
+/// Parse an `oslc.where` expression: terms joined by `and`.
+pub fn parse_where(input: &str, prefixes: &Prefixes) -> Result<Vec<Condition>, String> {
+    let mut tokens = tokenize(input)?.into_iter().peekable();
+    let mut conditions = Vec::new();
+    loop {
+        let Some(Token::Name(name)) = tokens.next() else {
+            return Err("expected a property name in oslc.where".to_owned());
+        };
+        let property = prefixes.expand(&name)?;
+        let condition = match tokens.next() {
+            Some(Token::Op(op)) => Condition {
+                property,
+                op,
+                values: vec![literal(tokens.next())?],
+            },
+            Some(Token::Name(kw)) if kw == "in" => {
+                if tokens.next() != Some(Token::Open) {
+                    return Err("expected '[' after 'in'".to_owned());
+                }
+                let mut values = Vec::new();
+                loop {
+                    values.push(literal(tokens.next())?);
+                    match tokens.next() {
+                        Some(Token::Comma) => {}
+                        Some(Token::Close) => break,
+                        _ => return Err("expected ',' or ']' in 'in' list".to_owned()),
+                    }
+                }
+                Condition {
+                    property,
+                    op: Op::Eq,
+                    values,
+                }
+            }
+            _ => return Err(format!("expected an operator after '{name}'")),
+        };
+        conditions.push(condition);
+        match tokens.next() {
+            None => return Ok(conditions),
+            Some(Token::Name(kw)) if kw == "and" => {}
+            Some(other) => return Err(format!("expected 'and' in oslc.where, found {other:?}")),
+        }
+    }
+}
+
+/// Parse `oslc.select` into full property URIs; `None` selects everything. Nested
+/// selections (`prop{...}`) select the property itself.
+pub fn parse_select(
+    input: Option<&str>,
+    prefixes: &Prefixes,
+) -> Result<Option<HashSet<String>>, String> {
+    let Some(input) = input.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let mut flat = String::new();
+    let mut depth = 0_usize;
+    for c in input.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            c if depth == 0 => flat.push(c),
+            _ => {}
+        }
+    }
+    let mut selected = HashSet::new();
+    for name in flat.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        if name == "*" {
+            return Ok(None);
+        }
+        let _ = selected.insert(prefixes.expand(name)?);
+    }
+    Ok(Some(selected))
+}
+
+fn term_value(term: &Term) -> Option<Value> {
+    match term {
+        Term::Literal(s) => Some(Value::Str(s.clone())),
+        Term::Typed(s, "integer" | "decimal" | "double") => s.parse().ok().map(Value::Num),
+        Term::Typed(s, "boolean") => Some(Value::Bool(s == "true")),
+        Term::Typed(s, _) => Some(Value::Str(s.clone())),
+        Term::Uri(u) => Some(Value::Uri(u.clone())),
+        Term::Node(_) => None,
+    }
+}
+
+fn compare(actual: &Value, expected: &Value) -> Option<Ordering> {
+    match (actual, expected) {
+        (Value::Num(a), Value::Num(b)) => a.partial_cmp(b),
+        (Value::Str(a), Value::Num(b)) => a.parse::<f64>().ok()?.partial_cmp(b),
+        (Value::Str(a), Value::Str(b)) | (Value::Uri(a), Value::Uri(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+impl Condition {
+    pub fn matches(&self, resource: &Resource) -> bool {
+        let actual: Vec<Value> = resource
+            .values(&self.property)
+            .filter_map(term_value)
+            .collect();
+        let holds = |a: &Value, e: &Value| {
+            let ord = compare(a, e);
+            match self.op {
+                Op::Eq | Op::Ne => ord == Some(Ordering::Equal),
+                Op::Lt => ord == Some(Ordering::Less),
+                Op::Gt => ord == Some(Ordering::Greater),
+                Op::Le => matches!(ord, Some(Ordering::Less | Ordering::Equal)),
+                Op::Ge => matches!(ord, Some(Ordering::Greater | Ordering::Equal)),
+            }
+        };
+        let any = actual
+            .iter()
+            .any(|a| self.values.iter().any(|e| holds(a, e)));
+        if self.op == Op::Ne { !any } else { any }
+    }
+}
+
+/// Drop the properties `select` does not name.
+pub fn apply_select(resource: &mut Resource, select: &HashSet<String>) {
+    resource
+        .properties
+        .retain(|(p, _)| select.contains(&super::rdf::expand(p)));
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn requirement() -> Resource {
+        Resource::new("urn:r", "oslc_rm:Requirement")
+            .literal("dcterms:title", "Brake response")
+            .literal("oslc:shortTitle", "1.2")
+            .with("req1:version", Term::Typed("3".to_owned(), "integer"))
+            .uri("oslc:serviceProvider", "http://x/sp")
+    }
+
+    fn matches(expr: &str) -> bool {
+        let prefixes = Prefixes::default();
+        parse_where(expr, &prefixes)
+            .unwrap()
+            .iter()
+            .all(|c| c.matches(&requirement()))
+    }
+
+    #[test]
+    fn test_where_operators() {
+        assert!(matches(r#"dcterms:title="Brake response""#));
+        assert!(!matches(r#"dcterms:title!="Brake response""#));
+        assert!(matches("req1:version>=3 and req1:version<4"));
+        assert!(!matches("req1:version>3"));
+        assert!(matches(r#"oslc:shortTitle in ["1.1","1.2"]"#));
+        assert!(matches("oslc:serviceProvider=<http://x/sp>"));
+        assert!(matches(r#"dcterms:title="Brake response"^^xsd:string"#));
+    }
+
+    #[test]
+    fn test_where_errors() {
+        let prefixes = Prefixes::default();
+        assert!(parse_where("dcterms:title", &prefixes).is_err());
+        assert!(parse_where(r#"nope:title="x""#, &prefixes).is_err());
+        assert!(parse_where(r#"dcterms:title="x" or dcterms:title="y""#, &prefixes).is_err());
+    }
+
+    #[test]
+    fn test_custom_prefix_and_select() {
+        let prefixes = Prefixes::parse(Some("dc=<http://purl.org/dc/terms/>")).unwrap();
+        let conditions = parse_where(r#"dc:title="Brake response""#, &prefixes).unwrap();
+        assert!(conditions[0].matches(&requirement()));
+
+        let select = parse_select(Some("dc:title,dcterms:creator{foaf:name}"), &prefixes)
+            .unwrap()
+            .unwrap();
+        let mut r = requirement();
+        apply_select(&mut r, &select);
+        assert_eq!(r.properties.len(), 1);
+        assert_eq!(parse_select(Some("*"), &prefixes).unwrap(), None);
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt::Write as _;
+
+/// Namespaces every OSLC document declares; predicates and types are written as
+/// prefixed names over these.
+pub const PREFIXES: &[(&str, &str)] = &[
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+    ("oslc", "http://open-services.net/ns/core#"),
+    ("oslc_rm", "http://open-services.net/ns/rm#"),
+    ("req1", "urn:req1:oslc#"),
+    ("req1_attr", "urn:req1:attribute#"),
+];
+
+/// Expand a prefixed name (`dcterms:title`) to its full URI; unknown prefixes are
+/// returned unchanged.
+pub fn expand(name: &str) -> String {
+    name.split_once(':')
+        .and_then(|(prefix, local)| {
+            PREFIXES
+                .iter()
+                .find(|(p, _)| *p == prefix)
+                .map(|(_, ns)| format!("{ns}{local}"))
+        })
+        .unwrap_or_else(|| name.to_owned())
+}
+
+/// Whether `name` can be used as the local part of a prefixed name in all three formats.
+pub fn is_local_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Literal(String),
+    /// Literal with an `xsd:` datatype, e.g. `("3", "integer")`.
+    Typed(String, &'static str),
+    Uri(String),
+    /// Nested blank node.
+    Node(Resource),
+}
+
+/// Subject with its types and property values. `about: None` is a blank node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Resource {
+    pub about: Option<String>,
+    pub types: Vec<String>,
+    pub properties: Vec<(String, Term)>,
+}
+
+impl Resource {
+    pub fn new(about: impl Into<String>, rdf_type: &str) -> Self {
+        Self {
+            about: Some(about.into()),
+            types: vec![rdf_type.to_owned()],
+            properties: Vec::new(),
+        }
+    }
+
+    pub fn blank(rdf_type: &str) -> Self {
+        Self {
+            about: None,
+            types: vec![rdf_type.to_owned()],
+            properties: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with(mut self, predicate: &str, term: Term) -> Self {
+        self.properties.push((predicate.to_owned(), term));
+        self
+    }
+
+    #[must_use]
+    pub fn literal(self, predicate: &str, value: impl Into<String>) -> Self {
+        self.with(predicate, Term::Literal(value.into()))
+    }
+
+    #[must_use]
+    pub fn uri(self, predicate: &str, value: impl Into<String>) -> Self {
+        self.with(predicate, Term::Uri(value.into()))
+    }
+
+    #[must_use]
+    pub fn node(self, predicate: &str, value: Self) -> Self {
+        self.with(predicate, Term::Node(value))
+    }
+
+    /// Values of a property given by its full URI.
+    pub fn values<'a>(&'a self, property: &'a str) -> impl Iterator<Item = &'a Term> {
+        self.properties
+            .iter()
+            .filter(move |(p, _)| expand(p) == property)
+            .map(|(_, t)| t)
+    }
+}
+
+/// Serializations the provider can negotiate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RdfFormat {
+    RdfXml,
+    Turtle,
+    JsonLd,
+}
+
+impl RdfFormat {
+    /// Pick a format from an `Accept` header by quality, defaulting to RDF/XML as
+    /// OSLC 2.0 clients expect.
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        let mut ranges: Vec<(f32, Self)> = accept
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let format = match parts.next()? {
+                    "application/rdf+xml" | "application/xml" | "text/xml" => Self::RdfXml,
+                    "text/turtle" => Self::Turtle,
+                    "application/ld+json" | "application/json" => Self::JsonLd,
+                    _ => return None,
+                };
+                let q = parts
+                    .find_map(|p| p.strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                Some((q, format))
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranges.first().map_or(Self::RdfXml, |(_, f)| *f)
+    }
+
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::RdfXml => "application/rdf+xml",
+            Self::Turtle => "text/turtle",
+            Self::JsonLd => "application/ld+json",
+        }
+    }
+
+    pub fn write(self, graph: &[Resource]) -> String {
+        match self {
+            Self::RdfXml => to_rdf_xml(graph),
+            Self::Turtle => to_turtle(graph),
+            Self::JsonLd => to_json_ld(graph),
+        }
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn to_rdf_xml(graph: &[Resource]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rdf:RDF");
+    for (prefix, ns) in PREFIXES {
+        let _ = write!(out, "\n    xmlns:{prefix}=\"{ns}\"");
+    }
+    out.push_str(">\n");
+    for r in graph {
+        xml_resource(&mut out, r, 1);
+    }
+    out.push_str("</rdf:RDF>\n");
+    out
+}
+
+fn xml_resource(out: &mut String, r: &Resource, depth: usize) {
+    let pad = "  ".repeat(depth);
+    match &r.about {
+        Some(about) => {
+            let _ = writeln!(
+                out,
+                "{pad}<rdf:Description rdf:about=\"{}\">",
+                xml_escape(about)
+            );
+        }
+        None => {
+            let _ = writeln!(out, "{pad}<rdf:Description>");
+        }
+    }
+    for t in &r.types {
+        let _ = writeln!(
+            out,
+            "{pad}  <rdf:type rdf:resource=\"{}\"/>",
+            xml_escape(&expand(t))
+        );
+    }
+    for (p, term) in &r.properties {
+        match term {
+            Term::Literal(s) => {
+                let _ = writeln!(out, "{pad}  <{p}>{}</{p}>", xml_escape(s));
+            }
+            Term::Typed(s, dt) => {
+                let _ = writeln!(
+                    out,
+                    "{pad}  <{p} rdf:datatype=\"{}\">{}</{p}>",
+                    expand(&format!("xsd:{dt}")),
+                    xml_escape(s)
+                );
+            }
+            Term::Uri(u) => {
+                let _ = writeln!(out, "{pad}  <{p} rdf:resource=\"{}\"/>", xml_escape(u));
+            }
+            Term::Node(n) => {
+                let _ = writeln!(out, "{pad}  <{p}>");
+                xml_resource(out, n, depth + 2);
+                let _ = writeln!(out, "{pad}  </{p}>");
+            }
+        }
+    }
+    let _ = writeln!(out, "{pad}</rdf:Description>");
+}
+
+fn turtle_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn to_turtle(graph: &[Resource]) -> String {
+    let mut out = String::new();
+    for (prefix, ns) in PREFIXES {
+        let _ = writeln!(out, "@prefix {prefix}: <{ns}> .");
+    }
+    for r in graph {
+        out.push('\n');
+        let subject = r
+            .about
+            .as_ref()
+            .map_or_else(|| "[]".to_owned(), |a| format!("<{a}>"));
+        out.push_str(&subject);
+        turtle_body(&mut out, r, 1);
+        out.push_str(" .\n");
+    }
+    out
+}
+
+fn turtle_body(out: &mut String, r: &Resource, depth: usize) {
+    let pad = "    ".repeat(depth);
+    let mut lines = Vec::new();
+    if !r.types.is_empty() {
+        lines.push(format!("a {}", r.types.join(", ")));
+    }
+    for (p, term) in &r.properties {
+        let value = match term {
+            Term::Literal(s) => turtle_string(s),
+            Term::Typed(s, dt) => format!("{}^^xsd:{dt}", turtle_string(s)),
+            Term::Uri(u) => format!("<{u}>"),
+            Term::Node(n) => {
+                let mut nested = String::from("[");
+                turtle_body(&mut nested, n, depth + 1);
+                let _ = write!(nested, "\n{pad}]");
+                nested
+            }
+        };
+        lines.push(format!("{p} {value}"));
+    }
+    for (i, line) in lines.iter().enumerate() {
+        let sep = if i == 0 { "" } else { " ;" };
+        let _ = write!(out, "{sep}\n{pad}{line}");
+    }
+}
+
+fn json_ld_node(r: &Resource) -> serde_json::Map<String, serde_json::Value> {
+    let mut node = serde_json::Map::new();
+    if let Some(about) = &r.about {
+        let _ = node.insert("@id".to_owned(), serde_json::json!(about));
+    }
+    if !r.types.is_empty() {
+        let _ = node.insert("@type".to_owned(), serde_json::json!(r.types));
+    }
+    for (p, term) in &r.properties {
+        let value = match term {
+            Term::Literal(s) => serde_json::json!(s),
+            Term::Typed(s, dt) => serde_json::json!({"@value": s, "@type": format!("xsd:{dt}")}),
+            Term::Uri(u) => serde_json::json!({"@id": u}),
+            Term::Node(n) => serde_json::Value::Object(json_ld_node(n)),
+        };
+        match node.get_mut(p) {
+            Some(serde_json::Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = serde_json::json!([existing.take(), value]),
+            None => {
+                let _ = node.insert(p.clone(), value);
+            }
+        }
+    }
+    node
+}
+
+pub fn to_json_ld(graph: &[Resource]) -> String {
+    let context: serde_json::Map<String, serde_json::Value> = PREFIXES
+        .iter()
+        .map(|(p, ns)| ((*p).to_owned(), serde_json::json!(ns)))
+        .collect();
+    let doc = match graph {
+        [single] => {
+            let mut node = json_ld_node(single);
+            let _ = node.insert("@context".to_owned(), serde_json::Value::Object(context));
+            serde_json::Value::Object(node)
+        }
+        _ => serde_json::json!({
+            "@context": context,
+            "@graph": graph.iter().map(json_ld_node).collect::<Vec<_>>(),
+        }),
+    };
+    serde_json::to_string_pretty(&doc).unwrap_or_default()
+}
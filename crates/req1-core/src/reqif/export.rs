@@ -19,9 +19,11 @@ use req1_reqif::{
 use crate::error::CoreError;
 
 use super::ExportResult;
+use super::profile::ExchangeProfile;
 use super::type_map::{entity_datatype_to_reqif, json_to_reqif_attr_value};
 
-/// Export a module and its contents to a `ReqIF` document.
+/// Export a module and its contents to a `ReqIF` document, limited to the
+/// attributes its exchange profile shares.
 #[allow(clippy::too_many_lines)]
 pub async fn export_reqif(
     db: &impl ConnectionTrait,
@@ -39,10 +41,15 @@ pub async fn export_reqif(
         .all(db)
         .await?;
 
+    // Attributes outside the exchange profile are not shared with the partner
+    let profile = ExchangeProfile::for_module(&module_entity)?;
     let attr_defs: Vec<attribute_definition::Model> = attribute_definition::Entity::find()
         .filter(attribute_definition::Column::ModuleId.eq(Some(module_id)))
         .all(db)
-        .await?;
+        .await?
+        .into_iter()
+        .filter(|ad| profile.exports(&ad.name))
+        .collect();
 
     let obj_types: Vec<object_type::Model> = object_type::Entity::find()
         .filter(object_type::Column::ModuleId.eq(module_id))
@@ -109,7 +116,10 @@ pub async fn export_reqif(
     let reqif_relations = build_spec_relations(&links, &object_reqif_ids);
 
     let specification = Specification {
-        identifier: format!("req1-{module_id}"),
+        identifier: module_entity
+            .reqif_id
+            .clone()
+            .unwrap_or_else(|| format!("req1-{module_id}")),
         long_name: Some(module_entity.name.clone()),
         last_change: None,
         desc: module_entity.description.clone(),
@@ -288,7 +298,11 @@ fn build_spec_objects(
     let mut object_reqif_ids: HashMap<Uuid, String> = HashMap::new();
 
     for obj in objects {
-        let obj_reqif_id = format!("req1-{}", obj.id);
+        // Identifiers from an earlier import are kept so the other tool can match them
+        let obj_reqif_id = obj
+            .reqif_id
+            .clone()
+            .unwrap_or_else(|| format!("req1-{}", obj.id));
         let _ = object_reqif_ids.insert(obj.id, obj_reqif_id.clone());
 
         let type_ref_id = obj.object_type_id.map_or_else(
@@ -339,7 +353,10 @@ fn build_spec_relations(
                 .unwrap_or_else(|| format!("req1-{}", lnk.target_object_id));
 
             SpecRelation {
-                identifier: format!("req1-{}", lnk.id),
+                identifier: lnk
+                    .reqif_id
+                    .clone()
+                    .unwrap_or_else(|| format!("req1-{}", lnk.id)),
                 long_name: None,
                 last_change: None,
                 desc: None,
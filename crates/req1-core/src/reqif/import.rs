@@ -6,17 +6,21 @@ use uuid::Uuid;
 
 use entity::{attribute_definition, link, link_type, module, object, object_type};
 use req1_reqif::{
-    AttributeDefinition as ReqifAttrDef, DatatypeDefinition, ReqIf, SpecHierarchyChildren, SpecType,
+    AttributeDefinition as ReqifAttrDef, DatatypeDefinition, ReqIf, ReqIfContent,
+    SpecHierarchyChildren, SpecType,
 };
 
 use crate::error::CoreError;
-use crate::fingerprint::compute_content_fingerprint;
+use crate::fingerprint::{FingerprintPolicy, compute_content_fingerprint};
+use crate::history::{self, HistoryEntry};
+use crate::link_history::{LinkHistoryEntry, insert_link_history};
+use crate::service::audit::{AuditContext, AuditService};
 
-use super::ImportResult;
 use super::type_map::{
     attr_value_def_ref, datatype_identifier, enum_values_to_json, extract_enum_values,
     reqif_attr_value_to_json, reqif_datatype_to_entity,
 };
+use super::{ImportResult, merge};
 
 /// Import a parsed `ReqIF` document into the database, creating entities for a given project.
 ///
@@ -24,6 +28,7 @@ use super::type_map::{
 #[allow(clippy::too_many_lines)]
 pub async fn import_reqif(
     db: &(impl ConnectionTrait + TransactionTrait),
+    ctx: &AuditContext,
     project_id: Uuid,
     doc: &ReqIf,
 ) -> Result<ImportResult, CoreError> {
@@ -44,39 +49,11 @@ pub async fn import_reqif(
 
     let txn = db.begin().await?;
 
-    // Build lookup maps from ReqIF elements
-    let datatypes: &[DatatypeDefinition] = content
-        .datatypes
-        .as_ref()
-        .map_or(&[], |d| d.definitions.as_slice());
-
-    let enum_values_by_id = extract_enum_values(datatypes);
-
-    let datatypes_by_id: HashMap<&str, &DatatypeDefinition> = datatypes
-        .iter()
-        .map(|d| (datatype_identifier(d), d))
-        .collect();
-
     let spec_types: &[SpecType] = content
         .spec_types
         .as_ref()
         .map_or(&[], |s| s.types.as_slice());
-
-    // reqif_attr_def_id → (attr long_name, entity data_type)
-    let mut attr_def_info: HashMap<&str, (&str, &str)> = HashMap::new();
-    for st in spec_types {
-        if let SpecType::SpecObjectType(sot) = st
-            && let Some(attrs) = &sot.spec_attributes
-        {
-            for ad in &attrs.definitions {
-                let (ad_id, ad_name, dt_ref_id) = extract_attr_def_info(ad);
-                if let Some(dt) = datatypes_by_id.get(dt_ref_id) {
-                    let entity_type = reqif_datatype_to_entity(dt);
-                    let _ = attr_def_info.insert(ad_id, (ad_name, entity_type));
-                }
-            }
-        }
-    }
+    let lookup = AttrLookup::new(content);
 
     let spec_objects = content
         .spec_objects
@@ -124,8 +101,11 @@ pub async fn import_reqif(
             publish_template: Set(None),
             default_lifecycle_model_id: Set(None),
             signature_config: Set(json!({})),
+            fingerprint_policy: Set(None),
             created_at: Set(now),
             updated_at: Set(now),
+            reqif_id: Set(Some(spec.identifier.clone())),
+            reqif_profile: Set(None),
         };
         let _ = module_model.insert(&txn).await?;
         let _ = id_map.insert(spec.identifier.clone(), module_id);
@@ -144,42 +124,12 @@ pub async fn import_reqif(
 
                 if let Some(attrs) = &sot.spec_attributes {
                     for ad in &attrs.definitions {
-                        let (ad_reqif_id, ad_name, dt_ref_id) = extract_attr_def_info(ad);
-                        let dt = datatypes_by_id.get(dt_ref_id);
-                        let entity_type = dt.map_or("string", |d| reqif_datatype_to_entity(d));
-
-                        let ad_id = Uuid::now_v7();
-
-                        // Extract enum values if enumeration
-                        let enum_vals = if entity_type == "enum" {
-                            dt.and_then(|d| {
-                                if let DatatypeDefinition::Enumeration(e) = d {
-                                    e.specified_values
-                                        .as_ref()
-                                        .map(|sv| enum_values_to_json(&sv.values))
-                                } else {
-                                    None
-                                }
-                            })
-                        } else {
-                            None
-                        };
-
-                        let multi_select = matches!(ad, ReqifAttrDef::Enumeration(e) if e.multi_valued == Some(true));
-
-                        let ad_model = attribute_definition::ActiveModel {
-                            id: Set(ad_id),
-                            module_id: Set(Some(module_id)),
-                            name: Set(ad_name.to_owned()),
-                            data_type: Set(entity_type.to_owned()),
-                            default_value: Set(None),
-                            enum_values: Set(enum_vals),
-                            multi_select: Set(multi_select),
-                            depends_on: Set(None),
-                            dependency_mapping: Set(None),
-                            created_at: Set(now),
-                        };
-                        let _ = ad_model.insert(&txn).await?;
+                        let (ad_reqif_id, ad_name, _) = extract_attr_def_info(ad);
+                        let inserted = new_attribute_definition(ad, &lookup, module_id, now)
+                            .insert(&txn)
+                            .await?;
+                        let ad_id = inserted.id;
+                        let entity_type = inserted.data_type;
 
                         let _ = id_map.insert(ad_reqif_id.to_owned(), ad_id);
                         total_attr_defs += 1;
@@ -200,6 +150,7 @@ pub async fn import_reqif(
                     default_classification: Set("normative".to_owned()),
                     required_attributes: Set(json!([])),
                     attribute_schema: Set(json!(schema_entries)),
+                    fingerprint_policy: Set(None),
                     created_at: Set(now),
                     updated_at: Set(now),
                 };
@@ -225,6 +176,11 @@ pub async fn import_reqif(
                     name: Set(lt_name),
                     description: Set(srt.desc.clone()),
                     created_at: Set(now),
+                    suspect_direction: Set("both".to_owned()),
+                    suspect_cascade: Set(false),
+                    suspect_skip_informative: Set(false),
+                    inverse_name: Set(None),
+                    rules: Set(None),
                 };
                 let _ = lt_model.insert(&txn).await?;
 
@@ -239,12 +195,18 @@ pub async fn import_reqif(
             let heading = so.long_name.clone();
 
             // Build attributes JSON
-            let attributes = build_attributes_json(so, &attr_def_info, &enum_values_by_id);
+            let attributes = build_attributes_json(so, &lookup);
 
             // Resolve object type
             let ot_uuid = id_map.get(&so.type_ref.value).copied();
 
-            let fp = compute_content_fingerprint(heading.as_deref(), None, attributes.as_ref());
+            // Freshly imported module and types carry no fingerprint policy
+            let fp = compute_content_fingerprint(
+                heading.as_deref(),
+                None,
+                attributes.as_ref(),
+                &FingerprintPolicy::default(),
+            );
 
             let obj_model = object::ActiveModel {
                 id: Set(obj_id),
@@ -252,9 +214,9 @@ pub async fn import_reqif(
                 parent_id: Set(None),
                 position: Set(0),
                 level: Set("0".to_owned()),
-                heading: Set(heading),
+                heading: Set(heading.clone()),
                 body: Set(None),
-                attributes: Set(attributes),
+                attributes: Set(attributes.clone()),
                 current_version: Set(1),
                 classification: Set("normative".to_owned()),
                 content_fingerprint: Set(fp),
@@ -272,9 +234,30 @@ pub async fn import_reqif(
                 deleted_at: Set(None),
                 created_at: Set(now),
                 updated_at: Set(now),
+                reqif_id: Set(Some(so.identifier.clone())),
+                reqif_base: Set(Some(merge::baseline(
+                    heading.as_deref(),
+                    attributes.as_ref(),
+                ))),
             };
             let _ = obj_model.insert(&txn).await?;
 
+            history::insert_history(
+                &txn,
+                ctx,
+                HistoryEntry {
+                    object_id: obj_id,
+                    module_id,
+                    version: 1,
+                    attribute_values: attributes,
+                    heading,
+                    body: None,
+                    change_type: "create".to_owned(),
+                    state: None,
+                },
+            )
+            .await?;
+
             let _ = id_map.insert(so.identifier.clone(), obj_id);
             total_objects += 1;
         }
@@ -304,13 +287,45 @@ pub async fn import_reqif(
                     target_fingerprint: Set(String::new()),
                     created_at: Set(now),
                     updated_at: Set(now),
+                    suspect_reason: Set(None),
+                    suspected_at: Set(None),
+                    version: Set(1),
+                    reqif_id: Set(Some(sr.identifier.clone())),
                 };
-                let _ = link_model.insert(&txn).await?;
+                let inserted = link_model.insert(&txn).await?;
+                insert_link_history(
+                    &txn,
+                    ctx,
+                    &inserted,
+                    LinkHistoryEntry {
+                        change_type: "create",
+                        ..Default::default()
+                    },
+                )
+                .await?;
                 total_links += 1;
             }
         }
     }
 
+    AuditService::event(
+        &txn,
+        ctx,
+        "import",
+        "module",
+        module_id,
+        json!({
+            "format": "reqif",
+            "project_id": project_id,
+            "objects_created": total_objects,
+            "links_created": total_links,
+            "attribute_definitions_created": total_attr_defs,
+            "object_types_created": total_object_types,
+            "link_types_created": total_link_types,
+        }),
+    )
+    .await?;
+
     txn.commit().await?;
 
     Ok(ImportResult {
@@ -323,11 +338,99 @@ pub async fn import_reqif(
     })
 }
 
+/// Lookups shared by import and merge: enum value names by identifier, datatypes
+/// by identifier, and attribute definition identifier → (name, entity data type).
+pub(super) struct AttrLookup<'a> {
+    pub enum_values_by_id: HashMap<String, String>,
+    pub datatypes_by_id: HashMap<&'a str, &'a DatatypeDefinition>,
+    pub attr_def_info: HashMap<&'a str, (&'a str, &'a str)>,
+}
+
+impl<'a> AttrLookup<'a> {
+    pub fn new(content: &'a ReqIfContent) -> Self {
+        let datatypes: &[DatatypeDefinition] = content
+            .datatypes
+            .as_ref()
+            .map_or(&[], |d| d.definitions.as_slice());
+        let datatypes_by_id: HashMap<&str, &DatatypeDefinition> = datatypes
+            .iter()
+            .map(|d| (datatype_identifier(d), d))
+            .collect();
+
+        let mut attr_def_info: HashMap<&str, (&str, &str)> = HashMap::new();
+        for st in content
+            .spec_types
+            .as_ref()
+            .map_or(&[][..], |s| s.types.as_slice())
+        {
+            if let SpecType::SpecObjectType(sot) = st
+                && let Some(attrs) = &sot.spec_attributes
+            {
+                for ad in &attrs.definitions {
+                    let (ad_id, ad_name, dt_ref_id) = extract_attr_def_info(ad);
+                    if let Some(dt) = datatypes_by_id.get(dt_ref_id) {
+                        let entity_type = reqif_datatype_to_entity(dt);
+                        let _ = attr_def_info.insert(ad_id, (ad_name, entity_type));
+                    }
+                }
+            }
+        }
+
+        Self {
+            enum_values_by_id: extract_enum_values(datatypes),
+            datatypes_by_id,
+            attr_def_info,
+        }
+    }
+}
+
+/// New module attribute definition mirroring a `ReqIF` attribute definition,
+/// including its enum values and multi-select flag.
+pub(super) fn new_attribute_definition(
+    ad: &ReqifAttrDef,
+    lookup: &AttrLookup<'_>,
+    module_id: Uuid,
+    now: chrono::DateTime<chrono::FixedOffset>,
+) -> attribute_definition::ActiveModel {
+    let (_, ad_name, dt_ref_id) = extract_attr_def_info(ad);
+    let dt = lookup.datatypes_by_id.get(dt_ref_id);
+    let entity_type = dt.map_or("string", |d| reqif_datatype_to_entity(d));
+
+    // Extract enum values if enumeration
+    let enum_vals = if entity_type == "enum" {
+        dt.and_then(|d| {
+            if let DatatypeDefinition::Enumeration(e) = d {
+                e.specified_values
+                    .as_ref()
+                    .map(|sv| enum_values_to_json(&sv.values))
+            } else {
+                None
+            }
+        })
+    } else {
+        None
+    };
+
+    let multi_select = matches!(ad, ReqifAttrDef::Enumeration(e) if e.multi_valued == Some(true));
+
+    attribute_definition::ActiveModel {
+        id: Set(Uuid::now_v7()),
+        module_id: Set(Some(module_id)),
+        name: Set(ad_name.to_owned()),
+        data_type: Set(entity_type.to_owned()),
+        default_value: Set(None),
+        enum_values: Set(enum_vals),
+        multi_select: Set(multi_select),
+        depends_on: Set(None),
+        dependency_mapping: Set(None),
+        created_at: Set(now),
+    }
+}
+
 /// Build the attributes JSON object for a `SpecObject` from its `AttributeValues`.
-fn build_attributes_json(
+pub(super) fn build_attributes_json(
     so: &req1_reqif::SpecObject,
-    attr_def_info: &HashMap<&str, (&str, &str)>,
-    enum_values_by_id: &HashMap<String, String>,
+    lookup: &AttrLookup<'_>,
 ) -> Option<serde_json::Value> {
     let values = so.values.as_ref()?;
     if values.values.is_empty() {
@@ -337,9 +440,12 @@ fn build_attributes_json(
     let mut attrs = serde_json::Map::new();
     for av in &values.values {
         let def_id = attr_value_def_ref(av);
-        let attr_name = attr_def_info.get(def_id).map_or(def_id, |(name, _)| *name);
+        let attr_name = lookup
+            .attr_def_info
+            .get(def_id)
+            .map_or(def_id, |(name, _)| *name);
 
-        let json_val = reqif_attr_value_to_json(av, enum_values_by_id);
+        let json_val = reqif_attr_value_to_json(av, &lookup.enum_values_by_id);
         let _ = attrs.insert(attr_name.to_owned(), json_val);
     }
 
@@ -347,7 +453,7 @@ fn build_attributes_json(
 }
 
 /// Recursively walk a `SpecHierarchy` tree, setting `parent_id` and `position` on objects.
-async fn walk_hierarchy(
+pub(super) async fn walk_hierarchy(
     db: &impl ConnectionTrait,
     children: &SpecHierarchyChildren,
     parent_uuid: Option<Uuid>,
@@ -382,7 +488,7 @@ async fn walk_hierarchy(
 }
 
 /// Extract (identifier, `long_name`, `datatype_ref_id`) from a `ReqIF` `AttributeDefinition`.
-fn extract_attr_def_info(ad: &ReqifAttrDef) -> (&str, &str, &str) {
+pub(super) fn extract_attr_def_info(ad: &ReqifAttrDef) -> (&str, &str, &str) {
     match ad {
         ReqifAttrDef::Boolean(d) => (
             d.identifier.as_str(),
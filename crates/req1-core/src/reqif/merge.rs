@@ -0,0 +1,989 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter,
+    QuerySelect, Set, TransactionTrait,
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use entity::{attribute_definition, link, link_type, module, object, object_type};
+use req1_reqif::{ReqIf, SpecHierarchyChildren, SpecObject, SpecType, Specification};
+
+use crate::auth::AuthUser;
+use crate::error::CoreError;
+use crate::fingerprint::{FingerprintPolicy, compute_content_fingerprint};
+use crate::history::{self, HistoryEntry};
+use crate::level;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::link::{CreateLinkInput, CreateLinkTypeInput, LinkService};
+use crate::service::object::{ObjectService, UpdateObjectInput};
+use crate::service::object_type::{CreateObjectTypeInput, ObjectTypeService};
+use crate::service::permission::{Permission, PermissionService};
+
+use super::import::{
+    AttrLookup, build_attributes_json, extract_attr_def_info, new_attribute_definition,
+    walk_hierarchy,
+};
+use super::profile::{ExchangeProfile, HEADING, OwnershipViolation};
+use super::{ConflictResolution, MergeConflict, MergeResult};
+
+/// Baseline kept on an object after each exchange: the heading and attributes the
+/// document carried, which the next merge diffs both sides against.
+pub(super) fn baseline(heading: Option<&str>, attributes: Option<&Value>) -> Value {
+    json!({ "heading": heading, "attributes": attributes })
+}
+
+/// Merge a `ReqIF` document into an existing module instead of importing it as a
+/// new one. Objects and links are matched by the `ReqIF` identifiers recorded at
+/// import (or the `req1-{uuid}` identifiers of an earlier export): changed
+/// objects are updated, new ones added, removed ones soft-deleted, and the
+/// hierarchy and relations reconciled with the document.
+///
+/// Headings and attributes are merged three-way against the baseline from the
+/// last exchange, so local edits survive unless the document changed the same
+/// field; those conflicts are settled by `on_conflict`. The module's exchange
+/// profile limits which fields the document may change at all.
+///
+/// Relations are only merged where `user` may link the objects, and relation
+/// types without a link type are only created by admins; other relations are
+/// skipped.
+pub async fn merge_reqif(
+    db: &(impl ConnectionTrait + TransactionTrait),
+    ctx: &AuditContext,
+    user: &AuthUser,
+    module_id: Uuid,
+    doc: &ReqIf,
+    on_conflict: ConflictResolution,
+) -> Result<MergeResult, CoreError> {
+    let txn = db.begin().await?;
+    let result = run_merge(&txn, ctx, user, module_id, doc, on_conflict, false).await?;
+    txn.commit().await?;
+    Ok(result)
+}
+
+/// Dry run of [`merge_reqif`]: performs the merge keeping local edits on
+/// conflict and reporting ownership violations, returns counts, conflicts and
+/// violations, and rolls everything back.
+pub async fn preview_merge(
+    db: &(impl ConnectionTrait + TransactionTrait),
+    ctx: &AuditContext,
+    user: &AuthUser,
+    module_id: Uuid,
+    doc: &ReqIf,
+) -> Result<MergeResult, CoreError> {
+    let txn = db.begin().await?;
+    let result = run_merge(
+        &txn,
+        ctx,
+        user,
+        module_id,
+        doc,
+        ConflictResolution::KeepLocal,
+        true,
+    )
+    .await?;
+    txn.rollback().await?;
+    Ok(result)
+}
+
+/// Which side of a three-way comparison wins.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pick {
+    Local,
+    Incoming,
+    Conflict,
+}
+
+fn pick(base: Option<&Value>, local: Option<&Value>, incoming: Option<&Value>) -> Pick {
+    fn present(v: Option<&Value>) -> Option<&Value> {
+        v.filter(|v| !v.is_null())
+    }
+    let (base, local, incoming) = (present(base), present(local), present(incoming));
+    if incoming == base || local == incoming {
+        Pick::Local
+    } else if local == base {
+        Pick::Incoming
+    } else {
+        Pick::Conflict
+    }
+}
+
+/// Merged content for a matched object.
+struct Planned {
+    existing: object::Model,
+    reqif_id: String,
+    heading: Option<String>,
+    attributes: Option<Value>,
+    object_type_id: Option<Uuid>,
+    base: Value,
+}
+
+/// Specification of the document that corresponds to the module.
+fn find_specification<'a>(
+    specs: &'a [Specification],
+    module: &module::Model,
+) -> Option<&'a Specification> {
+    let own = format!("req1-{}", module.id);
+    specs
+        .iter()
+        .find(|s| module.reqif_id.as_deref() == Some(s.identifier.as_str()) || s.identifier == own)
+        .or(match specs {
+            [only] => Some(only),
+            _ => None,
+        })
+}
+
+fn collect_hierarchy<'a>(children: &'a SpecHierarchyChildren, out: &mut HashSet<&'a str>) {
+    for sh in &children.hierarchies {
+        let _ = out.insert(sh.object.spec_object_ref.as_str());
+        if let Some(sub) = &sh.children {
+            collect_hierarchy(sub, out);
+        }
+    }
+}
+
+/// Key an existing row is matched under: its recorded `ReqIF` identifier, and the
+/// identifier req1 exports it with.
+fn match_keys(reqif_id: Option<&str>, id: Uuid) -> impl Iterator<Item = String> {
+    reqif_id
+        .map(str::to_owned)
+        .into_iter()
+        .chain(std::iter::once(format!("req1-{id}")))
+}
+
+#[allow(clippy::too_many_lines)]
+async fn run_merge(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    user: &AuthUser,
+    module_id: Uuid,
+    doc: &ReqIf,
+    on_conflict: ConflictResolution,
+    preview: bool,
+) -> Result<MergeResult, CoreError> {
+    let module = module::Entity::find_by_id(module_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("module {module_id} not found")))?;
+    let profile = ExchangeProfile::for_module(&module)?;
+
+    let content = &doc.core_content.req_if_content;
+    let specs = content
+        .specifications
+        .as_ref()
+        .map_or(&[][..], |s| s.specifications.as_slice());
+    let spec = find_specification(specs, &module).ok_or_else(|| {
+        CoreError::bad_request(format!(
+            "ReqIF document has no specification matching module {module_id}"
+        ))
+    })?;
+
+    let lookup = AttrLookup::new(content);
+    let spec_types: &[SpecType] = content
+        .spec_types
+        .as_ref()
+        .map_or(&[], |s| s.types.as_slice());
+    let spec_relations = content
+        .spec_relations
+        .as_ref()
+        .map_or(&[][..], |r| r.relations.as_slice());
+
+    // Objects belonging to this specification; with a single specification,
+    // everything in the document
+    let mut in_hierarchy = HashSet::new();
+    if let Some(children) = &spec.children {
+        collect_hierarchy(children, &mut in_hierarchy);
+    }
+    let spec_objects: Vec<&SpecObject> = content
+        .spec_objects
+        .as_ref()
+        .map_or(&[][..], |s| s.objects.as_slice())
+        .iter()
+        .filter(|so| specs.len() == 1 || in_hierarchy.contains(so.identifier.as_str()))
+        .collect();
+
+    let now = chrono::Utc::now().fixed_offset();
+    let mut result = MergeResult {
+        module_id,
+        ..Default::default()
+    };
+
+    // Attribute definitions missing from the module, by name
+    let mut attr_names: HashSet<String> = attribute_definition::Entity::find()
+        .filter(
+            Condition::any()
+                .add(attribute_definition::Column::ModuleId.eq(module_id))
+                .add(attribute_definition::Column::ModuleId.is_null()),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|ad| ad.name)
+        .collect();
+    for st in spec_types {
+        if let SpecType::SpecObjectType(sot) = st
+            && let Some(attrs) = &sot.spec_attributes
+        {
+            for ad in &attrs.definitions {
+                let (_, ad_name, _) = extract_attr_def_info(ad);
+                if attr_names.insert(ad_name.to_owned()) {
+                    let _ = new_attribute_definition(ad, &lookup, module_id, now)
+                        .insert(db)
+                        .await?;
+                    result.attribute_definitions_created += 1;
+                }
+            }
+        }
+    }
+
+    // SpecObjectType → object type, by exported id or name
+    let mut types = object_type::Entity::find()
+        .filter(object_type::Column::ModuleId.eq(module_id))
+        .all(db)
+        .await?;
+    let mut type_ids: HashMap<&str, Uuid> = HashMap::new();
+    for st in spec_types {
+        let SpecType::SpecObjectType(sot) = st else {
+            continue;
+        };
+        let name = sot.long_name.as_deref().unwrap_or(&sot.identifier);
+        let existing = types
+            .iter()
+            .find(|t| format!("req1-{}", t.id) == sot.identifier)
+            .or_else(|| types.iter().find(|t| t.name == name));
+        let id = if let Some(t) = existing {
+            t.id
+        } else {
+            let created = ObjectTypeService::create(
+                db,
+                ctx,
+                CreateObjectTypeInput {
+                    module_id,
+                    name: name.to_owned(),
+                    description: sot.desc.clone(),
+                    default_classification: None,
+                    required_attributes: None,
+                    attribute_schema: None,
+                    fingerprint_policy: None,
+                },
+            )
+            .await?;
+            result.object_types_created += 1;
+            let id = created.id;
+            types.push(created);
+            id
+        };
+        let _ = type_ids.insert(sot.identifier.as_str(), id);
+    }
+
+    // SpecRelationType → link type, by exported id or name. Link types are a
+    // global catalog, so only admins add missing ones; otherwise their
+    // relations are skipped
+    let may_create_link_types = PermissionService::require_admin(user).is_ok();
+    let mut link_types = link_type::Entity::find().all(db).await?;
+    let mut link_type_ids: HashMap<&str, Uuid> = HashMap::new();
+    for st in spec_types {
+        let SpecType::SpecRelationType(srt) = st else {
+            continue;
+        };
+        let name = srt.long_name.as_deref().unwrap_or(&srt.identifier);
+        let existing = link_types
+            .iter()
+            .find(|lt| format!("req1-{}", lt.id) == srt.identifier)
+            .or_else(|| {
+                link_types
+                    .iter()
+                    .find(|lt| lt.name.eq_ignore_ascii_case(name))
+            });
+        let id = if let Some(lt) = existing {
+            lt.id
+        } else if !may_create_link_types {
+            continue;
+        } else {
+            let created = LinkService::create_link_type(
+                db,
+                ctx,
+                CreateLinkTypeInput {
+                    name: name.to_owned(),
+                    description: srt.desc.clone(),
+                    suspect_direction: None,
+                    suspect_cascade: None,
+                    suspect_skip_informative: None,
+                    inverse_name: None,
+                    rules: None,
+                },
+            )
+            .await?;
+            result.link_types_created += 1;
+            let id = created.id;
+            link_types.push(created);
+            id
+        };
+        let _ = link_type_ids.insert(srt.identifier.as_str(), id);
+    }
+
+    // Plan object changes before writing anything, so conflicts can abort early
+    let existing = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .filter(object::Column::DeletedAt.is_null())
+        .all(db)
+        .await?;
+    let mut by_key: HashMap<String, &object::Model> = HashMap::new();
+    for obj in &existing {
+        for key in match_keys(obj.reqif_id.as_deref(), obj.id) {
+            let _ = by_key.entry(key).or_insert(obj);
+        }
+    }
+
+    let mut planned: Vec<Planned> = Vec::new();
+    let mut to_create: Vec<(&SpecObject, Option<Value>)> = Vec::new();
+    let mut matched: HashSet<Uuid> = HashSet::new();
+    let mut conflicts: Vec<MergeConflict> = Vec::new();
+    let mut violations: Vec<MergeConflict> = Vec::new();
+    for so in &spec_objects {
+        let incoming_attrs = build_attributes_json(so, &lookup);
+        let Some(obj) = by_key.get(&so.identifier).copied() else {
+            // New objects keep only the attributes the partner owns
+            let owned = incoming_attrs.and_then(|a| match a {
+                Value::Object(mut map) => {
+                    map.retain(|k, _| profile.accepts(k));
+                    (!map.is_empty()).then_some(Value::Object(map))
+                }
+                other => Some(other),
+            });
+            to_create.push((so, owned));
+            continue;
+        };
+        if !matched.insert(obj.id) {
+            continue;
+        }
+        // Without a baseline (an object exported but never imported) the
+        // document wins wherever it carries a value
+        let base = obj.reqif_base.as_ref();
+        let local_heading = obj.heading.clone().map(Value::String);
+        let incoming_heading = so.long_name.clone().map(Value::String);
+        let base_heading = match base {
+            Some(b) => b.get("heading"),
+            None => local_heading.as_ref(),
+        };
+        let violation = |field: String,
+                         base: Option<&Value>,
+                         local: Option<&Value>,
+                         incoming: Option<&Value>| MergeConflict {
+            object_id: obj.id,
+            reqif_id: so.identifier.clone(),
+            heading: obj.heading.clone(),
+            field,
+            kind: "not_owned".to_owned(),
+            base: base.cloned(),
+            local: local.cloned(),
+            incoming: incoming.cloned(),
+        };
+        let heading = match pick(
+            base_heading,
+            local_heading.as_ref(),
+            incoming_heading.as_ref(),
+        ) {
+            Pick::Local => obj.heading.clone(),
+            _ if !profile.accepts(HEADING) => {
+                violations.push(violation(
+                    HEADING.to_owned(),
+                    base_heading,
+                    local_heading.as_ref(),
+                    incoming_heading.as_ref(),
+                ));
+                obj.heading.clone()
+            }
+            Pick::Incoming => so.long_name.clone(),
+            Pick::Conflict => {
+                conflicts.push(MergeConflict {
+                    object_id: obj.id,
+                    reqif_id: so.identifier.clone(),
+                    heading: obj.heading.clone(),
+                    field: "heading".to_owned(),
+                    kind: "modified".to_owned(),
+                    base: base_heading.cloned(),
+                    local: local_heading.clone(),
+                    incoming: incoming_heading.clone(),
+                });
+                if on_conflict == ConflictResolution::TakeIncoming {
+                    so.long_name.clone()
+                } else {
+                    obj.heading.clone()
+                }
+            }
+        };
+
+        let empty = serde_json::Map::new();
+        let local_map = obj
+            .attributes
+            .as_ref()
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let incoming_map = incoming_attrs
+            .as_ref()
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let base_map = base.map(|b| {
+            b.get("attributes")
+                .and_then(Value::as_object)
+                .unwrap_or(&empty)
+        });
+        let keys: BTreeSet<&String> = local_map
+            .keys()
+            .chain(incoming_map.keys())
+            .chain(base_map.into_iter().flat_map(serde_json::Map::keys))
+            .collect();
+        let mut attributes = local_map.clone();
+        for key in keys {
+            let local = local_map.get(key);
+            let incoming = incoming_map.get(key);
+            let base = match base_map {
+                Some(m) => m.get(key),
+                None if incoming.is_some() => local,
+                None => None,
+            };
+            let take_incoming = match pick(base, local, incoming) {
+                Pick::Local => false,
+                // Attributes never shared are not expected back
+                _ if !profile.accepts(key) && !profile.exports(key) => false,
+                _ if !profile.accepts(key) => {
+                    violations.push(violation(
+                        format!("attributes.{key}"),
+                        base,
+                        local,
+                        incoming,
+                    ));
+                    false
+                }
+                Pick::Incoming => true,
+                Pick::Conflict => {
+                    conflicts.push(MergeConflict {
+                        object_id: obj.id,
+                        reqif_id: so.identifier.clone(),
+                        heading: obj.heading.clone(),
+                        field: format!("attributes.{key}"),
+                        kind: "modified".to_owned(),
+                        base: base.cloned(),
+                        local: local.cloned(),
+                        incoming: incoming.cloned(),
+                    });
+                    on_conflict == ConflictResolution::TakeIncoming
+                }
+            };
+            if take_incoming {
+                match incoming {
+                    Some(v) => {
+                        let _ = attributes.insert(key.clone(), v.clone());
+                    }
+                    None => {
+                        let _ = attributes.remove(key);
+                    }
+                }
+            }
+        }
+
+        planned.push(Planned {
+            existing: obj.clone(),
+            reqif_id: so.identifier.clone(),
+            heading,
+            attributes: (!attributes.is_empty()).then_some(Value::Object(attributes)),
+            object_type_id: obj
+                .object_type_id
+                .or_else(|| type_ids.get(so.type_ref.value.as_str()).copied()),
+            base: baseline(so.long_name.as_deref(), incoming_attrs.as_ref()),
+        });
+    }
+
+    // Previously exchanged objects the document no longer contains
+    let mut to_delete = Vec::new();
+    for obj in &existing {
+        if obj.reqif_id.is_none() || matched.contains(&obj.id) {
+            continue;
+        }
+        let edited = obj
+            .reqif_base
+            .as_ref()
+            .is_some_and(|b| *b != baseline(obj.heading.as_deref(), obj.attributes.as_ref()));
+        if edited {
+            conflicts.push(MergeConflict {
+                object_id: obj.id,
+                reqif_id: obj.reqif_id.clone().unwrap_or_default(),
+                heading: obj.heading.clone(),
+                field: "object".to_owned(),
+                kind: "deleted".to_owned(),
+                base: obj.reqif_base.clone(),
+                local: Some(baseline(obj.heading.as_deref(), obj.attributes.as_ref())),
+                incoming: None,
+            });
+            if on_conflict != ConflictResolution::TakeIncoming {
+                continue;
+            }
+        }
+        to_delete.push(obj.id);
+    }
+
+    if !violations.is_empty() && !preview && profile.on_violation == OwnershipViolation::Reject {
+        return Err(CoreError::forbidden(format!(
+            "ReqIF document changes {} field(s) the exchange partner does not own: {}",
+            violations.len(),
+            violations
+                .iter()
+                .map(|v| format!("{} {}", v.reqif_id, v.field))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    result.ownership_violations = violations;
+
+    if on_conflict == ConflictResolution::Fail && !conflicts.is_empty() {
+        return Err(CoreError::conflict(format!(
+            "ReqIF merge has {} conflict(s) with local edits; preview the merge and retry with on_conflict=keep_local or take_incoming",
+            conflicts.len()
+        )));
+    }
+    result.conflicts = conflicts;
+
+    // Apply object changes
+    let mut id_map: HashMap<String, Uuid> = HashMap::new();
+    for plan in planned {
+        let _ = id_map.insert(plan.reqif_id.clone(), plan.existing.id);
+        if update_object(db, ctx, plan).await? {
+            result.objects_updated += 1;
+        } else {
+            result.objects_unchanged += 1;
+        }
+    }
+    for (so, attributes) in to_create {
+        let id = create_object(
+            db,
+            ctx,
+            module_id,
+            so,
+            attributes,
+            type_ids.get(so.type_ref.value.as_str()).copied(),
+        )
+        .await?;
+        let _ = id_map.insert(so.identifier.clone(), id);
+        result.objects_created += 1;
+    }
+    for id in to_delete {
+        let _ = ObjectService::soft_delete(db, ctx, id).await?;
+        result.objects_deleted += 1;
+    }
+
+    if let Some(children) = &spec.children {
+        walk_hierarchy(db, children, None, &id_map, now).await?;
+    }
+    level::recompute_module_levels(db, module_id).await?;
+
+    merge_links(
+        db,
+        ctx,
+        user,
+        module_id,
+        spec_relations,
+        &id_map,
+        &link_type_ids,
+        &mut result,
+    )
+    .await?;
+
+    if module.reqif_id.as_deref() != Some(spec.identifier.as_str()) {
+        let update = module::ActiveModel {
+            id: Set(module_id),
+            reqif_id: Set(Some(spec.identifier.clone())),
+            ..Default::default()
+        };
+        let _ = update.update(db).await?;
+    }
+
+    AuditService::event(
+        db,
+        ctx,
+        "merge",
+        "module",
+        module_id,
+        json!({
+            "format": "reqif",
+            "specification": spec.identifier,
+            "objects_created": result.objects_created,
+            "objects_updated": result.objects_updated,
+            "objects_deleted": result.objects_deleted,
+            "links_created": result.links_created,
+            "links_deleted": result.links_deleted,
+            "conflicts": result.conflicts.len(),
+            "ownership_violations": result.ownership_violations.len(),
+            "on_conflict": format!("{on_conflict:?}"),
+        }),
+    )
+    .await?;
+
+    Ok(result)
+}
+
+/// Write merged content to an object through [`ObjectService::update`], so it is
+/// validated, runs triggers, flags suspect links and records history like any
+/// other edit. Returns whether the content changed.
+///
+/// The exchange identifier and baseline are bookkeeping and written separately.
+/// A heading the document dropped is kept, as an update cannot clear one.
+async fn update_object(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    plan: Planned,
+) -> Result<bool, CoreError> {
+    let existing = plan.existing;
+    let heading = plan
+        .heading
+        .filter(|h| existing.heading.as_ref() != Some(h));
+    // Cleared attributes are stored as an empty map, which plans as `None`
+    let current = existing
+        .attributes
+        .clone()
+        .filter(|a| a.as_object().is_none_or(|m| !m.is_empty()));
+    let attributes =
+        (plan.attributes != current).then(|| plan.attributes.unwrap_or_else(|| json!({})));
+    let object_type_id = plan
+        .object_type_id
+        .filter(|t| existing.object_type_id != Some(*t));
+    let changed = heading.is_some() || attributes.is_some() || object_type_id.is_some();
+
+    if changed {
+        let _ = ObjectService::update(
+            db,
+            ctx,
+            existing.id,
+            UpdateObjectInput {
+                parent_id: None,
+                position: None,
+                heading,
+                body: None,
+                attributes,
+                reviewed: None,
+                classification: None,
+                references: None,
+                object_type_id,
+                expected_version: Some(existing.current_version),
+                lifecycle_state: None,
+                signature: None,
+            },
+        )
+        .await?;
+    }
+
+    let update = object::ActiveModel {
+        id: Set(existing.id),
+        reqif_id: Set(Some(plan.reqif_id)),
+        reqif_base: Set(Some(plan.base)),
+        ..Default::default()
+    };
+    let _ = update.update(db).await?;
+    Ok(changed)
+}
+
+async fn create_object(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    module_id: Uuid,
+    so: &SpecObject,
+    attributes: Option<Value>,
+    object_type_id: Option<Uuid>,
+) -> Result<Uuid, CoreError> {
+    let id = Uuid::now_v7();
+    let now = chrono::Utc::now().fixed_offset();
+    let heading = so.long_name.clone();
+    let policy = FingerprintPolicy::resolve(db, module_id, object_type_id).await?;
+    let fp = compute_content_fingerprint(heading.as_deref(), None, attributes.as_ref(), &policy);
+
+    let model = object::ActiveModel {
+        id: Set(id),
+        module_id: Set(module_id),
+        parent_id: Set(None),
+        position: Set(0),
+        level: Set("0".to_owned()),
+        heading: Set(heading.clone()),
+        body: Set(None),
+        attributes: Set(attributes.clone()),
+        current_version: Set(1),
+        classification: Set("normative".to_owned()),
+        content_fingerprint: Set(fp),
+        reviewed_fingerprint: Set(None),
+        reviewed_at: Set(None),
+        reviewed_by: Set(None),
+        references_: Set(json!([])),
+        object_type_id: Set(object_type_id),
+        lifecycle_state: Set(None),
+        lifecycle_model_id: Set(None),
+        source_object_id: Set(None),
+        source_module_id: Set(None),
+        is_placeholder: Set(false),
+        docx_source_id: Set(None),
+        deleted_at: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+        reqif_id: Set(Some(so.identifier.clone())),
+        reqif_base: Set(Some(baseline(heading.as_deref(), attributes.as_ref()))),
+    };
+    let created = model.insert(db).await?;
+
+    history::insert_history(
+        db,
+        ctx,
+        HistoryEntry {
+            object_id: id,
+            module_id,
+            version: 1,
+            attribute_values: attributes,
+            heading,
+            body: None,
+            change_type: "create".to_owned(),
+            state: None,
+        },
+    )
+    .await?;
+    AuditService::record::<object::Entity>(db, ctx, "create", id, None, Some(&created)).await?;
+    Ok(id)
+}
+
+/// Reconcile the module's links with the document's relations: relations are
+/// matched by identifier (or by endpoints and type), links whose endpoints or
+/// type changed are recreated, and previously exchanged outgoing links the
+/// document dropped are deleted. Relations `user` may not link, as for
+/// `POST /links`, are skipped.
+#[allow(clippy::too_many_arguments)]
+async fn merge_links(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    user: &AuthUser,
+    module_id: Uuid,
+    relations: &[req1_reqif::SpecRelation],
+    id_map: &HashMap<String, Uuid>,
+    link_type_ids: &HashMap<&str, Uuid>,
+    result: &mut MergeResult,
+) -> Result<(), CoreError> {
+    let module_objects: HashSet<Uuid> = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|o| o.id)
+        .collect();
+    let existing: Vec<link::Model> = if module_objects.is_empty() {
+        Vec::new()
+    } else {
+        link::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(link::Column::SourceObjectId.is_in(module_objects.iter().copied()))
+                    .add(link::Column::TargetObjectId.is_in(module_objects.iter().copied())),
+            )
+            .all(db)
+            .await?
+    };
+    let mut by_key: HashMap<String, &link::Model> = HashMap::new();
+    for l in &existing {
+        for key in match_keys(l.reqif_id.as_deref(), l.id) {
+            let _ = by_key.entry(key).or_insert(l);
+        }
+    }
+
+    // Module of every object at either end of a link, for permission checks
+    let mut module_of: HashMap<Uuid, Uuid> =
+        module_objects.iter().map(|&id| (id, module_id)).collect();
+    let foreign_ends: HashSet<Uuid> = existing
+        .iter()
+        .flat_map(|l| [l.source_object_id, l.target_object_id])
+        .filter(|id| !module_objects.contains(id))
+        .collect();
+    if !foreign_ends.is_empty() {
+        let rows: Vec<(Uuid, Uuid)> = object::Entity::find()
+            .select_only()
+            .column(object::Column::Id)
+            .column(object::Column::ModuleId)
+            .filter(object::Column::Id.is_in(foreign_ends))
+            .into_tuple()
+            .all(db)
+            .await?;
+        module_of.extend(rows);
+    }
+    let mut access = LinkAccess {
+        user,
+        granted: HashMap::new(),
+    };
+
+    // Endpoints outside the document may still be referenced by exported id
+    let mut resolved: HashMap<&str, Option<Uuid>> = HashMap::new();
+    for r in relations {
+        for end in [&r.source.spec_object_ref, &r.target.spec_object_ref] {
+            if id_map.contains_key(end) || resolved.contains_key(end.as_str()) {
+                continue;
+            }
+            let foreign = match end
+                .strip_prefix("req1-")
+                .and_then(|s| s.parse::<Uuid>().ok())
+            {
+                Some(id) => object::Entity::find_by_id(id)
+                    .filter(object::Column::DeletedAt.is_null())
+                    .one(db)
+                    .await?
+                    .map(|o| {
+                        let _ = module_of.insert(o.id, o.module_id);
+                        o.id
+                    }),
+                None => None,
+            };
+            let _ = resolved.insert(end.as_str(), foreign);
+        }
+    }
+    let endpoint = |r: &str| id_map.get(r).copied().or_else(|| resolved.get(r).copied()?);
+
+    let mut kept: HashSet<Uuid> = HashSet::new();
+    for r in relations {
+        let source = endpoint(&r.source.spec_object_ref);
+        let target = endpoint(&r.target.spec_object_ref);
+        let link_type_id = link_type_ids.get(r.type_ref.value.as_str()).copied();
+        let (Some(source), Some(target), Some(link_type_id)) = (source, target, link_type_id)
+        else {
+            // Relations between objects of other specifications are not ours
+            if source.is_some() || target.is_some() {
+                result.links_skipped += 1;
+            }
+            continue;
+        };
+
+        let current = by_key
+            .get(&r.identifier)
+            .copied()
+            .filter(|l| !kept.contains(&l.id));
+        // Replacing a link deletes it, which needs write access to its source too
+        let allowed = access.may_link(db, &module_of, source, target).await?
+            && match current {
+                Some(l) if l.source_object_id != source => {
+                    access
+                        .allows(db, &module_of, l.source_object_id, Permission::Write)
+                        .await?
+                }
+                _ => true,
+            };
+        if !allowed {
+            if let Some(l) = current {
+                let _ = kept.insert(l.id);
+            }
+            result.links_skipped += 1;
+            continue;
+        }
+
+        let same = |l: &link::Model| {
+            l.source_object_id == source
+                && l.target_object_id == target
+                && l.link_type_id == link_type_id
+        };
+        if let Some(l) = current.filter(|l| same(l)) {
+            let _ = kept.insert(l.id);
+            set_link_reqif_id(db, l, &r.identifier).await?;
+            result.links_unchanged += 1;
+            continue;
+        }
+        if let Some(l) = current {
+            LinkService::delete(db, ctx, l.id).await?;
+            result.links_deleted += 1;
+            let _ = kept.insert(l.id);
+        } else if let Some(l) = existing.iter().find(|l| same(l) && !kept.contains(&l.id)) {
+            let _ = kept.insert(l.id);
+            set_link_reqif_id(db, l, &r.identifier).await?;
+            result.links_unchanged += 1;
+            continue;
+        }
+
+        let created = LinkService::create(
+            db,
+            ctx,
+            CreateLinkInput {
+                source_object_id: source,
+                target_object_id: target,
+                link_type_id,
+                attributes: None,
+            },
+        )
+        .await?;
+        set_link_reqif_id(db, &created, &r.identifier).await?;
+        let _ = kept.insert(created.id);
+        result.links_created += 1;
+    }
+
+    // Only links this module owns; incoming links belong to their source's exchange
+    for l in &existing {
+        if l.reqif_id.is_some()
+            && !kept.contains(&l.id)
+            && module_objects.contains(&l.source_object_id)
+        {
+            LinkService::delete(db, ctx, l.id).await?;
+            result.links_deleted += 1;
+        }
+    }
+    Ok(())
+}
+
+/// The merging user's access to the modules at the ends of relations, looked
+/// up once per module.
+struct LinkAccess<'a> {
+    user: &'a AuthUser,
+    granted: HashMap<Uuid, Option<Permission>>,
+}
+
+impl LinkAccess<'_> {
+    async fn allows(
+        &mut self,
+        db: &impl ConnectionTrait,
+        module_of: &HashMap<Uuid, Uuid>,
+        object_id: Uuid,
+        needed: Permission,
+    ) -> Result<bool, CoreError> {
+        let Some(&module_id) = module_of.get(&object_id) else {
+            return Ok(false);
+        };
+        let granted = match self.granted.get(&module_id) {
+            Some(granted) => *granted,
+            None => {
+                let granted =
+                    PermissionService::module_permission(db, self.user, module_id).await?;
+                let _ = self.granted.insert(module_id, granted);
+                granted
+            }
+        };
+        Ok(granted.is_some_and(|p| p >= needed))
+    }
+
+    /// Write access to the source's module and read access to the target's.
+    async fn may_link(
+        &mut self,
+        db: &impl ConnectionTrait,
+        module_of: &HashMap<Uuid, Uuid>,
+        source: Uuid,
+        target: Uuid,
+    ) -> Result<bool, CoreError> {
+        Ok(self
+            .allows(db, module_of, source, Permission::Write)
+            .await?
+            && self.allows(db, module_of, target, Permission::Read).await?)
+    }
+}
+
+async fn set_link_reqif_id(
+    db: &impl ConnectionTrait,
+    l: &link::Model,
+    reqif_id: &str,
+) -> Result<(), CoreError> {
+    if l.reqif_id.as_deref() == Some(reqif_id) {
+        return Ok(());
+    }
+    let update = link::ActiveModel {
+        id: Set(l.id),
+        reqif_id: Set(Some(reqif_id.to_owned())),
+        ..Default::default()
+    };
+    let _ = update.update(db).await?;
+    Ok(())
+}
@@ -1,7 +1,11 @@
 pub mod export;
 pub mod import;
+pub mod merge;
+pub mod profile;
 mod type_map;
 
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 /// Result of a `ReqIF` import operation.
@@ -22,3 +26,57 @@ pub struct ExportResult {
     pub objects_exported: usize,
     pub links_exported: usize,
 }
+
+/// How a `ReqIF` merge settles fields that changed both locally and in the
+/// incoming document since the last exchange.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Abort the merge if there is any conflict.
+    #[default]
+    Fail,
+    KeepLocal,
+    TakeIncoming,
+}
+
+/// A local edit that the incoming document contradicts, or an incoming change
+/// the exchange profile does not accept.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MergeConflict {
+    pub object_id: Uuid,
+    pub reqif_id: String,
+    pub heading: Option<String>,
+    /// `heading`, `attributes.<name>`, or `object` for a removed object.
+    pub field: String,
+    /// `modified` when both sides changed the field, `deleted` when the document
+    /// removed an object that was edited locally, `not_owned` when the document
+    /// changed a field its exchange profile does not let the partner change.
+    pub kind: String,
+    #[schema(value_type = Option<Object>)]
+    pub base: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
+    pub local: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
+    pub incoming: Option<serde_json::Value>,
+}
+
+/// Result of merging a `ReqIF` document into an existing module, or of
+/// previewing that merge.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct MergeResult {
+    pub module_id: Uuid,
+    pub objects_created: usize,
+    pub objects_updated: usize,
+    pub objects_unchanged: usize,
+    pub objects_deleted: usize,
+    pub links_created: usize,
+    pub links_deleted: usize,
+    pub links_unchanged: usize,
+    pub links_skipped: usize,
+    pub attribute_definitions_created: usize,
+    pub object_types_created: usize,
+    pub link_types_created: usize,
+    pub conflicts: Vec<MergeConflict>,
+    /// Changes to fields the partner does not own, which were not applied.
+    pub ownership_violations: Vec<MergeConflict>,
+}
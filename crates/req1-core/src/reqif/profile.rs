@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use entity::module;
+
+use crate::error::CoreError;
+
+/// Field name that stands for the object heading in [`ExchangeProfile::accept`].
+pub const HEADING: &str = "heading";
+
+/// What a merge does with a change to a field the exchange partner does not own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnershipViolation {
+    /// Abort the merge.
+    #[default]
+    Reject,
+    /// Keep the local value and list the change in the merge result.
+    Report,
+}
+
+/// Which attributes a module shares with its `ReqIF` exchange partner, e.g. an
+/// OEM exporting its requirements and taking back only the supplier's status
+/// and comment attributes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ExchangeProfile {
+    /// Attributes written to exports; all attributes when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<Vec<String>>,
+    /// Fields the partner owns and may change on re-import: attribute names, and
+    /// `heading` for the object heading. Everything when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept: Option<Vec<String>>,
+    #[serde(default)]
+    pub on_violation: OwnershipViolation,
+}
+
+impl ExchangeProfile {
+    /// Parse a stored profile; `null` means no profile.
+    pub fn from_json(value: Option<&serde_json::Value>) -> Result<Option<Self>, CoreError> {
+        match value {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(v) => serde_json::from_value(v.clone())
+                .map(Some)
+                .map_err(|e| CoreError::bad_request(format!("invalid reqif_profile: {e}"))),
+        }
+    }
+
+    /// Stored form of a profile; the default (everything shared) is stored as `null`.
+    pub fn to_json(&self) -> Option<serde_json::Value> {
+        (*self != Self::default()).then(|| serde_json::json!(self))
+    }
+
+    /// The module's profile, or the default when it has none.
+    pub fn for_module(module: &module::Model) -> Result<Self, CoreError> {
+        Ok(Self::from_json(module.reqif_profile.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn exports(&self, attribute: &str) -> bool {
+        self.export
+            .as_ref()
+            .is_none_or(|names| names.iter().any(|n| n == attribute))
+    }
+
+    pub fn accepts(&self, field: &str) -> bool {
+        self.accept
+            .as_ref()
+            .is_none_or(|names| names.iter().any(|n| n == field))
+    }
+}
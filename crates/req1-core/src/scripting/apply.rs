@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use entity::{link_type, object};
+
+use crate::error::CoreError;
+use crate::service::audit::AuditContext;
+use crate::service::link::{CreateLinkInput, LinkService};
+use crate::service::object::{
+    CreateObjectInput, MoveObjectInput, ObjectService, UpdateObjectInput,
+};
+
+use super::engine::Mutation;
+use super::world::WorldAccess;
+
+/// Field edits to one object, written as a single new version.
+#[derive(Default)]
+struct FieldEdits {
+    heading: Option<String>,
+    body: Option<String>,
+    attributes: Map<String, Value>,
+}
+
+/// Apply the mutations of an action script to its module, in order, through
+/// the object and link services so validation, triggers and history work as
+/// for API edits. Field edits are batched per object until the next structural
+/// change. Links to other modules need the same access as through the API,
+/// checked with `access`. Callers run this inside a transaction.
+pub async fn apply_mutations<C: ConnectionTrait>(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    access: &WorldAccess<'_, C>,
+    module_id: Uuid,
+    mutations: &[Mutation],
+) -> Result<(), CoreError> {
+    // Provisional ids handed out by `req1.create` -> ids of the created objects
+    let mut created: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut pending: Vec<(Uuid, FieldEdits)> = Vec::new();
+
+    for m in mutations {
+        if m.is_field_edit() {
+            stage(&mut pending, &created, m);
+            continue;
+        }
+        flush(db, ctx, module_id, &mut pending).await?;
+
+        match m {
+            Mutation::Create {
+                object_id,
+                parent_id,
+                position,
+                heading,
+                body,
+                attributes,
+            } => {
+                let parent_id = parent_id.map(|p| resolve(&created, p));
+                let position = match position {
+                    Some(p) => *p,
+                    None => append_position(db, module_id, parent_id).await?,
+                };
+                let obj = ObjectService::create(
+                    db,
+                    ctx,
+                    CreateObjectInput {
+                        module_id,
+                        parent_id,
+                        position: Some(position),
+                        heading: heading.clone(),
+                        body: body.clone(),
+                        attributes: attributes.clone(),
+                        classification: None,
+                        references: None,
+                        object_type_id: None,
+                        lifecycle_state: None,
+                        lifecycle_model_id: None,
+                        source_object_id: None,
+                        source_module_id: None,
+                        is_placeholder: None,
+                    },
+                )
+                .await?;
+                let _ = created.insert(*object_id, obj.id);
+            }
+            Mutation::Move {
+                object_id,
+                parent_id,
+                position,
+            } => {
+                let parent_id = parent_id.map(|p| resolve(&created, p));
+                let position = match position {
+                    Some(p) => *p,
+                    None => append_position(db, module_id, parent_id).await?,
+                };
+                let _ = ObjectService::move_object(
+                    db,
+                    ctx,
+                    module_id,
+                    resolve(&created, *object_id),
+                    MoveObjectInput::MoveTo {
+                        parent_id,
+                        position,
+                    },
+                )
+                .await?;
+            }
+            Mutation::Link {
+                source_object_id,
+                target_object_id,
+                link_type,
+            } => {
+                let source = resolve(&created, *source_object_id);
+                let target = resolve(&created, *target_object_id);
+                require_in_module(db, module_id, &[source, target]).await?;
+                require_link_access(db, access, source, target).await?;
+                let _ = LinkService::create(
+                    db,
+                    ctx,
+                    CreateLinkInput {
+                        source_object_id: source,
+                        target_object_id: target,
+                        link_type_id: find_link_type(db, link_type).await?,
+                        attributes: None,
+                    },
+                )
+                .await?;
+            }
+            Mutation::Unlink { link_id } => {
+                let l = LinkService::get(db, *link_id).await?;
+                require_in_module(db, module_id, &[l.source_object_id, l.target_object_id]).await?;
+                require_link_access(db, access, l.source_object_id, l.target_object_id).await?;
+                LinkService::delete(db, ctx, *link_id).await?;
+            }
+            Mutation::Delete { object_id } => {
+                let id = resolve(&created, *object_id);
+                require_in_module(db, module_id, &[id]).await?;
+                ObjectService::delete(db, ctx, id).await?;
+            }
+            Mutation::SetAttribute { .. }
+            | Mutation::SetHeading { .. }
+            | Mutation::SetBody { .. } => {}
+        }
+    }
+
+    flush(db, ctx, module_id, &mut pending).await
+}
+
+fn resolve(created: &HashMap<Uuid, Uuid>, id: Uuid) -> Uuid {
+    created.get(&id).copied().unwrap_or(id)
+}
+
+fn stage(pending: &mut Vec<(Uuid, FieldEdits)>, created: &HashMap<Uuid, Uuid>, m: &Mutation) {
+    let (Mutation::SetAttribute { object_id, .. }
+    | Mutation::SetHeading { object_id, .. }
+    | Mutation::SetBody { object_id, .. }) = m
+    else {
+        return;
+    };
+    let id = resolve(created, *object_id);
+    let idx = pending
+        .iter()
+        .position(|(p, _)| *p == id)
+        .unwrap_or_else(|| {
+            pending.push((id, FieldEdits::default()));
+            pending.len() - 1
+        });
+    let edits = &mut pending[idx].1;
+    match m {
+        Mutation::SetAttribute { key, value, .. } => {
+            let _ = edits.attributes.insert(key.clone(), value.clone());
+        }
+        Mutation::SetHeading { heading, .. } => edits.heading = Some(heading.clone()),
+        Mutation::SetBody { body, .. } => edits.body = Some(body.clone()),
+        _ => {}
+    }
+}
+
+/// Write the staged field edits, one update per object.
+async fn flush(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    module_id: Uuid,
+    pending: &mut Vec<(Uuid, FieldEdits)>,
+) -> Result<(), CoreError> {
+    for (id, edits) in pending.drain(..) {
+        let obj = ObjectService::get(db, id).await?;
+        if obj.module_id != module_id {
+            return Err(CoreError::bad_request(format!(
+                "object {id} is not in module {module_id}"
+            )));
+        }
+        // Updates replace the attribute map, so merge the edits into it first
+        let attributes = (!edits.attributes.is_empty()).then(|| {
+            let mut attrs = obj.attributes.unwrap_or_else(|| Value::Object(Map::new()));
+            if let Some(map) = attrs.as_object_mut() {
+                map.extend(edits.attributes);
+            }
+            attrs
+        });
+        let _ = ObjectService::update(
+            db,
+            ctx,
+            id,
+            UpdateObjectInput {
+                parent_id: None,
+                position: None,
+                heading: edits.heading,
+                body: edits.body,
+                attributes,
+                reviewed: None,
+                classification: None,
+                references: None,
+                object_type_id: None,
+                expected_version: None,
+                lifecycle_state: None,
+                signature: None,
+            },
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Scripts may only touch links and objects of their own module; a link counts
+/// when either end is in it.
+async fn require_in_module(
+    db: &impl ConnectionTrait,
+    module_id: Uuid,
+    object_ids: &[Uuid],
+) -> Result<(), CoreError> {
+    let n = object::Entity::find()
+        .filter(object::Column::Id.is_in(object_ids.iter().copied()))
+        .filter(object::Column::ModuleId.eq(module_id))
+        .count(db)
+        .await?;
+    if n == 0 {
+        return Err(CoreError::bad_request(format!(
+            "object {} is not in module {module_id}",
+            object_ids[0]
+        )));
+    }
+    Ok(())
+}
+
+/// Like `POST /links`: write access to the source's module and read access to the
+/// target's. One end is in the script's own module, so this guards the other one.
+async fn require_link_access<C: ConnectionTrait>(
+    db: &impl ConnectionTrait,
+    access: &WorldAccess<'_, C>,
+    source: Uuid,
+    target: Uuid,
+) -> Result<(), CoreError> {
+    let source = ObjectService::get(db, source).await?;
+    let target = ObjectService::get(db, target).await?;
+    if !access.can_write(source.module_id).await? {
+        return Err(CoreError::forbidden(format!(
+            "write access to module {} required",
+            source.module_id
+        )));
+    }
+    if !access.can_read(target.module_id).await? {
+        return Err(CoreError::forbidden(format!(
+            "read access to module {} required",
+            target.module_id
+        )));
+    }
+    Ok(())
+}
+
+/// Position after the last child of `parent_id`.
+async fn append_position(
+    db: &impl ConnectionTrait,
+    module_id: Uuid,
+    parent_id: Option<Uuid>,
+) -> Result<i32, CoreError> {
+    let last = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .filter(match parent_id {
+            Some(p) => object::Column::ParentId.eq(p),
+            None => object::Column::ParentId.is_null(),
+        })
+        .filter(object::Column::DeletedAt.is_null())
+        .order_by_desc(object::Column::Position)
+        .one(db)
+        .await?;
+    Ok(last.map_or(0, |o| o.position + 1))
+}
+
+/// Link type by id or name.
+pub(super) async fn find_link_type(
+    db: &impl ConnectionTrait,
+    link_type: &str,
+) -> Result<Uuid, CoreError> {
+    let found = match link_type.parse::<Uuid>() {
+        Ok(id) => link_type::Entity::find_by_id(id).one(db).await?,
+        Err(_) => {
+            link_type::Entity::find()
+                .filter(link_type::Column::Name.eq(link_type))
+                .one(db)
+                .await?
+        }
+    };
+    found
+        .map(|lt| lt.id)
+        .ok_or_else(|| CoreError::bad_request(format!("unknown link type '{link_type}'")))
+}
@@ -1,40 +1,110 @@
-// bootstrap.js — runs before every user script to set up the req1 sandbox.
+// bootstrap.js — sets up the req1 sandbox. Evaluated once into the V8 startup
+// snapshot, so it must not read any per-run state.
 //
-// Reads module/context/obj state from Rust ops and exposes frozen globals.
+// `__req1_init()` runs before every script: it reads module/context/obj state
+// from Rust ops, exposes it as frozen globals and removes `Deno`. Warm runtimes
+// call `__req1_seal()` once a script is compiled and `__req1_reset()` before
+// each later run.
 
 ((globalThis) => {
   const core = Deno.core;
 
-  // --- Module / context / obj globals (frozen) ---
+  // --- Module / context / obj globals (frozen, set per run) ---
 
-  const rawModule = core.ops.op_get_module();
-  if (rawModule) {
-    globalThis.module = Object.freeze(rawModule);
+  function init() {
+    for (const [name, value] of [
+      ["module", core.ops.op_get_module()],
+      ["context", core.ops.op_get_context()],
+      ["obj", core.ops.op_get_obj()],
+    ]) {
+      if (value) {
+        globalThis[name] = Object.freeze(value);
+      } else {
+        delete globalThis[name];
+      }
+    }
+
+    // --- Lock down sandbox ---
+
+    delete globalThis.Deno;
+  }
+
+  Object.defineProperty(globalThis, "__req1_init", { value: init });
+
+  // --- Global reset between the runs of a warm runtime ---
+
+  let sealed = null;
+
+  function seal() {
+    if (sealed === null) {
+      sealed = new Map(
+        Reflect.ownKeys(globalThis).map((key) => [
+          key,
+          Reflect.getOwnPropertyDescriptor(globalThis, key),
+        ]),
+      );
+    }
   }
 
-  const rawContext = core.ops.op_get_context();
-  if (rawContext) {
-    globalThis.context = Object.freeze(rawContext);
+  function same(a, b) {
+    return a !== undefined &&
+      ["value", "get", "set", "writable", "enumerable", "configurable"]
+        .every((field) => Object.is(a[field], b[field]));
   }
 
-  const rawObj = core.ops.op_get_obj();
-  if (rawObj) {
-    globalThis.obj = Object.freeze(rawObj);
+  // Returns false when a global could not be removed or restored
+  function reset() {
+    let clean = true;
+    for (const key of Reflect.ownKeys(globalThis)) {
+      if (!sealed.has(key) && !Reflect.deleteProperty(globalThis, key)) {
+        clean = false;
+      }
+    }
+    for (const [key, descriptor] of sealed) {
+      if (
+        !same(Reflect.getOwnPropertyDescriptor(globalThis, key), descriptor) &&
+        !Reflect.defineProperty(globalThis, key, descriptor)
+      ) {
+        clean = false;
+      }
+    }
+    return clean;
   }
 
+  Object.defineProperty(globalThis, "__req1_seal", { value: seal });
+  Object.defineProperty(globalThis, "__req1_reset", { value: reset });
+
   // --- req1 namespace ---
 
   globalThis.req1 = Object.freeze({
     objects()          { return core.ops.op_objects(); },
     get_object(id)     { return core.ops.op_get_object(id); },
+    module(nameOrId) {
+      const m = core.ops.op_module(String(nameOrId));
+      return Object.freeze({
+        ...m,
+        objects() { return core.ops.op_module_objects(m.id); },
+      });
+    },
+    follow(objectId, linkType, direction) {
+      return core.ops.op_follow(objectId, {
+        link_type: linkType ?? null,
+        direction: direction ?? "out",
+      });
+    },
     links(objectId)    { return core.ops.op_links(objectId ?? null); },
     set(objectId, key, value) { core.ops.op_set(objectId, key, value); },
+    setHeading(objectId, heading) { core.ops.op_set_heading(objectId, String(heading)); },
+    setBody(objectId, body) { core.ops.op_set_body(objectId, String(body)); },
+    create(data)       { return core.ops.op_create(data ?? {}); },
+    move(objectId, parentId, position) {
+      core.ops.op_move(objectId, { parent: parentId ?? null, position: position ?? null });
+    },
+    link(sourceId, targetId, type) { core.ops.op_link(sourceId, targetId, String(type)); },
+    unlink(linkId)     { core.ops.op_unlink(linkId); },
+    delete(objectId)   { core.ops.op_delete(objectId); },
     reject(reason)     { core.ops.op_reject(reason ?? null); },
     log(msg)           { core.ops.op_log(String(msg)); },
     print(msg)         { core.ops.op_script_print(String(msg)); },
   });
-
-  // --- Lock down sandbox ---
-
-  delete globalThis.Deno;
 })(globalThis);
@@ -1,12 +1,62 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex, OnceLock, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use deno_core::{JsRuntime, OpState, RuntimeOptions, op2};
+use deno_core::error::ModuleLoaderError;
+use deno_core::{
+    JsRuntime, JsRuntimeForSnapshot, ModuleLoadResponse, ModuleLoader, ModuleSource,
+    ModuleSourceCode, ModuleSpecifier, ModuleType, OpState, PollEventLoopOptions,
+    RequestedModuleType, ResolutionKind, RuntimeOptions, op2, v8,
+};
+use deno_error::JsErrorBox;
+use sea_orm::ConnectionTrait;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use entity::{link, object};
+
 use crate::error::CoreError;
 
+use super::world::{Direction, WorldAccess, WorldQuery};
+
+// ---------------------------------------------------------------------------
+// Resource limits
+// ---------------------------------------------------------------------------
+
+/// Resource limits applied to every script run.
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptLimits {
+    /// Time a single run may take before V8 terminates it.
+    pub timeout: Duration,
+    /// Maximum V8 heap size in bytes.
+    pub heap_limit: usize,
+    /// Number of threads scripts run on, i.e. how many run at once.
+    pub workers: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            heap_limit: 128 * 1024 * 1024,
+            workers: thread::available_parallelism().map_or(4, usize::from),
+        }
+    }
+}
+
+static LIMITS: OnceLock<ScriptLimits> = OnceLock::new();
+
+fn limits() -> ScriptLimits {
+    *LIMITS.get_or_init(ScriptLimits::default)
+}
+
 // ---------------------------------------------------------------------------
 // Data types exchanged between Rust and JavaScript
 // ---------------------------------------------------------------------------
@@ -24,6 +74,20 @@ pub struct ScriptObject {
     pub version: i32,
 }
 
+impl From<object::Model> for ScriptObject {
+    fn from(o: object::Model) -> Self {
+        Self {
+            id: o.id.to_string(),
+            heading: o.heading,
+            body: o.body,
+            level: Some(o.level),
+            classification: Some(o.classification),
+            attributes: o.attributes,
+            version: o.current_version,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScriptLink {
     pub id: String,
@@ -33,22 +97,82 @@ pub struct ScriptLink {
     pub suspect: bool,
 }
 
+impl From<link::Model> for ScriptLink {
+    fn from(l: link::Model) -> Self {
+        Self {
+            id: l.id.to_string(),
+            source_object_id: l.source_object_id.to_string(),
+            target_object_id: l.target_object_id.to_string(),
+            link_type_id: l.link_type_id.to_string(),
+            suspect: l.suspect,
+        }
+    }
+}
+
 /// Mutations collected during script execution, applied to DB afterwards.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
 pub enum Mutation {
     SetAttribute {
         object_id: Uuid,
         key: String,
         value: serde_json::Value,
     },
+    SetHeading {
+        object_id: Uuid,
+        heading: String,
+    },
+    SetBody {
+        object_id: Uuid,
+        body: String,
+    },
+    /// `object_id` is provisional; later mutations may use it to refer to the
+    /// new object.
+    Create {
+        object_id: Uuid,
+        parent_id: Option<Uuid>,
+        position: Option<i32>,
+        heading: Option<String>,
+        body: Option<String>,
+        attributes: Option<serde_json::Value>,
+    },
+    /// Move under `parent_id` (top level when `None`), appending when `position`
+    /// is omitted.
+    Move {
+        object_id: Uuid,
+        parent_id: Option<Uuid>,
+        position: Option<i32>,
+    },
+    /// `link_type` is a link type id or name.
+    Link {
+        source_object_id: Uuid,
+        target_object_id: Uuid,
+        link_type: String,
+    },
+    Unlink {
+        link_id: Uuid,
+    },
+    Delete {
+        object_id: Uuid,
+    },
 }
 
-/// Execution context loaded before running a script.
+impl Mutation {
+    /// Whether this only changes a field of an object, as opposed to changing
+    /// the module's structure.
+    pub const fn is_field_edit(&self) -> bool {
+        matches!(
+            self,
+            Self::SetAttribute { .. } | Self::SetHeading { .. } | Self::SetBody { .. }
+        )
+    }
+}
+
+/// The module a script runs in. Its objects and links are read lazily, on
+/// the script's first `req1.objects()` or `req1.links()` call.
 pub struct ScriptWorld {
     pub module_id: Uuid,
     pub module_name: String,
-    pub objects: Vec<ScriptObject>,
-    pub links: Vec<ScriptLink>,
 }
 
 /// Input for trigger scripts: the object being saved/deleted.
@@ -79,29 +203,6 @@ pub struct ActionResult {
     pub mutations: Vec<Mutation>,
 }
 
-// Mutation needs Serialize/Deserialize for the result types above
-impl Serialize for Mutation {
-    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        use serde::ser::SerializeStruct;
-        let Mutation::SetAttribute {
-            object_id,
-            key,
-            value,
-        } = self;
-        let mut s = serializer.serialize_struct("Mutation", 3)?;
-        s.serialize_field("object_id", &object_id.to_string())?;
-        s.serialize_field("key", key)?;
-        s.serialize_field("value", value)?;
-        s.end()
-    }
-}
-
-impl<'de> Deserialize<'de> for Mutation {
-    fn deserialize<D: serde::Deserializer<'de>>(_deserializer: D) -> Result<Self, D::Error> {
-        Err(serde::de::Error::custom("Mutation cannot be deserialized"))
-    }
-}
-
 // ---------------------------------------------------------------------------
 // Script state stored in V8's OpState (single-threaded, no Arc<Mutex>)
 // ---------------------------------------------------------------------------
@@ -119,16 +220,61 @@ struct ContextInfo {
 }
 
 struct ScriptState {
+    module_id: Uuid,
     module_info: Option<ModuleInfo>,
     context_info: Option<ContextInfo>,
     current_obj: Option<ScriptObject>,
-    objects: Vec<ScriptObject>,
-    links: Vec<ScriptLink>,
+    /// The module's objects and links, fetched on first use.
+    objects: Option<serde_json::Value>,
+    links: Option<serde_json::Value>,
     mutations: Vec<Mutation>,
     rejected: Option<String>,
     output: Vec<String>,
 }
 
+impl ScriptState {
+    fn new(world: &ScriptWorld) -> Self {
+        Self {
+            module_id: world.module_id,
+            module_info: Some(ModuleInfo {
+                id: world.module_id.to_string(),
+                name: world.module_name.clone(),
+            }),
+            context_info: None,
+            current_obj: None,
+            objects: None,
+            links: None,
+            mutations: Vec::new(),
+            rejected: None,
+            output: Vec::new(),
+        }
+    }
+
+    fn for_trigger(world: &ScriptWorld, ctx: &TriggerContext) -> Self {
+        Self {
+            context_info: Some(ContextInfo {
+                hook: ctx.hook_point.clone(),
+                object: ctx.object.clone(),
+            }),
+            ..Self::new(world)
+        }
+    }
+
+    fn for_layout(world: &ScriptWorld, object: &ScriptObject) -> Self {
+        Self {
+            current_obj: Some(object.clone()),
+            ..Self::new(world)
+        }
+    }
+}
+
+/// Answer to a [`WorldQuery`], sent back to the waiting op.
+type Reply = mpsc::Sender<Result<serde_json::Value, String>>;
+
+/// Where ops send their [`WorldQuery`]s; stored in `OpState` next to the
+/// [`ScriptState`].
+type QuerySender = tokio::sync::mpsc::UnboundedSender<(WorldQuery, Reply)>;
+
 // ---------------------------------------------------------------------------
 // Op error type
 // ---------------------------------------------------------------------------
@@ -164,35 +310,112 @@ fn op_get_obj(state: &mut OpState) -> Option<ScriptObject> {
 
 #[op2]
 #[serde]
-fn op_objects(state: &mut OpState) -> Vec<ScriptObject> {
-    state.borrow::<ScriptState>().objects.clone()
+fn op_objects(state: &mut OpState) -> Result<serde_json::Value, OpError> {
+    let ss = state.borrow::<ScriptState>();
+    if let Some(objects) = &ss.objects {
+        return Ok(objects.clone());
+    }
+    let objects = ask(state, WorldQuery::Objects(ss.module_id))?;
+    state.borrow_mut::<ScriptState>().objects = Some(objects.clone());
+    Ok(objects)
+}
+
+/// Ask the caller for module data and wait for the answer.
+fn ask(state: &OpState, query: WorldQuery) -> Result<serde_json::Value, OpError> {
+    request(state.borrow::<QuerySender>(), query).map_err(OpError::Generic)
+}
+
+fn request(queries: &QuerySender, query: WorldQuery) -> Result<serde_json::Value, String> {
+    let (reply, answer) = mpsc::channel();
+    queries
+        .send((query, reply))
+        .map_err(|_| "world queries are not available".to_owned())?;
+    answer
+        .recv()
+        .map_err(|_| "world query was cancelled".to_owned())?
 }
 
 #[op2]
 #[serde]
 #[allow(clippy::needless_pass_by_value)]
-fn op_get_object(state: &mut OpState, #[string] id: String) -> Option<ScriptObject> {
-    state
-        .borrow::<ScriptState>()
-        .objects
-        .iter()
-        .find(|o| o.id == id)
-        .cloned()
+fn op_get_object(state: &mut OpState, #[string] id: String) -> Result<serde_json::Value, OpError> {
+    match Uuid::parse_str(&id) {
+        Ok(oid) => ask(state, WorldQuery::Object(oid)),
+        Err(_) => Ok(serde_json::Value::Null),
+    }
 }
 
 #[op2]
 #[serde]
-fn op_links(state: &mut OpState, #[string] object_id: Option<String>) -> Vec<ScriptLink> {
-    let ss = state.borrow::<ScriptState>();
-    match object_id {
-        Some(oid) => ss
-            .links
-            .iter()
-            .filter(|l| l.source_object_id == oid || l.target_object_id == oid)
-            .cloned()
-            .collect(),
-        None => ss.links.clone(),
+#[allow(clippy::needless_pass_by_value)]
+fn op_module(
+    state: &mut OpState,
+    #[string] name_or_id: String,
+) -> Result<serde_json::Value, OpError> {
+    ask(state, WorldQuery::Module(name_or_id))
+}
+
+#[op2]
+#[serde]
+#[allow(clippy::needless_pass_by_value)]
+fn op_module_objects(
+    state: &mut OpState,
+    #[string] module_id: String,
+) -> Result<serde_json::Value, OpError> {
+    let module_id = parse_id(&module_id)?;
+    ask(state, WorldQuery::Objects(module_id))
+}
+
+/// Links `req1.follow` walks.
+#[derive(Debug, Deserialize)]
+struct FollowSpec {
+    link_type: Option<String>,
+    #[serde(default)]
+    direction: Direction,
+}
+
+#[op2]
+#[serde]
+#[allow(clippy::needless_pass_by_value)]
+fn op_follow(
+    state: &mut OpState,
+    #[string] object_id: String,
+    #[serde] spec: FollowSpec,
+) -> Result<serde_json::Value, OpError> {
+    let object_id = parse_id(&object_id)?;
+    ask(
+        state,
+        WorldQuery::Follow {
+            object_id,
+            link_type: spec.link_type,
+            direction: spec.direction,
+        },
+    )
+}
+
+#[op2]
+#[serde]
+fn op_links(
+    state: &mut OpState,
+    #[string] object_id: Option<String>,
+) -> Result<serde_json::Value, OpError> {
+    if let Some(oid) = object_id {
+        return ask(state, WorldQuery::Links(Some(parse_id(&oid)?)));
     }
+    if let Some(links) = &state.borrow::<ScriptState>().links {
+        return Ok(links.clone());
+    }
+    let links = ask(state, WorldQuery::Links(None))?;
+    state.borrow_mut::<ScriptState>().links = Some(links.clone());
+    Ok(links)
+}
+
+fn parse_id(id: &str) -> Result<Uuid, OpError> {
+    Uuid::parse_str(id).map_err(|e| OpError::Generic(format!("invalid UUID: {e}")))
+}
+
+fn push_mutation(state: &mut OpState, mutation: Mutation) {
+    state.borrow_mut::<ScriptState>().mutations.push(mutation);
 }
 
 #[op2]
@@ -203,16 +426,132 @@ fn op_set(
     #[string] key: String,
     #[serde] value: serde_json::Value,
 ) -> Result<(), OpError> {
-    let oid =
-        Uuid::parse_str(&object_id).map_err(|e| OpError::Generic(format!("invalid UUID: {e}")))?;
-    state
-        .borrow_mut::<ScriptState>()
-        .mutations
-        .push(Mutation::SetAttribute {
-            object_id: oid,
+    let object_id = parse_id(&object_id)?;
+    push_mutation(
+        state,
+        Mutation::SetAttribute {
+            object_id,
             key,
             value,
-        });
+        },
+    );
+    Ok(())
+}
+
+#[op2(fast)]
+#[allow(clippy::needless_pass_by_value)]
+fn op_set_heading(
+    state: &mut OpState,
+    #[string] object_id: String,
+    #[string] heading: String,
+) -> Result<(), OpError> {
+    let object_id = parse_id(&object_id)?;
+    push_mutation(state, Mutation::SetHeading { object_id, heading });
+    Ok(())
+}
+
+#[op2(fast)]
+#[allow(clippy::needless_pass_by_value)]
+fn op_set_body(
+    state: &mut OpState,
+    #[string] object_id: String,
+    #[string] body: String,
+) -> Result<(), OpError> {
+    let object_id = parse_id(&object_id)?;
+    push_mutation(state, Mutation::SetBody { object_id, body });
+    Ok(())
+}
+
+/// Fields accepted by `req1.create`.
+#[derive(Debug, Deserialize)]
+struct NewObject {
+    parent: Option<String>,
+    position: Option<i32>,
+    heading: Option<String>,
+    body: Option<String>,
+    attributes: Option<serde_json::Value>,
+}
+
+#[op2]
+#[string]
+fn op_create(state: &mut OpState, #[serde] data: NewObject) -> Result<String, OpError> {
+    let object_id = Uuid::now_v7();
+    let parent_id = data.parent.as_deref().map(parse_id).transpose()?;
+    push_mutation(
+        state,
+        Mutation::Create {
+            object_id,
+            parent_id,
+            position: data.position,
+            heading: data.heading,
+            body: data.body,
+            attributes: data.attributes,
+        },
+    );
+    Ok(object_id.to_string())
+}
+
+/// Destination accepted by `req1.move`.
+#[derive(Debug, Deserialize)]
+struct MoveTarget {
+    parent: Option<String>,
+    position: Option<i32>,
+}
+
+#[op2]
+#[allow(clippy::needless_pass_by_value)]
+fn op_move(
+    state: &mut OpState,
+    #[string] object_id: String,
+    #[serde] target: MoveTarget,
+) -> Result<(), OpError> {
+    let object_id = parse_id(&object_id)?;
+    let parent_id = target.parent.as_deref().map(parse_id).transpose()?;
+    push_mutation(
+        state,
+        Mutation::Move {
+            object_id,
+            parent_id,
+            position: target.position,
+        },
+    );
+    Ok(())
+}
+
+#[op2(fast)]
+#[allow(clippy::needless_pass_by_value)]
+fn op_link(
+    state: &mut OpState,
+    #[string] source_object_id: String,
+    #[string] target_object_id: String,
+    #[string] link_type: String,
+) -> Result<(), OpError> {
+    let source_object_id = parse_id(&source_object_id)?;
+    let target_object_id = parse_id(&target_object_id)?;
+    push_mutation(
+        state,
+        Mutation::Link {
+            source_object_id,
+            target_object_id,
+            link_type,
+        },
+    );
+    Ok(())
+}
+
+#[op2(fast)]
+#[allow(clippy::needless_pass_by_value)]
+fn op_unlink(state: &mut OpState, #[string] link_id: String) -> Result<(), OpError> {
+    let link_id = parse_id(&link_id)?;
+    push_mutation(state, Mutation::Unlink { link_id });
+    Ok(())
+}
+
+#[op2(fast)]
+#[allow(clippy::needless_pass_by_value)]
+fn op_delete(state: &mut OpState, #[string] object_id: String) -> Result<(), OpError> {
+    let object_id = parse_id(&object_id)?;
+    push_mutation(state, Mutation::Delete { object_id });
     Ok(())
 }
 
@@ -245,8 +584,18 @@ deno_core::extension!(
         op_get_obj,
         op_objects,
         op_get_object,
+        op_module,
+        op_module_objects,
+        op_follow,
         op_links,
         op_set,
+        op_set_heading,
+        op_set_body,
+        op_create,
+        op_move,
+        op_link,
+        op_unlink,
+        op_delete,
         op_reject,
         op_log,
         op_script_print,
@@ -254,32 +603,687 @@ deno_core::extension!(
 );
 
 // ---------------------------------------------------------------------------
-// Bootstrap JS (run before every user script)
+// Bootstrap JS (baked into the startup snapshot)
 // ---------------------------------------------------------------------------
 
 const BOOTSTRAP_JS: &str = include_str!("bootstrap.js");
 
+static SNAPSHOT: OnceLock<Option<&'static [u8]>> = OnceLock::new();
+
+/// V8 startup snapshot with `bootstrap.js` already evaluated, made once per
+/// process. `None` if it could not be made; runtimes then evaluate the
+/// bootstrap themselves.
+fn snapshot() -> Option<&'static [u8]> {
+    *SNAPSHOT.get_or_init(|| {
+        // Set up V8 for regular runtimes first, so snapshotting does not switch
+        // the whole process to its deterministic mode
+        JsRuntime::init_platform(None, false);
+        let made = JsRuntimeForSnapshot::try_new(RuntimeOptions {
+            extensions: vec![req1_scripting::init_ops()],
+            ..Default::default()
+        })
+        .map_err(|e| e.to_string())
+        .and_then(|mut runtime| {
+            let _ = runtime
+                .execute_script("<bootstrap>", BOOTSTRAP_JS)
+                .map_err(|e| e.to_string())?;
+            Ok(runtime.snapshot())
+        });
+        match made {
+            Ok(snapshot) => Some(&*Box::leak(snapshot)),
+            Err(e) => {
+                tracing::error!("failed to create the script snapshot: {e}");
+                None
+            }
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Script libraries
+// ---------------------------------------------------------------------------
+
+const LIBRARY_PREFIX: &str = "req1:lib/";
+
+/// Specifier of a script that imports libraries.
+const SCRIPT_SPECIFIER: &str = "req1:script";
+
+/// Resolves `req1:lib/<name>[@version]` imports to script library sources,
+/// fetched through the world query channel.
+struct LibraryLoader {
+    queries: QuerySender,
+}
+
+impl ModuleLoader for LibraryLoader {
+    fn resolve(
+        &self,
+        specifier: &str,
+        _referrer: &str,
+        _kind: ResolutionKind,
+    ) -> Result<ModuleSpecifier, ModuleLoaderError> {
+        if !specifier.starts_with(LIBRARY_PREFIX) {
+            return Err(JsErrorBox::generic(format!(
+                "cannot import '{specifier}': only {LIBRARY_PREFIX}<name> modules can be imported"
+            ))
+            .into());
+        }
+        ModuleSpecifier::parse(specifier)
+            .map_err(|e| JsErrorBox::generic(format!("invalid import '{specifier}': {e}")).into())
+    }
+
+    fn load(
+        &self,
+        specifier: &ModuleSpecifier,
+        _referrer: Option<&ModuleSpecifier>,
+        is_dyn_import: bool,
+        _requested_module_type: RequestedModuleType,
+    ) -> ModuleLoadResponse {
+        ModuleLoadResponse::Sync(
+            self.fetch(specifier, is_dyn_import).map_err(|e| {
+                JsErrorBox::generic(format!("cannot import '{specifier}': {e}")).into()
+            }),
+        )
+    }
+}
+
+impl LibraryLoader {
+    fn fetch(
+        &self,
+        specifier: &ModuleSpecifier,
+        is_dyn_import: bool,
+    ) -> Result<ModuleSource, String> {
+        if is_dyn_import {
+            return Err("use a static import".to_owned());
+        }
+        let path = specifier
+            .as_str()
+            .strip_prefix(LIBRARY_PREFIX)
+            .unwrap_or_default();
+        let (name, version) = match path.split_once('@') {
+            Some((name, v)) => (
+                name,
+                Some(
+                    v.parse::<i32>()
+                        .map_err(|_| format!("invalid version '{v}'"))?,
+                ),
+            ),
+            None => (path, None),
+        };
+        let source = request(
+            &self.queries,
+            WorldQuery::Library {
+                name: name.to_owned(),
+                version,
+            },
+        )?;
+        let code = source.as_str().unwrap_or_default().to_owned();
+        Ok(ModuleSource::new(
+            ModuleType::JavaScript,
+            ModuleSourceCode::String(code.into()),
+            specifier,
+            None,
+        ))
+    }
+}
+
+/// Split a script's leading `import ... from "..."` declarations from its
+/// body. The imports are joined on one line and blanked out of the body, so
+/// the body keeps its line numbers. `None` when the script imports nothing.
+fn split_imports(source: &str) -> Option<(String, String)> {
+    let bytes = source.as_bytes();
+    let mut imports = String::new();
+    let mut body = String::with_capacity(source.len());
+    let mut copied = 0;
+    let mut i = 0;
+    loop {
+        // Whitespace and comments between declarations stay in the body
+        while i < bytes.len() {
+            if bytes[i].is_ascii_whitespace() {
+                i += 1;
+            } else if source[i..].starts_with("//") {
+                i = source[i..].find('\n').map_or(bytes.len(), |n| i + n);
+            } else if source[i..].starts_with("/*") {
+                i = source[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |n| i + n + 4);
+            } else {
+                break;
+            }
+        }
+        let rest = &source[i..];
+        let is_import = rest.starts_with("import")
+            && rest[6..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_whitespace() || matches!(c, '{' | '*' | '"' | '\''));
+        if !is_import {
+            break;
+        }
+        // The declaration ends after its (first) string literal, the specifier
+        let Some(open) = rest.find(['"', '\'']) else {
+            break;
+        };
+        let quote = rest[open..].chars().next()?;
+        let close = rest[open + 1..].find(quote)? + open + 2;
+        let mut end = close;
+        let trailing = rest[end..].len() - rest[end..].trim_start_matches([' ', '\t']).len();
+        if rest[end + trailing..].starts_with(';') {
+            end += trailing + 1;
+        }
+
+        let decl = &rest[..end];
+        body.push_str(&source[copied..i]);
+        body.extend(decl.chars().filter(|&c| c == '\n'));
+        imports.push_str(&decl.replace(['\n', '\r'], " "));
+        if !decl.ends_with(';') {
+            imports.push(';');
+        }
+        imports.push(' ');
+        i += end;
+        copied = i;
+    }
+    if imports.is_empty() {
+        return None;
+    }
+    body.push_str(&source[copied..]);
+    Some((imports, body))
+}
+
 // ---------------------------------------------------------------------------
 // Runtime creation
 // ---------------------------------------------------------------------------
 
-fn create_runtime(script_state: ScriptState) -> Result<JsRuntime, CoreError> {
+fn create_runtime(
+    script_state: ScriptState,
+    queries: QuerySender,
+    limits: &ScriptLimits,
+) -> Result<JsRuntime, CoreError> {
+    let snapshot = snapshot();
     let mut runtime = JsRuntime::new(RuntimeOptions {
         extensions: vec![req1_scripting::init_ops()],
+        module_loader: Some(Rc::new(LibraryLoader {
+            queries: queries.clone(),
+        })),
+        startup_snapshot: snapshot,
+        create_params: Some(v8::CreateParams::default().heap_limits(0, limits.heap_limit)),
         ..Default::default()
     });
 
     {
         let op_state: Rc<RefCell<OpState>> = runtime.op_state();
         op_state.borrow_mut().put(script_state);
+        op_state.borrow_mut().put(queries);
+    }
+
+    if snapshot.is_none() {
+        let _ = runtime
+            .execute_script("<bootstrap>", BOOTSTRAP_JS)
+            .map_err(|e| CoreError::internal(format!("bootstrap error: {e}")))?;
     }
 
-    // Run bootstrap to set up globals
+    Ok(runtime)
+}
+
+/// Record the globals of a freshly compiled script, for [`reset_globals`] to return to.
+fn seal_globals(runtime: &mut JsRuntime) -> Result<(), CoreError> {
     let _ = runtime
-        .execute_script("<bootstrap>", BOOTSTRAP_JS.to_owned())
+        .execute_script("<seal>", "__req1_seal()")
         .map_err(|e| CoreError::internal(format!("bootstrap error: {e}")))?;
+    Ok(())
+}
 
-    Ok(runtime)
+/// Put the globals back as [`seal_globals`] recorded them. Returns false when the
+/// previous run left a global that cannot be removed or restored.
+fn reset_globals(runtime: &mut JsRuntime) -> Result<bool, CoreError> {
+    let clean = runtime
+        .execute_script("<reset>", "__req1_reset()")
+        .map_err(|e| CoreError::internal(format!("bootstrap error: {e}")))?;
+    let scope = &mut runtime.handle_scope();
+    Ok(v8::Local::new(scope, clean).is_true())
+}
+
+/// Set the `module` / `context` / `obj` globals from the current [`ScriptState`].
+fn init_globals(runtime: &mut JsRuntime) -> Result<(), CoreError> {
+    let _ = runtime
+        .execute_script("<init>", "__req1_init()")
+        .map_err(|e| CoreError::internal(format!("bootstrap error: {e}")))?;
+    Ok(())
+}
+
+/// A run the watchdog terminates unless it is disarmed before `deadline`.
+struct Armed {
+    deadline: Instant,
+    handle: v8::IsolateHandle,
+    abort: Arc<AtomicU8>,
+}
+
+#[derive(Default)]
+struct WatchState {
+    armed: Option<Armed>,
+    closed: bool,
+}
+
+/// Enforces the time limit for the runs of one script thread. The watchdog
+/// thread lives as long as the script thread and sleeps until the deadline of
+/// the current run, so a run does not start a thread of its own.
+#[derive(Default)]
+struct Watchdog {
+    shared: Arc<(Mutex<WatchState>, Condvar)>,
+    started: Cell<bool>,
+}
+
+thread_local! {
+    static WATCHDOG: Watchdog = Watchdog::default();
+}
+
+impl Watchdog {
+    fn arm(&self, armed: Armed) -> Result<(), CoreError> {
+        if !self.started.get() {
+            let shared = Arc::clone(&self.shared);
+            let _ = thread::Builder::new()
+                .name("req1-script-watchdog".to_owned())
+                .spawn(move || Self::watch(&shared))
+                .map_err(|e| CoreError::internal(format!("failed to spawn watchdog: {e}")))?;
+            self.started.set(true);
+        }
+        let (lock, wake) = &*self.shared;
+        lock.lock().unwrap_or_else(PoisonError::into_inner).armed = Some(armed);
+        wake.notify_one();
+        Ok(())
+    }
+
+    /// Once this returns, the current run can no longer be terminated.
+    fn disarm(&self) {
+        let (lock, _) = &*self.shared;
+        lock.lock().unwrap_or_else(PoisonError::into_inner).armed = None;
+    }
+
+    fn watch(shared: &(Mutex<WatchState>, Condvar)) {
+        let (lock, wake) = shared;
+        let mut state = lock.lock().unwrap_or_else(PoisonError::into_inner);
+        while !state.closed {
+            let Some(deadline) = state.armed.as_ref().map(|a| a.deadline) else {
+                state = wake.wait(state).unwrap_or_else(PoisonError::into_inner);
+                continue;
+            };
+            let now = Instant::now();
+            if now < deadline {
+                state = wake
+                    .wait_timeout(state, deadline - now)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0;
+            } else if let Some(expired) = state.armed.take() {
+                let _ = expired.abort.compare_exchange(
+                    RUNNING,
+                    TIMED_OUT,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                );
+                let _ = expired.handle.terminate_execution();
+            }
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        let (lock, wake) = &*self.shared;
+        lock.lock().unwrap_or_else(PoisonError::into_inner).closed = true;
+        wake.notify_one();
+    }
+}
+
+const RUNNING: u8 = 0;
+const TIMED_OUT: u8 = 1;
+const OUT_OF_MEMORY: u8 = 2;
+
+/// Run `source` under the time and heap limits, see [`run_limited`].
+fn execute_limited(
+    runtime: &mut JsRuntime,
+    name: &'static str,
+    source: String,
+    limits: &ScriptLimits,
+    label: &str,
+) -> Result<v8::Global<v8::Value>, CoreError> {
+    run_limited(runtime, limits, label, |runtime| {
+        runtime
+            .execute_script(name, source)
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// Evaluate `code` as an ES module, loading the libraries it imports, under
+/// the time and heap limits.
+fn evaluate_module_limited(
+    runtime: &mut JsRuntime,
+    code: String,
+    limits: &ScriptLimits,
+    label: &str,
+) -> Result<(), CoreError> {
+    let specifier = ModuleSpecifier::parse(SCRIPT_SPECIFIER)
+        .map_err(|e| CoreError::internal(format!("invalid script specifier: {e}")))?;
+    // Loading and evaluation are async in deno_core, but our loader and ops
+    // never wait on anything, so a local executor drives them to completion
+    let executor = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .map_err(|e| CoreError::internal(format!("failed to start module loader: {e}")))?;
+    run_limited(runtime, limits, label, |runtime| {
+        executor
+            .block_on(async {
+                let id = runtime
+                    .load_side_es_module_from_code(&specifier, code)
+                    .await?;
+                let evaluated = runtime.mod_evaluate(id);
+                runtime
+                    .run_event_loop(PollEventLoopOptions::default())
+                    .await?;
+                evaluated.await
+            })
+            .map_err(|e| e.to_string())
+    })
+}
+
+/// Run `run` under the time and heap limits. V8 terminates the script when
+/// either is exceeded; `label` prefixes the error message.
+fn run_limited<T>(
+    runtime: &mut JsRuntime,
+    limits: &ScriptLimits,
+    label: &str,
+    run: impl FnOnce(&mut JsRuntime) -> Result<T, String>,
+) -> Result<T, CoreError> {
+    let abort = Arc::new(AtomicU8::new(RUNNING));
+    let handle = runtime.v8_isolate().thread_safe_handle();
+
+    {
+        let abort = Arc::clone(&abort);
+        let handle = handle.clone();
+        runtime.add_near_heap_limit_callback(move |current, _initial| {
+            abort.store(OUT_OF_MEMORY, Ordering::SeqCst);
+            let _ = handle.terminate_execution();
+            // Room for V8 to unwind the terminated script instead of aborting the process
+            current * 2
+        });
+    }
+
+    WATCHDOG.with(|watchdog| {
+        watchdog.arm(Armed {
+            deadline: Instant::now() + limits.timeout,
+            handle,
+            abort: Arc::clone(&abort),
+        })
+    })?;
+    let result = run(runtime);
+    WATCHDOG.with(Watchdog::disarm);
+
+    match abort.load(Ordering::SeqCst) {
+        TIMED_OUT => Err(CoreError::script_timeout(format!(
+            "{label} exceeded the {} ms time limit",
+            limits.timeout.as_millis()
+        ))),
+        OUT_OF_MEMORY => Err(CoreError::script_memory_limit(format!(
+            "{label} exceeded the {} MiB heap limit",
+            limits.heap_limit / (1024 * 1024)
+        ))),
+        _ => result.map_err(|e| CoreError::bad_request(format!("{label} error: {e}"))),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Warm runtimes
+// ---------------------------------------------------------------------------
+
+/// Scripts compiled into functions and kept in their runtimes, so evaluating
+/// a trigger or layout script for many objects compiles it once. Globals are
+/// reset to their state after compilation before each object, so no run sees
+/// what the previous one left behind.
+struct WarmRuntimes {
+    limits: ScriptLimits,
+    queries: QuerySender,
+    runtimes: HashMap<String, JsRuntime>,
+}
+
+impl WarmRuntimes {
+    fn new(limits: ScriptLimits, queries: QuerySender) -> Self {
+        Self {
+            limits,
+            queries,
+            runtimes: HashMap::new(),
+        }
+    }
+
+    /// Call `source` with `state`, compiling it on first use. `read` takes the
+    /// outcome out of the runtime. A runtime whose run failed, or whose globals
+    /// cannot be reset, is dropped, so a terminated script never runs again.
+    fn call<T>(
+        &mut self,
+        name: &'static str,
+        source: &str,
+        state: ScriptState,
+        label: &str,
+        read: impl FnOnce(&mut JsRuntime, v8::Global<v8::Value>) -> T,
+    ) -> Result<T, CoreError> {
+        let mut warm = self.runtimes.remove(source);
+        if let Some(runtime) = &mut warm
+            && !reset_globals(runtime)?
+        {
+            warm = None;
+        }
+        let mut runtime = match warm {
+            Some(mut runtime) => {
+                runtime.op_state().borrow_mut().put(state);
+                runtime
+            }
+            None => {
+                let mut runtime = create_runtime(state, self.queries.clone(), &self.limits)?;
+                // Same line as the source, so error positions stay right. Scripts
+                // that import libraries become modules, with the imports in scope
+                match split_imports(source) {
+                    None => {
+                        let compile =
+                            format!("globalThis.__req1_main = function () {{ {source}\n}};");
+                        let _ = execute_limited(&mut runtime, name, compile, &self.limits, label)?;
+                    }
+                    Some((imports, body)) => {
+                        let compile =
+                            format!("{imports}globalThis.__req1_main = function () {{ {body}\n}};");
+                        evaluate_module_limited(&mut runtime, compile, &self.limits, label)?;
+                    }
+                }
+                seal_globals(&mut runtime)?;
+                runtime
+            }
+        };
+        init_globals(&mut runtime)?;
+        let value = execute_limited(
+            &mut runtime,
+            name,
+            "__req1_main()".to_owned(),
+            &self.limits,
+            label,
+        )?;
+        let out = read(&mut runtime, value);
+        let _ = self.runtimes.insert(source.to_owned(), runtime);
+        Ok(out)
+    }
+
+    fn trigger(&mut self, source: &str, state: ScriptState) -> Result<TriggerResult, CoreError> {
+        self.call("<trigger>", source, state, "script", |runtime, _| {
+            let rc = runtime.op_state();
+            let borrowed = rc.borrow();
+            let ss = borrowed.borrow::<ScriptState>();
+            TriggerResult {
+                rejected: ss.rejected.is_some(),
+                reason: ss.rejected.clone(),
+                mutations: ss.mutations.clone(),
+            }
+        })
+    }
+
+    fn action(&mut self, source: &str, state: ScriptState) -> Result<ActionResult, CoreError> {
+        self.call("<action>", source, state, "action script", |runtime, _| {
+            let rc = runtime.op_state();
+            let borrowed = rc.borrow();
+            let ss = borrowed.borrow::<ScriptState>();
+            ActionResult {
+                output: ss.output.clone(),
+                mutations: ss.mutations.clone(),
+            }
+        })
+    }
+
+    fn layout(&mut self, source: &str, state: ScriptState) -> Result<LayoutResult, CoreError> {
+        self.call(
+            "<layout>",
+            source,
+            state,
+            "layout script",
+            |runtime, result| {
+                let scope = &mut runtime.handle_scope();
+                let local = v8::Local::new(scope, result);
+                LayoutResult {
+                    value: v8_to_string(scope, local),
+                }
+            },
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Script pool
+// ---------------------------------------------------------------------------
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Dedicated threads that scripts run on, so a busy script never blocks a
+/// Tokio worker.
+struct ScriptPool {
+    jobs: mpsc::Sender<Job>,
+}
+
+static POOL: OnceLock<ScriptPool> = OnceLock::new();
+
+impl ScriptPool {
+    fn new(workers: usize) -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        for i in 0..workers.max(1) {
+            let queue = Arc::clone(&queue);
+            let spawned = thread::Builder::new()
+                .name(format!("req1-script-{i}"))
+                .spawn(move || {
+                    loop {
+                        let job = match queue.lock() {
+                            Ok(q) => q.recv(),
+                            Err(_) => return,
+                        };
+                        let Ok(job) = job else { return };
+                        // A panicking script must not take the worker down with it
+                        let _ = catch_unwind(AssertUnwindSafe(job));
+                    }
+                });
+            if let Err(e) = spawned {
+                tracing::error!("failed to spawn script worker: {e}");
+            }
+        }
+        Self { jobs }
+    }
+
+    /// Run `f` on a pool thread and wait for its result, answering the
+    /// script's world queries through `access` in the meantime.
+    async fn run<T, F>(access: &WorldAccess<'_, impl ConnectionTrait>, f: F) -> Result<T, CoreError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&ScriptLimits, QuerySender) -> Result<T, CoreError> + Send + 'static,
+    {
+        let limits = limits();
+        let pool = POOL.get_or_init(|| Self::new(limits.workers));
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let (queries, mut pending) = tokio::sync::mpsc::unbounded_channel();
+        pool.jobs
+            .send(Box::new(move || {
+                let _ = tx.send(f(&limits, queries));
+            }))
+            .map_err(|_| CoreError::internal("script pool is not running"))?;
+
+        serve(access, rx, &mut pending).await
+    }
+}
+
+/// Wait for a script's result, answering its world queries meanwhile.
+async fn serve<T>(
+    access: &WorldAccess<'_, impl ConnectionTrait>,
+    mut result: tokio::sync::oneshot::Receiver<Result<T, CoreError>>,
+    pending: &mut tokio::sync::mpsc::UnboundedReceiver<(WorldQuery, Reply)>,
+) -> Result<T, CoreError> {
+    loop {
+        tokio::select! {
+            result = &mut result => {
+                return result.map_err(|_| CoreError::internal("script worker panicked"))?;
+            }
+            Some((query, reply)) = pending.recv() => {
+                let answer = access.answer(query).await.map_err(|e| e.to_string());
+                let _ = reply.send(answer);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Batches
+// ---------------------------------------------------------------------------
+
+type BatchCall = (
+    String,
+    ScriptState,
+    tokio::sync::oneshot::Sender<Result<TriggerResult, CoreError>>,
+);
+
+/// A thread holding warm trigger runtimes for the object saves of one bulk
+/// operation, see [`ScriptEngine::batched`].
+struct ScriptBatch {
+    calls: mpsc::Sender<BatchCall>,
+    queries: tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<(WorldQuery, Reply)>>,
+}
+
+tokio::task_local! {
+    static BATCH: Arc<ScriptBatch>;
+}
+
+impl ScriptBatch {
+    fn new() -> Result<Self, CoreError> {
+        let (calls, inbox) = mpsc::channel::<BatchCall>();
+        let (queries, pending) = tokio::sync::mpsc::unbounded_channel();
+        let limits = limits();
+        let _ = thread::Builder::new()
+            .name("req1-script-batch".to_owned())
+            .spawn(move || {
+                let mut warm = WarmRuntimes::new(limits, queries);
+                // Ends when the batch, and with it the sender, is dropped
+                while let Ok((source, state, reply)) = inbox.recv() {
+                    let _ = reply.send(warm.trigger(&source, state));
+                }
+            })
+            .map_err(|e| CoreError::internal(format!("failed to spawn script batch: {e}")))?;
+        Ok(Self {
+            calls,
+            queries: tokio::sync::Mutex::new(pending),
+        })
+    }
+
+    async fn trigger(
+        &self,
+        source: &str,
+        state: ScriptState,
+        access: &WorldAccess<'_, impl ConnectionTrait>,
+    ) -> Result<TriggerResult, CoreError> {
+        // One trigger at a time, so queries are answered for the right caller
+        let mut pending = self.queries.lock().await;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.calls
+            .send((source.to_owned(), state, tx))
+            .map_err(|_| CoreError::internal("script batch is not running"))?;
+        serve(access, rx, &mut pending).await
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -289,117 +1293,123 @@ fn create_runtime(script_state: ScriptState) -> Result<JsRuntime, CoreError> {
 pub struct ScriptEngine;
 
 impl ScriptEngine {
+    /// Set the limits scripts run under. Only the first call takes effect; runs
+    /// before it use [`ScriptLimits::default`].
+    pub fn configure(limits: ScriptLimits) {
+        let _ = LIMITS.set(limits);
+    }
+
+    /// Run `fut` with trigger scripts kept warm across the object saves it
+    /// makes, for bulk operations such as imports.
+    pub async fn batched<F: Future>(fut: F) -> F::Output {
+        if BATCH.try_with(|_| ()).is_ok() {
+            return fut.await;
+        }
+        match ScriptBatch::new() {
+            Ok(batch) => BATCH.scope(Arc::new(batch), fut).await,
+            Err(e) => {
+                tracing::warn!("running triggers unbatched: {e}");
+                fut.await
+            }
+        }
+    }
+
     /// Run a trigger script (`pre_save` / `post_save` / `pre_delete` / `post_delete`).
-    pub fn run_trigger(
+    pub async fn run_trigger(
         source: &str,
         world: &ScriptWorld,
         trigger_ctx: &TriggerContext,
+        access: &WorldAccess<'_, impl ConnectionTrait>,
     ) -> Result<TriggerResult, CoreError> {
-        let state = ScriptState {
-            module_info: Some(ModuleInfo {
-                id: world.module_id.to_string(),
-                name: world.module_name.clone(),
-            }),
-            context_info: Some(ContextInfo {
-                hook: trigger_ctx.hook_point.clone(),
-                object: trigger_ctx.object.clone(),
-            }),
-            current_obj: None,
-            objects: world.objects.clone(),
-            links: world.links.clone(),
-            mutations: Vec::new(),
-            rejected: None,
-            output: Vec::new(),
-        };
-
-        let mut runtime = create_runtime(state)?;
+        let state = ScriptState::for_trigger(world, trigger_ctx);
+        if let Ok(batch) = BATCH.try_with(Arc::clone) {
+            return batch.trigger(source, state, access).await;
+        }
+        let source = source.to_owned();
 
-        let _ = runtime
-            .execute_script("<trigger>", source.to_owned())
-            .map_err(|e| CoreError::bad_request(format!("script error: {e}")))?;
+        ScriptPool::run(access, move |limits, queries| {
+            WarmRuntimes::new(*limits, queries).trigger(&source, state)
+        })
+        .await
+    }
 
-        let rc = runtime.op_state();
-        let borrowed = rc.borrow();
-        let ss = borrowed.borrow::<ScriptState>();
+    /// Run a trigger script for each of `contexts` in one runtime. A failure
+    /// for one object does not stop the others.
+    pub async fn run_triggers(
+        source: &str,
+        world: &ScriptWorld,
+        contexts: &[TriggerContext],
+        access: &WorldAccess<'_, impl ConnectionTrait>,
+    ) -> Result<Vec<Result<TriggerResult, CoreError>>, CoreError> {
+        let states: Vec<ScriptState> = contexts
+            .iter()
+            .map(|ctx| ScriptState::for_trigger(world, ctx))
+            .collect();
+        let source = source.to_owned();
 
-        Ok(TriggerResult {
-            rejected: ss.rejected.is_some(),
-            reason: ss.rejected.clone(),
-            mutations: ss.mutations.clone(),
+        ScriptPool::run(access, move |limits, queries| {
+            let mut warm = WarmRuntimes::new(*limits, queries);
+            Ok(states
+                .into_iter()
+                .map(|state| warm.trigger(&source, state))
+                .collect())
         })
+        .await
     }
 
     /// Run a layout script (computed column).
     ///
     /// Receives the current object as `obj`. Must return a string value.
-    pub fn run_layout(
+    pub async fn run_layout(
         source: &str,
         world: &ScriptWorld,
         object: &ScriptObject,
+        access: &WorldAccess<'_, impl ConnectionTrait>,
     ) -> Result<LayoutResult, CoreError> {
-        let state = ScriptState {
-            module_info: Some(ModuleInfo {
-                id: world.module_id.to_string(),
-                name: world.module_name.clone(),
-            }),
-            context_info: None,
-            current_obj: Some(object.clone()),
-            objects: world.objects.clone(),
-            links: world.links.clone(),
-            mutations: Vec::new(),
-            rejected: None,
-            output: Vec::new(),
-        };
-
-        let mut runtime = create_runtime(state)?;
-
-        // Wrap in an IIFE so `return` works at the top level.
-        let wrapped = format!("((function() {{ {source} }})())");
-
-        let result = runtime
-            .execute_script("<layout>", wrapped)
-            .map_err(|e| CoreError::bad_request(format!("layout script error: {e}")))?;
+        let mut results =
+            Self::run_layouts(source, world, std::slice::from_ref(object), access).await?;
+        results
+            .pop()
+            .ok_or_else(|| CoreError::internal("layout script returned no result"))
+    }
 
-        // Extract the return value from V8
-        let value = {
-            let scope = &mut runtime.handle_scope();
-            let local = deno_core::v8::Local::new(scope, result);
-            v8_to_string(scope, local)
-        };
+    /// Run a layout script for each of `objects` in one runtime, stopping at
+    /// the first failure.
+    pub async fn run_layouts(
+        source: &str,
+        world: &ScriptWorld,
+        objects: &[ScriptObject],
+        access: &WorldAccess<'_, impl ConnectionTrait>,
+    ) -> Result<Vec<LayoutResult>, CoreError> {
+        let states: Vec<ScriptState> = objects
+            .iter()
+            .map(|obj| ScriptState::for_layout(world, obj))
+            .collect();
+        let source = source.to_owned();
 
-        Ok(LayoutResult { value })
+        ScriptPool::run(access, move |limits, queries| {
+            let mut warm = WarmRuntimes::new(*limits, queries);
+            states
+                .into_iter()
+                .map(|state| warm.layout(&source, state))
+                .collect()
+        })
+        .await
     }
 
     /// Run an action script (batch operation).
-    pub fn run_action(source: &str, world: &ScriptWorld) -> Result<ActionResult, CoreError> {
-        let state = ScriptState {
-            module_info: Some(ModuleInfo {
-                id: world.module_id.to_string(),
-                name: world.module_name.clone(),
-            }),
-            context_info: None,
-            current_obj: None,
-            objects: world.objects.clone(),
-            links: world.links.clone(),
-            mutations: Vec::new(),
-            rejected: None,
-            output: Vec::new(),
-        };
-
-        let mut runtime = create_runtime(state)?;
-
-        let _ = runtime
-            .execute_script("<action>", source.to_owned())
-            .map_err(|e| CoreError::bad_request(format!("action script error: {e}")))?;
-
-        let rc = runtime.op_state();
-        let borrowed = rc.borrow();
-        let ss = borrowed.borrow::<ScriptState>();
+    pub async fn run_action(
+        source: &str,
+        world: &ScriptWorld,
+        access: &WorldAccess<'_, impl ConnectionTrait>,
+    ) -> Result<ActionResult, CoreError> {
+        let state = ScriptState::new(world);
+        let source = source.to_owned();
 
-        Ok(ActionResult {
-            output: ss.output.clone(),
-            mutations: ss.mutations.clone(),
+        ScriptPool::run(access, move |limits, queries| {
+            WarmRuntimes::new(*limits, queries).action(&source, state)
         })
+        .await
     }
 }
 
@@ -408,10 +1418,7 @@ impl ScriptEngine {
 // ---------------------------------------------------------------------------
 
 /// Convert a V8 value to a Rust String for layout results.
-fn v8_to_string(
-    scope: &mut deno_core::v8::HandleScope,
-    value: deno_core::v8::Local<deno_core::v8::Value>,
-) -> String {
+fn v8_to_string(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> String {
     if value.is_null_or_undefined() {
         return String::new();
     }
@@ -420,3 +1427,25 @@ fn v8_to_string(
     }
     String::new()
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_imports() {
+        assert!(split_imports("req1.log('x');").is_none());
+        assert!(split_imports("const m = import.meta;").is_none());
+
+        let source = "// helpers\nimport { fmt } from \"req1:lib/text\";\nimport {\n  a,\n  b\n} from 'req1:lib/util@2'\nreq1.log(fmt(a));";
+        let (imports, body) = split_imports(source).unwrap();
+        assert_eq!(
+            imports,
+            "import { fmt } from \"req1:lib/text\"; import {   a,   b } from 'req1:lib/util@2'; "
+        );
+        // Declarations are blanked out line for line
+        assert_eq!(body, "// helpers\n\n\n\n\n\nreq1.log(fmt(a));");
+        assert_eq!(body.lines().count(), source.lines().count());
+    }
+}
@@ -1 +1,3 @@
+pub mod apply;
 pub mod engine;
+pub mod world;
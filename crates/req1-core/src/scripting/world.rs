@@ -0,0 +1,358 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait,
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+use entity::{app_user, link, module, object};
+
+use crate::auth::AuthUser;
+use crate::error::CoreError;
+use crate::service::permission::{Permission, PermissionService};
+use crate::service::script_library::ScriptLibraryService;
+
+use super::apply::find_link_type;
+use super::engine::{ScriptLink, ScriptObject};
+
+/// A read of module data, answered by the caller while the script waits.
+/// Nothing is loaded up front; scripts pay only for what they read.
+#[derive(Debug)]
+pub(crate) enum WorldQuery {
+    /// Module by id or name.
+    Module(String),
+    Objects(Uuid),
+    Object(Uuid),
+    /// Links of an object, or of the script's own module when `None`.
+    Links(Option<Uuid>),
+    /// Source of a script library imported as `req1:lib/<name>[@version]`.
+    Library {
+        name: String,
+        version: Option<i32>,
+    },
+    Follow {
+        object_id: Uuid,
+        link_type: Option<String>,
+        direction: Direction,
+    },
+}
+
+/// Which links `req1.follow` walks.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Direction {
+    /// Links from the object to others.
+    #[default]
+    Out,
+    /// Links from others to the object.
+    In,
+    Both,
+}
+
+/// Lazy, read-only access to other modules for a running script. Reads are
+/// limited to the modules the caller can read; runs without a user (scheduled
+/// scripts) are confined to the script's own module, as nobody's grants apply.
+pub struct WorldAccess<'a, C> {
+    db: &'a C,
+    user_id: Option<Uuid>,
+    module_id: Uuid,
+    user: OnceCell<Option<AuthUser>>,
+    readable: Mutex<HashMap<Uuid, bool>>,
+}
+
+impl<'a, C: ConnectionTrait> WorldAccess<'a, C> {
+    pub fn new(db: &'a C, user_id: Option<Uuid>, module_id: Uuid) -> Self {
+        Self {
+            db,
+            user_id,
+            module_id,
+            user: OnceCell::new(),
+            readable: Mutex::new(HashMap::from([(module_id, true)])),
+        }
+    }
+
+    pub(crate) async fn answer(&self, query: WorldQuery) -> Result<Value, CoreError> {
+        match query {
+            WorldQuery::Module(name_or_id) => {
+                let m = self.find_module(&name_or_id).await?;
+                Ok(json!({ "id": m.id.to_string(), "name": m.name }))
+            }
+            WorldQuery::Objects(module_id) => {
+                self.require_read(module_id).await?;
+                let objects: Vec<ScriptObject> = object::Entity::find()
+                    .filter(object::Column::ModuleId.eq(module_id))
+                    .filter(object::Column::DeletedAt.is_null())
+                    .order_by_asc(object::Column::Position)
+                    .all(self.db)
+                    .await?
+                    .into_iter()
+                    .map(ScriptObject::from)
+                    .collect();
+                Ok(json!(objects))
+            }
+            WorldQuery::Object(id) => {
+                let Some(obj) = object::Entity::find_by_id(id)
+                    .filter(object::Column::DeletedAt.is_null())
+                    .one(self.db)
+                    .await?
+                else {
+                    return Ok(Value::Null);
+                };
+                self.require_read(obj.module_id).await?;
+                Ok(json!(ScriptObject::from(obj)))
+            }
+            WorldQuery::Links(object_id) => self.links(object_id).await,
+            WorldQuery::Library { name, version } => {
+                let v =
+                    ScriptLibraryService::resolve(self.db, self.module_id, &name, version).await?;
+                Ok(Value::String(v.source_code))
+            }
+            WorldQuery::Follow {
+                object_id,
+                link_type,
+                direction,
+            } => {
+                self.follow(object_id, link_type.as_deref(), direction)
+                    .await
+            }
+        }
+    }
+
+    /// Links touching `object_id`, or with either end in the script's module, whose
+    /// far end the script may read.
+    async fn links(&self, object_id: Option<Uuid>) -> Result<Value, CoreError> {
+        let ends = match object_id {
+            Some(id) => {
+                let obj = object::Entity::find_by_id(id)
+                    .one(self.db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("object {id} not found")))?;
+                self.require_read(obj.module_id).await?;
+                Condition::any()
+                    .add(link::Column::SourceObjectId.eq(id))
+                    .add(link::Column::TargetObjectId.eq(id))
+            }
+            None => {
+                let in_module = object::Entity::find()
+                    .select_only()
+                    .column(object::Column::Id)
+                    .filter(object::Column::ModuleId.eq(self.module_id))
+                    .into_query();
+                Condition::any()
+                    .add(link::Column::SourceObjectId.in_subquery(in_module.clone()))
+                    .add(link::Column::TargetObjectId.in_subquery(in_module))
+            }
+        };
+        let links = link::Entity::find().filter(ends).all(self.db).await?;
+        if links.is_empty() {
+            return Ok(json!([]));
+        }
+
+        let end_ids: HashSet<Uuid> = links
+            .iter()
+            .flat_map(|l| [l.source_object_id, l.target_object_id])
+            .collect();
+        let module_of: HashMap<Uuid, Uuid> = object::Entity::find()
+            .select_only()
+            .column(object::Column::Id)
+            .column(object::Column::ModuleId)
+            .filter(object::Column::Id.is_in(end_ids))
+            .into_tuple::<(Uuid, Uuid)>()
+            .all(self.db)
+            .await?
+            .into_iter()
+            .collect();
+        let mut visible = Vec::new();
+        for l in links {
+            let readable = match (
+                module_of.get(&l.source_object_id),
+                module_of.get(&l.target_object_id),
+            ) {
+                (Some(&source), Some(&target)) => {
+                    self.can_read(source).await? && self.can_read(target).await?
+                }
+                _ => false,
+            };
+            if readable {
+                visible.push(ScriptLink::from(l));
+            }
+        }
+        Ok(json!(visible))
+    }
+
+    /// Objects at the far end of `object_id`'s links. Objects in modules the
+    /// caller cannot read are left out.
+    async fn follow(
+        &self,
+        object_id: Uuid,
+        link_type: Option<&str>,
+        direction: Direction,
+    ) -> Result<Value, CoreError> {
+        let obj = object::Entity::find_by_id(object_id)
+            .one(self.db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("object {object_id} not found")))?;
+        self.require_read(obj.module_id).await?;
+
+        let ends = match direction {
+            Direction::Out => Condition::all().add(link::Column::SourceObjectId.eq(object_id)),
+            Direction::In => Condition::all().add(link::Column::TargetObjectId.eq(object_id)),
+            Direction::Both => Condition::any()
+                .add(link::Column::SourceObjectId.eq(object_id))
+                .add(link::Column::TargetObjectId.eq(object_id)),
+        };
+        let mut query = link::Entity::find().filter(ends);
+        if let Some(lt) = link_type {
+            query = query.filter(link::Column::LinkTypeId.eq(find_link_type(self.db, lt).await?));
+        }
+        let other_ids: Vec<Uuid> = query
+            .all(self.db)
+            .await?
+            .into_iter()
+            .map(|l| {
+                if l.source_object_id == object_id {
+                    l.target_object_id
+                } else {
+                    l.source_object_id
+                }
+            })
+            .collect();
+        if other_ids.is_empty() {
+            return Ok(json!([]));
+        }
+
+        let mut objects = Vec::new();
+        for o in object::Entity::find()
+            .filter(object::Column::Id.is_in(other_ids))
+            .filter(object::Column::DeletedAt.is_null())
+            .all(self.db)
+            .await?
+        {
+            if self.can_read(o.module_id).await? {
+                objects.push(ScriptObject::from(o));
+            }
+        }
+        Ok(json!(objects))
+    }
+
+    /// Module by id, or by name among the readable modules, preferring the
+    /// script's own project.
+    async fn find_module(&self, name_or_id: &str) -> Result<module::Model, CoreError> {
+        if let Ok(id) = name_or_id.parse::<Uuid>() {
+            let m = module::Entity::find_by_id(id)
+                .one(self.db)
+                .await?
+                .ok_or_else(|| CoreError::not_found(format!("module {id} not found")))?;
+            self.require_read(m.id).await?;
+            return Ok(m);
+        }
+
+        let mut candidates = Vec::new();
+        for m in module::Entity::find()
+            .filter(module::Column::Name.eq(name_or_id))
+            .all(self.db)
+            .await?
+        {
+            if self.can_read(m.id).await? {
+                candidates.push(m);
+            }
+        }
+        if candidates.len() > 1 {
+            let home = self.home_module().await?;
+            if candidates.iter().any(|m| m.project_id == home.project_id) {
+                candidates.retain(|m| m.project_id == home.project_id);
+            }
+        }
+        match candidates.len() {
+            0 => Err(CoreError::not_found(format!(
+                "module '{name_or_id}' not found"
+            ))),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(CoreError::bad_request(format!(
+                "module name '{name_or_id}' is ambiguous, use its id"
+            ))),
+        }
+    }
+
+    async fn require_read(&self, module_id: Uuid) -> Result<(), CoreError> {
+        if self.can_read(module_id).await? {
+            Ok(())
+        } else {
+            Err(CoreError::forbidden(format!(
+                "read access to module {module_id} required"
+            )))
+        }
+    }
+
+    pub(super) async fn can_read(&self, module_id: Uuid) -> Result<bool, CoreError> {
+        if let Some(known) = self.cached(module_id) {
+            return Ok(known);
+        }
+        let readable = match self.user().await? {
+            Some(user) => PermissionService::module_permission(self.db, user, module_id)
+                .await?
+                .is_some(),
+            None => false,
+        };
+        if let Ok(mut cache) = self.readable.lock() {
+            let _ = cache.insert(module_id, readable);
+        }
+        Ok(readable)
+    }
+
+    /// Whether the script's mutations may change data in `module_id`: its own module,
+    /// or others the user has write access to.
+    pub(super) async fn can_write(&self, module_id: Uuid) -> Result<bool, CoreError> {
+        if module_id == self.module_id {
+            return Ok(true);
+        }
+        match self.user().await? {
+            Some(user) => Ok(
+                PermissionService::module_permission(self.db, user, module_id)
+                    .await?
+                    .is_some_and(|p| p >= Permission::Write),
+            ),
+            None => Ok(false),
+        }
+    }
+
+    fn cached(&self, module_id: Uuid) -> Option<bool> {
+        self.readable
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&module_id).copied())
+    }
+
+    async fn user(&self) -> Result<Option<&AuthUser>, CoreError> {
+        let user = self
+            .user
+            .get_or_try_init(|| async {
+                let Some(id) = self.user_id else {
+                    return Ok::<_, CoreError>(None);
+                };
+                Ok(app_user::Entity::find_by_id(id)
+                    .one(self.db)
+                    .await?
+                    .filter(|u| u.active)
+                    .map(|u| AuthUser {
+                        id: u.id,
+                        email: u.email,
+                        role: u.role,
+                    }))
+            })
+            .await?;
+        Ok(user.as_ref())
+    }
+
+    async fn home_module(&self) -> Result<module::Model, CoreError> {
+        module::Entity::find_by_id(self.module_id)
+            .one(self.db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("module {} not found", self.module_id)))
+    }
+}
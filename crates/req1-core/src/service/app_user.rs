@@ -9,6 +9,8 @@ use entity::app_user;
 
 use crate::PaginatedResponse;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
 
 const VALID_ROLES: &[&str] = &["admin", "editor", "reviewer", "viewer"];
 
@@ -45,6 +47,7 @@ pub struct AppUserService;
 impl AppUserService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateAppUserInput,
     ) -> Result<app_user::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -69,11 +72,14 @@ impl AppUserService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<app_user::Entity>(db, ctx, "create", result.id, None, Some(&result))
+            .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateAppUserInput,
     ) -> Result<app_user::Model, CoreError> {
@@ -82,7 +88,7 @@ impl AppUserService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("app_user {id} not found")))?;
 
-        let mut active: app_user::ActiveModel = existing.into();
+        let mut active: app_user::ActiveModel = existing.clone().into();
         if let Some(display_name) = input.display_name {
             active.display_name = Set(display_name);
         }
@@ -100,15 +106,24 @@ impl AppUserService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<app_user::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = app_user::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("app_user {id} not found")));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<app_user::Entity>(db, ctx, id, "app_user").await
     }
 
     pub async fn get(db: &impl ConnectionTrait, id: Uuid) -> Result<app_user::Model, CoreError> {
@@ -5,12 +5,14 @@ use uuid::Uuid;
 use entity::attachment;
 
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 pub struct AttachmentService;
 
 impl AttachmentService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         object_id: Uuid,
         file_name: String,
         content_type: String,
@@ -47,6 +49,15 @@ impl AttachmentService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<attachment::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -68,7 +79,11 @@ impl AttachmentService {
             .ok_or_else(|| CoreError::not_found(format!("attachment {id} not found")))
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
         let existing = Self::get(db, id).await?;
 
         // Remove file from filesystem
@@ -79,11 +94,9 @@ impl AttachmentService {
             );
         }
 
-        let result = attachment::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("attachment {id} not found")));
-        }
-        Ok(())
+        let _ = attachment::Entity::delete_by_id(id).exec(db).await?;
+        AuditService::record::<attachment::Entity>(db, ctx, "delete", id, Some(&existing), None)
+            .await
     }
 
     pub fn read_file(storage_path: &str) -> Result<Vec<u8>, CoreError> {
@@ -2,15 +2,57 @@ use sea_orm::{
     ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, Order, PaginatorTrait,
     QueryFilter, QueryOrder, Set,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use utoipa::IntoParams;
 use uuid::Uuid;
 
 use entity::audit_log;
 
 use crate::PaginatedResponse;
+use crate::auth::AuthUser;
 use crate::error::CoreError;
 
+/// Who performed a mutation and from where. Passed to every mutating service call so the
+/// audit record is written in the same transaction as the change.
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub user_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    /// Name of the script making the change, if it did not come from the user directly.
+    pub script: Option<String>,
+}
+
+impl AuditContext {
+    pub fn new(user: &AuthUser, ip_address: Option<String>) -> Self {
+        Self {
+            user_id: Some(user.id),
+            ip_address,
+            script: None,
+        }
+    }
+
+    /// Context for changes made by the server itself (seeding, scheduled jobs).
+    pub fn system() -> Self {
+        Self::default()
+    }
+
+    /// Same user and address, but attributing the change to the named script.
+    pub fn for_script(&self, name: &str) -> Self {
+        Self {
+            script: Some(name.to_owned()),
+            ..self.clone()
+        }
+    }
+
+    /// `script:<name>` for script-originated changes, otherwise the user id.
+    pub fn actor(&self) -> Option<String> {
+        self.script
+            .as_ref()
+            .map(|name| format!("script:{name}"))
+            .or_else(|| self.user_id.map(|id| id.to_string()))
+    }
+}
+
 const fn default_limit() -> u64 {
     50
 }
@@ -54,6 +96,58 @@ impl AuditService {
         Ok(())
     }
 
+    /// Record an action that is not a single-row change, e.g. an import into a module.
+    pub async fn event(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        action: &str,
+        entity_type: &str,
+        entity_id: Uuid,
+        details: serde_json::Value,
+    ) -> Result<(), CoreError> {
+        Self::log(
+            db,
+            ctx.user_id,
+            action,
+            entity_type,
+            Some(entity_id),
+            Some(details),
+            ctx.ip_address.clone(),
+        )
+        .await
+    }
+
+    /// Record `action` on an entity row, with its state before and after the change.
+    /// The entity type is the table name of `E`.
+    pub async fn record<E>(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        action: &str,
+        entity_id: Uuid,
+        before: Option<&E::Model>,
+        after: Option<&E::Model>,
+    ) -> Result<(), CoreError>
+    where
+        E: EntityTrait,
+        E::Model: Serialize,
+    {
+        let details = serde_json::json!({
+            "actor": ctx.actor(),
+            "before": before,
+            "after": after,
+        });
+        Self::log(
+            db,
+            ctx.user_id,
+            action,
+            E::default().table_name(),
+            Some(entity_id),
+            Some(details),
+            ctx.ip_address.clone(),
+        )
+        .await
+    }
+
     pub async fn list(
         db: &impl ConnectionTrait,
         filter: AuditLogFilter,
@@ -7,6 +7,7 @@ use entity::app_user;
 
 use crate::auth::{AuthUser, Claims};
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
@@ -19,6 +20,7 @@ pub struct AuthService;
 impl AuthService {
     pub async fn register(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         email: &str,
         password: &str,
         display_name: &str,
@@ -52,6 +54,8 @@ impl AuthService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<app_user::Entity>(db, ctx, "create", result.id, None, Some(&result))
+            .await?;
         Ok(result)
     }
 
@@ -144,6 +148,7 @@ impl AuthService {
 
     pub async fn change_password(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         user_id: Uuid,
         old_password: &str,
         new_password: &str,
@@ -173,7 +178,16 @@ impl AuthService {
         let mut active: app_user::ActiveModel = user.into();
         active.password_hash = Set(Some(new_hash));
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
-        let _ = active.update(db).await?;
+        let result = active.update(db).await?;
+        AuditService::record::<app_user::Entity>(
+            db,
+            ctx,
+            "change_password",
+            user_id,
+            None,
+            Some(&result),
+        )
+        .await?;
 
         Ok(())
     }
@@ -1,16 +1,24 @@
+use std::collections::{HashMap, HashSet};
+
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, Set,
-    Statement,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QueryResult, Set, Statement,
 };
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use entity::{baseline, baseline_entry};
+use entity::{baseline, baseline_entry, baseline_link, object};
 
 use crate::PaginatedResponse;
 use crate::baseline as baseline_core;
 use crate::error::CoreError;
+use crate::fingerprint::{FingerprintPolicy, compute_content_fingerprint};
+use crate::history::{self, Content, HistoryEntry};
+use crate::level;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
+use crate::suspect::{self, SuspectTrigger};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateBaselineInput {
@@ -25,6 +33,7 @@ pub struct BaselineWithEntries {
     #[serde(flatten)]
     pub baseline: baseline::Model,
     pub entries: Vec<baseline_entry::Model>,
+    pub links: Vec<baseline_link::Model>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -40,6 +49,12 @@ pub struct BaselineDiff {
     pub added: Vec<DiffEntry>,
     pub removed: Vec<DiffEntry>,
     pub modified: Vec<DiffModified>,
+    /// Objects present in both baselines whose parent or position changed, with their
+    /// old and new outline levels.
+    pub moved: Vec<DiffMoved>,
+    pub links_added: Vec<DiffLink>,
+    pub links_removed: Vec<DiffLink>,
+    pub links_retyped: Vec<DiffLinkRetyped>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -52,6 +67,25 @@ pub struct DiffEntry {
     pub attributes: Option<serde_json::Value>,
 }
 
+impl DiffEntry {
+    fn new(object_id: Uuid, version: i32, content: Content) -> Self {
+        Self {
+            object_id,
+            version,
+            heading: content.heading,
+            body: content.body,
+            attributes: attributes_value(content.attributes),
+        }
+    }
+}
+
+/// Folded attributes as reported in a diff; an object without attributes has none.
+fn attributes_value(
+    attributes: serde_json::Map<String, serde_json::Value>,
+) -> Option<serde_json::Value> {
+    (!attributes.is_empty()).then_some(serde_json::Value::Object(attributes))
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct DiffModified {
     pub object_id: Uuid,
@@ -67,11 +101,82 @@ pub struct DiffModified {
     pub attributes_b: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiffMoved {
+    pub object_id: Uuid,
+    pub parent_id_a: Option<Uuid>,
+    pub parent_id_b: Option<Uuid>,
+    pub position_a: i32,
+    pub position_b: i32,
+    pub level_a: Option<String>,
+    pub level_b: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiffLink {
+    pub link_id: Uuid,
+    pub source_object_id: Uuid,
+    pub target_object_id: Uuid,
+    pub link_type_id: Uuid,
+    pub suspect: bool,
+}
+
+impl From<baseline_link::Model> for DiffLink {
+    fn from(l: baseline_link::Model) -> Self {
+        Self {
+            link_id: l.link_id,
+            source_object_id: l.source_object_id,
+            target_object_id: l.target_object_id,
+            link_type_id: l.link_type_id,
+            suspect: l.suspect,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiffLinkRetyped {
+    pub link_id: Uuid,
+    pub source_object_id: Uuid,
+    pub target_object_id: Uuid,
+    pub link_type_id_a: Uuid,
+    pub link_type_id_b: Uuid,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RestoreBaselineInput {
+    /// Objects to restore; all objects of the baseline when omitted. Only a full
+    /// restore soft-deletes objects created after the baseline.
+    pub object_ids: Option<Vec<Uuid>>,
+    /// Return the planned changes without applying them.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A planned or applied change for one object.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreChange {
+    pub object_id: Uuid,
+    /// Any of `undelete`, `revert`, `move`, `remove`; `missing` when the object was
+    /// hard-deleted and cannot be restored.
+    pub actions: Vec<String>,
+    pub current_version: Option<i32>,
+    pub baselined_version: Option<i32>,
+    pub heading: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreResult {
+    pub baseline_id: Uuid,
+    pub dry_run: bool,
+    pub changes: Vec<RestoreChange>,
+}
+
 pub struct BaselineService;
 
 impl BaselineService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateBaselineInput,
     ) -> Result<BaselineWithEntries, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -82,18 +187,22 @@ impl BaselineService {
             module_id: Set(input.module_id),
             name: Set(input.name),
             description: Set(input.description),
-            created_by: Set(None),
+            created_by: Set(ctx.user_id),
             created_at: Set(now),
             locked: Set(true),
             baseline_set_id: Set(input.baseline_set_id),
         };
 
         let bl = model.insert(db).await?;
+        AuditService::record::<baseline::Entity>(db, ctx, "create", id, None, Some(&bl)).await?;
         let entries = baseline_core::snapshot_baseline(db, id, input.module_id).await?;
+        let object_ids = entries.iter().map(|e| e.object_id).collect();
+        let links = baseline_core::snapshot_links(db, id, object_ids).await?;
 
         Ok(BaselineWithEntries {
             baseline: bl,
             entries,
+            links,
         })
     }
 
@@ -110,19 +219,24 @@ impl BaselineService {
             .filter(baseline_entry::Column::BaselineId.eq(id))
             .all(db)
             .await?;
+        let links = baseline_link::Entity::find()
+            .filter(baseline_link::Column::BaselineId.eq(id))
+            .all(db)
+            .await?;
 
         Ok(BaselineWithEntries {
             baseline: bl,
             entries,
+            links,
         })
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = baseline::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("baseline {id} not found")));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<baseline::Entity>(db, ctx, id, "baseline").await
     }
 
     pub async fn list(
@@ -146,12 +260,18 @@ impl BaselineService {
         })
     }
 
+    /// List baselines across modules, restricted to `visible` module ids when given.
     pub async fn list_all(
         db: &impl ConnectionTrait,
+        visible: Option<Vec<Uuid>>,
         offset: u64,
         limit: u64,
     ) -> Result<PaginatedResponse<baseline::Model>, CoreError> {
-        let paginator = baseline::Entity::find().paginate(db, limit);
+        let mut select = baseline::Entity::find();
+        if let Some(module_ids) = visible {
+            select = select.filter(baseline::Column::ModuleId.is_in(module_ids));
+        }
+        let paginator = select.paginate(db, limit);
         let total = paginator.num_items().await?;
         let page = offset / limit;
         let items = paginator.fetch_page(page).await?;
@@ -173,16 +293,16 @@ impl BaselineService {
                 COALESCE(a.object_id, b.object_id) AS object_id,
                 a.version AS version_a,
                 b.version AS version_b,
-                ha.heading AS heading_a,
-                hb.heading AS heading_b,
-                ha.body AS body_a,
-                hb.body AS body_b,
-                ha.attribute_values AS attributes_a,
-                hb.attribute_values AS attributes_b
+                CASE WHEN a.position IS NULL THEN ha.parent_id ELSE a.parent_id END AS parent_id_a,
+                CASE WHEN b.position IS NULL THEN hb.parent_id ELSE b.parent_id END AS parent_id_b,
+                COALESCE(a.position, ha.position) AS position_a,
+                COALESCE(b.position, hb.position) AS position_b,
+                COALESCE(a.level, ha.level) AS level_a,
+                COALESCE(b.level, hb.level) AS level_b
             FROM
-                (SELECT object_id, version FROM baseline_entry WHERE baseline_id = $1) a
+                (SELECT * FROM baseline_entry WHERE baseline_id = $1) a
             FULL OUTER JOIN
-                (SELECT object_id, version FROM baseline_entry WHERE baseline_id = $2) b
+                (SELECT * FROM baseline_entry WHERE baseline_id = $2) b
                 ON a.object_id = b.object_id
             LEFT JOIN object_history ha
                 ON ha.object_id = a.object_id AND ha.version = a.version
@@ -197,64 +317,467 @@ impl BaselineService {
         );
 
         let rows = db.query_all(stmt).await?;
+        let moved_ids = Self::moved_objects(&rows)?;
+
+        // History rows carry only the fields each change set, so compare the content
+        // folded up to each baselined version
+        let mut versions_a = HashMap::new();
+        let mut versions_b = HashMap::new();
+        for row in &rows {
+            let object_id: Uuid = row.try_get("", "object_id")?;
+            if let Some(va) = row.try_get::<Option<i32>>("", "version_a")? {
+                let _ = versions_a.insert(object_id, va);
+            }
+            if let Some(vb) = row.try_get::<Option<i32>>("", "version_b")? {
+                let _ = versions_b.insert(object_id, vb);
+            }
+        }
+        let mut contents_a = history::contents_at(db, &versions_a).await?;
+        let mut contents_b = history::contents_at(db, &versions_b).await?;
 
         let mut added = Vec::new();
         let mut removed = Vec::new();
         let mut modified = Vec::new();
+        let mut moved = Vec::new();
 
         for row in &rows {
             let object_id: Uuid = row.try_get("", "object_id")?;
             let version_a: Option<i32> = row.try_get("", "version_a")?;
             let version_b: Option<i32> = row.try_get("", "version_b")?;
-            let heading_a: Option<String> = row.try_get("", "heading_a")?;
-            let heading_b: Option<String> = row.try_get("", "heading_b")?;
-            let body_a: Option<String> = row.try_get("", "body_a")?;
-            let body_b: Option<String> = row.try_get("", "body_b")?;
-            let attributes_a: Option<serde_json::Value> = row.try_get("", "attributes_a")?;
-            let attributes_b: Option<serde_json::Value> = row.try_get("", "attributes_b")?;
+            let content_a = contents_a.remove(&object_id).unwrap_or_default();
+            let content_b = contents_b.remove(&object_id).unwrap_or_default();
+            let parent_id_a: Option<Uuid> = row.try_get("", "parent_id_a")?;
+            let parent_id_b: Option<Uuid> = row.try_get("", "parent_id_b")?;
+            let position_a: Option<i32> = row.try_get("", "position_a")?;
+            let position_b: Option<i32> = row.try_get("", "position_b")?;
+            let level_a: Option<String> = row.try_get("", "level_a")?;
+            let level_b: Option<String> = row.try_get("", "level_b")?;
+
+            if moved_ids.contains(&object_id)
+                && let (Some(pa), Some(pb)) = (position_a, position_b)
+            {
+                moved.push(DiffMoved {
+                    object_id,
+                    parent_id_a,
+                    parent_id_b,
+                    position_a: pa,
+                    position_b: pb,
+                    level_a,
+                    level_b,
+                });
+            }
 
             match (version_a, version_b) {
                 (None, Some(vb)) => {
-                    added.push(DiffEntry {
-                        object_id,
-                        version: vb,
-                        heading: heading_b,
-                        body: body_b,
-                        attributes: attributes_b,
-                    });
+                    added.push(DiffEntry::new(object_id, vb, content_b));
                 }
                 (Some(va), None) => {
-                    removed.push(DiffEntry {
-                        object_id,
-                        version: va,
-                        heading: heading_a,
-                        body: body_a,
-                        attributes: attributes_a,
-                    });
+                    removed.push(DiffEntry::new(object_id, va, content_a));
                 }
-                (Some(va), Some(vb)) if va != vb => {
+                // A version bumped only by a move is reported under `moved` alone
+                (Some(va), Some(vb)) if content_a != content_b => {
                     modified.push(DiffModified {
                         object_id,
                         version_a: va,
                         version_b: vb,
-                        heading_a,
-                        heading_b,
-                        body_a,
-                        body_b,
-                        attributes_a,
-                        attributes_b,
+                        heading_a: content_a.heading,
+                        heading_b: content_b.heading,
+                        body_a: content_a.body,
+                        body_b: content_b.body,
+                        attributes_a: attributes_value(content_a.attributes),
+                        attributes_b: attributes_value(content_b.attributes),
                     });
                 }
                 _ => {}
             }
         }
 
+        let (links_added, links_removed, links_retyped) =
+            Self::diff_links(db, input.a, input.b).await?;
+
         Ok(BaselineDiff {
             baseline_a: input.a,
             baseline_b: input.b,
             added,
             removed,
             modified,
+            moved,
+            links_added,
+            links_removed,
+            links_retyped,
+        })
+    }
+
+    /// Objects that changed parent, or whose order among siblings kept in both
+    /// baselines changed. Inserting or removing an object shifts the positions of its
+    /// siblings without moving them. An object counts as moved when both of its
+    /// neighbours among the kept siblings differ, so moving one object past others
+    /// reports that object only.
+    fn moved_objects(rows: &[QueryResult]) -> Result<HashSet<Uuid>, CoreError> {
+        let mut moved = HashSet::new();
+        let mut kept: HashMap<Option<Uuid>, Vec<(Uuid, i32, i32)>> = HashMap::new();
+        for row in rows {
+            let object_id: Uuid = row.try_get("", "object_id")?;
+            let parent_id_a: Option<Uuid> = row.try_get("", "parent_id_a")?;
+            let parent_id_b: Option<Uuid> = row.try_get("", "parent_id_b")?;
+            let position_a: Option<i32> = row.try_get("", "position_a")?;
+            let position_b: Option<i32> = row.try_get("", "position_b")?;
+            // Baselines taken before structure snapshots fall back to the structure
+            // recorded in history; rows older than that have no position to compare
+            let (Some(pa), Some(pb)) = (position_a, position_b) else {
+                continue;
+            };
+            if parent_id_a == parent_id_b {
+                kept.entry(parent_id_a)
+                    .or_default()
+                    .push((object_id, pa, pb));
+            } else {
+                moved.insert(object_id);
+            }
+        }
+
+        for siblings in kept.values_mut() {
+            let neighbours = |siblings: &mut Vec<(Uuid, i32, i32)>, by_b: bool| {
+                siblings.sort_by_key(|s| (if by_b { s.2 } else { s.1 }, s.0));
+                siblings
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| {
+                        let prev = i.checked_sub(1).map(|p| siblings[p].0);
+                        let next = siblings.get(i + 1).map(|n| n.0);
+                        (s.0, (prev, next))
+                    })
+                    .collect::<HashMap<_, _>>()
+            };
+            let in_a = neighbours(siblings, false);
+            let in_b = neighbours(siblings, true);
+            for (id, (prev_a, next_a)) in in_a {
+                let (prev_b, next_b) = in_b[&id];
+                if prev_a != prev_b && next_a != next_b {
+                    moved.insert(id);
+                }
+            }
+        }
+        Ok(moved)
+    }
+
+    /// Restore objects to their baselined content and hierarchy. Reverted objects get a
+    /// new version with change type `restore`.
+    #[allow(clippy::too_many_lines)]
+    pub async fn restore(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        module_id: Uuid,
+        id: Uuid,
+        input: RestoreBaselineInput,
+    ) -> Result<RestoreResult, CoreError> {
+        let bl = baseline::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .filter(|b| b.module_id == module_id)
+            .ok_or_else(|| CoreError::not_found(format!("baseline {id} not found")))?;
+
+        let mut entries = baseline_entry::Entity::find()
+            .filter(baseline_entry::Column::BaselineId.eq(id))
+            .order_by(baseline_entry::Column::Level, Order::Asc)
+            .all(db)
+            .await?;
+        if let Some(ids) = &input.object_ids {
+            let baselined: HashSet<Uuid> = entries.iter().map(|e| e.object_id).collect();
+            if let Some(missing) = ids.iter().find(|o| !baselined.contains(o)) {
+                return Err(CoreError::bad_request(format!(
+                    "object {missing} is not part of baseline {id}"
+                )));
+            }
+            entries.retain(|e| ids.contains(&e.object_id));
+        }
+
+        let current: HashMap<Uuid, object::Model> = object::Entity::find()
+            .filter(object::Column::ModuleId.eq(bl.module_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|o| (o.id, o))
+            .collect();
+
+        let versions: HashMap<Uuid, i32> = entries
+            .iter()
+            .filter(|e| current.contains_key(&e.object_id))
+            .map(|e| (e.object_id, e.version))
+            .collect();
+        let baselined = history::contents_at(db, &versions).await?;
+
+        let mut changes = Vec::new();
+        let mut restored = Vec::new();
+        let mut placed: HashSet<Option<Uuid>> = HashSet::new();
+        for entry in &entries {
+            let Some(obj) = current.get(&entry.object_id) else {
+                changes.push(RestoreChange {
+                    object_id: entry.object_id,
+                    actions: vec!["missing".to_owned()],
+                    current_version: None,
+                    baselined_version: Some(entry.version),
+                    heading: None,
+                });
+                continue;
+            };
+            let snapshot = baselined.get(&entry.object_id);
+
+            let mut actions = Vec::new();
+            if obj.deleted_at.is_some() {
+                actions.push("undelete".to_owned());
+            }
+            let revert = snapshot.filter(|c| **c != Content::of(obj));
+            if revert.is_some() {
+                actions.push("revert".to_owned());
+            }
+            // Baselines taken before structure snapshots carry no position; a parent that
+            // no longer exists is left as is
+            let structure = entry
+                .position
+                .filter(|&p| p != obj.position || entry.parent_id != obj.parent_id)
+                .filter(|_| entry.parent_id.is_none_or(|p| current.contains_key(&p)));
+            if structure.is_some() {
+                actions.push("move".to_owned());
+            }
+            if actions.is_empty() {
+                continue;
+            }
+
+            changes.push(RestoreChange {
+                object_id: obj.id,
+                actions,
+                current_version: Some(obj.current_version),
+                baselined_version: Some(entry.version),
+                heading: snapshot.map_or(obj.heading.clone(), |c| c.heading.clone()),
+            });
+            if input.dry_run {
+                continue;
+            }
+
+            let new_version = obj.current_version + 1;
+            let mut active: object::ActiveModel = obj.clone().into();
+            active.deleted_at = Set(None);
+            active.current_version = Set(new_version);
+            active.updated_at = Set(chrono::Utc::now().fixed_offset());
+            if let Some(c) = revert {
+                let attributes = serde_json::Value::Object(c.attributes.clone());
+                let policy =
+                    FingerprintPolicy::resolve(db, bl.module_id, obj.object_type_id).await?;
+                let fp = compute_content_fingerprint(
+                    c.heading.as_deref(),
+                    c.body.as_deref(),
+                    Some(&attributes),
+                    &policy,
+                );
+                active.heading = Set(c.heading.clone());
+                active.body = Set(c.body.clone());
+                active.attributes = Set(Some(attributes.clone()));
+                active.content_fingerprint = Set(fp.clone());
+                active.reviewed_fingerprint = Set(None);
+                active.reviewed_at = Set(None);
+                active.reviewed_by = Set(None);
+                let changed = suspect::changed_fields(
+                    &policy,
+                    (
+                        obj.heading.as_deref(),
+                        obj.body.as_deref(),
+                        obj.attributes.as_ref(),
+                    ),
+                    (c.heading.as_deref(), c.body.as_deref(), Some(&attributes)),
+                );
+                let _ = suspect::flag_suspect_links(
+                    db,
+                    ctx,
+                    &SuspectTrigger {
+                        object_id: obj.id,
+                        version: new_version,
+                        classification: &obj.classification,
+                        fingerprint: &fp,
+                        changed,
+                    },
+                )
+                .await?;
+            }
+            if let Some(position) = structure {
+                active.parent_id = Set(entry.parent_id);
+                active.position = Set(position);
+                let _ = placed.insert(entry.parent_id);
+            } else if obj.deleted_at.is_some() {
+                let _ = placed.insert(obj.parent_id);
+            }
+            let _ = active.update(db).await?;
+            restored.push(obj);
+        }
+
+        // A partial restore puts objects back among siblings that kept their positions
+        if input.object_ids.is_some() && !input.dry_run {
+            let restored_ids: HashSet<Uuid> = restored.iter().map(|o| o.id).collect();
+            for parent_id in placed {
+                renumber_siblings(db, bl.module_id, parent_id, &restored_ids).await?;
+            }
+        }
+
+        // Objects created after the baseline are only removed by a full restore
+        let mut removed = Vec::new();
+        if input.object_ids.is_none() {
+            let baselined: HashSet<Uuid> = entries.iter().map(|e| e.object_id).collect();
+            let mut added: Vec<&object::Model> = current
+                .values()
+                .filter(|o| o.deleted_at.is_none() && !baselined.contains(&o.id))
+                .collect();
+            added.sort_by(|a, b| a.level.cmp(&b.level));
+            for obj in added {
+                changes.push(RestoreChange {
+                    object_id: obj.id,
+                    actions: vec!["remove".to_owned()],
+                    current_version: Some(obj.current_version),
+                    baselined_version: None,
+                    heading: obj.heading.clone(),
+                });
+                if !input.dry_run {
+                    let mut active: object::ActiveModel = obj.clone().into();
+                    active.deleted_at = Set(Some(chrono::Utc::now().fixed_offset()));
+                    removed.push((obj, active.update(db).await?));
+                }
+            }
+        }
+
+        if !input.dry_run {
+            level::recompute_module_levels(db, bl.module_id).await?;
+
+            for before in restored {
+                let after = object::Entity::find_by_id(before.id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| {
+                        CoreError::internal("object not found after restore".to_owned())
+                    })?;
+                history::insert_history(
+                    db,
+                    ctx,
+                    HistoryEntry {
+                        object_id: after.id,
+                        module_id: after.module_id,
+                        version: after.current_version,
+                        attribute_values: after.attributes.clone(),
+                        heading: after.heading.clone(),
+                        body: after.body.clone(),
+                        change_type: "restore".to_owned(),
+                        state: None,
+                    },
+                )
+                .await?;
+                AuditService::record::<object::Entity>(
+                    db,
+                    ctx,
+                    "restore",
+                    after.id,
+                    Some(before),
+                    Some(&after),
+                )
+                .await?;
+            }
+            for (before, after) in removed {
+                AuditService::record::<object::Entity>(
+                    db,
+                    ctx,
+                    "soft_delete",
+                    before.id,
+                    Some(before),
+                    Some(&after),
+                )
+                .await?;
+            }
+
+            AuditService::event(
+                db,
+                ctx,
+                "restore",
+                "baseline",
+                id,
+                serde_json::json!({
+                    "module_id": bl.module_id,
+                    "object_ids": input.object_ids,
+                    "changes": changes.len(),
+                }),
+            )
+            .await?;
+        }
+
+        Ok(RestoreResult {
+            baseline_id: id,
+            dry_run: input.dry_run,
+            changes,
         })
     }
+
+    async fn diff_links(
+        db: &impl ConnectionTrait,
+        a: Uuid,
+        b: Uuid,
+    ) -> Result<(Vec<DiffLink>, Vec<DiffLink>, Vec<DiffLinkRetyped>), CoreError> {
+        let load = |id: Uuid| {
+            baseline_link::Entity::find()
+                .filter(baseline_link::Column::BaselineId.eq(id))
+                .all(db)
+        };
+        let mut links_a: HashMap<Uuid, baseline_link::Model> =
+            load(a).await?.into_iter().map(|l| (l.link_id, l)).collect();
+        let links_b = load(b).await?;
+
+        let mut added = Vec::new();
+        let mut retyped = Vec::new();
+        for lb in links_b {
+            match links_a.remove(&lb.link_id) {
+                None => added.push(DiffLink::from(lb)),
+                Some(la) if la.link_type_id != lb.link_type_id => {
+                    retyped.push(DiffLinkRetyped {
+                        link_id: lb.link_id,
+                        source_object_id: lb.source_object_id,
+                        target_object_id: lb.target_object_id,
+                        link_type_id_a: la.link_type_id,
+                        link_type_id_b: lb.link_type_id,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        let mut removed: Vec<DiffLink> = links_a.into_values().map(DiffLink::from).collect();
+        removed.sort_by_key(|l| l.link_id);
+
+        Ok((added, removed, retyped))
+    }
+}
+
+/// Renumber the live children of `parent_id` to consecutive positions. A restored object
+/// goes before a sibling that holds the same position.
+async fn renumber_siblings(
+    db: &impl ConnectionTrait,
+    module_id: Uuid,
+    parent_id: Option<Uuid>,
+    restored: &HashSet<Uuid>,
+) -> Result<(), CoreError> {
+    let mut siblings = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .filter(if parent_id.is_some() {
+            object::Column::ParentId.eq(parent_id)
+        } else {
+            object::Column::ParentId.is_null()
+        })
+        .filter(object::Column::DeletedAt.is_null())
+        .all(db)
+        .await?;
+    siblings.sort_by_key(|o| (o.position, !restored.contains(&o.id)));
+
+    for (index, sibling) in siblings.into_iter().enumerate() {
+        let position = i32::try_from(index)
+            .map_err(|_| CoreError::internal("too many siblings to renumber".to_owned()))?;
+        if sibling.position != position {
+            let mut active: object::ActiveModel = sibling.into();
+            active.position = Set(position);
+            let _ = active.update(db).await?;
+        }
+    }
+    Ok(())
 }
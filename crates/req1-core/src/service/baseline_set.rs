@@ -1,12 +1,19 @@
-use sea_orm::{ActiveModelTrait, ConnectionTrait, EntityTrait, Set};
-use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, Order, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use entity::baseline_set;
+use entity::{baseline, baseline_entry, baseline_link, baseline_set, object, object_history};
 
 use crate::crud_service;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateBaselineSetInput {
@@ -23,11 +30,60 @@ pub struct UpdateBaselineSetInput {
     pub description: Option<String>,
 }
 
+/// A baseline belonging to a set.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetMember {
+    pub module_id: Uuid,
+    pub baseline_id: Uuid,
+    pub name: String,
+    /// Another baseline of the same module in this set is newer.
+    pub superseded_in_set: bool,
+    /// Newest later baseline of the same module, in or outside the set.
+    pub superseded_by: Option<Uuid>,
+}
+
+/// A cross-module link end that the set does not freeze consistently.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConsistencyIssue {
+    /// `module_missing`, `object_missing` or `version_mismatch`
+    pub kind: String,
+    pub link_id: Uuid,
+    pub object_id: Uuid,
+    pub module_id: Option<Uuid>,
+    /// Version of the object when the linking baseline was taken.
+    pub expected_version: Option<i32>,
+    /// Version frozen by the set's baseline of the object's module.
+    pub baselined_version: Option<i32>,
+    pub message: String,
+}
+
+/// Cross-module trace counts for one (source module, target module) pair.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModulePairTrace {
+    pub source_module_id: Uuid,
+    pub target_module_id: Uuid,
+    pub links: u64,
+    pub suspect_links: u64,
+    pub inconsistent_links: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BaselineSetConsistency {
+    pub baseline_set_id: Uuid,
+    pub consistent: bool,
+    pub members: Vec<SetMember>,
+    /// Modules reached by cross-module links that have no baseline in the set.
+    pub missing_modules: Vec<Uuid>,
+    pub issues: Vec<ConsistencyIssue>,
+    pub module_pairs: Vec<ModulePairTrace>,
+}
+
 pub struct BaselineSetService;
 
 impl BaselineSetService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateBaselineSetInput,
     ) -> Result<baseline_set::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -43,11 +99,21 @@ impl BaselineSetService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<baseline_set::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateBaselineSetInput,
     ) -> Result<baseline_set::Model, CoreError> {
@@ -56,7 +122,7 @@ impl BaselineSetService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("baseline_set {id} not found")))?;
 
-        let mut active: baseline_set::ActiveModel = existing.into();
+        let mut active: baseline_set::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -68,8 +134,284 @@ impl BaselineSetService {
         }
 
         let result = active.update(db).await?;
+        AuditService::record::<baseline_set::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
+
+    /// Check that every cross-module link frozen in the set points at an object version
+    /// the set also freezes, and summarize the links per module pair.
+    #[allow(clippy::too_many_lines)]
+    pub async fn check_consistency(
+        db: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<BaselineSetConsistency, CoreError> {
+        let _set = baseline_set::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("baseline_set {id} not found")))?;
+
+        let baselines = baseline::Entity::find()
+            .filter(baseline::Column::BaselineSetId.eq(id))
+            .order_by(baseline::Column::CreatedAt, Order::Asc)
+            .all(db)
+            .await?;
+
+        // The latest baseline of each module represents it in the set
+        let effective: HashMap<Uuid, &baseline::Model> =
+            baselines.iter().map(|b| (b.module_id, b)).collect();
+
+        let module_ids: Vec<Uuid> = effective.keys().copied().collect();
+        let effective_ids: Vec<Uuid> = effective.values().map(|b| b.id).collect();
+
+        // Newest first, so the first later baseline of a module is its newest
+        let module_baselines = baseline::Entity::find()
+            .filter(baseline::Column::ModuleId.is_in(module_ids))
+            .order_by(baseline::Column::CreatedAt, Order::Desc)
+            .all(db)
+            .await?;
+        let members = baselines
+            .iter()
+            .map(|b| SetMember {
+                module_id: b.module_id,
+                baseline_id: b.id,
+                name: b.name.clone(),
+                superseded_in_set: effective.get(&b.module_id).is_some_and(|e| e.id != b.id),
+                superseded_by: module_baselines
+                    .iter()
+                    .find(|later| later.module_id == b.module_id && later.created_at > b.created_at)
+                    .map(|later| later.id),
+            })
+            .collect::<Vec<_>>();
+
+        // object -> (module, frozen version) across the effective baselines
+        let module_by_baseline: HashMap<Uuid, Uuid> =
+            effective.values().map(|b| (b.id, b.module_id)).collect();
+        let frozen: HashMap<Uuid, (Uuid, i32)> = baseline_entry::Entity::find()
+            .filter(baseline_entry::Column::BaselineId.is_in(effective_ids.clone()))
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|e| {
+                let module_id = module_by_baseline.get(&e.baseline_id)?;
+                Some((e.object_id, (*module_id, e.version)))
+            })
+            .collect();
+
+        let mut links_by_baseline: HashMap<Uuid, Vec<baseline_link::Model>> = HashMap::new();
+        for l in baseline_link::Entity::find()
+            .filter(baseline_link::Column::BaselineId.is_in(effective_ids))
+            .all(db)
+            .await?
+        {
+            links_by_baseline.entry(l.baseline_id).or_default().push(l);
+        }
+        let ends: HashSet<Uuid> = links_by_baseline
+            .values()
+            .flatten()
+            .flat_map(|l| [l.source_object_id, l.target_object_id])
+            .collect();
+
+        // Link ends the set does not freeze are placed by their live object row
+        let unfrozen: Vec<Uuid> = ends
+            .iter()
+            .filter(|id| !frozen.contains_key(id))
+            .copied()
+            .collect();
+        let live: HashMap<Uuid, Uuid> = object::Entity::find()
+            .select_only()
+            .column(object::Column::Id)
+            .column(object::Column::ModuleId)
+            .filter(object::Column::Id.is_in(unfrozen))
+            .into_tuple::<(Uuid, Uuid)>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+
+        // Version history of the frozen link ends, for the version each link was traced against
+        let frozen_ends: Vec<Uuid> = ends
+            .iter()
+            .filter(|id| frozen.contains_key(id))
+            .copied()
+            .collect();
+        let mut history: HashMap<Uuid, Vec<(i32, DateTimeWithTimeZone)>> = HashMap::new();
+        for (object_id, version, changed_at) in object_history::Entity::find()
+            .select_only()
+            .column(object_history::Column::ObjectId)
+            .column(object_history::Column::Version)
+            .column(object_history::Column::ChangedAt)
+            .filter(object_history::Column::ObjectId.is_in(frozen_ends))
+            .into_tuple::<(Uuid, i32, DateTimeWithTimeZone)>()
+            .all(db)
+            .await?
+        {
+            history
+                .entry(object_id)
+                .or_default()
+                .push((version, changed_at));
+        }
+        let lookup = Lookup {
+            effective: &effective,
+            frozen: &frozen,
+            live: &live,
+            history: &history,
+        };
+
+        let mut seen_links: HashSet<Uuid> = HashSet::new();
+        let mut seen_ends: HashSet<(Uuid, Uuid)> = HashSet::new();
+        let mut missing_modules: HashSet<Uuid> = HashSet::new();
+        let mut issues = Vec::new();
+        let mut pairs: BTreeMap<(Uuid, Uuid), ModulePairTrace> = BTreeMap::new();
+
+        for b in effective.values() {
+            for l in links_by_baseline.remove(&b.id).unwrap_or_default() {
+                let source_module = lookup.module_of(l.source_object_id);
+                let target_module = lookup.module_of(l.target_object_id);
+                if source_module.is_some() && source_module == target_module {
+                    continue;
+                }
+
+                // The end outside this baseline's module must be frozen by the set too
+                let far_end = if source_module == Some(b.module_id) {
+                    (l.target_object_id, target_module)
+                } else {
+                    (l.source_object_id, source_module)
+                };
+                let issue = if seen_ends.insert((l.link_id, far_end.0)) {
+                    lookup.check_link_end(b, l.link_id, far_end)
+                } else {
+                    None
+                };
+                if let Some(issue) = &issue
+                    && issue.kind == "module_missing"
+                    && let Some(m) = issue.module_id
+                {
+                    let _ = missing_modules.insert(m);
+                }
+
+                let first_sighting = seen_links.insert(l.link_id);
+                if let (Some(src), Some(tgt)) = (source_module, target_module) {
+                    let pair = pairs.entry((src, tgt)).or_insert(ModulePairTrace {
+                        source_module_id: src,
+                        target_module_id: tgt,
+                        links: 0,
+                        suspect_links: 0,
+                        inconsistent_links: 0,
+                    });
+                    if first_sighting {
+                        pair.links += 1;
+                        if l.suspect {
+                            pair.suspect_links += 1;
+                        }
+                    }
+                    if issue.is_some() {
+                        pair.inconsistent_links += 1;
+                    }
+                }
+                issues.extend(issue);
+            }
+        }
+
+        let consistent = issues.is_empty() && !members.iter().any(|m| m.superseded_in_set);
+        let mut missing_modules: Vec<Uuid> = missing_modules.into_iter().collect();
+        missing_modules.sort();
+
+        Ok(BaselineSetConsistency {
+            baseline_set_id: id,
+            consistent,
+            members,
+            missing_modules,
+            issues,
+            module_pairs: pairs.into_values().collect(),
+        })
+    }
+}
+
+/// Batched lookups for the links of one consistency check.
+struct Lookup<'a> {
+    effective: &'a HashMap<Uuid, &'a baseline::Model>,
+    /// object -> (module, frozen version)
+    frozen: &'a HashMap<Uuid, (Uuid, i32)>,
+    /// object -> module, for link ends the set does not freeze
+    live: &'a HashMap<Uuid, Uuid>,
+    /// object -> (version, changed at) of every history row
+    history: &'a HashMap<Uuid, Vec<(i32, DateTimeWithTimeZone)>>,
+}
+
+impl Lookup<'_> {
+    /// Module of an object, from the set's frozen entries or else the live object row.
+    fn module_of(&self, object_id: Uuid) -> Option<Uuid> {
+        self.frozen
+            .get(&object_id)
+            .map(|(module_id, _)| *module_id)
+            .or_else(|| self.live.get(&object_id).copied())
+    }
+
+    fn check_link_end(
+        &self,
+        linking: &baseline::Model,
+        link_id: Uuid,
+        (object_id, module_id): (Uuid, Option<Uuid>),
+    ) -> Option<ConsistencyIssue> {
+        let Some(module_id) = module_id.filter(|m| self.effective.contains_key(m)) else {
+            return Some(ConsistencyIssue {
+                kind: "module_missing".to_owned(),
+                link_id,
+                object_id,
+                module_id,
+                expected_version: None,
+                baselined_version: None,
+                message: format!(
+                    "link {link_id} reaches object {object_id} in a module without a baseline in the set"
+                ),
+            });
+        };
+
+        let Some((_, baselined)) = self.frozen.get(&object_id).copied() else {
+            return Some(ConsistencyIssue {
+                kind: "object_missing".to_owned(),
+                link_id,
+                object_id,
+                module_id: Some(module_id),
+                expected_version: None,
+                baselined_version: None,
+                message: format!("object {object_id} is not part of the module's baseline"),
+            });
+        };
+
+        // Version the link was traced against when the linking baseline was taken
+        let expected = self
+            .history
+            .get(&object_id)
+            .into_iter()
+            .flatten()
+            .filter(|(_, changed_at)| *changed_at <= linking.created_at)
+            .map(|(version, _)| *version)
+            .max();
+
+        match expected {
+            Some(v) if v != baselined => Some(ConsistencyIssue {
+                kind: "version_mismatch".to_owned(),
+                link_id,
+                object_id,
+                module_id: Some(module_id),
+                expected_version: Some(v),
+                baselined_version: Some(baselined),
+                message: format!(
+                    "link {link_id} was baselined against version {v} of object {object_id}, but the set freezes version {baselined}"
+                ),
+            }),
+            _ => None,
+        }
+    }
 }
 
 crud_service!(BaselineSetService, baseline_set::Entity, "baseline_set");
@@ -7,6 +7,7 @@ use entity::change_proposal;
 
 use crate::crud_service;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 const VALID_STATUSES: &[&str] = &[
     "draft",
@@ -42,6 +43,7 @@ pub struct ChangeProposalService;
 impl ChangeProposalService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateChangeProposalInput,
     ) -> Result<change_proposal::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -60,11 +62,21 @@ impl ChangeProposalService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<change_proposal::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateChangeProposalInput,
     ) -> Result<change_proposal::Model, CoreError> {
@@ -73,7 +85,7 @@ impl ChangeProposalService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("change_proposal {id} not found")))?;
 
-        let mut active: change_proposal::ActiveModel = existing.into();
+        let mut active: change_proposal::ActiveModel = existing.clone().into();
         if let Some(title) = input.title {
             active.title = Set(title);
         }
@@ -94,6 +106,15 @@ impl ChangeProposalService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<change_proposal::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 }
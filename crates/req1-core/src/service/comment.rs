@@ -7,6 +7,7 @@ use entity::comment;
 
 use crate::crud_service;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 use crate::service::mention::MentionService;
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -28,6 +29,7 @@ pub struct CommentService;
 impl CommentService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateCommentInput,
     ) -> Result<comment::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -50,6 +52,8 @@ impl CommentService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<comment::Entity>(db, ctx, "create", result.id, None, Some(&result))
+            .await?;
 
         if !mentioned_ids.is_empty() {
             MentionService::notify_mentioned(db, &mentioned_ids, author, "comment", id, &body_text)
@@ -61,6 +65,7 @@ impl CommentService {
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateCommentInput,
     ) -> Result<comment::Model, CoreError> {
@@ -69,7 +74,7 @@ impl CommentService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("comment {id} not found")))?;
 
-        let mut active: comment::ActiveModel = existing.into();
+        let mut active: comment::ActiveModel = existing.clone().into();
         if let Some(body) = input.body {
             active.body = Set(body);
         }
@@ -79,6 +84,15 @@ impl CommentService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<comment::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 }
@@ -6,6 +6,7 @@ use uuid::Uuid;
 
 use crate::PaginatedResponse;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 pub async fn get_by_id<E>(
     db: &impl ConnectionTrait,
@@ -25,20 +26,18 @@ where
 
 pub async fn delete_by_id<E>(
     db: &impl ConnectionTrait,
+    ctx: &AuditContext,
     id: Uuid,
     entity_name: &str,
 ) -> Result<(), CoreError>
 where
     E: EntityTrait,
+    <E as EntityTrait>::Model: Sync + Serialize,
     <<E as EntityTrait>::PrimaryKey as PrimaryKeyTrait>::ValueType: From<Uuid>,
 {
-    let result = E::delete_by_id(id).exec(db).await?;
-    if result.rows_affected == 0 {
-        return Err(CoreError::not_found(format!(
-            "{entity_name} {id} not found"
-        )));
-    }
-    Ok(())
+    let existing = get_by_id::<E>(db, id, entity_name).await?;
+    let _ = E::delete_by_id(id).exec(db).await?;
+    AuditService::record::<E>(db, ctx, "delete", id, Some(&existing), None).await
 }
 
 pub async fn list_all<E>(
@@ -113,9 +112,10 @@ macro_rules! crud_service {
 
             pub async fn delete(
                 db: &impl sea_orm::ConnectionTrait,
+                ctx: &$crate::service::audit::AuditContext,
                 id: uuid::Uuid,
             ) -> Result<(), $crate::error::CoreError> {
-                $crate::service::crud::delete_by_id::<$entity>(db, id, $name).await
+                $crate::service::crud::delete_by_id::<$entity>(db, ctx, id, $name).await
             }
 
             pub async fn list(
@@ -143,9 +143,10 @@ macro_rules! crud_service {
 
             pub async fn delete(
                 db: &impl sea_orm::ConnectionTrait,
+                ctx: &$crate::service::audit::AuditContext,
                 id: uuid::Uuid,
             ) -> Result<(), $crate::error::CoreError> {
-                $crate::service::crud::delete_by_id::<$entity>(db, id, $name).await
+                $crate::service::crud::delete_by_id::<$entity>(db, ctx, id, $name).await
             }
 
             pub async fn list(
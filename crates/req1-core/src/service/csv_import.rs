@@ -6,6 +6,8 @@ use uuid::Uuid;
 use entity::attribute_definition;
 
 use crate::error::CoreError;
+use crate::scripting::engine::ScriptEngine;
+use crate::service::audit::{AuditContext, AuditService};
 use crate::service::object::{CreateObjectInput, ObjectService};
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -18,6 +20,17 @@ pub struct CsvImportService;
 impl CsvImportService {
     pub async fn import_csv(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        module_id: Uuid,
+        csv_content: &str,
+    ) -> Result<CsvImportResult, CoreError> {
+        // Keep trigger scripts warm across the imported objects
+        ScriptEngine::batched(Self::import_csv_rows(db, ctx, module_id, csv_content)).await
+    }
+
+    async fn import_csv_rows(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         module_id: Uuid,
         csv_content: &str,
     ) -> Result<CsvImportResult, CoreError> {
@@ -131,11 +144,21 @@ impl CsvImportService {
                 is_placeholder: None,
             };
 
-            let created = ObjectService::create(db, input).await?;
+            let created = ObjectService::create(db, ctx, input).await?;
             level_stack.push((level, created.id));
             objects_created += 1;
         }
 
+        AuditService::event(
+            db,
+            ctx,
+            "import",
+            "module",
+            module_id,
+            serde_json::json!({"format": "csv", "objects_created": objects_created}),
+        )
+        .await?;
+
         Ok(CsvImportResult { objects_created })
     }
 }
@@ -9,6 +9,8 @@ use uuid::Uuid;
 use entity::{dashboard, dashboard_widget};
 
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
 
 const VALID_WIDGET_TYPES: &[&str] = &[
     "coverage_chart",
@@ -85,6 +87,7 @@ impl DashboardService {
 
     pub async fn create_dashboard(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateDashboardInput,
     ) -> Result<dashboard::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -102,6 +105,15 @@ impl DashboardService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<dashboard::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -117,11 +129,12 @@ impl DashboardService {
 
     pub async fn update_dashboard(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateDashboardInput,
     ) -> Result<dashboard::Model, CoreError> {
         let existing = Self::get_dashboard(db, id).await?;
-        let mut active: dashboard::ActiveModel = existing.into();
+        let mut active: dashboard::ActiveModel = existing.clone().into();
 
         if let Some(name) = input.name {
             active.name = Set(name);
@@ -132,15 +145,24 @@ impl DashboardService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<dashboard::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete_dashboard(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = dashboard::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("dashboard {id} not found")));
-        }
-        Ok(())
+    pub async fn delete_dashboard(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<dashboard::Entity>(db, ctx, id, "dashboard").await
     }
 
     // --- Widget CRUD ---
@@ -160,6 +182,7 @@ impl DashboardService {
 
     pub async fn create_widget(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateWidgetInput,
     ) -> Result<dashboard_widget::Model, CoreError> {
         if !VALID_WIDGET_TYPES.contains(&input.widget_type.as_str()) {
@@ -187,6 +210,15 @@ impl DashboardService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<dashboard_widget::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -202,6 +234,7 @@ impl DashboardService {
 
     pub async fn update_widget(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateWidgetInput,
     ) -> Result<dashboard_widget::Model, CoreError> {
@@ -215,7 +248,7 @@ impl DashboardService {
             )));
         }
 
-        let mut active: dashboard_widget::ActiveModel = existing.into();
+        let mut active: dashboard_widget::ActiveModel = existing.clone().into();
         if let Some(widget_type) = input.widget_type {
             active.widget_type = Set(widget_type);
         }
@@ -240,15 +273,24 @@ impl DashboardService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<dashboard_widget::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete_widget(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = dashboard_widget::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("widget {id} not found")));
-        }
-        Ok(())
+    pub async fn delete_widget(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<dashboard_widget::Entity>(db, ctx, id, "widget").await
     }
 
     // --- Widget Data ---
@@ -10,6 +10,8 @@ use entity::diagram;
 
 use crate::PaginatedResponse;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
 
 const VALID_DIAGRAM_TYPES: &[&str] = &["use_case", "sequence", "class", "flowchart", "state", "er"];
 
@@ -51,6 +53,7 @@ pub struct DiagramService;
 impl DiagramService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateDiagramInput,
     ) -> Result<diagram::Model, CoreError> {
         let dtype = input.diagram_type.as_deref().unwrap_or("use_case");
@@ -80,11 +83,14 @@ impl DiagramService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<diagram::Entity>(db, ctx, "create", result.id, None, Some(&result))
+            .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateDiagramInput,
     ) -> Result<diagram::Model, CoreError> {
@@ -101,7 +107,7 @@ impl DiagramService {
             )));
         }
 
-        let mut active: diagram::ActiveModel = existing.into();
+        let mut active: diagram::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -121,15 +127,24 @@ impl DiagramService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<diagram::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = diagram::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("diagram {id} not found")));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<diagram::Entity>(db, ctx, id, "diagram").await
     }
 
     pub async fn get(db: &impl ConnectionTrait, id: Uuid) -> Result<diagram::Model, CoreError> {
@@ -8,6 +8,8 @@ use uuid::Uuid;
 use entity::object;
 
 use crate::error::CoreError;
+use crate::scripting::engine::ScriptEngine;
+use crate::service::audit::{AuditContext, AuditService};
 use crate::service::object::{CreateObjectInput, ObjectService, UpdateObjectInput};
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -88,9 +90,21 @@ impl DocxImportService {
         })
     }
 
-    #[allow(clippy::too_many_lines)]
     pub async fn import_docx(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        module_id: Uuid,
+        data: &[u8],
+        input: DocxImportInput,
+    ) -> Result<DocxImportResult, CoreError> {
+        // Keep trigger scripts warm across the imported objects
+        ScriptEngine::batched(Self::import_docx_rows(db, ctx, module_id, data, input)).await
+    }
+
+    #[allow(clippy::too_many_lines)]
+    async fn import_docx_rows(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         module_id: Uuid,
         data: &[u8],
         input: DocxImportInput,
@@ -157,6 +171,7 @@ impl DocxImportService {
                     &mut objects_created,
                     &mut objects_updated,
                     db,
+                    ctx,
                     module_id,
                 )
                 .await?;
@@ -186,7 +201,23 @@ impl DocxImportService {
             &mut objects_created,
             &mut objects_updated,
             db,
+            ctx,
+            module_id,
+        )
+        .await?;
+
+        AuditService::event(
+            db,
+            ctx,
+            "import",
+            "module",
             module_id,
+            serde_json::json!({
+                "format": "docx",
+                "objects_created": objects_created,
+                "objects_updated": objects_updated,
+                "paragraphs_skipped": paragraphs_skipped,
+            }),
         )
         .await?;
 
@@ -207,6 +238,7 @@ async fn flush_heading(
     objects_created: &mut usize,
     objects_updated: &mut usize,
     db: &impl ConnectionTrait,
+    ctx: &AuditContext,
     module_id: Uuid,
 ) -> Result<(), CoreError> {
     let h = heading.take();
@@ -235,8 +267,9 @@ async fn flush_heading(
                 object_type_id: None,
                 expected_version: None,
                 lifecycle_state: None,
+                signature: None,
             };
-            let _ = ObjectService::update(db, existing.id, update_input).await?;
+            let _ = ObjectService::update(db, ctx, existing.id, update_input).await?;
             *objects_updated += 1;
             let depth = existing.level.matches('.').count();
             level_stack.truncate(depth);
@@ -263,7 +296,7 @@ async fn flush_heading(
             source_module_id: None,
             is_placeholder: None,
         };
-        let created = ObjectService::create(db, create_input).await?;
+        let created = ObjectService::create(db, ctx, create_input).await?;
 
         // Set docx_source_id if bookmark present
         if let Some(bm) = bookmark {
@@ -7,6 +7,7 @@ use uuid::Uuid;
 use entity::{app_user, e_signature, module, review_package};
 
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct SignInput {
@@ -21,6 +22,7 @@ impl ESignatureService {
     /// Create an e-signature by re-authenticating the user with their password.
     pub async fn sign(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         user_id: Uuid,
         entity_type: &str,
         entity_id: Uuid,
@@ -63,11 +65,20 @@ impl ESignatureService {
             entity_id: Set(entity_id),
             meaning: Set(input.meaning),
             signature_hash: Set(signature_hash),
-            ip_address: Set(input.ip_address),
+            ip_address: Set(input.ip_address.or_else(|| ctx.ip_address.clone())),
             created_at: Set(now),
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<e_signature::Entity>(
+            db,
+            ctx,
+            "sign",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
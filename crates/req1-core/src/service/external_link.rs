@@ -0,0 +1,384 @@
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use entity::{external_link, link_type, object};
+
+use crate::PaginatedResponse;
+use crate::error::CoreError;
+use crate::external::ExternalCheckers;
+use crate::link_rules::{LinkEnd, LinkTypeRules};
+use crate::service::audit::{AuditContext, AuditService};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateExternalLinkInput {
+    pub source_object_id: Uuid,
+    pub link_type_id: Uuid,
+    pub uri: String,
+    pub label: Option<String>,
+    /// Known hash of the resource; computed by a checker when omitted.
+    pub content_hash: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateExternalLinkInput {
+    pub uri: Option<String>,
+    pub label: Option<String>,
+    /// `false` accepts the resource's current content and the source's current version.
+    pub suspect: Option<bool>,
+    pub content_hash: Option<String>,
+}
+
+const fn default_limit() -> u64 {
+    50
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListExternalLinksFilter {
+    pub source_object_id: Option<Uuid>,
+    pub module_id: Option<Uuid>,
+    pub suspect: Option<bool>,
+    #[serde(default)]
+    pub offset: u64,
+    #[serde(default = "default_limit")]
+    pub limit: u64,
+}
+
+/// Outcome of re-verifying one external link.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExternalCheckResult {
+    pub link_id: Uuid,
+    /// `unchanged`, `changed`, `unsupported` (no checker for the URI) or `error`.
+    pub status: String,
+    pub content_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+fn validate_uri(uri: &str) -> Result<String, CoreError> {
+    let uri = uri.trim();
+    match uri.split_once(':') {
+        Some((scheme, rest)) if !scheme.is_empty() && !rest.is_empty() => Ok(uri.to_owned()),
+        _ => Err(CoreError::bad_request(format!(
+            "'{uri}' is not an absolute URI"
+        ))),
+    }
+}
+
+pub struct ExternalLinkService;
+
+impl ExternalLinkService {
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        checkers: &ExternalCheckers,
+        input: CreateExternalLinkInput,
+    ) -> Result<external_link::Model, CoreError> {
+        let uri = validate_uri(&input.uri)?;
+        let source = object::Entity::find_by_id(input.source_object_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                CoreError::not_found(format!(
+                    "source object {} not found",
+                    input.source_object_id
+                ))
+            })?;
+        let lt = link_type::Entity::find_by_id(input.link_type_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                CoreError::not_found(format!("link type {} not found", input.link_type_id))
+            })?;
+        if !LinkTypeRules::from_json(lt.rules.as_ref())?.allows(LinkEnd::Source, &source) {
+            return Err(CoreError::bad_request(format!(
+                "object {} is not an allowed source for link type '{}'",
+                source.id, lt.name
+            )));
+        }
+
+        let existing = external_link::Entity::find()
+            .filter(external_link::Column::SourceObjectId.eq(source.id))
+            .filter(external_link::Column::LinkTypeId.eq(lt.id))
+            .filter(external_link::Column::Uri.eq(uri.clone()))
+            .one(db)
+            .await?;
+        if existing.is_some() {
+            return Err(CoreError::bad_request(
+                "an external link with this source, type, and URI already exists".to_owned(),
+            ));
+        }
+
+        let now = chrono::Utc::now().fixed_offset();
+        let (content_hash, checked, check_error) = match input.content_hash {
+            Some(hash) => (Some(hash), false, None),
+            None => match checkers.content_hash(&uri).await {
+                Some(Ok(hash)) => (Some(hash), true, None),
+                Some(Err(e)) => (None, true, Some(e)),
+                None => (None, false, None),
+            },
+        };
+
+        let model = external_link::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            source_object_id: Set(source.id),
+            link_type_id: Set(lt.id),
+            uri: Set(uri),
+            label: Set(input.label),
+            content_hash: Set(content_hash),
+            source_fingerprint: Set(source.content_fingerprint),
+            suspect: Set(false),
+            suspect_reason: Set(None),
+            suspected_at: Set(None),
+            last_checked_at: Set(checked.then_some(now)),
+            check_error: Set(check_error),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        let result = model.insert(db).await?;
+        AuditService::record::<external_link::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn update(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        checkers: &ExternalCheckers,
+        id: Uuid,
+        input: UpdateExternalLinkInput,
+    ) -> Result<external_link::Model, CoreError> {
+        let existing = Self::get(db, id).await?;
+        let mut active: external_link::ActiveModel = existing.clone().into();
+        let now = chrono::Utc::now().fixed_offset();
+
+        let uri = match input.uri {
+            Some(uri) => validate_uri(&uri)?,
+            None => existing.uri.clone(),
+        };
+        let uri_changed = uri != existing.uri;
+        active.uri = Set(uri.clone());
+        if let Some(label) = input.label {
+            active.label = Set(Some(label));
+        }
+
+        let resolving = input.suspect == Some(false);
+        if let Some(hash) = input.content_hash {
+            active.content_hash = Set(Some(hash));
+        } else if uri_changed || resolving {
+            // Re-baseline on the resource as it is now
+            match checkers.content_hash(&uri).await {
+                Some(Ok(hash)) => {
+                    active.content_hash = Set(Some(hash));
+                    active.last_checked_at = Set(Some(now));
+                    active.check_error = Set(None);
+                }
+                Some(Err(e)) => {
+                    active.last_checked_at = Set(Some(now));
+                    active.check_error = Set(Some(e));
+                }
+                None if uri_changed => active.content_hash = Set(None),
+                None => {}
+            }
+        }
+
+        if let Some(suspect) = input.suspect {
+            if suspect {
+                if !existing.suspect {
+                    active.suspected_at = Set(Some(now));
+                }
+            } else {
+                let source = object::Entity::find_by_id(existing.source_object_id)
+                    .one(db)
+                    .await?;
+                if let Some(s) = source {
+                    active.source_fingerprint = Set(s.content_fingerprint);
+                }
+                active.suspect_reason = Set(None);
+                active.suspected_at = Set(None);
+            }
+            active.suspect = Set(suspect);
+        }
+        active.updated_at = Set(now);
+
+        let result = active.update(db).await?;
+        AuditService::record::<external_link::Entity>(
+            db,
+            ctx,
+            "update",
+            id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crate::service::crud::delete_by_id::<external_link::Entity>(db, ctx, id, "external link")
+            .await
+    }
+
+    pub async fn get(
+        db: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<external_link::Model, CoreError> {
+        external_link::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("external link {id} not found")))
+    }
+
+    pub async fn list(
+        db: &impl ConnectionTrait,
+        filter: ListExternalLinksFilter,
+    ) -> Result<PaginatedResponse<external_link::Model>, CoreError> {
+        let mut query = external_link::Entity::find().order_by_asc(external_link::Column::Id);
+        if let Some(source) = filter.source_object_id {
+            query = query.filter(external_link::Column::SourceObjectId.eq(source));
+        }
+        if let Some(module_id) = filter.module_id {
+            let object_ids: Vec<Uuid> = object::Entity::find()
+                .filter(object::Column::ModuleId.eq(module_id))
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|o| o.id)
+                .collect();
+            query = query.filter(external_link::Column::SourceObjectId.is_in(object_ids));
+        }
+        if let Some(suspect) = filter.suspect {
+            query = query.filter(external_link::Column::Suspect.eq(suspect));
+        }
+
+        let paginator = query.paginate(db, filter.limit);
+        let total = paginator.num_items().await?;
+        let page = filter.offset.checked_div(filter.limit).unwrap_or(0);
+        let items = paginator.fetch_page(page).await?;
+        Ok(PaginatedResponse {
+            items,
+            total,
+            offset: filter.offset,
+            limit: filter.limit,
+        })
+    }
+
+    /// Re-verify a link's resource and flag the link suspect when its hash moved on.
+    pub async fn check(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        checkers: &ExternalCheckers,
+        id: Uuid,
+    ) -> Result<ExternalCheckResult, CoreError> {
+        let existing = Self::get(db, id).await?;
+        let Some(outcome) = checkers.content_hash(&existing.uri).await else {
+            return Ok(ExternalCheckResult {
+                link_id: id,
+                status: "unsupported".to_owned(),
+                content_hash: None,
+                error: None,
+            });
+        };
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut active: external_link::ActiveModel = existing.clone().into();
+        active.last_checked_at = Set(Some(now));
+        let result = match outcome {
+            Err(e) => {
+                active.check_error = Set(Some(e.clone()));
+                ExternalCheckResult {
+                    link_id: id,
+                    status: "error".to_owned(),
+                    content_hash: None,
+                    error: Some(e),
+                }
+            }
+            Ok(hash) => {
+                active.check_error = Set(None);
+                let changed = existing.content_hash.as_ref().is_some_and(|h| *h != hash);
+                match &existing.content_hash {
+                    None => active.content_hash = Set(Some(hash.clone())),
+                    Some(_) if changed && !existing.suspect => {
+                        let lt = link_type::Entity::find_by_id(existing.link_type_id)
+                            .one(db)
+                            .await?;
+                        // 'forward' and 'none' types ignore changes on the target side
+                        if lt.is_none_or(|lt| {
+                            matches!(lt.suspect_direction.as_str(), "both" | "backward")
+                        }) {
+                            active.suspect = Set(true);
+                            active.suspected_at = Set(Some(now));
+                            active.suspect_reason = Set(Some(serde_json::json!({
+                                "uri": existing.uri,
+                                "expected_hash": existing.content_hash,
+                                "actual_hash": hash,
+                            })));
+                        }
+                    }
+                    Some(_) => {}
+                }
+                ExternalCheckResult {
+                    link_id: id,
+                    status: if changed { "changed" } else { "unchanged" }.to_owned(),
+                    content_hash: Some(hash),
+                    error: None,
+                }
+            }
+        };
+
+        let updated = active.update(db).await?;
+        if updated.suspect != existing.suspect {
+            AuditService::record::<external_link::Entity>(
+                db,
+                ctx,
+                "suspect",
+                id,
+                Some(&existing),
+                Some(&updated),
+            )
+            .await?;
+        }
+        Ok(result)
+    }
+
+    /// Re-verify every external link whose source lies in the module.
+    pub async fn check_module(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        checkers: &ExternalCheckers,
+        module_id: Uuid,
+    ) -> Result<Vec<ExternalCheckResult>, CoreError> {
+        let object_ids: Vec<Uuid> = object::Entity::find()
+            .filter(object::Column::ModuleId.eq(module_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|o| o.id)
+            .collect();
+        let links = external_link::Entity::find()
+            .filter(external_link::Column::SourceObjectId.is_in(object_ids))
+            .order_by_asc(external_link::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut results = Vec::with_capacity(links.len());
+        for l in links {
+            results.push(Self::check(db, ctx, checkers, l.id).await?);
+        }
+        Ok(results)
+    }
+}
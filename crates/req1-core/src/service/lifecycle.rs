@@ -1,11 +1,18 @@
-use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use std::collections::HashMap;
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, Order, QueryFilter, QueryOrder,
+    Set,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use entity::lifecycle_model;
+use entity::{e_signature, lifecycle_model, object_history};
 
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
 
 #[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct LifecycleState {
@@ -38,11 +45,29 @@ pub struct UpdateLifecycleModelInput {
     pub transitions: Option<Vec<LifecycleTransition>>,
 }
 
+/// One lifecycle state change of an object, with the e-signature given for it (if any).
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StateTimelineEntry {
+    pub object_id: Uuid,
+    pub version: i32,
+    /// `create` for the initial state, `state_transition` afterwards.
+    pub change_type: String,
+    pub from_state: Option<String>,
+    pub to_state: String,
+    pub lifecycle_model_id: Option<Uuid>,
+    pub changed_by: Option<Uuid>,
+    pub actor: Option<String>,
+    #[schema(value_type = String)]
+    pub changed_at: chrono::DateTime<chrono::FixedOffset>,
+    pub e_signature: Option<e_signature::Model>,
+}
+
 pub struct LifecycleService;
 
 impl LifecycleService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         module_id: Uuid,
         input: CreateLifecycleModelInput,
     ) -> Result<lifecycle_model::Model, CoreError> {
@@ -67,6 +92,15 @@ impl LifecycleService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<lifecycle_model::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -93,6 +127,7 @@ impl LifecycleService {
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateLifecycleModelInput,
     ) -> Result<lifecycle_model::Model, CoreError> {
@@ -101,7 +136,7 @@ impl LifecycleService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("lifecycle model {id} not found")))?;
 
-        let mut active: lifecycle_model::ActiveModel = existing.into();
+        let mut active: lifecycle_model::ActiveModel = existing.clone().into();
 
         if let Some(name) = input.name {
             active.name = Set(name);
@@ -126,17 +161,24 @@ impl LifecycleService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<lifecycle_model::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = lifecycle_model::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!(
-                "lifecycle model {id} not found"
-            )));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<lifecycle_model::Entity>(db, ctx, id, "lifecycle model").await
     }
 
     /// Validate that a state transition is allowed by the lifecycle model.
@@ -163,4 +205,55 @@ impl LifecycleService {
 
         Ok(())
     }
+
+    /// Lifecycle state changes in chronological order, for one object or a whole module.
+    pub async fn timeline(
+        db: &impl ConnectionTrait,
+        module_id: Uuid,
+        object_id: Option<Uuid>,
+    ) -> Result<Vec<StateTimelineEntry>, CoreError> {
+        let mut select = object_history::Entity::find()
+            .filter(object_history::Column::ModuleId.eq(module_id))
+            .filter(object_history::Column::ToState.is_not_null());
+        if let Some(object_id) = object_id {
+            select = select.filter(object_history::Column::ObjectId.eq(object_id));
+        }
+        let rows = select
+            .order_by(object_history::Column::ChangedAt, Order::Asc)
+            .order_by(object_history::Column::Id, Order::Asc)
+            .all(db)
+            .await?;
+
+        let sig_ids: Vec<Uuid> = rows.iter().filter_map(|r| r.e_signature_id).collect();
+        let mut signatures: HashMap<Uuid, e_signature::Model> = if sig_ids.is_empty() {
+            HashMap::new()
+        } else {
+            e_signature::Entity::find()
+                .filter(e_signature::Column::Id.is_in(sig_ids))
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|s| (s.id, s))
+                .collect()
+        };
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|r| {
+                let to_state = r.to_state?;
+                Some(StateTimelineEntry {
+                    object_id: r.object_id,
+                    version: r.version,
+                    change_type: r.change_type,
+                    from_state: r.from_state,
+                    to_state,
+                    lifecycle_model_id: r.lifecycle_model_id,
+                    changed_by: r.changed_by,
+                    actor: r.actor,
+                    changed_at: r.changed_at,
+                    e_signature: r.e_signature_id.and_then(|id| signatures.remove(&id)),
+                })
+            })
+            .collect())
+    }
 }
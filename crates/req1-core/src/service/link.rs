@@ -1,14 +1,19 @@
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set,
 };
 use serde::Deserialize;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use entity::{link, link_type, object};
+use entity::{link, link_history, link_type, object};
 
 use crate::PaginatedResponse;
 use crate::error::CoreError;
+use crate::link_history::{LinkHistoryEntry, insert_link_history};
+use crate::link_rules::{self, LinkTypeRules};
+use crate::service::audit::{AuditContext, AuditService};
+use crate::suspect::SUSPECT_DIRECTIONS;
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateLinkInput {
@@ -22,6 +27,8 @@ pub struct CreateLinkInput {
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateLinkInput {
     pub suspect: Option<bool>,
+    /// Retype the link.
+    pub link_type_id: Option<Uuid>,
     #[schema(value_type = Option<Object>)]
     pub attributes: Option<serde_json::Value>,
 }
@@ -46,6 +53,62 @@ pub struct ListLinksFilter {
 pub struct CreateLinkTypeInput {
     pub name: String,
     pub description: Option<String>,
+    /// Which end's changes flag links of this type: `forward` (source), `backward`
+    /// (target), `both` (default) or `none`.
+    pub suspect_direction: Option<String>,
+    /// Flagging a link also flags the links of the object at its other end.
+    pub suspect_cascade: Option<bool>,
+    /// Edits to informative objects never flag links of this type.
+    pub suspect_skip_informative: Option<bool>,
+    /// Name read from the target side, e.g. "satisfied by" for "satisfies".
+    pub inverse_name: Option<String>,
+    pub rules: Option<LinkTypeRules>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateLinkTypeInput {
+    pub description: Option<String>,
+    pub suspect_direction: Option<String>,
+    pub suspect_cascade: Option<bool>,
+    pub suspect_skip_informative: Option<bool>,
+    pub inverse_name: Option<String>,
+    /// Replaces the type's rules; `{}` lifts all restrictions.
+    pub rules: Option<LinkTypeRules>,
+}
+
+fn validate_suspect_direction(direction: &str) -> Result<(), CoreError> {
+    if SUSPECT_DIRECTIONS.contains(&direction) {
+        Ok(())
+    } else {
+        Err(CoreError::bad_request(format!(
+            "invalid suspect_direction '{direction}', expected one of: {}",
+            SUSPECT_DIRECTIONS.join(", ")
+        )))
+    }
+}
+
+/// Accept the current content of both ends: store their fingerprints on the link and
+/// drop the suspect reason. The caller sets `suspect` itself.
+pub(crate) async fn clear_suspect(
+    db: &impl ConnectionTrait,
+    existing: &link::Model,
+    active: &mut link::ActiveModel,
+) -> Result<(), CoreError> {
+    let source = object::Entity::find_by_id(existing.source_object_id)
+        .one(db)
+        .await?;
+    let target = object::Entity::find_by_id(existing.target_object_id)
+        .one(db)
+        .await?;
+    if let Some(s) = source {
+        active.source_fingerprint = Set(s.content_fingerprint);
+    }
+    if let Some(t) = target {
+        active.target_fingerprint = Set(t.content_fingerprint);
+    }
+    active.suspect_reason = Set(None);
+    active.suspected_at = Set(None);
+    Ok(())
 }
 
 pub struct LinkService;
@@ -53,6 +116,7 @@ pub struct LinkService;
 impl LinkService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateLinkInput,
     ) -> Result<link::Model, CoreError> {
         if input.source_object_id == input.target_object_id {
@@ -93,6 +157,14 @@ impl LinkService {
                 ))
             })?;
 
+        let lt = link_type::Entity::find_by_id(input.link_type_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                CoreError::not_found(format!("link type {} not found", input.link_type_id))
+            })?;
+        link_rules::check_link(db, &lt, &source, &target, None).await?;
+
         let now = chrono::Utc::now().fixed_offset();
         let id = Uuid::now_v7();
 
@@ -107,14 +179,31 @@ impl LinkService {
             target_fingerprint: Set(target.content_fingerprint),
             created_at: Set(now),
             updated_at: Set(now),
+            suspect_reason: Set(None),
+            suspected_at: Set(None),
+            version: Set(1),
+            reqif_id: Set(None),
         };
 
         let result = model.insert(db).await?;
+        insert_link_history(
+            db,
+            ctx,
+            &result,
+            LinkHistoryEntry {
+                change_type: "create",
+                ..Default::default()
+            },
+        )
+        .await?;
+        AuditService::record::<link::Entity>(db, ctx, "create", result.id, None, Some(&result))
+            .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateLinkInput,
     ) -> Result<link::Model, CoreError> {
@@ -127,37 +216,121 @@ impl LinkService {
 
         if let Some(suspect) = input.suspect {
             if !suspect {
-                // Resolving suspect: update stored fingerprints to current object fingerprints
-                let source = object::Entity::find_by_id(existing.source_object_id)
-                    .one(db)
-                    .await?;
-                let target = object::Entity::find_by_id(existing.target_object_id)
-                    .one(db)
-                    .await?;
-                if let Some(s) = source {
-                    active.source_fingerprint = Set(s.content_fingerprint);
-                }
-                if let Some(t) = target {
-                    active.target_fingerprint = Set(t.content_fingerprint);
-                }
+                clear_suspect(db, &existing, &mut active).await?;
+            } else if !existing.suspect {
+                active.suspected_at = Set(Some(chrono::Utc::now().fixed_offset()));
             }
             active.suspect = Set(suspect);
         }
+        if let Some(link_type_id) = input.link_type_id.filter(|t| *t != existing.link_type_id) {
+            let lt = link_type::Entity::find_by_id(link_type_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| {
+                    CoreError::not_found(format!("link type {link_type_id} not found"))
+                })?;
+            let duplicate = link::Entity::find()
+                .filter(link::Column::SourceObjectId.eq(existing.source_object_id))
+                .filter(link::Column::TargetObjectId.eq(existing.target_object_id))
+                .filter(link::Column::LinkTypeId.eq(link_type_id))
+                .one(db)
+                .await?;
+            if duplicate.is_some() {
+                return Err(CoreError::bad_request(
+                    "a link with this source, target, and type already exists".to_owned(),
+                ));
+            }
+            let source = Self::end_object(db, existing.source_object_id).await?;
+            let target = Self::end_object(db, existing.target_object_id).await?;
+            link_rules::check_link(db, &lt, &source, &target, Some(id)).await?;
+            active.link_type_id = Set(link_type_id);
+        }
         if let Some(attributes) = input.attributes {
             active.attributes = Set(Some(attributes));
         }
+        active.version = Set(existing.version + 1);
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        let change_type = match (existing.suspect, result.suspect) {
+            (true, false) => "resolve",
+            (false, true) => "suspect",
+            _ => "update",
+        };
+        insert_link_history(
+            db,
+            ctx,
+            &result,
+            LinkHistoryEntry {
+                change_type,
+                suspect_reason: existing
+                    .suspect_reason
+                    .clone()
+                    .filter(|_| change_type == "resolve"),
+                old_attributes: existing.attributes.clone(),
+                old_link_type_id: Some(existing.link_type_id).filter(|t| *t != result.link_type_id),
+                ..Default::default()
+            },
+        )
+        .await?;
+        AuditService::record::<link::Entity>(db, ctx, "update", id, Some(&existing), Some(&result))
+            .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = link::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("link {id} not found")));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        let existing = Self::get(db, id).await?;
+        // The delete record is the link's last version
+        let tombstone = link::Model {
+            version: existing.version + 1,
+            ..existing.clone()
+        };
+        insert_link_history(
+            db,
+            ctx,
+            &tombstone,
+            LinkHistoryEntry {
+                change_type: "delete",
+                old_attributes: existing.attributes.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let _ = link::Entity::delete_by_id(id).exec(db).await?;
+        AuditService::record::<link::Entity>(db, ctx, "delete", id, Some(&existing), None).await
+    }
+
+    async fn end_object(db: &impl ConnectionTrait, id: Uuid) -> Result<object::Model, CoreError> {
+        object::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("object {id} not found")))
+    }
+
+    /// History of a link, newest first. Records outlive the link itself.
+    pub async fn history(
+        db: &impl ConnectionTrait,
+        id: Uuid,
+        offset: u64,
+        limit: u64,
+    ) -> Result<PaginatedResponse<link_history::Model>, CoreError> {
+        let paginator = link_history::Entity::find()
+            .filter(link_history::Column::LinkId.eq(id))
+            .order_by_desc(link_history::Column::Version)
+            .order_by_desc(link_history::Column::Id)
+            .paginate(db, limit);
+        let total = paginator.num_items().await?;
+        let items = paginator.fetch_page(offset / limit).await?;
+        Ok(PaginatedResponse {
+            items,
+            total,
+            offset,
+            limit,
+        })
     }
 
     pub async fn get(db: &impl ConnectionTrait, id: Uuid) -> Result<link::Model, CoreError> {
@@ -218,19 +391,86 @@ impl LinkService {
 
     pub async fn create_link_type(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateLinkTypeInput,
     ) -> Result<link_type::Model, CoreError> {
+        let suspect_direction = input.suspect_direction.unwrap_or_else(|| "both".to_owned());
+        validate_suspect_direction(&suspect_direction)?;
+
         let now = chrono::Utc::now().fixed_offset();
         let id = Uuid::now_v7();
 
+        let rules = input.rules.unwrap_or_default();
+        rules.validate()?;
+
         let model = link_type::ActiveModel {
             id: Set(id),
             name: Set(input.name),
             description: Set(input.description),
             created_at: Set(now),
+            suspect_direction: Set(suspect_direction),
+            suspect_cascade: Set(input.suspect_cascade.unwrap_or(false)),
+            suspect_skip_informative: Set(input.suspect_skip_informative.unwrap_or(false)),
+            inverse_name: Set(input.inverse_name),
+            rules: Set(rules.to_json()),
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<link_type::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn update_link_type(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+        input: UpdateLinkTypeInput,
+    ) -> Result<link_type::Model, CoreError> {
+        let existing = link_type::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("link type {id} not found")))?;
+
+        let mut active: link_type::ActiveModel = existing.clone().into();
+        if let Some(description) = input.description {
+            active.description = Set(Some(description));
+        }
+        if let Some(direction) = input.suspect_direction {
+            validate_suspect_direction(&direction)?;
+            active.suspect_direction = Set(direction);
+        }
+        if let Some(cascade) = input.suspect_cascade {
+            active.suspect_cascade = Set(cascade);
+        }
+        if let Some(skip) = input.suspect_skip_informative {
+            active.suspect_skip_informative = Set(skip);
+        }
+        if let Some(inverse_name) = input.inverse_name {
+            active.inverse_name = Set(Some(inverse_name));
+        }
+        if let Some(rules) = input.rules {
+            rules.validate()?;
+            active.rules = Set(rules.to_json());
+        }
+
+        let result = active.update(db).await?;
+        AuditService::record::<link_type::Entity>(
+            db,
+            ctx,
+            "update",
+            id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 }
@@ -12,13 +12,16 @@ pub mod dashboard;
 pub mod diagram;
 pub mod docx_import;
 pub mod e_signature;
+pub mod external_link;
 pub mod lifecycle;
 pub mod link;
 pub mod mention;
 pub mod module;
+pub mod module_permission;
 pub mod notification;
 pub mod object;
 pub mod object_type;
+pub mod permission;
 pub mod project;
 pub mod project_template;
 pub mod publish;
@@ -26,9 +29,12 @@ pub mod review_assignment;
 pub mod review_comment;
 pub mod review_package;
 pub mod scheduler;
+pub mod script_library;
+pub mod suspect_queue;
 pub mod test;
 pub mod validation_service;
 pub mod view;
 pub mod webhook;
 pub mod workspace;
+pub mod workspace_member;
 pub mod xlsx_import;
@@ -10,6 +10,11 @@ use entity::{attribute_definition, link, module, object, object_type, script};
 
 use crate::PaginatedResponse;
 use crate::error::CoreError;
+use crate::fingerprint::{self, FingerprintPolicy};
+use crate::link_history::{LinkHistoryEntry, insert_link_history};
+use crate::reqif::profile::ExchangeProfile;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
 
 const VALID_CLASSIFICATIONS: &[&str] = &["normative", "informative", "heading"];
 
@@ -28,6 +33,10 @@ pub struct CreateModuleInput {
     pub default_lifecycle_model_id: Option<Uuid>,
     #[schema(value_type = Option<Object>)]
     pub signature_config: Option<serde_json::Value>,
+    /// Which attributes count as content for suspect-link fingerprints.
+    pub fingerprint_policy: Option<FingerprintPolicy>,
+    /// Which attributes are exported to and accepted from the `ReqIF` partner.
+    pub reqif_profile: Option<ExchangeProfile>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -43,6 +52,10 @@ pub struct UpdateModuleInput {
     pub default_lifecycle_model_id: Option<Uuid>,
     #[schema(value_type = Option<Object>)]
     pub signature_config: Option<serde_json::Value>,
+    /// Which attributes count as content for suspect-link fingerprints.
+    pub fingerprint_policy: Option<FingerprintPolicy>,
+    /// Which attributes are exported to and accepted from the `ReqIF` partner.
+    pub reqif_profile: Option<ExchangeProfile>,
 }
 
 const fn default_limit() -> u64 {
@@ -72,6 +85,7 @@ pub struct ModuleService;
 impl ModuleService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateModuleInput,
     ) -> Result<module::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -101,17 +115,23 @@ impl ModuleService {
             publish_template: Set(input.publish_template),
             default_lifecycle_model_id: Set(input.default_lifecycle_model_id),
             signature_config: Set(input.signature_config.unwrap_or(serde_json::json!({}))),
+            fingerprint_policy: Set(input.fingerprint_policy.and_then(|p| p.to_json())),
             created_at: Set(now),
             updated_at: Set(now),
+            reqif_id: Set(None),
+            reqif_profile: Set(input.reqif_profile.and_then(|p| p.to_json())),
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<module::Entity>(db, ctx, "create", result.id, None, Some(&result))
+            .await?;
         Ok(result)
     }
 
     #[allow(clippy::too_many_lines)]
     pub async fn create_from_template(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateModuleFromTemplateInput,
     ) -> Result<module::Model, CoreError> {
         let template = module::Entity::find_by_id(input.template_module_id)
@@ -140,8 +160,11 @@ impl ModuleService {
             publish_template: Set(template.publish_template),
             default_lifecycle_model_id: Set(None),
             signature_config: Set(template.signature_config),
+            fingerprint_policy: Set(template.fingerprint_policy),
             created_at: Set(now),
             updated_at: Set(now),
+            reqif_id: Set(None),
+            reqif_profile: Set(template.reqif_profile),
         };
         let _ = new_module.insert(db).await?;
 
@@ -207,6 +230,7 @@ impl ModuleService {
                 default_classification: Set(ot.default_classification.clone()),
                 required_attributes: Set(ot.required_attributes.clone()),
                 attribute_schema: Set(ot.attribute_schema.clone()),
+                fingerprint_policy: Set(ot.fingerprint_policy.clone()),
                 created_at: Set(now),
                 updated_at: Set(now),
             };
@@ -263,6 +287,8 @@ impl ModuleService {
                     deleted_at: Set(None),
                     created_at: Set(now),
                     updated_at: Set(now),
+                    reqif_id: Set(None),
+                    reqif_base: Set(None),
                 };
                 let _ = copy.insert(db).await?;
             }
@@ -292,8 +318,22 @@ impl ModuleService {
                             target_fingerprint: Set(lnk.target_fingerprint.clone()),
                             created_at: Set(now),
                             updated_at: Set(now),
+                            suspect_reason: Set(None),
+                            suspected_at: Set(None),
+                            version: Set(1),
+                            reqif_id: Set(None),
                         };
-                        let _ = copy.insert(db).await?;
+                        let copy = copy.insert(db).await?;
+                        insert_link_history(
+                            db,
+                            ctx,
+                            &copy,
+                            LinkHistoryEntry {
+                                change_type: "create",
+                                ..Default::default()
+                            },
+                        )
+                        .await?;
                     }
                 }
             }
@@ -302,14 +342,18 @@ impl ModuleService {
             crate::level::recompute_module_levels(db, new_id).await?;
         }
 
-        module::Entity::find_by_id(new_id)
+        let result = module::Entity::find_by_id(new_id)
             .one(db)
             .await?
-            .ok_or_else(|| CoreError::internal("module not found after insert".to_owned()))
+            .ok_or_else(|| CoreError::internal("module not found after insert".to_owned()))?;
+        AuditService::record::<module::Entity>(db, ctx, "create", new_id, None, Some(&result))
+            .await?;
+        Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateModuleInput,
     ) -> Result<module::Model, CoreError> {
@@ -318,7 +362,7 @@ impl ModuleService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("module {id} not found")))?;
 
-        let mut active: module::ActiveModel = existing.into();
+        let mut active: module::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -354,18 +398,40 @@ impl ModuleService {
         if let Some(signature_config) = input.signature_config {
             active.signature_config = Set(signature_config);
         }
+        let policy_changed = input
+            .fingerprint_policy
+            .map(|p| p.to_json())
+            .filter(|p| *p != existing.fingerprint_policy);
+        if let Some(policy) = &policy_changed {
+            active.fingerprint_policy = Set(policy.clone());
+        }
+        if let Some(profile) = input.reqif_profile {
+            active.reqif_profile = Set(profile.to_json());
+        }
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        if policy_changed.is_some() {
+            let _ = fingerprint::refingerprint_module(db, id).await?;
+        }
+        AuditService::record::<module::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = module::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("module {id} not found")));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<module::Entity>(db, ctx, id, "module").await
     }
 
     pub async fn get(db: &impl ConnectionTrait, id: Uuid) -> Result<module::Model, CoreError> {
@@ -375,14 +441,19 @@ impl ModuleService {
             .ok_or_else(|| CoreError::not_found(format!("module {id} not found")))
     }
 
+    /// List modules, restricted to `visible` module ids when given.
     pub async fn list(
         db: &impl ConnectionTrait,
         filter: ListModulesFilter,
+        visible: Option<Vec<Uuid>>,
     ) -> Result<PaginatedResponse<module::Model>, CoreError> {
         let mut select = module::Entity::find();
         if let Some(project_id) = filter.project_id {
             select = select.filter(module::Column::ProjectId.eq(project_id));
         }
+        if let Some(module_ids) = visible {
+            select = select.filter(module::Column::Id.is_in(module_ids));
+        }
         let paginator = select.paginate(db, filter.limit);
         let total = paginator.num_items().await?;
         let page = filter.offset / filter.limit;
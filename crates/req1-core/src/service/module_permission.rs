@@ -0,0 +1,121 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use entity::{app_user, module_permission};
+
+use crate::crud_service;
+use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+
+const VALID_PERMISSIONS: &[&str] = &["read", "write", "admin"];
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateModulePermissionInput {
+    #[serde(default)]
+    pub module_id: Uuid,
+    pub user_id: Uuid,
+    pub permission: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateModulePermissionInput {
+    pub permission: String,
+}
+
+fn check_permission(permission: &str) -> Result<(), CoreError> {
+    if VALID_PERMISSIONS.contains(&permission) {
+        Ok(())
+    } else {
+        Err(CoreError::bad_request(format!(
+            "invalid permission '{permission}', must be one of: {VALID_PERMISSIONS:?}"
+        )))
+    }
+}
+
+pub struct ModulePermissionService;
+
+impl ModulePermissionService {
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        input: CreateModulePermissionInput,
+    ) -> Result<module_permission::Model, CoreError> {
+        let permission = input.permission.unwrap_or_else(|| "read".to_owned());
+        check_permission(&permission)?;
+
+        let _ = app_user::Entity::find_by_id(input.user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("user {} not found", input.user_id)))?;
+
+        let existing = module_permission::Entity::find()
+            .filter(module_permission::Column::UserId.eq(input.user_id))
+            .filter(module_permission::Column::ModuleId.eq(input.module_id))
+            .one(db)
+            .await?;
+        if existing.is_some() {
+            return Err(CoreError::conflict(format!(
+                "user {} already has a grant on module {}",
+                input.user_id, input.module_id
+            )));
+        }
+
+        let model = module_permission::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            user_id: Set(input.user_id),
+            module_id: Set(input.module_id),
+            permission: Set(permission),
+            created_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        let result = model.insert(db).await?;
+        AuditService::record::<module_permission::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn update(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+        input: UpdateModulePermissionInput,
+    ) -> Result<module_permission::Model, CoreError> {
+        check_permission(&input.permission)?;
+
+        let existing = module_permission::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("module permission {id} not found")))?;
+
+        let mut active: module_permission::ActiveModel = existing.clone().into();
+        active.permission = Set(input.permission);
+
+        let result = active.update(db).await?;
+        AuditService::record::<module_permission::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+}
+
+crud_service!(
+    ModulePermissionService,
+    module_permission::Entity,
+    "module permission",
+    parent: module_permission::Column::ModuleId
+);
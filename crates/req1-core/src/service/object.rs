@@ -7,18 +7,20 @@ use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use entity::{attribute_definition, link, object, script};
+use entity::{attribute_definition, object, script};
 
 use crate::PaginatedResponse;
 use crate::error::CoreError;
-use crate::fingerprint::compute_content_fingerprint;
-use crate::history::{self, HistoryEntry};
+use crate::fingerprint::{FingerprintPolicy, compute_content_fingerprint};
+use crate::history::{self, HistoryEntry, StateChange};
 use crate::level;
-use crate::scripting::engine::{
-    Mutation, ScriptEngine, ScriptLink, ScriptObject, ScriptWorld, TriggerContext,
-};
+use crate::scripting::engine::{Mutation, ScriptEngine, ScriptObject, ScriptWorld, TriggerContext};
+use crate::scripting::world::WorldAccess;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::e_signature::{ESignatureService, SignInput};
+use crate::service::scheduler::SchedulerService;
 use crate::service::webhook::WebhookService;
-use crate::suspect;
+use crate::suspect::{self, SuspectTrigger};
 use crate::validation;
 
 const VALID_CLASSIFICATIONS: &[&str] = &["normative", "informative", "heading"];
@@ -77,6 +79,8 @@ pub struct UpdateObjectInput {
     pub object_type_id: Option<Uuid>,
     pub expected_version: Option<i32>,
     pub lifecycle_state: Option<String>,
+    /// Optional e-signature for a lifecycle state change, linked from the history entry.
+    pub signature: Option<SignInput>,
 }
 
 const fn default_limit() -> u64 {
@@ -99,7 +103,8 @@ pub struct ListObjectsFilter {
     pub include_deleted: Option<bool>,
 }
 
-/// Load the `ScriptWorld` for a module (all objects + links).
+/// Load the `ScriptWorld` for a module. Objects and links are read lazily
+/// by the scripts that use them.
 pub async fn load_world(
     db: &impl ConnectionTrait,
     module_id: Uuid,
@@ -109,56 +114,16 @@ pub async fn load_world(
         .await?
         .ok_or_else(|| CoreError::not_found(format!("module {module_id} not found")))?;
 
-    let objects: Vec<ScriptObject> = object::Entity::find()
-        .filter(object::Column::ModuleId.eq(module_id))
-        .all(db)
-        .await?
-        .into_iter()
-        .map(|o| ScriptObject {
-            id: o.id.to_string(),
-            heading: o.heading,
-            body: o.body,
-            level: Some(o.level),
-            classification: Some(o.classification),
-            attributes: o.attributes,
-            version: o.current_version,
-        })
-        .collect();
-
-    let object_ids: Vec<Uuid> = objects.iter().filter_map(|o| o.id.parse().ok()).collect();
-    let links: Vec<ScriptLink> = if object_ids.is_empty() {
-        Vec::new()
-    } else {
-        link::Entity::find()
-            .filter(
-                link::Column::SourceObjectId
-                    .is_in(object_ids.clone())
-                    .or(link::Column::TargetObjectId.is_in(object_ids)),
-            )
-            .all(db)
-            .await?
-            .into_iter()
-            .map(|l| ScriptLink {
-                id: l.id.to_string(),
-                source_object_id: l.source_object_id.to_string(),
-                target_object_id: l.target_object_id.to_string(),
-                link_type_id: l.link_type_id.to_string(),
-                suspect: l.suspect,
-            })
-            .collect()
-    };
-
     Ok(ScriptWorld {
         module_id,
         module_name: module.name,
-        objects,
-        links,
     })
 }
 
 /// Run all enabled trigger scripts for a module + `hook_point`. Returns attribute mutations to apply.
 async fn run_triggers(
     db: &impl ConnectionTrait,
+    ctx: &AuditContext,
     module_id: Uuid,
     hook_point: &str,
     script_obj: &ScriptObject,
@@ -177,7 +142,8 @@ async fn run_triggers(
     }
 
     let world = load_world(db, module_id).await?;
-    let ctx = TriggerContext {
+    let access = WorldAccess::new(db, ctx.user_id, module_id);
+    let trigger_ctx = TriggerContext {
         hook_point: hook_point.to_owned(),
         object: script_obj.clone(),
     };
@@ -185,7 +151,15 @@ async fn run_triggers(
     let mut all_mutations = Vec::new();
 
     for s in &scripts {
-        let result = ScriptEngine::run_trigger(&s.source_code, &world, &ctx)?;
+        let started_at = chrono::Utc::now().fixed_offset();
+        let result =
+            match ScriptEngine::run_trigger(&s.source_code, &world, &trigger_ctx, &access).await {
+                Ok(r) => r,
+                Err(e) => {
+                    SchedulerService::note_failed_run(s.id, started_at, &e);
+                    return Err(e);
+                }
+            };
         if result.rejected {
             return Err(CoreError::bad_request(format!(
                 "script '{}' rejected: {}",
@@ -195,6 +169,12 @@ async fn run_triggers(
                     .unwrap_or_else(|| "no reason given".to_owned())
             )));
         }
+        if !result.mutations.iter().all(Mutation::is_field_edit) {
+            return Err(CoreError::bad_request(format!(
+                "script '{}': triggers may only set attributes, headings and bodies",
+                s.name
+            )));
+        }
         all_mutations.extend(result.mutations);
     }
 
@@ -203,12 +183,14 @@ async fn run_triggers(
 
 /// Run all enabled post-trigger scripts for a module + `hook_point`.
 /// Post-triggers do NOT block the save — rejections are logged as warnings.
+/// Returns the names of the scripts that produced mutations, and the mutations.
 async fn run_post_triggers(
     db: &impl ConnectionTrait,
+    ctx: &AuditContext,
     module_id: Uuid,
     hook_point: &str,
     script_obj: &ScriptObject,
-) -> Vec<Mutation> {
+) -> (Vec<String>, Vec<Mutation>) {
     let scripts = match script::Entity::find()
         .filter(script::Column::ModuleId.eq(module_id))
         .filter(script::Column::ScriptType.eq("trigger"))
@@ -221,31 +203,34 @@ async fn run_post_triggers(
         Ok(s) => s,
         Err(e) => {
             tracing::warn!("failed to load {hook_point} scripts for module {module_id}: {e}");
-            return Vec::new();
+            return (Vec::new(), Vec::new());
         }
     };
 
     if scripts.is_empty() {
-        return Vec::new();
+        return (Vec::new(), Vec::new());
     }
 
     let world = match load_world(db, module_id).await {
         Ok(w) => w,
         Err(e) => {
             tracing::warn!("failed to load world for {hook_point} scripts: {e}");
-            return Vec::new();
+            return (Vec::new(), Vec::new());
         }
     };
 
-    let ctx = TriggerContext {
+    let access = WorldAccess::new(db, ctx.user_id, module_id);
+    let trigger_ctx = TriggerContext {
         hook_point: hook_point.to_owned(),
         object: script_obj.clone(),
     };
 
+    let mut names = Vec::new();
     let mut all_mutations = Vec::new();
 
     for s in &scripts {
-        match ScriptEngine::run_trigger(&s.source_code, &world, &ctx) {
+        let started_at = chrono::Utc::now().fixed_offset();
+        match ScriptEngine::run_trigger(&s.source_code, &world, &trigger_ctx, &access).await {
             Ok(result) => {
                 if result.rejected {
                     tracing::warn!(
@@ -255,20 +240,139 @@ async fn run_post_triggers(
                             .reason
                             .unwrap_or_else(|| "no reason given".to_owned())
                     );
-                } else {
+                } else if !result.mutations.iter().all(Mutation::is_field_edit) {
+                    tracing::warn!(
+                        "post-trigger '{}' changed the module structure (ignored)",
+                        s.name
+                    );
+                } else if !result.mutations.is_empty() {
+                    names.push(s.name.clone());
                     all_mutations.extend(result.mutations);
                 }
             }
             Err(e) => {
                 tracing::warn!("post-trigger '{}' failed (ignored): {e}", s.name);
+                SchedulerService::note_failed_run(s.id, started_at, &e);
             }
         }
     }
 
-    all_mutations
+    (names, all_mutations)
+}
+
+/// Run `post_save` triggers on a freshly saved object. Any mutations are written as a
+/// follow-up version attributed to the scripts that produced them.
+async fn apply_post_save(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    saved: object::Model,
+) -> Result<object::Model, CoreError> {
+    let post_obj = ScriptObject {
+        id: saved.id.to_string(),
+        heading: saved.heading.clone(),
+        body: saved.body.clone(),
+        level: Some(saved.level.clone()),
+        classification: Some(saved.classification.clone()),
+        attributes: saved.attributes.clone(),
+        version: saved.current_version,
+    };
+    let (scripts, mutations) =
+        run_post_triggers(db, ctx, saved.module_id, "post_save", &post_obj).await;
+    if mutations.is_empty() {
+        return Ok(saved);
+    }
+
+    let ctx = ctx.for_script(&scripts.join(","));
+    let new_version = saved.current_version + 1;
+    let mut active: object::ActiveModel = saved.clone().into();
+    apply_mutations(&mut active, saved.id, &mutations, saved.attributes.as_ref());
+    active.current_version = Set(new_version);
+    refresh_fingerprint(db, &ctx, &saved, &mut active).await?;
+    active.updated_at = Set(chrono::Utc::now().fixed_offset());
+    let result = active.update(db).await?;
+
+    history::insert_history(
+        db,
+        &ctx,
+        HistoryEntry {
+            object_id: result.id,
+            module_id: result.module_id,
+            version: new_version,
+            attribute_values: result.attributes.clone(),
+            heading: result.heading.clone(),
+            body: result.body.clone(),
+            change_type: "update".to_owned(),
+            state: None,
+        },
+    )
+    .await?;
+
+    AuditService::record::<object::Entity>(
+        db,
+        &ctx,
+        "update",
+        result.id,
+        Some(&saved),
+        Some(&result),
+    )
+    .await?;
+    Ok(result)
 }
 
-/// Apply attribute mutations from scripts to the active model.
+/// Recompute the fingerprint of `active`'s final content, which `existing` is the
+/// previous version of. If it changed, the review is cleared and links are flagged
+/// suspect; edits to attributes outside the fingerprint policy keep both intact.
+async fn refresh_fingerprint(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    existing: &object::Model,
+    active: &mut object::ActiveModel,
+) -> Result<(), CoreError> {
+    let heading = active.heading.as_ref().clone();
+    let body = active.body.as_ref().clone();
+    let attributes = active.attributes.as_ref().clone();
+    let policy =
+        FingerprintPolicy::resolve(db, existing.module_id, *active.object_type_id.as_ref()).await?;
+    let fp = compute_content_fingerprint(
+        heading.as_deref(),
+        body.as_deref(),
+        attributes.as_ref(),
+        &policy,
+    );
+    if fp == existing.content_fingerprint {
+        return Ok(());
+    }
+
+    active.content_fingerprint = Set(fp.clone());
+    active.reviewed_fingerprint = Set(None);
+    active.reviewed_at = Set(None);
+    active.reviewed_by = Set(None);
+
+    let changed = suspect::changed_fields(
+        &policy,
+        (
+            existing.heading.as_deref(),
+            existing.body.as_deref(),
+            existing.attributes.as_ref(),
+        ),
+        (heading.as_deref(), body.as_deref(), attributes.as_ref()),
+    );
+    let _ = suspect::flag_suspect_links(
+        db,
+        ctx,
+        &SuspectTrigger {
+            object_id: existing.id,
+            version: *active.current_version.as_ref(),
+            classification: active.classification.as_ref(),
+            fingerprint: &fp,
+            changed,
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Apply field mutations from scripts to the active model.
 fn apply_mutations(
     active: &mut object::ActiveModel,
     target_id: Uuid,
@@ -292,7 +396,13 @@ fn apply_mutations(
                     changed = true;
                 }
             }
-            Mutation::SetAttribute { .. } => {}
+            Mutation::SetHeading { object_id, heading } if *object_id == target_id => {
+                active.heading = Set(Some(heading.clone()));
+            }
+            Mutation::SetBody { object_id, body } if *object_id == target_id => {
+                active.body = Set(Some(body.clone()));
+            }
+            _ => {}
         }
     }
 
@@ -307,6 +417,7 @@ impl ObjectService {
     #[allow(clippy::too_many_lines)]
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateObjectInput,
     ) -> Result<object::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -351,20 +462,25 @@ impl ObjectService {
             attributes: input.attributes.clone(),
             version: 1,
         };
-        let mutations = run_triggers(db, input.module_id, "pre_save", &script_obj).await?;
+        let mutations = run_triggers(db, ctx, input.module_id, "pre_save", &script_obj).await?;
 
-        // Build final attributes (base + script mutations)
+        // Build final fields (base + script mutations)
         let mut final_attributes = input.attributes.clone();
-        if !mutations.is_empty() {
-            let mut attrs =
-                final_attributes.unwrap_or(serde_json::Value::Object(serde_json::Map::default()));
-            for m in &mutations {
-                let Mutation::SetAttribute { key, value, .. } = m;
-                if let Some(obj) = attrs.as_object_mut() {
-                    let _ = obj.insert(key.clone(), value.clone());
+        let mut final_heading = input.heading.clone();
+        let mut final_body = input.body.clone();
+        for m in &mutations {
+            match m {
+                Mutation::SetAttribute { key, value, .. } => {
+                    let attrs = final_attributes
+                        .get_or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+                    if let Some(obj) = attrs.as_object_mut() {
+                        let _ = obj.insert(key.clone(), value.clone());
+                    }
                 }
+                Mutation::SetHeading { heading, .. } => final_heading = Some(heading.clone()),
+                Mutation::SetBody { body, .. } => final_body = Some(body.clone()),
+                _ => {}
             }
-            final_attributes = Some(attrs);
         }
 
         // Apply attribute defaults from definitions for any missing keys
@@ -404,8 +520,6 @@ impl ObjectService {
 
         // Placeholder auto-population from source
         let is_ph = input.is_placeholder.unwrap_or(false);
-        let mut final_heading = input.heading.clone();
-        let mut final_body = input.body.clone();
         if is_ph {
             if let Some(source_id) = input.source_object_id {
                 // Validate source is not itself a placeholder
@@ -438,10 +552,12 @@ impl ObjectService {
         }
 
         // Recompute fingerprint with potentially populated values
+        let policy = FingerprintPolicy::resolve(db, input.module_id, input.object_type_id).await?;
         let fp = compute_content_fingerprint(
             final_heading.as_deref(),
             final_body.as_deref(),
             final_attributes.as_ref(),
+            &policy,
         );
 
         let model = object::ActiveModel {
@@ -452,7 +568,7 @@ impl ObjectService {
             level: Set("0".to_owned()),
             heading: Set(final_heading.clone()),
             body: Set(final_body.clone()),
-            attributes: Set(final_attributes),
+            attributes: Set(final_attributes.clone()),
             current_version: Set(1),
             classification: Set(classification.to_owned()),
             content_fingerprint: Set(fp),
@@ -461,7 +577,7 @@ impl ObjectService {
             reviewed_by: Set(None),
             references_: Set(input.references.unwrap_or(serde_json::json!([]))),
             object_type_id: Set(input.object_type_id),
-            lifecycle_state: Set(lc_state),
+            lifecycle_state: Set(lc_state.clone()),
             lifecycle_model_id: Set(lc_model_id),
             source_object_id: Set(input.source_object_id),
             source_module_id: Set(input.source_module_id),
@@ -470,6 +586,8 @@ impl ObjectService {
             deleted_at: Set(None),
             created_at: Set(now),
             updated_at: Set(now),
+            reqif_id: Set(None),
+            reqif_base: Set(None),
         };
 
         let _ = model.insert(db).await?;
@@ -477,22 +595,29 @@ impl ObjectService {
         let webhook_heading = final_heading;
         let webhook_body = final_body;
 
+        level::recompute_module_levels(db, input.module_id).await?;
+
         history::insert_history(
             db,
+            ctx,
             HistoryEntry {
                 object_id: id,
                 module_id: input.module_id,
                 version: 1,
-                attribute_values: input.attributes,
-                heading: input.heading,
-                body: input.body,
+                attribute_values: final_attributes,
+                heading: webhook_heading.clone(),
+                body: webhook_body.clone(),
                 change_type: "create".to_owned(),
+                state: lc_state.map(|to_state| StateChange {
+                    from_state: None,
+                    to_state,
+                    lifecycle_model_id: lc_model_id,
+                    e_signature_id: None,
+                }),
             },
         )
         .await?;
 
-        level::recompute_module_levels(db, input.module_id).await?;
-
         let _ = WebhookService::fire(
             db,
             input.module_id,
@@ -506,40 +631,15 @@ impl ObjectService {
             .one(db)
             .await?
             .ok_or_else(|| CoreError::internal("object not found after insert".to_owned()))?;
+        AuditService::record::<object::Entity>(db, ctx, "create", id, None, Some(&created)).await?;
 
-        // Run post_save triggers (non-blocking)
-        let post_obj = ScriptObject {
-            id: created.id.to_string(),
-            heading: created.heading.clone(),
-            body: created.body.clone(),
-            level: Some(created.level.clone()),
-            classification: Some(created.classification.clone()),
-            attributes: created.attributes.clone(),
-            version: created.current_version,
-        };
-        let post_mutations = run_post_triggers(db, input.module_id, "post_save", &post_obj).await;
-        if !post_mutations.is_empty() {
-            let mut post_active: object::ActiveModel = created.clone().into();
-            apply_mutations(
-                &mut post_active,
-                id,
-                &post_mutations,
-                created.attributes.as_ref(),
-            );
-            post_active.updated_at = Set(chrono::Utc::now().fixed_offset());
-            let _ = post_active.update(db).await?;
-            return object::Entity::find_by_id(id)
-                .one(db)
-                .await?
-                .ok_or_else(|| CoreError::internal("object not found after post_save".to_owned()));
-        }
-
-        Ok(created)
+        apply_post_save(db, ctx, created).await
     }
 
     #[allow(clippy::too_many_lines)]
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateObjectInput,
     ) -> Result<object::Model, CoreError> {
@@ -563,6 +663,17 @@ impl ObjectService {
         let module_id = existing.module_id;
         let content_changed =
             input.heading.is_some() || input.body.is_some() || input.attributes.is_some();
+        let state_changed = input
+            .lifecycle_state
+            .as_ref()
+            .is_some_and(|s| existing.lifecycle_state.as_ref() != Some(s));
+        let action = if state_changed {
+            "transition"
+        } else if input.reviewed.is_some() && !content_changed {
+            "review"
+        } else {
+            "update"
+        };
 
         let mut active: object::ActiveModel = existing.clone().into();
         active.current_version = Set(new_version);
@@ -598,6 +709,7 @@ impl ObjectService {
         }
 
         // Lifecycle state transition enforcement
+        let mut state_change = None;
         if let Some(ref new_state) = input.lifecycle_state {
             if let Some(lc_model_id) = existing.lifecycle_model_id {
                 let current_state = existing.lifecycle_state.as_deref().unwrap_or("");
@@ -609,6 +721,28 @@ impl ObjectService {
                 )
                 .await?;
             }
+            if state_changed {
+                let e_signature_id = match input.signature {
+                    Some(sign_input) => {
+                        let signer_id = ctx.user_id.ok_or_else(|| {
+                            CoreError::unauthorized(
+                                "e-signature requires an authenticated user".to_owned(),
+                            )
+                        })?;
+                        let sig =
+                            ESignatureService::sign(db, ctx, signer_id, "object", id, sign_input)
+                                .await?;
+                        Some(sig.id)
+                    }
+                    None => None,
+                };
+                state_change = Some(StateChange {
+                    from_state: existing.lifecycle_state.clone(),
+                    to_state: new_state.clone(),
+                    lifecycle_model_id: existing.lifecycle_model_id,
+                    e_signature_id,
+                });
+            }
             active.lifecycle_state = Set(Some(new_state.clone()));
         }
 
@@ -653,7 +787,7 @@ impl ObjectService {
             attributes: input.attributes.clone().or(existing.attributes.clone()),
             version: new_version,
         };
-        let mutations = run_triggers(db, module_id, "pre_save", &script_obj).await?;
+        let mutations = run_triggers(db, ctx, module_id, "pre_save", &script_obj).await?;
         apply_mutations(
             &mut active,
             id,
@@ -661,19 +795,9 @@ impl ObjectService {
             input.attributes.as_ref().or(existing.attributes.as_ref()),
         );
 
-        // Recompute fingerprint on content change
-        if content_changed {
-            let new_heading = input.heading.as_deref().or(existing.heading.as_deref());
-            let new_body = input.body.as_deref().or(existing.body.as_deref());
-            let new_attrs = input.attributes.as_ref().or(existing.attributes.as_ref());
-            let fp = compute_content_fingerprint(new_heading, new_body, new_attrs);
-            active.content_fingerprint = Set(fp.clone());
-
-            active.reviewed_fingerprint = Set(None);
-            active.reviewed_at = Set(None);
-            active.reviewed_by = Set(None);
-
-            suspect::flag_suspect_links(db, id, &fp).await?;
+        // Recompute fingerprint on content change, including the triggers' edits
+        if content_changed || !mutations.is_empty() {
+            refresh_fingerprint(db, ctx, &existing, &mut active).await?;
         } else if let Some(reviewed) = input.reviewed {
             if reviewed {
                 active.reviewed_fingerprint = Set(Some(existing.content_fingerprint.clone()));
@@ -687,29 +811,46 @@ impl ObjectService {
 
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
-        let _ = active.update(db).await?;
+        let saved = active.update(db).await?;
+
+        // History carries the fields the caller or a trigger changed, as saved
+        let heading = (input.heading.is_some() || saved.heading != existing.heading)
+            .then(|| saved.heading.clone())
+            .flatten();
+        let body = (input.body.is_some() || saved.body != existing.body)
+            .then(|| saved.body.clone())
+            .flatten();
+        let attributes = (input.attributes.is_some() || saved.attributes != existing.attributes)
+            .then(|| saved.attributes.clone())
+            .flatten();
+        let webhook_heading = heading.clone();
+        let webhook_body = body.clone();
 
-        let webhook_heading = input.heading.clone();
-        let webhook_body = input.body.clone();
+        if input.parent_id.is_some() || input.position.is_some() {
+            level::recompute_module_levels(db, module_id).await?;
+        }
 
         history::insert_history(
             db,
+            ctx,
             HistoryEntry {
                 object_id: id,
                 module_id,
                 version: new_version,
-                attribute_values: input.attributes,
-                heading: input.heading,
-                body: input.body,
-                change_type: "update".to_owned(),
+                attribute_values: attributes,
+                heading,
+                body,
+                change_type: if state_change.is_some() {
+                    "state_transition"
+                } else {
+                    "update"
+                }
+                .to_owned(),
+                state: state_change,
             },
         )
         .await?;
 
-        if input.parent_id.is_some() || input.position.is_some() {
-            level::recompute_module_levels(db, module_id).await?;
-        }
-
         let _ = WebhookService::fire(
             db,
             module_id,
@@ -723,38 +864,24 @@ impl ObjectService {
             .one(db)
             .await?
             .ok_or_else(|| CoreError::internal("object not found after update".to_owned()))?;
+        AuditService::record::<object::Entity>(
+            db,
+            ctx,
+            action,
+            id,
+            Some(&existing),
+            Some(&updated),
+        )
+        .await?;
 
-        // Run post_save triggers (non-blocking)
-        let post_obj = ScriptObject {
-            id: updated.id.to_string(),
-            heading: updated.heading.clone(),
-            body: updated.body.clone(),
-            level: Some(updated.level.clone()),
-            classification: Some(updated.classification.clone()),
-            attributes: updated.attributes.clone(),
-            version: updated.current_version,
-        };
-        let post_mutations = run_post_triggers(db, module_id, "post_save", &post_obj).await;
-        if !post_mutations.is_empty() {
-            let mut post_active: object::ActiveModel = updated.clone().into();
-            apply_mutations(
-                &mut post_active,
-                id,
-                &post_mutations,
-                updated.attributes.as_ref(),
-            );
-            post_active.updated_at = Set(chrono::Utc::now().fixed_offset());
-            let _ = post_active.update(db).await?;
-            return object::Entity::find_by_id(id)
-                .one(db)
-                .await?
-                .ok_or_else(|| CoreError::internal("object not found after post_save".to_owned()));
-        }
-
-        Ok(updated)
+        apply_post_save(db, ctx, updated).await
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
         let existing = object::Entity::find_by_id(id)
             .one(db)
             .await?
@@ -770,10 +897,12 @@ impl ObjectService {
             attributes: existing.attributes.clone(),
             version: existing.current_version,
         };
-        let _mutations = run_triggers(db, existing.module_id, "pre_delete", &script_obj).await?;
+        let _mutations =
+            run_triggers(db, ctx, existing.module_id, "pre_delete", &script_obj).await?;
 
         history::insert_history(
             db,
+            ctx,
             HistoryEntry {
                 object_id: id,
                 module_id: existing.module_id,
@@ -782,6 +911,7 @@ impl ObjectService {
                 heading: existing.heading.clone(),
                 body: existing.body.clone(),
                 change_type: "delete".to_owned(),
+                state: None,
             },
         )
         .await?;
@@ -791,6 +921,8 @@ impl ObjectService {
         if result.rows_affected == 0 {
             return Err(CoreError::not_found(format!("object {id} not found")));
         }
+        AuditService::record::<object::Entity>(db, ctx, "delete", id, Some(&existing), None)
+            .await?;
 
         level::recompute_module_levels(db, module_id).await?;
 
@@ -804,13 +936,14 @@ impl ObjectService {
         .await;
 
         // Run post_delete triggers (non-blocking, no mutations to apply)
-        let _ = run_post_triggers(db, module_id, "post_delete", &script_obj).await;
+        let _ = run_post_triggers(db, ctx, module_id, "post_delete", &script_obj).await;
 
         Ok(())
     }
 
     pub async fn soft_delete(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
     ) -> Result<object::Model, CoreError> {
         let existing = object::Entity::find_by_id(id)
@@ -818,9 +951,18 @@ impl ObjectService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("object {id} not found")))?;
 
-        let mut active: object::ActiveModel = existing.into();
+        let mut active: object::ActiveModel = existing.clone().into();
         active.deleted_at = Set(Some(chrono::Utc::now().fixed_offset()));
         let result = active.update(db).await?;
+        AuditService::record::<object::Entity>(
+            db,
+            ctx,
+            "soft_delete",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -916,6 +1058,7 @@ impl ObjectService {
     #[allow(clippy::too_many_lines)]
     pub async fn move_object(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         module_id: Uuid,
         object_id: Uuid,
         input: MoveObjectInput,
@@ -930,6 +1073,7 @@ impl ObjectService {
                 "object does not belong to this module".to_owned(),
             ));
         }
+        let before = obj.clone();
 
         match input {
             MoveObjectInput::Up => {
@@ -1121,15 +1265,48 @@ impl ObjectService {
 
         level::recompute_module_levels(db, module_id).await?;
 
-        object::Entity::find_by_id(object_id)
+        // A move is a new version of the object so baseline diffs can see it
+        let moved = object::Entity::find_by_id(object_id)
             .one(db)
             .await?
-            .ok_or_else(|| CoreError::internal("object not found after move".to_owned()))
+            .ok_or_else(|| CoreError::internal("object not found after move".to_owned()))?;
+        let new_version = moved.current_version + 1;
+        let mut active: object::ActiveModel = moved.into();
+        active.current_version = Set(new_version);
+        active.updated_at = Set(chrono::Utc::now().fixed_offset());
+        let result = active.update(db).await?;
+
+        history::insert_history(
+            db,
+            ctx,
+            HistoryEntry {
+                object_id,
+                module_id,
+                version: new_version,
+                attribute_values: result.attributes.clone(),
+                heading: result.heading.clone(),
+                body: result.body.clone(),
+                change_type: "move".to_owned(),
+                state: None,
+            },
+        )
+        .await?;
+        AuditService::record::<object::Entity>(
+            db,
+            ctx,
+            "move",
+            object_id,
+            Some(&before),
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
     }
 
     /// Sync a placeholder object with its source, copying heading/body/attributes.
     pub async fn sync_placeholder(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
     ) -> Result<object::Model, CoreError> {
         let existing = object::Entity::find_by_id(id)
@@ -1152,10 +1329,13 @@ impl ObjectService {
             .ok_or_else(|| CoreError::not_found(format!("source object {source_id} not found")))?;
 
         let new_version = existing.current_version + 1;
+        let policy =
+            FingerprintPolicy::resolve(db, existing.module_id, existing.object_type_id).await?;
         let fp = compute_content_fingerprint(
             source.heading.as_deref(),
             source.body.as_deref(),
             source.attributes.as_ref(),
+            &policy,
         );
 
         let mut active: object::ActiveModel = existing.clone().into();
@@ -1172,6 +1352,7 @@ impl ObjectService {
 
         history::insert_history(
             db,
+            ctx,
             HistoryEntry {
                 object_id: id,
                 module_id: existing.module_id,
@@ -1180,19 +1361,24 @@ impl ObjectService {
                 heading: source.heading,
                 body: source.body,
                 change_type: "sync".to_owned(),
+                state: None,
             },
         )
         .await?;
 
-        object::Entity::find_by_id(id)
+        let result = object::Entity::find_by_id(id)
             .one(db)
             .await?
-            .ok_or_else(|| CoreError::internal("object not found after sync".to_owned()))
+            .ok_or_else(|| CoreError::internal("object not found after sync".to_owned()))?;
+        AuditService::record::<object::Entity>(db, ctx, "sync", id, Some(&existing), Some(&result))
+            .await?;
+        Ok(result)
     }
 
     /// Break the placeholder link, making the object independent.
     pub async fn break_placeholder_link(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
     ) -> Result<object::Model, CoreError> {
         let existing = object::Entity::find_by_id(id)
@@ -1206,18 +1392,28 @@ impl ObjectService {
             ));
         }
 
-        let mut active: object::ActiveModel = existing.into();
+        let mut active: object::ActiveModel = existing.clone().into();
         active.is_placeholder = Set(false);
         active.source_object_id = Set(None);
         active.source_module_id = Set(None);
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
         let result = active.update(db).await?;
+        AuditService::record::<object::Entity>(
+            db,
+            ctx,
+            "break_placeholder_link",
+            id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
     /// Sync all placeholders in a module, returning the count synced.
     pub async fn sync_all_placeholders(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         module_id: Uuid,
     ) -> Result<u64, CoreError> {
         let placeholders = object::Entity::find()
@@ -1229,7 +1425,7 @@ impl ObjectService {
 
         let mut count = 0u64;
         for ph in &placeholders {
-            match Self::sync_placeholder(db, ph.id).await {
+            match Self::sync_placeholder(db, ctx, ph.id).await {
                 Ok(_) => count += 1,
                 Err(e) => {
                     tracing::warn!("failed to sync placeholder {}: {e}", ph.id);
@@ -1240,18 +1436,24 @@ impl ObjectService {
         Ok(count)
     }
 
-    /// Search across all modules using full-text search.
+    /// Search across all modules using full-text search, restricted to `visible`
+    /// module ids when given.
     pub async fn search_global(
         db: &impl ConnectionTrait,
         query: &str,
         limit: u64,
+        visible: Option<Vec<Uuid>>,
     ) -> Result<Vec<GlobalSearchResult>, CoreError> {
-        let objects = object::Entity::find()
+        let mut select = object::Entity::find()
             .filter(object::Column::DeletedAt.is_null())
             .filter(Expr::cust_with_values(
                 "to_tsvector('english', COALESCE(heading, '') || ' ' || COALESCE(body, '')) @@ plainto_tsquery('english', $1)",
                 [Value::from(query.to_owned())],
-            ))
+            ));
+        if let Some(module_ids) = visible {
+            select = select.filter(object::Column::ModuleId.is_in(module_ids));
+        }
+        let objects = select
             .order_by(object::Column::UpdatedAt, Order::Desc)
             .limit(limit)
             .all(db)
@@ -7,6 +7,8 @@ use entity::object_type;
 
 use crate::crud_service;
 use crate::error::CoreError;
+use crate::fingerprint::{self, FingerprintPolicy};
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Deserialize)]
 pub struct FormLayout {
@@ -57,6 +59,8 @@ pub struct CreateObjectTypeInput {
     pub required_attributes: Option<serde_json::Value>,
     #[schema(value_type = Option<Object>)]
     pub attribute_schema: Option<serde_json::Value>,
+    /// Overrides the module's fingerprint policy for objects of this type.
+    pub fingerprint_policy: Option<FingerprintPolicy>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -68,6 +72,8 @@ pub struct UpdateObjectTypeInput {
     pub required_attributes: Option<serde_json::Value>,
     #[schema(value_type = Option<Object>)]
     pub attribute_schema: Option<serde_json::Value>,
+    /// Overrides the module's fingerprint policy for objects of this type.
+    pub fingerprint_policy: Option<FingerprintPolicy>,
 }
 
 pub struct ObjectTypeService;
@@ -75,6 +81,7 @@ pub struct ObjectTypeService;
 impl ObjectTypeService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateObjectTypeInput,
     ) -> Result<object_type::Model, CoreError> {
         if let Some(ref schema) = input.attribute_schema {
@@ -94,16 +101,27 @@ impl ObjectTypeService {
                 .unwrap_or_else(|| "normative".to_owned())),
             required_attributes: Set(input.required_attributes.unwrap_or(serde_json::json!([]))),
             attribute_schema: Set(input.attribute_schema.unwrap_or(serde_json::json!({}))),
+            fingerprint_policy: Set(input.fingerprint_policy.and_then(|p| p.to_json())),
             created_at: Set(now),
             updated_at: Set(now),
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<object_type::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateObjectTypeInput,
     ) -> Result<object_type::Model, CoreError> {
@@ -116,7 +134,7 @@ impl ObjectTypeService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("object_type {id} not found")))?;
 
-        let mut active: object_type::ActiveModel = existing.into();
+        let mut active: object_type::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -132,9 +150,28 @@ impl ObjectTypeService {
         if let Some(attribute_schema) = input.attribute_schema {
             active.attribute_schema = Set(attribute_schema);
         }
+        let policy_changed = input
+            .fingerprint_policy
+            .map(|p| p.to_json())
+            .filter(|p| *p != existing.fingerprint_policy);
+        if let Some(policy) = &policy_changed {
+            active.fingerprint_policy = Set(policy.clone());
+        }
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        if policy_changed.is_some() {
+            let _ = fingerprint::refingerprint_module(db, result.module_id).await?;
+        }
+        AuditService::record::<object_type::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 }
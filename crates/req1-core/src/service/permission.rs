@@ -0,0 +1,457 @@
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, QueryFilter, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use entity::{
+    baseline, dashboard, external_link, link, module, module_permission, object, object_type,
+    project, review_package, script_library, test_case, workspace, workspace_member,
+};
+
+use crate::auth::AuthUser;
+use crate::error::CoreError;
+
+/// Access level on a workspace or module. Ordered so that a higher level implies the lower ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    Read,
+    Write,
+    Admin,
+}
+
+impl Permission {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Parse a `module_permission.permission` value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(Self::Read),
+            "write" => Some(Self::Write),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+
+    /// Map a workspace member role onto the permission it grants.
+    pub fn from_role(role: &str) -> Option<Self> {
+        match role {
+            "admin" => Some(Self::Admin),
+            "editor" => Some(Self::Write),
+            "reviewer" | "viewer" => Some(Self::Read),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A resource an API call touches, resolved up to its module or workspace for authorization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Workspace(Uuid),
+    Project(Uuid),
+    Module(Uuid),
+    Object(Uuid),
+    Link(Uuid),
+    ExternalLink(Uuid),
+    ReviewPackage(Uuid),
+    TestCase(Uuid),
+    Dashboard(Uuid),
+    Baseline(Uuid),
+    ScriptLibrary(Uuid),
+    ObjectType(Uuid),
+}
+
+/// Where a scope ends up once its parents are resolved.
+enum Resolved {
+    Workspace(Uuid),
+    Module(Uuid),
+}
+
+pub struct PermissionService;
+
+impl PermissionService {
+    fn is_global_admin(user: &AuthUser) -> bool {
+        user.role == "admin"
+    }
+
+    /// Require a server admin, for global catalogs that belong to no workspace.
+    pub fn require_admin(user: &AuthUser) -> Result<(), CoreError> {
+        if Self::is_global_admin(user) {
+            Ok(())
+        } else {
+            Err(CoreError::forbidden("admin role required"))
+        }
+    }
+
+    /// Effective permission of `user` on a workspace, from its membership role.
+    pub async fn workspace_permission(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+        workspace_id: Uuid,
+    ) -> Result<Option<Permission>, CoreError> {
+        if Self::is_global_admin(user) {
+            return Ok(Some(Permission::Admin));
+        }
+        let member = workspace_member::Entity::find()
+            .filter(workspace_member::Column::UserId.eq(user.id))
+            .filter(workspace_member::Column::WorkspaceId.eq(workspace_id))
+            .one(db)
+            .await?;
+        Ok(member.and_then(|m| Permission::from_role(&m.role)))
+    }
+
+    /// Effective permission of `user` on a module.
+    ///
+    /// Workspace admins always have admin access. A module without any explicit grants
+    /// inherits the workspace role; once a module has grants it is restricted to its
+    /// grantees, each at the granted level.
+    pub async fn module_permission(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+        module_id: Uuid,
+    ) -> Result<Option<Permission>, CoreError> {
+        if Self::is_global_admin(user) {
+            return Ok(Some(Permission::Admin));
+        }
+        let module = module::Entity::find_by_id(module_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("module {module_id} not found")))?;
+        let project = project::Entity::find_by_id(module.project_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                CoreError::not_found(format!("project {} not found", module.project_id))
+            })?;
+
+        let workspace = Self::workspace_permission(db, user, project.workspace_id).await?;
+        if workspace == Some(Permission::Admin) {
+            return Ok(workspace);
+        }
+
+        let grants = module_permission::Entity::find()
+            .filter(module_permission::Column::ModuleId.eq(module_id))
+            .all(db)
+            .await?;
+        if grants.is_empty() {
+            return Ok(workspace);
+        }
+        Ok(grants
+            .iter()
+            .find(|g| g.user_id == user.id)
+            .and_then(|g| Permission::parse(&g.permission)))
+    }
+
+    /// Require at least `needed` on the given scope, returning `Forbidden` otherwise.
+    pub async fn require(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+        scope: Scope,
+        needed: Permission,
+    ) -> Result<(), CoreError> {
+        if Self::is_global_admin(user) {
+            return Ok(());
+        }
+        let (granted, what) = match Self::resolve(db, scope).await? {
+            Resolved::Workspace(id) => (
+                Self::workspace_permission(db, user, id).await?,
+                format!("workspace {id}"),
+            ),
+            Resolved::Module(id) => (
+                Self::module_permission(db, user, id).await?,
+                format!("module {id}"),
+            ),
+        };
+        if granted.is_some_and(|p| p >= needed) {
+            Ok(())
+        } else {
+            Err(CoreError::forbidden(format!(
+                "{needed} access to {what} required"
+            )))
+        }
+    }
+
+    pub async fn require_workspace(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+        workspace_id: Uuid,
+        needed: Permission,
+    ) -> Result<(), CoreError> {
+        Self::require(db, user, Scope::Workspace(workspace_id), needed).await
+    }
+
+    pub async fn require_project(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+        project_id: Uuid,
+        needed: Permission,
+    ) -> Result<(), CoreError> {
+        Self::require(db, user, Scope::Project(project_id), needed).await
+    }
+
+    pub async fn require_module(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+        module_id: Uuid,
+        needed: Permission,
+    ) -> Result<(), CoreError> {
+        Self::require(db, user, Scope::Module(module_id), needed).await
+    }
+
+    pub async fn require_object(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+        object_id: Uuid,
+        needed: Permission,
+    ) -> Result<(), CoreError> {
+        Self::require(db, user, Scope::Object(object_id), needed).await
+    }
+
+    async fn resolve(db: &impl ConnectionTrait, scope: Scope) -> Result<Resolved, CoreError> {
+        let resolved = match scope {
+            Scope::Workspace(id) => {
+                let w = workspace::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("workspace {id} not found")))?;
+                Resolved::Workspace(w.id)
+            }
+            Scope::Module(id) => Resolved::Module(id),
+            Scope::Project(id) => {
+                let p = project::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("project {id} not found")))?;
+                Resolved::Workspace(p.workspace_id)
+            }
+            Scope::Dashboard(id) => {
+                let d = dashboard::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("dashboard {id} not found")))?;
+                Resolved::Workspace(d.workspace_id)
+            }
+            Scope::ScriptLibrary(id) => {
+                let l = script_library::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| {
+                        CoreError::not_found(format!("script library {id} not found"))
+                    })?;
+                match (l.workspace_id, l.project_id) {
+                    (Some(ws), _) => Resolved::Workspace(ws),
+                    (None, Some(project_id)) => {
+                        let p = project::Entity::find_by_id(project_id)
+                            .one(db)
+                            .await?
+                            .ok_or_else(|| {
+                                CoreError::not_found(format!("project {project_id} not found"))
+                            })?;
+                        Resolved::Workspace(p.workspace_id)
+                    }
+                    (None, None) => {
+                        return Err(CoreError::not_found(format!(
+                            "script library {id} not found"
+                        )));
+                    }
+                }
+            }
+            Scope::Object(id) => {
+                let o = object::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("object {id} not found")))?;
+                Resolved::Module(o.module_id)
+            }
+            Scope::Link(id) => {
+                let l = link::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("link {id} not found")))?;
+                let source = object::Entity::find_by_id(l.source_object_id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| {
+                        CoreError::not_found(format!(
+                            "source object {} not found",
+                            l.source_object_id
+                        ))
+                    })?;
+                Resolved::Module(source.module_id)
+            }
+            Scope::ExternalLink(id) => {
+                let l = external_link::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("external link {id} not found")))?;
+                let source = object::Entity::find_by_id(l.source_object_id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| {
+                        CoreError::not_found(format!(
+                            "source object {} not found",
+                            l.source_object_id
+                        ))
+                    })?;
+                Resolved::Module(source.module_id)
+            }
+            Scope::ReviewPackage(id) => {
+                let p = review_package::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| {
+                        CoreError::not_found(format!("review package {id} not found"))
+                    })?;
+                Resolved::Module(p.module_id)
+            }
+            Scope::Baseline(id) => {
+                let b = baseline::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("baseline {id} not found")))?;
+                Resolved::Module(b.module_id)
+            }
+            Scope::ObjectType(id) => {
+                let t = object_type::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("object type {id} not found")))?;
+                Resolved::Module(t.module_id)
+            }
+            Scope::TestCase(id) => {
+                let t = test_case::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| CoreError::not_found(format!("test case {id} not found")))?;
+                Resolved::Module(t.module_id)
+            }
+        };
+        Ok(resolved)
+    }
+
+    /// Look up the parent id stored in `table.parent_column` for row `id`.
+    ///
+    /// Used to make sure a nested `{id}` actually belongs to the parent named in the path.
+    /// Table and column names must be trusted constants.
+    pub async fn parent_of(
+        db: &impl ConnectionTrait,
+        table: &str,
+        parent_column: &str,
+        id: Uuid,
+    ) -> Result<Option<Uuid>, CoreError> {
+        let sql = format!("SELECT {parent_column} AS parent FROM {table} WHERE id = $1");
+        let row = db
+            .query_one(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                &sql,
+                [id.into()],
+            ))
+            .await?;
+        Ok(row.and_then(|r| r.try_get::<Option<Uuid>>("", "parent").ok().flatten()))
+    }
+
+    /// Workspace ids `user` is a member of, or `None` when unrestricted.
+    pub async fn visible_workspace_ids(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+    ) -> Result<Option<Vec<Uuid>>, CoreError> {
+        if Self::is_global_admin(user) {
+            return Ok(None);
+        }
+        let ids = workspace_member::Entity::find()
+            .filter(workspace_member::Column::UserId.eq(user.id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|m| m.workspace_id)
+            .collect();
+        Ok(Some(ids))
+    }
+
+    /// Module ids `user` can read, or `None` when unrestricted.
+    pub async fn visible_module_ids(
+        db: &impl ConnectionTrait,
+        user: &AuthUser,
+    ) -> Result<Option<Vec<Uuid>>, CoreError> {
+        if Self::is_global_admin(user) {
+            return Ok(None);
+        }
+
+        let memberships: HashMap<Uuid, Option<Permission>> = workspace_member::Entity::find()
+            .filter(workspace_member::Column::UserId.eq(user.id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|m| (m.workspace_id, Permission::from_role(&m.role)))
+            .collect();
+
+        let own_grants: HashSet<Uuid> = module_permission::Entity::find()
+            .filter(module_permission::Column::UserId.eq(user.id))
+            .all(db)
+            .await?
+            .into_iter()
+            .filter(|g| Permission::parse(&g.permission).is_some())
+            .map(|g| g.module_id)
+            .collect();
+
+        let mut visible: HashSet<Uuid> = own_grants.clone();
+        if memberships.is_empty() {
+            return Ok(Some(visible.into_iter().collect()));
+        }
+
+        let projects: HashMap<Uuid, Uuid> = project::Entity::find()
+            .filter(project::Column::WorkspaceId.is_in(memberships.keys().copied()))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|p| (p.id, p.workspace_id))
+            .collect();
+        if projects.is_empty() {
+            return Ok(Some(visible.into_iter().collect()));
+        }
+
+        let modules = module::Entity::find()
+            .filter(module::Column::ProjectId.is_in(projects.keys().copied()))
+            .all(db)
+            .await?;
+        let restricted: HashSet<Uuid> = module_permission::Entity::find()
+            .filter(module_permission::Column::ModuleId.is_in(modules.iter().map(|m| m.id)))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|g| g.module_id)
+            .collect();
+
+        for m in &modules {
+            let role = projects
+                .get(&m.project_id)
+                .and_then(|ws| memberships.get(ws))
+                .copied()
+                .flatten();
+            let readable = match role {
+                Some(Permission::Admin) => true,
+                Some(_) => !restricted.contains(&m.id),
+                None => false,
+            };
+            if readable {
+                let _ = visible.insert(m.id);
+            }
+        }
+
+        Ok(Some(visible.into_iter().collect()))
+    }
+}
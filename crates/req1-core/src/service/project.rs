@@ -7,6 +7,7 @@ use entity::project;
 
 use crate::crud_service;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateProjectInput {
@@ -27,6 +28,7 @@ pub struct ProjectService;
 impl ProjectService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateProjectInput,
     ) -> Result<project::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -42,11 +44,14 @@ impl ProjectService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<project::Entity>(db, ctx, "create", result.id, None, Some(&result))
+            .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateProjectInput,
     ) -> Result<project::Model, CoreError> {
@@ -55,7 +60,7 @@ impl ProjectService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("project {id} not found")))?;
 
-        let mut active: project::ActiveModel = existing.into();
+        let mut active: project::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -65,6 +70,15 @@ impl ProjectService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<project::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 }
@@ -8,6 +8,7 @@ use entity::{
 };
 
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateTemplateInput {
@@ -56,6 +57,7 @@ impl ProjectTemplateService {
 
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateTemplateInput,
     ) -> Result<project_template::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -75,6 +77,15 @@ impl ProjectTemplateService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<project_template::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -90,6 +101,7 @@ impl ProjectTemplateService {
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateTemplateInput,
     ) -> Result<project_template::Model, CoreError> {
@@ -98,7 +110,7 @@ impl ProjectTemplateService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("project template {id} not found")))?;
 
-        let mut active: project_template::ActiveModel = existing.into();
+        let mut active: project_template::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -117,10 +129,23 @@ impl ProjectTemplateService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<project_template::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
         let existing = project_template::Entity::find_by_id(id)
             .one(db)
             .await?
@@ -132,18 +157,22 @@ impl ProjectTemplateService {
             ));
         }
 
-        let result = project_template::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!(
-                "project template {id} not found"
-            )));
-        }
-        Ok(())
+        let _ = project_template::Entity::delete_by_id(id).exec(db).await?;
+        AuditService::record::<project_template::Entity>(
+            db,
+            ctx,
+            "delete",
+            id,
+            Some(&existing),
+            None,
+        )
+        .await
     }
 
     #[allow(clippy::too_many_lines)]
     pub async fn instantiate(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: InstantiateInput,
     ) -> Result<InstantiateResult, CoreError> {
@@ -163,7 +192,16 @@ impl ProjectTemplateService {
             created_at: Set(now),
             updated_at: Set(now),
         };
-        let _ = project_model.insert(db).await?;
+        let project = project_model.insert(db).await?;
+        AuditService::record::<project::Entity>(
+            db,
+            ctx,
+            "create",
+            project_id,
+            None,
+            Some(&project),
+        )
+        .await?;
 
         let data = &template.template_data;
         let modules_arr = data
@@ -216,10 +254,22 @@ impl ProjectTemplateService {
                 publish_template: Set(None),
                 default_lifecycle_model_id: Set(None),
                 signature_config: Set(serde_json::json!({})),
+                fingerprint_policy: Set(None),
                 created_at: Set(now),
                 updated_at: Set(now),
+                reqif_id: Set(None),
+                reqif_profile: Set(None),
             };
-            let _ = new_module.insert(db).await?;
+            let created = new_module.insert(db).await?;
+            AuditService::record::<module::Entity>(
+                db,
+                ctx,
+                "create",
+                module_id,
+                None,
+                Some(&created),
+            )
+            .await?;
 
             // Create attribute definitions
             let attr_defs = module_def
@@ -285,6 +335,7 @@ impl ProjectTemplateService {
                     default_classification: Set(ot_class),
                     required_attributes: Set(required_attrs),
                     attribute_schema: Set(serde_json::json!({})),
+                    fingerprint_policy: Set(None),
                     created_at: Set(now),
                     updated_at: Set(now),
                 };
@@ -373,6 +424,8 @@ impl ProjectTemplateService {
                         deleted_at: Set(None),
                         created_at: Set(now),
                         updated_at: Set(now),
+                        reqif_id: Set(None),
+                        reqif_base: Set(None),
                     };
                     let _ = parent_obj.insert(db).await?;
 
@@ -423,6 +476,8 @@ impl ProjectTemplateService {
                             deleted_at: Set(None),
                             created_at: Set(now),
                             updated_at: Set(now),
+                            reqif_id: Set(None),
+                            reqif_base: Set(None),
                         };
                         let _ = child_obj.insert(db).await?;
                     }
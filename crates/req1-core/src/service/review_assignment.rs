@@ -7,6 +7,7 @@ use entity::review_assignment;
 
 use crate::crud_service;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 const VALID_STATUSES: &[&str] = &["pending", "approved", "rejected", "abstained"];
 
@@ -28,6 +29,7 @@ pub struct ReviewAssignmentService;
 impl ReviewAssignmentService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateReviewAssignmentInput,
     ) -> Result<review_assignment::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -44,11 +46,21 @@ impl ReviewAssignmentService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<review_assignment::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateReviewAssignmentInput,
     ) -> Result<review_assignment::Model, CoreError> {
@@ -57,7 +69,7 @@ impl ReviewAssignmentService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("review_assignment {id} not found")))?;
 
-        let mut active: review_assignment::ActiveModel = existing.into();
+        let mut active: review_assignment::ActiveModel = existing.clone().into();
         if let Some(ref status) = input.status {
             if !VALID_STATUSES.contains(&status.as_str()) {
                 return Err(CoreError::bad_request(format!(
@@ -74,6 +86,15 @@ impl ReviewAssignmentService {
         }
 
         let result = active.update(db).await?;
+        AuditService::record::<review_assignment::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 }
@@ -10,6 +10,8 @@ use entity::review_comment;
 
 use crate::PaginatedResponse;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
 use crate::service::mention::MentionService;
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -30,6 +32,7 @@ pub struct ReviewCommentService;
 impl ReviewCommentService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateReviewCommentInput,
     ) -> Result<review_comment::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -51,6 +54,15 @@ impl ReviewCommentService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<review_comment::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
 
         if !mentioned_ids.is_empty() {
             MentionService::notify_mentioned(
@@ -69,6 +81,7 @@ impl ReviewCommentService {
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateReviewCommentInput,
     ) -> Result<review_comment::Model, CoreError> {
@@ -77,24 +90,31 @@ impl ReviewCommentService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("review_comment {id} not found")))?;
 
-        let mut active: review_comment::ActiveModel = existing.into();
+        let mut active: review_comment::ActiveModel = existing.clone().into();
         if let Some(body) = input.body {
             active.body = Set(body);
         }
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<review_comment::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = review_comment::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!(
-                "review_comment {id} not found"
-            )));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<review_comment::Entity>(db, ctx, id, "review_comment").await
     }
 
     pub async fn get(
@@ -9,6 +9,8 @@ use entity::{review_assignment, review_package};
 
 use crate::PaginatedResponse;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
 use crate::service::e_signature::{ESignatureService, SignInput};
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -53,6 +55,7 @@ pub struct ReviewPackageService;
 impl ReviewPackageService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateReviewPackageInput,
     ) -> Result<review_package::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -70,11 +73,21 @@ impl ReviewPackageService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<review_package::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateReviewPackageInput,
     ) -> Result<review_package::Model, CoreError> {
@@ -83,7 +96,7 @@ impl ReviewPackageService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("review_package {id} not found")))?;
 
-        let mut active: review_package::ActiveModel = existing.into();
+        let mut active: review_package::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -101,17 +114,24 @@ impl ReviewPackageService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<review_package::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = review_package::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!(
-                "review_package {id} not found"
-            )));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<review_package::Entity>(db, ctx, id, "review_package").await
     }
 
     pub async fn get(
@@ -197,6 +217,7 @@ impl ReviewPackageService {
     /// Transition a review package status with optional e-signature enforcement.
     pub async fn transition_status(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         new_status: &str,
         signer_id: Uuid,
@@ -233,14 +254,24 @@ impl ReviewPackageService {
                 ESignatureService::check_four_eyes(db, signer_id, "review_package", id).await?;
             }
 
-            let _sig = ESignatureService::sign(db, signer_id, "review_package", id, input).await?;
+            let _sig =
+                ESignatureService::sign(db, ctx, signer_id, "review_package", id, input).await?;
         }
 
-        let mut active: review_package::ActiveModel = existing.into();
+        let mut active: review_package::ActiveModel = existing.clone().into();
         active.status = Set(new_status.to_owned());
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<review_package::Entity>(
+            db,
+            ctx,
+            "transition",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 }
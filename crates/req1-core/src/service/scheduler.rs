@@ -1,5 +1,8 @@
+use std::cell::RefCell;
+use std::future::Future;
 use std::str::FromStr;
 
+use chrono::{DateTime, FixedOffset};
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, Order, PaginatorTrait,
     QueryFilter, QueryOrder, Set,
@@ -11,6 +14,32 @@ use entity::{script, script_execution};
 use crate::PaginatedResponse;
 use crate::error::CoreError;
 
+/// A failed run of a script that ran inside a transaction, kept until that
+/// transaction has ended.
+struct FailedRun {
+    script_id: Uuid,
+    started_at: DateTime<FixedOffset>,
+    finished_at: DateTime<FixedOffset>,
+    status: &'static str,
+    error_message: String,
+}
+
+impl FailedRun {
+    fn new(script_id: Uuid, started_at: DateTime<FixedOffset>, error: &CoreError) -> Self {
+        Self {
+            script_id,
+            started_at,
+            finished_at: chrono::Utc::now().fixed_offset(),
+            status: SchedulerService::failure_status(error),
+            error_message: error.to_string(),
+        }
+    }
+}
+
+tokio::task_local! {
+    static FAILED_RUNS: RefCell<Vec<FailedRun>>;
+}
+
 pub struct SchedulerService;
 
 impl SchedulerService {
@@ -94,6 +123,84 @@ impl SchedulerService {
         Ok(result)
     }
 
+    /// Note a failed run of a script that ran inside a transaction, such as a
+    /// trigger. Its failure rolls that transaction back, so the record is written by
+    /// the enclosing `record_failed_runs` once the transaction has ended.
+    pub fn note_failed_run(script_id: Uuid, started_at: DateTime<FixedOffset>, error: &CoreError) {
+        let run = FailedRun::new(script_id, started_at, error);
+        if FAILED_RUNS
+            .try_with(|runs| runs.borrow_mut().push(run))
+            .is_err()
+        {
+            tracing::warn!("failed run of script {script_id} not recorded: {error}");
+        }
+    }
+
+    /// Run `fut`, then record the failed runs noted while it ran.
+    pub async fn record_failed_runs<F: Future>(db: &impl ConnectionTrait, fut: F) -> F::Output {
+        let (output, runs) = FAILED_RUNS
+            .scope(RefCell::new(Vec::new()), async {
+                let output = fut.await;
+                (output, FAILED_RUNS.with(RefCell::take))
+            })
+            .await;
+        for run in runs {
+            let script_id = run.script_id;
+            if let Err(e) = Self::insert_failed_run(db, run).await {
+                tracing::warn!("failed to record failed run of script {script_id}: {e}");
+            }
+        }
+        output
+    }
+
+    /// Record a failed run that had no execution started for it, such as a layout
+    /// script, which runs too often to record every success.
+    pub async fn record_failed_run(
+        db: &impl ConnectionTrait,
+        script_id: Uuid,
+        started_at: DateTime<FixedOffset>,
+        error: &CoreError,
+    ) -> Result<script_execution::Model, CoreError> {
+        Self::insert_failed_run(db, FailedRun::new(script_id, started_at, error)).await
+    }
+
+    async fn insert_failed_run(
+        db: &impl ConnectionTrait,
+        run: FailedRun,
+    ) -> Result<script_execution::Model, CoreError> {
+        let model = script_execution::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            script_id: Set(run.script_id),
+            status: Set(run.status.to_string()),
+            started_at: Set(run.started_at),
+            finished_at: Set(Some(run.finished_at)),
+            duration_ms: Set(Some((run.finished_at - run.started_at).num_milliseconds())),
+            output: Set(None),
+            error_message: Set(Some(run.error_message)),
+            created_at: Set(run.finished_at),
+        };
+        Ok(model.insert(db).await?)
+    }
+
+    /// Finish a failed execution; runs stopped by the time or heap limit are recorded as
+    /// `timeout` or `memory_limit`.
+    pub async fn record_execution_failure(
+        db: &impl ConnectionTrait,
+        execution: script_execution::Model,
+        error: &CoreError,
+    ) -> Result<script_execution::Model, CoreError> {
+        let status = Self::failure_status(error);
+        Self::record_execution_finish(db, execution, status, None, Some(error.to_string())).await
+    }
+
+    fn failure_status(error: &CoreError) -> &'static str {
+        match error {
+            CoreError::ScriptTimeout(_) => "timeout",
+            CoreError::ScriptMemoryLimit(_) => "memory_limit",
+            _ => "error",
+        }
+    }
+
     pub async fn update_script_run_times(
         db: &impl ConnectionTrait,
         script_model: script::Model,
@@ -0,0 +1,315 @@
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, Order, QueryFilter, QueryOrder,
+    Set,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use entity::{module, project, script_library, script_library_version};
+
+use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateScriptLibraryInput {
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub source_code: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateScriptLibraryInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Stored as a new version.
+    pub source_code: Option<String>,
+    /// Whether the new version becomes the active one; defaults to true. Pass
+    /// false to stage a version for scripts that pin it before rolling it out.
+    pub activate: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ActivateVersionInput {
+    pub version: i32,
+}
+
+pub struct ScriptLibraryService;
+
+impl ScriptLibraryService {
+    pub async fn list_for_workspace(
+        db: &impl ConnectionTrait,
+        workspace_id: Uuid,
+    ) -> Result<Vec<script_library::Model>, CoreError> {
+        let items = script_library::Entity::find()
+            .filter(script_library::Column::WorkspaceId.eq(workspace_id))
+            .order_by(script_library::Column::Name, Order::Asc)
+            .all(db)
+            .await?;
+        Ok(items)
+    }
+
+    pub async fn list_for_project(
+        db: &impl ConnectionTrait,
+        project_id: Uuid,
+    ) -> Result<Vec<script_library::Model>, CoreError> {
+        let items = script_library::Entity::find()
+            .filter(script_library::Column::ProjectId.eq(project_id))
+            .order_by(script_library::Column::Name, Order::Asc)
+            .all(db)
+            .await?;
+        Ok(items)
+    }
+
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        input: CreateScriptLibraryInput,
+    ) -> Result<script_library::Model, CoreError> {
+        if input.workspace_id.is_some() == input.project_id.is_some() {
+            return Err(CoreError::bad_request(
+                "a script library belongs to either a workspace or a project".to_owned(),
+            ));
+        }
+        validate_name(&input.name)?;
+        ensure_unique(db, input.workspace_id, input.project_id, &input.name).await?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let model = script_library::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            workspace_id: Set(input.workspace_id),
+            project_id: Set(input.project_id),
+            name: Set(input.name),
+            description: Set(input.description),
+            active_version: Set(1),
+            latest_version: Set(1),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        let result = model.insert(db).await?;
+        insert_version(db, ctx, result.id, 1, input.source_code).await?;
+
+        AuditService::record::<script_library::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn get(
+        db: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<script_library::Model, CoreError> {
+        script_library::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("script library {id} not found")))
+    }
+
+    pub async fn update(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+        input: UpdateScriptLibraryInput,
+    ) -> Result<script_library::Model, CoreError> {
+        let existing = Self::get(db, id).await?;
+        let mut active: script_library::ActiveModel = existing.clone().into();
+
+        if let Some(name) = input.name.filter(|n| *n != existing.name) {
+            validate_name(&name)?;
+            ensure_unique(db, existing.workspace_id, existing.project_id, &name).await?;
+            active.name = Set(name);
+        }
+        if let Some(description) = input.description {
+            active.description = Set(Some(description));
+        }
+        if let Some(source_code) = input.source_code {
+            let version = existing.latest_version + 1;
+            insert_version(db, ctx, id, version, source_code).await?;
+            active.latest_version = Set(version);
+            if input.activate.unwrap_or(true) {
+                active.active_version = Set(version);
+            }
+        }
+        active.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        let result = active.update(db).await?;
+        AuditService::record::<script_library::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+
+    /// Make `version` the one scripts import, to roll a change out or back.
+    pub async fn activate(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+        version: i32,
+    ) -> Result<script_library::Model, CoreError> {
+        let existing = Self::get(db, id).await?;
+        let _ = Self::get_version(db, id, version).await?;
+
+        let mut active: script_library::ActiveModel = existing.clone().into();
+        active.active_version = Set(version);
+        active.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        let result = active.update(db).await?;
+        AuditService::record::<script_library::Entity>(
+            db,
+            ctx,
+            "activate",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<script_library::Entity>(db, ctx, id, "script library").await
+    }
+
+    pub async fn list_versions(
+        db: &impl ConnectionTrait,
+        id: Uuid,
+    ) -> Result<Vec<script_library_version::Model>, CoreError> {
+        let _ = Self::get(db, id).await?;
+        let items = script_library_version::Entity::find()
+            .filter(script_library_version::Column::LibraryId.eq(id))
+            .order_by(script_library_version::Column::Version, Order::Desc)
+            .all(db)
+            .await?;
+        Ok(items)
+    }
+
+    pub async fn get_version(
+        db: &impl ConnectionTrait,
+        id: Uuid,
+        version: i32,
+    ) -> Result<script_library_version::Model, CoreError> {
+        script_library_version::Entity::find()
+            .filter(script_library_version::Column::LibraryId.eq(id))
+            .filter(script_library_version::Column::Version.eq(version))
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                CoreError::not_found(format!(
+                    "version {version} of script library {id} not found"
+                ))
+            })
+    }
+
+    /// The library a script in `module_id` gets for `req1:lib/<name>`: the
+    /// project's library of that name, else the workspace's. Without `version`
+    /// the active version.
+    pub async fn resolve(
+        db: &impl ConnectionTrait,
+        module_id: Uuid,
+        name: &str,
+        version: Option<i32>,
+    ) -> Result<script_library_version::Model, CoreError> {
+        let m = module::Entity::find_by_id(module_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("module {module_id} not found")))?;
+        let p = project::Entity::find_by_id(m.project_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("project {} not found", m.project_id)))?;
+
+        let mut libraries = script_library::Entity::find()
+            .filter(script_library::Column::Name.eq(name))
+            .filter(
+                script_library::Column::ProjectId
+                    .eq(p.id)
+                    .or(script_library::Column::WorkspaceId.eq(p.workspace_id)),
+            )
+            .all(db)
+            .await?;
+        // Project libraries shadow workspace libraries of the same name
+        libraries.sort_by_key(|l| l.project_id.is_none());
+        let library = libraries
+            .into_iter()
+            .next()
+            .ok_or_else(|| CoreError::not_found(format!("script library '{name}' not found")))?;
+
+        Self::get_version(db, library.id, version.unwrap_or(library.active_version)).await
+    }
+}
+
+async fn insert_version(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    library_id: Uuid,
+    version: i32,
+    source_code: String,
+) -> Result<(), CoreError> {
+    let model = script_library_version::ActiveModel {
+        id: Set(Uuid::now_v7()),
+        library_id: Set(library_id),
+        version: Set(version),
+        source_code: Set(source_code),
+        created_by: Set(ctx.user_id),
+        created_at: Set(chrono::Utc::now().fixed_offset()),
+    };
+    let _ = model.insert(db).await?;
+    Ok(())
+}
+
+async fn ensure_unique(
+    db: &impl ConnectionTrait,
+    workspace_id: Option<Uuid>,
+    project_id: Option<Uuid>,
+    name: &str,
+) -> Result<(), CoreError> {
+    let mut query = script_library::Entity::find().filter(script_library::Column::Name.eq(name));
+    query = match (workspace_id, project_id) {
+        (Some(ws), _) => query.filter(script_library::Column::WorkspaceId.eq(ws)),
+        (None, Some(p)) => query.filter(script_library::Column::ProjectId.eq(p)),
+        (None, None) => return Ok(()),
+    };
+    if query.one(db).await?.is_some() {
+        return Err(CoreError::conflict(format!(
+            "script library '{name}' already exists"
+        )));
+    }
+    Ok(())
+}
+
+/// Names end up in `req1:lib/<name>` specifiers, so keep them to plain
+/// identifier characters.
+fn validate_name(name: &str) -> Result<(), CoreError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(CoreError::bad_request(format!(
+            "invalid script library name '{name}': use letters, digits, '_' and '-'"
+        )))
+    }
+}
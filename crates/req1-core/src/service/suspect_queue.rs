@@ -0,0 +1,294 @@
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+    prelude::DateTimeWithTimeZone,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use entity::{link, link_history, link_type, object, object_history};
+
+use crate::PaginatedResponse;
+use crate::error::CoreError;
+use crate::history::Content;
+use crate::link_history::{LinkHistoryEntry, insert_link_history};
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::link::clear_suspect;
+
+const fn default_limit() -> u64 {
+    50
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SuspectQueueFilter {
+    /// Links with either end in this module.
+    pub module_id: Option<Uuid>,
+    pub link_type_id: Option<Uuid>,
+    /// Only links that have been suspect for at least this many days.
+    pub min_age_days: Option<i64>,
+    #[serde(default)]
+    pub offset: u64,
+    #[serde(default = "default_limit")]
+    pub limit: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldChange {
+    /// `heading`, `body` or `attributes.<name>`.
+    pub field: String,
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<serde_json::Value>,
+}
+
+/// One end of a suspect link and how it changed since the link was last cleared.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SuspectLinkEnd {
+    pub object_id: Uuid,
+    pub module_id: Uuid,
+    pub heading: Option<String>,
+    pub current_version: i32,
+    /// Object version when the link was last cleared (or created); `None` without history.
+    pub cleared_version: Option<i32>,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SuspectLinkEntry {
+    #[serde(flatten)]
+    pub link: link::Model,
+    pub link_type_name: String,
+    #[schema(value_type = String)]
+    pub cleared_at: DateTimeWithTimeZone,
+    pub source: SuspectLinkEnd,
+    pub target: SuspectLinkEnd,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ResolveSuspectsInput {
+    pub link_ids: Vec<Uuid>,
+    /// Why the changes do not affect the links; stored with each clearance.
+    pub justification: String,
+}
+
+pub struct SuspectQueueService;
+
+impl SuspectQueueService {
+    /// Suspect links across modules, oldest first. `visible` limits the result to links
+    /// whose ends both lie in readable modules.
+    pub async fn list(
+        db: &impl ConnectionTrait,
+        filter: SuspectQueueFilter,
+        visible: Option<Vec<Uuid>>,
+    ) -> Result<PaginatedResponse<SuspectLinkEntry>, CoreError> {
+        let mut query = link::Entity::find()
+            .filter(link::Column::Suspect.eq(true))
+            .order_by_asc(link::Column::SuspectedAt)
+            .order_by_asc(link::Column::Id);
+        if let Some(lt) = filter.link_type_id {
+            query = query.filter(link::Column::LinkTypeId.eq(lt));
+        }
+        if let Some(days) = filter.min_age_days {
+            let cutoff = chrono::Utc::now().fixed_offset() - chrono::Duration::days(days);
+            query = query.filter(link::Column::SuspectedAt.lte(cutoff));
+        }
+        let links = query.all(db).await?;
+
+        let object_ids: HashSet<Uuid> = links
+            .iter()
+            .flat_map(|l| [l.source_object_id, l.target_object_id])
+            .collect();
+        let objects: HashMap<Uuid, object::Model> = object::Entity::find()
+            .filter(object::Column::Id.is_in(object_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|o| (o.id, o))
+            .collect();
+        let visible: Option<HashSet<Uuid>> = visible.map(|v| v.into_iter().collect());
+
+        let links: Vec<link::Model> = links
+            .into_iter()
+            .filter(|l| {
+                let (Some(s), Some(t)) = (
+                    objects.get(&l.source_object_id),
+                    objects.get(&l.target_object_id),
+                ) else {
+                    return false;
+                };
+                visible
+                    .as_ref()
+                    .is_none_or(|v| v.contains(&s.module_id) && v.contains(&t.module_id))
+                    && filter
+                        .module_id
+                        .is_none_or(|m| s.module_id == m || t.module_id == m)
+            })
+            .collect();
+        let total = links.len() as u64;
+
+        let type_names: HashMap<Uuid, String> = link_type::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|lt| (lt.id, lt.name))
+            .collect();
+
+        let mut items = Vec::new();
+        for l in links
+            .into_iter()
+            .skip(filter.offset as usize)
+            .take(filter.limit as usize)
+        {
+            let cleared_at = link_history::Entity::find()
+                .filter(link_history::Column::LinkId.eq(l.id))
+                .filter(link_history::Column::ChangeType.eq("resolve"))
+                .order_by_desc(link_history::Column::ChangedAt)
+                .one(db)
+                .await?
+                .map_or(l.created_at, |h| h.changed_at);
+            let source = end_changes(db, &objects[&l.source_object_id], cleared_at).await?;
+            let target = end_changes(db, &objects[&l.target_object_id], cleared_at).await?;
+            items.push(SuspectLinkEntry {
+                link_type_name: type_names.get(&l.link_type_id).cloned().unwrap_or_default(),
+                link: l,
+                cleared_at,
+                source,
+                target,
+            });
+        }
+
+        Ok(PaginatedResponse {
+            items,
+            total,
+            offset: filter.offset,
+            limit: filter.limit,
+        })
+    }
+
+    /// Clear several suspect links at once, recording the justification in each link's
+    /// history.
+    pub async fn resolve(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        input: ResolveSuspectsInput,
+    ) -> Result<Vec<link::Model>, CoreError> {
+        let justification = input.justification.trim();
+        if justification.is_empty() {
+            return Err(CoreError::bad_request(
+                "a justification is required to resolve suspect links".to_owned(),
+            ));
+        }
+        if input.link_ids.is_empty() {
+            return Err(CoreError::bad_request("no link_ids given".to_owned()));
+        }
+
+        let mut seen = HashSet::new();
+        let mut resolved = Vec::new();
+        for id in input.link_ids {
+            if !seen.insert(id) {
+                continue;
+            }
+            let existing = link::Entity::find_by_id(id)
+                .one(db)
+                .await?
+                .ok_or_else(|| CoreError::not_found(format!("link {id} not found")))?;
+            if !existing.suspect {
+                return Err(CoreError::bad_request(format!("link {id} is not suspect")));
+            }
+
+            let mut active: link::ActiveModel = existing.clone().into();
+            clear_suspect(db, &existing, &mut active).await?;
+            active.suspect = Set(false);
+            active.version = Set(existing.version + 1);
+            active.updated_at = Set(chrono::Utc::now().fixed_offset());
+            let result = active.update(db).await?;
+
+            insert_link_history(
+                db,
+                ctx,
+                &result,
+                LinkHistoryEntry {
+                    change_type: "resolve",
+                    justification: Some(justification.to_owned()),
+                    suspect_reason: existing.suspect_reason.clone(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            AuditService::record::<link::Entity>(
+                db,
+                ctx,
+                "resolve",
+                id,
+                Some(&existing),
+                Some(&result),
+            )
+            .await?;
+            resolved.push(result);
+        }
+        Ok(resolved)
+    }
+}
+
+async fn end_changes(
+    db: &impl ConnectionTrait,
+    obj: &object::Model,
+    cleared_at: DateTimeWithTimeZone,
+) -> Result<SuspectLinkEnd, CoreError> {
+    let rows = object_history::Entity::find()
+        .filter(object_history::Column::ObjectId.eq(obj.id))
+        .filter(object_history::Column::ChangedAt.lte(cleared_at))
+        .order_by_asc(object_history::Column::Version)
+        .order_by_asc(object_history::Column::Id)
+        .all(db)
+        .await?;
+    let cleared_version = rows.last().map(|h| h.version);
+
+    let before = Content::fold(&rows);
+    let after = Content::of(obj);
+
+    let mut changes = Vec::new();
+    if cleared_version.is_some() {
+        for (field, old, new) in [
+            ("heading", &before.heading, &after.heading),
+            ("body", &before.body, &after.body),
+        ] {
+            if old != new {
+                changes.push(FieldChange {
+                    field: field.to_owned(),
+                    before: old.clone().map(serde_json::Value::String),
+                    after: new.clone().map(serde_json::Value::String),
+                });
+            }
+        }
+        let mut keys: Vec<&String> = before
+            .attributes
+            .keys()
+            .chain(after.attributes.keys())
+            .collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let (old, new) = (before.attributes.get(key), after.attributes.get(key));
+            if old != new {
+                changes.push(FieldChange {
+                    field: format!("attributes.{key}"),
+                    before: old.cloned(),
+                    after: new.cloned(),
+                });
+            }
+        }
+    }
+
+    Ok(SuspectLinkEnd {
+        object_id: obj.id,
+        module_id: obj.module_id,
+        heading: obj.heading.clone(),
+        current_version: obj.current_version,
+        cleared_version,
+        changes,
+    })
+}
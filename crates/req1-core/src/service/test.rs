@@ -12,6 +12,8 @@ use uuid::Uuid;
 use entity::{object, test_case, test_execution};
 
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+use crate::service::crud;
 
 const VALID_TEST_TYPES: &[&str] = &["manual", "automated", "exploratory"];
 const VALID_PRIORITIES: &[&str] = &["critical", "high", "medium", "low"];
@@ -117,6 +119,7 @@ impl TestService {
 
     pub async fn create_test_case(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         module_id: Uuid,
         input: CreateTestCaseInput,
     ) -> Result<test_case::Model, CoreError> {
@@ -166,6 +169,15 @@ impl TestService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<test_case::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -193,6 +205,7 @@ impl TestService {
 
     pub async fn update_test_case(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateTestCaseInput,
     ) -> Result<test_case::Model, CoreError> {
@@ -201,7 +214,7 @@ impl TestService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("test case {id} not found")))?;
 
-        let mut active: test_case::ActiveModel = existing.into();
+        let mut active: test_case::ActiveModel = existing.clone().into();
 
         if let Some(name) = input.name {
             active.name = Set(name);
@@ -246,21 +259,31 @@ impl TestService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<test_case::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete_test_case(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = test_case::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("test case {id} not found")));
-        }
-        Ok(())
+    pub async fn delete_test_case(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        crud::delete_by_id::<test_case::Entity>(db, ctx, id, "test case").await
     }
 
     // --- Test Execution CRUD ---
 
     pub async fn create_test_execution(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         test_case_id: Uuid,
         input: CreateTestExecutionInput,
     ) -> Result<test_execution::Model, CoreError> {
@@ -291,6 +314,15 @@ impl TestService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<test_execution::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -318,6 +350,7 @@ impl TestService {
 
     pub async fn update_test_execution(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateTestExecutionInput,
     ) -> Result<test_execution::Model, CoreError> {
@@ -326,7 +359,7 @@ impl TestService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("test execution {id} not found")))?;
 
-        let mut active: test_execution::ActiveModel = existing.into();
+        let mut active: test_execution::ActiveModel = existing.clone().into();
 
         if let Some(status) = input.status {
             if !VALID_EXEC_STATUSES.contains(&status.as_str()) {
@@ -353,20 +386,24 @@ impl TestService {
         }
 
         let result = active.update(db).await?;
+        AuditService::record::<test_execution::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
     pub async fn delete_test_execution(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
     ) -> Result<(), CoreError> {
-        let result = test_execution::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!(
-                "test execution {id} not found"
-            )));
-        }
-        Ok(())
+        crud::delete_by_id::<test_execution::Entity>(db, ctx, id, "test execution").await
     }
 
     // --- Coverage ---
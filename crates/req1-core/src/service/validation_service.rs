@@ -1,14 +1,16 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
 use serde::Serialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use entity::{link, object, script};
+use entity::{link, link_type, object, script};
 
 use crate::error::CoreError;
-use crate::scripting::engine::{ScriptEngine, ScriptObject, ScriptWorld, TriggerContext};
+use crate::link_rules::{self, LinkEnd, LinkTypeRules};
+use crate::scripting::engine::{ScriptEngine, ScriptObject, TriggerContext};
+use crate::scripting::world::WorldAccess;
 
 use super::object::load_world;
 
@@ -34,10 +36,12 @@ pub struct ValidationReport {
 pub struct ValidationService;
 
 impl ValidationService {
-    /// Run all validation rules against a module and return a report.
+    /// Run all validation rules against a module and return a report. Validation
+    /// scripts read other modules as `user_id`.
     pub async fn validate(
         db: &impl ConnectionTrait,
         module_id: Uuid,
+        user_id: Option<Uuid>,
     ) -> Result<ValidationReport, CoreError> {
         let module = entity::module::Entity::find_by_id(module_id)
             .one(db)
@@ -69,7 +73,8 @@ impl ValidationService {
         check_objects(&objects, &id_set, &mut issues);
         check_links(&links, &id_set, &mut issues);
         check_required_attributes(&objects, &module, &mut issues);
-        check_scripts(db, module_id, &objects, &mut issues).await?;
+        check_link_types(db, &objects, &links, &mut issues).await?;
+        check_scripts(db, module_id, user_id, &objects, &mut issues).await?;
 
         Ok(ValidationReport {
             module_id: module_id.to_string(),
@@ -217,9 +222,136 @@ fn check_required_attributes(
     }
 }
 
+/// Endpoint, cardinality and cycle rules of the link types in use.
+async fn check_link_types(
+    db: &impl ConnectionTrait,
+    objects: &[object::Model],
+    links: &[link::Model],
+    issues: &mut Vec<ValidationIssue>,
+) -> Result<(), CoreError> {
+    let mut types = Vec::new();
+    for lt in link_type::Entity::find().all(db).await? {
+        let rules = LinkTypeRules::from_json(lt.rules.as_ref())?;
+        if rules != LinkTypeRules::default() {
+            types.push((lt, rules));
+        }
+    }
+    if types.is_empty() {
+        return Ok(());
+    }
+
+    let mut ends: HashMap<Uuid, object::Model> =
+        objects.iter().map(|o| (o.id, o.clone())).collect();
+    let missing: Vec<Uuid> = links
+        .iter()
+        .flat_map(|l| [l.source_object_id, l.target_object_id])
+        .filter(|id| !ends.contains_key(id))
+        .collect();
+    if !missing.is_empty() {
+        for o in object::Entity::find()
+            .filter(object::Column::Id.is_in(missing))
+            .all(db)
+            .await?
+        {
+            let _ = ends.insert(o.id, o);
+        }
+    }
+
+    for (lt, rules) in &types {
+        let typed: Vec<&link::Model> = links.iter().filter(|l| l.link_type_id == lt.id).collect();
+
+        for lnk in &typed {
+            for (end, id) in [
+                (LinkEnd::Source, lnk.source_object_id),
+                (LinkEnd::Target, lnk.target_object_id),
+            ] {
+                if let Some(obj) = ends.get(&id)
+                    && !rules.allows(end, obj)
+                {
+                    issues.push(ValidationIssue {
+                        rule: "link_endpoint".to_owned(),
+                        severity: "error".to_owned(),
+                        object_id: Some(id.to_string()),
+                        link_id: Some(lnk.id.to_string()),
+                        message: format!(
+                            "object {id} is not an allowed {} for link type '{}'",
+                            end.as_str(),
+                            lt.name
+                        ),
+                    });
+                }
+            }
+        }
+
+        for obj in objects {
+            for end in [LinkEnd::Source, LinkEnd::Target] {
+                let (min, max) = rules.bounds(end);
+                let count = typed
+                    .iter()
+                    .filter(|l| match end {
+                        LinkEnd::Source => l.source_object_id == obj.id,
+                        LinkEnd::Target => l.target_object_id == obj.id,
+                    })
+                    .count();
+                // Minimums only bind objects the rules select for that end
+                let too_few = min.is_some_and(|m| {
+                    rules.is_scoped(end) && rules.allows(end, obj) && count < m as usize
+                });
+                let too_many = max.is_some_and(|m| count > m as usize);
+                if too_few || too_many {
+                    issues.push(ValidationIssue {
+                        rule: "link_cardinality".to_owned(),
+                        severity: "error".to_owned(),
+                        object_id: Some(obj.id.to_string()),
+                        link_id: None,
+                        message: format!(
+                            "[{}] {} — has {count} {} '{}' link(s), expected {}",
+                            obj.level,
+                            obj.heading.as_deref().unwrap_or("(no heading)"),
+                            end.direction(),
+                            lt.name,
+                            match (min, max) {
+                                (Some(min), Some(max)) => format!("{min} to {max}"),
+                                (Some(min), None) => format!("at least {min}"),
+                                (None, Some(max)) => format!("at most {max}"),
+                                (None, None) => String::new(),
+                            }
+                        ),
+                    });
+                }
+            }
+        }
+
+        if !rules.allow_cycles && !typed.is_empty() {
+            let all = link::Entity::find()
+                .filter(link::Column::LinkTypeId.eq(lt.id))
+                .all(db)
+                .await?;
+            let graph = link_rules::adjacency(all.iter());
+            for lnk in &typed {
+                if link_rules::reaches(&graph, lnk.target_object_id, lnk.source_object_id) {
+                    issues.push(ValidationIssue {
+                        rule: "link_cycle".to_owned(),
+                        severity: "error".to_owned(),
+                        object_id: None,
+                        link_id: Some(lnk.id.to_string()),
+                        message: format!(
+                            "link {} -> {} is part of a '{}' cycle",
+                            lnk.source_object_id, lnk.target_object_id, lt.name
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 async fn check_scripts(
     db: &impl ConnectionTrait,
     module_id: Uuid,
+    user_id: Option<Uuid>,
     objects: &[object::Model],
     issues: &mut Vec<ValidationIssue>,
 ) -> Result<(), CoreError> {
@@ -236,61 +368,57 @@ async fn check_scripts(
     }
 
     let world = load_world(db, module_id).await?;
+    let access = WorldAccess::new(db, user_id, module_id);
+    let contexts: Vec<TriggerContext> = objects
+        .iter()
+        .map(|obj| TriggerContext {
+            hook_point: "validate".to_owned(),
+            object: ScriptObject::from(obj.clone()),
+        })
+        .collect();
 
-    for obj in objects {
-        run_scripts_for_object(obj, &scripts, &world, issues);
+    // One runtime per script for all objects
+    let mut results = Vec::with_capacity(scripts.len());
+    for s in &scripts {
+        results.push(ScriptEngine::run_triggers(&s.source_code, &world, &contexts, &access).await);
     }
 
-    Ok(())
-}
-
-fn run_scripts_for_object(
-    obj: &object::Model,
-    scripts: &[script::Model],
-    world: &ScriptWorld,
-    issues: &mut Vec<ValidationIssue>,
-) {
-    let script_obj = ScriptObject {
-        id: obj.id.to_string(),
-        heading: obj.heading.clone(),
-        body: obj.body.clone(),
-        level: Some(obj.level.clone()),
-        classification: Some(obj.classification.clone()),
-        attributes: obj.attributes.clone(),
-        version: obj.current_version,
-    };
-    let ctx = TriggerContext {
-        hook_point: "validate".to_owned(),
-        object: script_obj,
-    };
-
-    for s in scripts {
-        let result = ScriptEngine::run_trigger(&s.source_code, world, &ctx);
-        match result {
-            Ok(r) if r.rejected => {
-                issues.push(ValidationIssue {
-                    rule: format!("script:{}", s.name),
-                    severity: "error".to_owned(),
-                    object_id: Some(obj.id.to_string()),
-                    link_id: None,
-                    message: format!(
-                        "[{}] {} — {}",
-                        obj.level,
-                        obj.heading.as_deref().unwrap_or("(no heading)"),
-                        r.reason.unwrap_or_else(|| "rejected by script".to_owned())
-                    ),
-                });
+    for (i, obj) in objects.iter().enumerate() {
+        for (s, result) in scripts.iter().zip(&results) {
+            let outcome = match result {
+                Ok(per_object) => per_object
+                    .get(i)
+                    .map(|r| r.as_ref().map_err(ToString::to_string)),
+                Err(e) => Some(Err(e.to_string())),
+            };
+            match outcome {
+                Some(Ok(r)) if r.rejected => {
+                    issues.push(ValidationIssue {
+                        rule: format!("script:{}", s.name),
+                        severity: "error".to_owned(),
+                        object_id: Some(obj.id.to_string()),
+                        link_id: None,
+                        message: format!(
+                            "[{}] {} — {}",
+                            obj.level,
+                            obj.heading.as_deref().unwrap_or("(no heading)"),
+                            r.reason.as_deref().unwrap_or("rejected by script")
+                        ),
+                    });
+                }
+                Some(Err(e)) => {
+                    issues.push(ValidationIssue {
+                        rule: format!("script:{}", s.name),
+                        severity: "error".to_owned(),
+                        object_id: Some(obj.id.to_string()),
+                        link_id: None,
+                        message: format!("[{}] script '{}' error: {e}", obj.level, s.name),
+                    });
+                }
+                _ => {}
             }
-            Err(e) => {
-                issues.push(ValidationIssue {
-                    rule: format!("script:{}", s.name),
-                    severity: "error".to_owned(),
-                    object_id: Some(obj.id.to_string()),
-                    link_id: None,
-                    message: format!("[{}] script '{}' error: {e}", obj.level, s.name),
-                });
-            }
-            _ => {}
         }
     }
+
+    Ok(())
 }
@@ -7,6 +7,7 @@ use entity::view;
 
 use crate::crud_service;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateViewInput {
@@ -39,6 +40,7 @@ pub struct ViewService;
 impl ViewService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateViewInput,
     ) -> Result<view::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -57,11 +59,14 @@ impl ViewService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<view::Entity>(db, ctx, "create", result.id, None, Some(&result))
+            .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateViewInput,
     ) -> Result<view::Model, CoreError> {
@@ -70,7 +75,7 @@ impl ViewService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("view {id} not found")))?;
 
-        let mut active: view::ActiveModel = existing.into();
+        let mut active: view::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -89,6 +94,15 @@ impl ViewService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<view::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 }
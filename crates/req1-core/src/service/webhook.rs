@@ -6,6 +6,7 @@ use uuid::Uuid;
 use entity::webhook;
 
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateWebhookInput {
@@ -36,9 +37,18 @@ pub struct WebhookPayload {
 
 pub struct WebhookService;
 
+/// The webhook as recorded in the audit log, which never holds the secret itself.
+fn redacted(webhook: &webhook::Model) -> webhook::Model {
+    webhook::Model {
+        secret: webhook.secret.as_ref().map(|_| "***".to_owned()),
+        ..webhook.clone()
+    }
+}
+
 impl WebhookService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateWebhookInput,
     ) -> Result<webhook::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -60,6 +70,15 @@ impl WebhookService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<webhook::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&redacted(&result)),
+        )
+        .await?;
         Ok(result)
     }
 
@@ -83,11 +102,12 @@ impl WebhookService {
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateWebhookInput,
     ) -> Result<webhook::Model, CoreError> {
         let existing = Self::get(db, id).await?;
-        let mut active: webhook::ActiveModel = existing.into();
+        let mut active: webhook::ActiveModel = existing.clone().into();
 
         if let Some(name) = input.name {
             active.name = Set(name);
@@ -107,15 +127,34 @@ impl WebhookService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<webhook::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&redacted(&existing)),
+            Some(&redacted(&result)),
+        )
+        .await?;
         Ok(result)
     }
 
-    pub async fn delete(db: &impl ConnectionTrait, id: Uuid) -> Result<(), CoreError> {
-        let result = webhook::Entity::delete_by_id(id).exec(db).await?;
-        if result.rows_affected == 0 {
-            return Err(CoreError::not_found(format!("webhook {id} not found")));
-        }
-        Ok(())
+    pub async fn delete(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+    ) -> Result<(), CoreError> {
+        let existing = Self::get(db, id).await?;
+        let _ = webhook::Entity::delete_by_id(id).exec(db).await?;
+        AuditService::record::<webhook::Entity>(
+            db,
+            ctx,
+            "delete",
+            id,
+            Some(&redacted(&existing)),
+            None,
+        )
+        .await
     }
 
     /// Fire webhooks for a given module/event. Spawns background tasks — does not block.
@@ -1,12 +1,16 @@
-use sea_orm::{ActiveModelTrait, ConnectionTrait, EntityTrait, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, Set,
+};
 use serde::Deserialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use entity::workspace;
 
+use crate::PaginatedResponse;
 use crate::crud_service;
 use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateWorkspaceInput {
@@ -25,6 +29,7 @@ pub struct WorkspaceService;
 impl WorkspaceService {
     pub async fn create(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         input: CreateWorkspaceInput,
     ) -> Result<workspace::Model, CoreError> {
         let now = chrono::Utc::now().fixed_offset();
@@ -39,11 +44,21 @@ impl WorkspaceService {
         };
 
         let result = model.insert(db).await?;
+        AuditService::record::<workspace::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
 
     pub async fn update(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         id: Uuid,
         input: UpdateWorkspaceInput,
     ) -> Result<workspace::Model, CoreError> {
@@ -52,7 +67,7 @@ impl WorkspaceService {
             .await?
             .ok_or_else(|| CoreError::not_found(format!("workspace {id} not found")))?;
 
-        let mut active: workspace::ActiveModel = existing.into();
+        let mut active: workspace::ActiveModel = existing.clone().into();
         if let Some(name) = input.name {
             active.name = Set(name);
         }
@@ -62,8 +77,41 @@ impl WorkspaceService {
         active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
         let result = active.update(db).await?;
+        AuditService::record::<workspace::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
         Ok(result)
     }
+
+    /// List workspaces, restricted to `visible` workspace ids when given.
+    pub async fn list_visible(
+        db: &impl ConnectionTrait,
+        visible: Option<Vec<Uuid>>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<PaginatedResponse<workspace::Model>, CoreError> {
+        let mut select = workspace::Entity::find();
+        if let Some(ids) = visible {
+            select = select.filter(workspace::Column::Id.is_in(ids));
+        }
+        let paginator = select.paginate(db, limit);
+        let total = paginator.num_items().await?;
+        let page = offset / limit;
+        let items = paginator.fetch_page(page).await?;
+
+        Ok(PaginatedResponse {
+            items,
+            total,
+            offset,
+            limit,
+        })
+    }
 }
 
 crud_service!(WorkspaceService, workspace::Entity, "workspace");
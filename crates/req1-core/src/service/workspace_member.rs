@@ -0,0 +1,121 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use entity::{app_user, workspace_member};
+
+use crate::crud_service;
+use crate::error::CoreError;
+use crate::service::audit::{AuditContext, AuditService};
+
+const VALID_ROLES: &[&str] = &["admin", "editor", "reviewer", "viewer"];
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWorkspaceMemberInput {
+    #[serde(default)]
+    pub workspace_id: Uuid,
+    pub user_id: Uuid,
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateWorkspaceMemberInput {
+    pub role: String,
+}
+
+fn check_role(role: &str) -> Result<(), CoreError> {
+    if VALID_ROLES.contains(&role) {
+        Ok(())
+    } else {
+        Err(CoreError::bad_request(format!(
+            "invalid role '{role}', must be one of: {VALID_ROLES:?}"
+        )))
+    }
+}
+
+pub struct WorkspaceMemberService;
+
+impl WorkspaceMemberService {
+    pub async fn create(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        input: CreateWorkspaceMemberInput,
+    ) -> Result<workspace_member::Model, CoreError> {
+        let role = input.role.unwrap_or_else(|| "viewer".to_owned());
+        check_role(&role)?;
+
+        let _ = app_user::Entity::find_by_id(input.user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("user {} not found", input.user_id)))?;
+
+        let existing = workspace_member::Entity::find()
+            .filter(workspace_member::Column::UserId.eq(input.user_id))
+            .filter(workspace_member::Column::WorkspaceId.eq(input.workspace_id))
+            .one(db)
+            .await?;
+        if existing.is_some() {
+            return Err(CoreError::conflict(format!(
+                "user {} is already a member of workspace {}",
+                input.user_id, input.workspace_id
+            )));
+        }
+
+        let model = workspace_member::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            user_id: Set(input.user_id),
+            workspace_id: Set(input.workspace_id),
+            role: Set(role),
+            created_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        let result = model.insert(db).await?;
+        AuditService::record::<workspace_member::Entity>(
+            db,
+            ctx,
+            "create",
+            result.id,
+            None,
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+
+    pub async fn update(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        id: Uuid,
+        input: UpdateWorkspaceMemberInput,
+    ) -> Result<workspace_member::Model, CoreError> {
+        check_role(&input.role)?;
+
+        let existing = workspace_member::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| CoreError::not_found(format!("workspace member {id} not found")))?;
+
+        let mut active: workspace_member::ActiveModel = existing.clone().into();
+        active.role = Set(input.role);
+
+        let result = active.update(db).await?;
+        AuditService::record::<workspace_member::Entity>(
+            db,
+            ctx,
+            "update",
+            result.id,
+            Some(&existing),
+            Some(&result),
+        )
+        .await?;
+        Ok(result)
+    }
+}
+
+crud_service!(
+    WorkspaceMemberService,
+    workspace_member::Entity,
+    "workspace member",
+    parent: workspace_member::Column::WorkspaceId
+);
@@ -9,6 +9,8 @@ use uuid::Uuid;
 use entity::attribute_definition;
 
 use crate::error::CoreError;
+use crate::scripting::engine::ScriptEngine;
+use crate::service::audit::{AuditContext, AuditService};
 use crate::service::object::{CreateObjectInput, ObjectService, UpdateObjectInput};
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -20,9 +22,20 @@ pub struct XlsxImportResult {
 pub struct XlsxImportService;
 
 impl XlsxImportService {
-    #[allow(clippy::too_many_lines)]
     pub async fn import_xlsx(
         db: &impl ConnectionTrait,
+        ctx: &AuditContext,
+        module_id: Uuid,
+        data: &[u8],
+    ) -> Result<XlsxImportResult, CoreError> {
+        // Keep trigger scripts warm across the imported objects
+        ScriptEngine::batched(Self::import_xlsx_rows(db, ctx, module_id, data)).await
+    }
+
+    #[allow(clippy::too_many_lines)]
+    async fn import_xlsx_rows(
+        db: &impl ConnectionTrait,
+        ctx: &AuditContext,
         module_id: Uuid,
         data: &[u8],
     ) -> Result<XlsxImportResult, CoreError> {
@@ -190,8 +203,9 @@ impl XlsxImportService {
                     object_type_id: None,
                     expected_version: None,
                     lifecycle_state,
+                    signature: None,
                 };
-                let _ = ObjectService::update(db, obj_id, update_input).await?;
+                let _ = ObjectService::update(db, ctx, obj_id, update_input).await?;
                 objects_updated += 1;
                 level_stack.truncate(level.matches('.').count());
                 level_stack.push((level, obj_id));
@@ -220,11 +234,25 @@ impl XlsxImportService {
                 is_placeholder: None,
             };
 
-            let created = ObjectService::create(db, create_input).await?;
+            let created = ObjectService::create(db, ctx, create_input).await?;
             level_stack.push((level, created.id));
             objects_created += 1;
         }
 
+        AuditService::event(
+            db,
+            ctx,
+            "import",
+            "module",
+            module_id,
+            serde_json::json!({
+                "format": "xlsx",
+                "objects_created": objects_created,
+                "objects_updated": objects_updated,
+            }),
+        )
+        .await?;
+
         Ok(XlsxImportResult {
             objects_created,
             objects_updated,
@@ -1,31 +1,253 @@
-use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use entity::{external_link, link, link_type, object};
+
 use crate::error::CoreError;
+use crate::fingerprint::FingerprintPolicy;
+use crate::link_history::{LinkHistoryEntry, insert_link_history};
+use crate::service::audit::{AuditContext, AuditService};
+
+/// Allowed values of `link_type.suspect_direction`. `forward` flags a link when its
+/// source changes, `backward` when its target changes.
+pub const SUSPECT_DIRECTIONS: &[&str] = &["forward", "backward", "both", "none"];
+
+/// Stored on a suspect link: which object change raised the flag and what changed.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SuspectReason {
+    pub object_id: Uuid,
+    pub version: i32,
+    /// Changed content fields, e.g. `heading`, `body`, `attributes.priority`.
+    pub changed: Vec<String>,
+    /// Set when the flag cascaded from another suspect link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub via_link_id: Option<Uuid>,
+}
+
+/// A content change that may flag the object's links as suspect.
+pub struct SuspectTrigger<'a> {
+    pub object_id: Uuid,
+    pub version: i32,
+    pub classification: &'a str,
+    pub fingerprint: &'a str,
+    pub changed: Vec<String>,
+}
 
-/// Flag links as suspect when the object's content fingerprint has changed
-/// since the link was created/last resolved.
+/// Content fields that differ between two versions of an object, limited to the
+/// attributes the fingerprint policy counts.
+pub fn changed_fields(
+    policy: &FingerprintPolicy,
+    before: (Option<&str>, Option<&str>, Option<&serde_json::Value>),
+    after: (Option<&str>, Option<&str>, Option<&serde_json::Value>),
+) -> Vec<String> {
+    let mut changed = Vec::new();
+    if before.0.unwrap_or("") != after.0.unwrap_or("") {
+        changed.push("heading".to_owned());
+    }
+    if before.1.unwrap_or("") != after.1.unwrap_or("") {
+        changed.push("body".to_owned());
+    }
+    let empty = serde_json::Map::new();
+    let old = before.2.and_then(|v| v.as_object()).unwrap_or(&empty);
+    let new = after.2.and_then(|v| v.as_object()).unwrap_or(&empty);
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        if policy.counts(key) && old.get(key) != new.get(key) {
+            changed.push(format!("attributes.{key}"));
+        }
+    }
+    changed
+}
+
+/// Flag links as suspect when the object's content fingerprint has changed since the
+/// link was created/last resolved, following each link type's propagation rules.
+/// Returns the number of links flagged.
 pub async fn flag_suspect_links(
     db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    trigger: &SuspectTrigger<'_>,
+) -> Result<u64, CoreError> {
+    let mut types: HashMap<Uuid, link_type::Model> = HashMap::new();
+    let mut seen_links = HashSet::new();
+    let mut seen_objects = HashSet::from([trigger.object_id]);
+    let mut queue = VecDeque::from([(
+        trigger.object_id,
+        trigger.classification.to_owned(),
+        None::<Uuid>,
+    )]);
+    let mut flagged = 0;
+
+    while let Some((object_id, classification, via_link_id)) = queue.pop_front() {
+        let links = link::Entity::find()
+            .filter(link::Column::Suspect.eq(false))
+            .filter(
+                link::Column::SourceObjectId
+                    .eq(object_id)
+                    .or(link::Column::TargetObjectId.eq(object_id)),
+            )
+            .all(db)
+            .await?;
+
+        for l in links {
+            if !seen_links.insert(l.id) {
+                continue;
+            }
+            let Some(lt) = cached_link_type(db, &mut types, l.link_type_id).await? else {
+                continue;
+            };
+
+            let from_source = l.source_object_id == object_id;
+            let propagates = match lt.suspect_direction.as_str() {
+                "forward" => from_source,
+                "backward" => !from_source,
+                "none" => false,
+                _ => true,
+            };
+            if !propagates || (lt.suspect_skip_informative && classification == "informative") {
+                continue;
+            }
+            // A direct change only counts when the link last saw different content;
+            // cascaded flags have nothing to compare against
+            if via_link_id.is_none() {
+                let seen = if from_source {
+                    &l.source_fingerprint
+                } else {
+                    &l.target_fingerprint
+                };
+                if seen == trigger.fingerprint {
+                    continue;
+                }
+            }
+
+            let reason = SuspectReason {
+                object_id: trigger.object_id,
+                version: trigger.version,
+                changed: trigger.changed.clone(),
+                via_link_id,
+            };
+            let other = if from_source {
+                l.target_object_id
+            } else {
+                l.source_object_id
+            };
+            let reason = serde_json::json!(reason);
+            let version = l.version + 1;
+            let mut active: link::ActiveModel = l.into();
+            active.suspect = Set(true);
+            active.suspect_reason = Set(Some(reason.clone()));
+            let now = chrono::Utc::now().fixed_offset();
+            active.suspected_at = Set(Some(now));
+            active.updated_at = Set(now);
+            active.version = Set(version);
+            let flagged_link = active.update(db).await?;
+            insert_link_history(
+                db,
+                ctx,
+                &flagged_link,
+                LinkHistoryEntry {
+                    change_type: "suspect",
+                    suspect_reason: Some(reason),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            flagged += 1;
+
+            if lt.suspect_cascade
+                && seen_objects.insert(other)
+                && let Some(next) = object::Entity::find_by_id(other).one(db).await?
+            {
+                queue.push_back((next.id, next.classification, Some(flagged_link.id)));
+            }
+        }
+
+        flagged += flag_external_links(
+            db,
+            ctx,
+            &mut types,
+            trigger,
+            object_id,
+            &classification,
+            via_link_id,
+        )
+        .await?;
+    }
+
+    Ok(flagged)
+}
+
+async fn cached_link_type(
+    db: &impl ConnectionTrait,
+    types: &mut HashMap<Uuid, link_type::Model>,
+    id: Uuid,
+) -> Result<Option<link_type::Model>, CoreError> {
+    if let Some(lt) = types.get(&id) {
+        return Ok(Some(lt.clone()));
+    }
+    let lt = link_type::Entity::find_by_id(id).one(db).await?;
+    if let Some(lt) = &lt {
+        let _ = types.insert(lt.id, lt.clone());
+    }
+    Ok(lt)
+}
+
+/// Flag the object's external links; the object is always their source end.
+async fn flag_external_links(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    types: &mut HashMap<Uuid, link_type::Model>,
+    trigger: &SuspectTrigger<'_>,
     object_id: Uuid,
-    new_fingerprint: &str,
-) -> Result<(), CoreError> {
-    let sql = r"
-UPDATE link SET suspect = true, updated_at = NOW()
-WHERE suspect = false
-  AND (
-    (source_object_id = $1 AND source_fingerprint <> $2)
-    OR (target_object_id = $1 AND target_fingerprint <> $2)
-  )
-";
-
-    let _ = db
-        .execute(Statement::from_sql_and_values(
-            DatabaseBackend::Postgres,
-            sql,
-            [object_id.into(), new_fingerprint.into()],
-        ))
+    classification: &str,
+    via_link_id: Option<Uuid>,
+) -> Result<u64, CoreError> {
+    let links = external_link::Entity::find()
+        .filter(external_link::Column::SourceObjectId.eq(object_id))
+        .filter(external_link::Column::Suspect.eq(false))
+        .all(db)
         .await?;
 
-    Ok(())
+    let mut flagged = 0;
+    for l in links {
+        let Some(lt) = cached_link_type(db, types, l.link_type_id).await? else {
+            continue;
+        };
+        if !matches!(lt.suspect_direction.as_str(), "forward" | "both")
+            || (lt.suspect_skip_informative && classification == "informative")
+            || (via_link_id.is_none() && l.source_fingerprint == trigger.fingerprint)
+        {
+            continue;
+        }
+
+        let reason = SuspectReason {
+            object_id: trigger.object_id,
+            version: trigger.version,
+            changed: trigger.changed.clone(),
+            via_link_id,
+        };
+        let now = chrono::Utc::now().fixed_offset();
+        let mut active: external_link::ActiveModel = l.clone().into();
+        active.suspect = Set(true);
+        active.suspect_reason = Set(Some(serde_json::json!(reason)));
+        active.suspected_at = Set(Some(now));
+        active.updated_at = Set(now);
+        let updated = active.update(db).await?;
+        AuditService::record::<external_link::Entity>(
+            db,
+            ctx,
+            "suspect",
+            updated.id,
+            Some(&l),
+            Some(&updated),
+        )
+        .await?;
+        flagged += 1;
+    }
+    Ok(flagged)
 }
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use entity::{link, link_type, module, object, object_type};
+
+use crate::error::CoreError;
+
+use super::{SATISFY_LINK_TYPE, sysml_reference};
+
+/// Element id an object is exported under: the SysML id it was imported from, or
+/// its own id.
+fn object_element_id(obj: &object::Model) -> String {
+    sysml_reference(&obj.references_)
+        .and_then(|r| r.get("path")?.as_str())
+        .map_or_else(|| obj.id.to_string(), str::to_owned)
+}
+
+/// Stable id for the `Documentation` element carrying a requirement's text, so
+/// repeated exports produce the same model.
+fn documentation_id(element_id: &str) -> String {
+    let digest = Sha256::digest(format!("{element_id}/documentation").as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_custom_bytes(bytes)
+        .into_uuid()
+        .to_string()
+}
+
+/// Export a module as SysML v2 API JSON: a `RequirementUsage` per object, the
+/// `RequirementDefinition`s of the object types in use and a
+/// `SatisfyRequirementUsage` per outgoing "satisfies" link.
+pub async fn export_sysml(
+    db: &impl ConnectionTrait,
+    module_id: Uuid,
+) -> Result<Vec<Value>, CoreError> {
+    let _ = module::Entity::find_by_id(module_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("module {module_id} not found")))?;
+
+    let objects = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .filter(object::Column::DeletedAt.is_null())
+        .order_by_asc(object::Column::Position)
+        .all(db)
+        .await?;
+    let mut element_ids: HashMap<Uuid, String> = objects
+        .iter()
+        .map(|o| (o.id, object_element_id(o)))
+        .collect();
+
+    let mut elements = Vec::new();
+
+    let type_ids: Vec<Uuid> = objects.iter().filter_map(|o| o.object_type_id).collect();
+    if !type_ids.is_empty() {
+        let types = object_type::Entity::find()
+            .filter(object_type::Column::Id.is_in(type_ids))
+            .order_by_asc(object_type::Column::Name)
+            .all(db)
+            .await?;
+        for t in types {
+            elements.push(json!({
+                "@id": t.id,
+                "@type": "RequirementDefinition",
+                "declaredName": t.name,
+                "text": t.description.into_iter().collect::<Vec<_>>(),
+            }));
+        }
+    }
+
+    for obj in &objects {
+        let id = &element_ids[&obj.id];
+        let short_name = sysml_reference(&obj.references_).map_or(Some(obj.level.as_str()), |r| {
+            r.get("short_name").and_then(Value::as_str)
+        });
+        let owner = obj
+            .parent_id
+            .and_then(|p| element_ids.get(&p))
+            .map(|p| json!({ "@id": p }));
+        let definition = obj.object_type_id.map(|t| json!({ "@id": t }));
+        let text: Vec<&str> = obj.body.as_deref().into_iter().collect();
+        let documentation: Vec<Value> = text
+            .iter()
+            .map(|_| json!({ "@id": documentation_id(id) }))
+            .collect();
+        elements.push(json!({
+            "@id": id,
+            "@type": "RequirementUsage",
+            "declaredName": obj.heading,
+            "declaredShortName": short_name,
+            "reqId": short_name,
+            "text": text,
+            "owner": owner,
+            "requirementDefinition": definition,
+            "documentation": documentation,
+        }));
+        if let Some(body) = &obj.body {
+            elements.push(json!({
+                "@id": documentation_id(id),
+                "@type": "Documentation",
+                "body": body,
+                "annotatedElement": [{ "@id": id }],
+                "owner": { "@id": id },
+            }));
+        }
+    }
+
+    let object_ids: Vec<Uuid> = objects.iter().map(|o| o.id).collect();
+    let satisfies: Vec<Uuid> = link_type::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|lt| lt.name.eq_ignore_ascii_case(SATISFY_LINK_TYPE))
+        .map(|lt| lt.id)
+        .collect();
+    if object_ids.is_empty() || satisfies.is_empty() {
+        return Ok(elements);
+    }
+    let links = link::Entity::find()
+        .filter(link::Column::SourceObjectId.is_in(object_ids))
+        .filter(link::Column::LinkTypeId.is_in(satisfies))
+        .order_by_asc(link::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    // Targets outside the module are referenced by their own element ids
+    let foreign: Vec<Uuid> = links
+        .iter()
+        .map(|l| l.target_object_id)
+        .filter(|id| !element_ids.contains_key(id))
+        .collect();
+    if !foreign.is_empty() {
+        for obj in object::Entity::find()
+            .filter(object::Column::Id.is_in(foreign))
+            .all(db)
+            .await?
+        {
+            let _ = element_ids.insert(obj.id, object_element_id(&obj));
+        }
+    }
+
+    for l in links {
+        let (Some(source), Some(target)) = (
+            element_ids.get(&l.source_object_id),
+            element_ids.get(&l.target_object_id),
+        ) else {
+            continue;
+        };
+        let id = l
+            .attributes
+            .as_ref()
+            .and_then(|a| a.get("sysml_id")?.as_str())
+            .map_or_else(|| l.id.to_string(), str::to_owned);
+        elements.push(json!({
+            "@id": id,
+            "@type": "SatisfyRequirementUsage",
+            "satisfyingFeature": { "@id": source },
+            "satisfiedRequirement": { "@id": target },
+        }));
+    }
+
+    Ok(elements)
+}
@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use entity::{link, link_type, module, object, object_type};
+
+use crate::error::CoreError;
+use crate::service::audit::AuditContext;
+use crate::service::link::{CreateLinkInput, CreateLinkTypeInput, LinkService};
+use crate::service::object::{CreateObjectInput, ObjectService, UpdateObjectInput};
+use crate::service::object_type::{CreateObjectTypeInput, ObjectTypeService};
+
+use super::{
+    REFERENCE_TYPE, SATISFY_LINK_TYPE, SysmlImportResult, element_id, element_type, elements, name,
+    ref_id, short_name, sysml_reference,
+};
+
+/// Documentation bodies keyed by the element they document, resolved both from
+/// an element's `documentation` list and from each `Documentation`'s owner.
+struct Docs<'a> {
+    by_id: HashMap<&'a str, &'a str>,
+    by_owner: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> Docs<'a> {
+    fn collect(els: &[&'a Value]) -> Self {
+        let mut by_id = HashMap::new();
+        let mut by_owner: HashMap<&str, Vec<&str>> = HashMap::new();
+        for el in els.iter().filter(|el| element_type(el) == "Documentation") {
+            let Some(body) = el.get("body").and_then(Value::as_str) else {
+                continue;
+            };
+            if let Some(id) = element_id(el) {
+                let _ = by_id.insert(id, body);
+            }
+            if let Some(owner) =
+                ref_id(el.get("annotatedElement")).or_else(|| ref_id(el.get("owner")))
+            {
+                by_owner.entry(owner).or_default().push(body);
+            }
+        }
+        Self { by_id, by_owner }
+    }
+
+    fn text(&self, el: &'a Value) -> Option<String> {
+        let mut parts: Vec<&str> = match el.get("text") {
+            Some(Value::String(s)) => vec![s.as_str()],
+            Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if parts.is_empty()
+            && let Some(Value::Array(refs)) = el.get("documentation")
+        {
+            parts = refs
+                .iter()
+                .filter_map(|r| element_id(r).and_then(|id| self.by_id.get(id).copied()))
+                .collect();
+        }
+        if parts.is_empty()
+            && let Some(bodies) = element_id(el).and_then(|id| self.by_owner.get(id))
+        {
+            parts.clone_from(bodies);
+        }
+        parts.retain(|p| !p.trim().is_empty());
+        (!parts.is_empty()).then(|| parts.join("\n\n"))
+    }
+}
+
+fn owner_id(el: &Value) -> Option<&str> {
+    ref_id(el.get("owner"))
+        .or_else(|| ref_id(el.get("owningUsage")))
+        .or_else(|| ref_id(el.get("owningNamespace")))
+}
+
+fn definition_id(el: &Value) -> Option<&str> {
+    ref_id(el.get("requirementDefinition"))
+        .or_else(|| ref_id(el.get("definition")))
+        .or_else(|| ref_id(el.get("type")))
+}
+
+/// Import SysML v2 API JSON into a module. `RequirementDefinition`s become object
+/// types, `RequirementUsage`s become objects (nested by owner) and
+/// `SatisfyRequirementUsage`s become "satisfies" links.
+///
+/// Objects keep their element id in `references_`, so importing the same model
+/// again updates them in place instead of creating duplicates.
+#[allow(clippy::too_many_lines)]
+pub async fn import_sysml(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+    module_id: Uuid,
+    doc: &Value,
+) -> Result<SysmlImportResult, CoreError> {
+    let _ = module::Entity::find_by_id(module_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| CoreError::not_found(format!("module {module_id} not found")))?;
+
+    let els = elements(doc);
+    let usages: Vec<&Value> = els
+        .iter()
+        .copied()
+        .filter(|el| element_type(el) == "RequirementUsage" && element_id(el).is_some())
+        .collect();
+    let definitions: Vec<&Value> = els
+        .iter()
+        .copied()
+        .filter(|el| element_type(el) == "RequirementDefinition" && element_id(el).is_some())
+        .collect();
+    if usages.is_empty() && definitions.is_empty() {
+        return Err(CoreError::bad_request(
+            "document contains no SysML requirement elements",
+        ));
+    }
+    let docs = Docs::collect(&els);
+    let mut result = SysmlImportResult::default();
+
+    // RequirementDefinition → object type, matched by id (from a previous export) or name
+    let mut types = object_type::Entity::find()
+        .filter(object_type::Column::ModuleId.eq(module_id))
+        .all(db)
+        .await?;
+    let mut type_ids: HashMap<&str, Uuid> = HashMap::new();
+    for def in &definitions {
+        let def_id = element_id(def).unwrap_or_default();
+        let type_name = name(def).or_else(|| short_name(def)).unwrap_or(def_id);
+        let existing = types
+            .iter()
+            .find(|t| t.id.to_string() == def_id)
+            .or_else(|| types.iter().find(|t| t.name == type_name));
+        let id = if let Some(t) = existing {
+            t.id
+        } else {
+            let created = ObjectTypeService::create(
+                db,
+                ctx,
+                CreateObjectTypeInput {
+                    module_id,
+                    name: type_name.to_owned(),
+                    description: docs.text(def),
+                    default_classification: None,
+                    required_attributes: None,
+                    attribute_schema: None,
+                    fingerprint_policy: None,
+                },
+            )
+            .await?;
+            result.object_types_created += 1;
+            let id = created.id;
+            types.push(created);
+            id
+        };
+        let _ = type_ids.insert(def_id, id);
+    }
+
+    // Element id → existing object, by recorded SysML id or by req1 id
+    let existing = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .filter(object::Column::DeletedAt.is_null())
+        .all(db)
+        .await?;
+    let mut by_element: HashMap<String, object::Model> = HashMap::new();
+    for obj in existing {
+        if let Some(path) = sysml_reference(&obj.references_).and_then(|r| r.get("path")?.as_str())
+        {
+            let _ = by_element.insert(path.to_owned(), obj.clone());
+        }
+        let _ = by_element.entry(obj.id.to_string()).or_insert(obj);
+    }
+
+    // Parents before children: order usages by their depth among imported usages
+    let usage_ids: HashMap<&str, &Value> = usages
+        .iter()
+        .filter_map(|el| Some((element_id(el)?, *el)))
+        .collect();
+    let depth = |el: &Value| {
+        let mut depth = 0;
+        let mut current = owner_id(el);
+        while let Some(owner) = current.and_then(|id| usage_ids.get(id)) {
+            depth += 1;
+            if depth > usage_ids.len() {
+                break;
+            }
+            current = owner_id(owner);
+        }
+        depth
+    };
+    let mut ordered = usages.clone();
+    ordered.sort_by_cached_key(|el| depth(el));
+
+    let mut object_ids: HashMap<&str, Uuid> = HashMap::new();
+    let mut next_position: HashMap<Option<Uuid>, i32> = HashMap::new();
+    for el in ordered {
+        let el_id = element_id(el).unwrap_or_default();
+        let heading = name(el).map(str::to_owned);
+        let body = docs.text(el);
+        let object_type_id = definition_id(el).and_then(|d| type_ids.get(d).copied());
+        let parent_id = owner_id(el).and_then(|o| object_ids.get(o).copied());
+        let reference = json!({
+            "type": REFERENCE_TYPE,
+            "path": el_id,
+            "description": "SysML v2 RequirementUsage",
+            "short_name": short_name(el),
+        });
+
+        if let Some(obj) = by_element.get(el_id) {
+            let mut references = obj.references_.as_array().cloned().unwrap_or_default();
+            let reference_changed = match references
+                .iter_mut()
+                .find(|r| r.get("type").and_then(Value::as_str) == Some(REFERENCE_TYPE))
+            {
+                Some(r) if *r == reference => false,
+                Some(r) => {
+                    *r = reference;
+                    true
+                }
+                None => {
+                    references.push(reference);
+                    true
+                }
+            };
+            let changed = obj.heading != heading
+                || obj.body != body
+                || (object_type_id.is_some() && obj.object_type_id != object_type_id)
+                || (parent_id.is_some() && obj.parent_id != parent_id);
+            if changed || reference_changed {
+                let _ = ObjectService::update(
+                    db,
+                    ctx,
+                    obj.id,
+                    UpdateObjectInput {
+                        parent_id: parent_id.filter(|p| obj.parent_id != Some(*p)),
+                        position: None,
+                        heading: heading.filter(|h| obj.heading.as_ref() != Some(h)),
+                        body: body.filter(|b| obj.body.as_ref() != Some(b)),
+                        attributes: None,
+                        reviewed: None,
+                        classification: None,
+                        references: reference_changed.then(|| Value::Array(references)),
+                        object_type_id: object_type_id.filter(|t| obj.object_type_id != Some(*t)),
+                        expected_version: None,
+                        lifecycle_state: None,
+                        signature: None,
+                    },
+                )
+                .await?;
+                result.objects_updated += 1;
+            } else {
+                result.objects_unchanged += 1;
+            }
+            let _ = object_ids.insert(el_id, obj.id);
+        } else {
+            let position = next_position.entry(parent_id).or_insert(0);
+            let created = ObjectService::create(
+                db,
+                ctx,
+                CreateObjectInput {
+                    module_id,
+                    parent_id,
+                    position: Some(*position),
+                    heading,
+                    body,
+                    attributes: None,
+                    classification: None,
+                    references: Some(json!([reference])),
+                    object_type_id,
+                    lifecycle_state: None,
+                    lifecycle_model_id: None,
+                    source_object_id: None,
+                    source_module_id: None,
+                    is_placeholder: None,
+                },
+            )
+            .await?;
+            *position += 1;
+            result.objects_created += 1;
+            let _ = object_ids.insert(el_id, created.id);
+        }
+    }
+
+    // SatisfyRequirementUsage → "satisfies" link from the satisfying feature
+    let resolve = |id: Option<&str>| -> Option<Uuid> {
+        let id = id?;
+        object_ids
+            .get(id)
+            .copied()
+            .or_else(|| by_element.get(id).map(|o| o.id))
+    };
+    let mut satisfies: Option<Uuid> = None;
+    for rel in els
+        .iter()
+        .filter(|el| element_type(el) == "SatisfyRequirementUsage")
+    {
+        let source = resolve(ref_id(rel.get("satisfyingFeature")));
+        let target = resolve(ref_id(rel.get("satisfiedRequirement")));
+        let (Some(source), Some(target)) = (source, target) else {
+            result.links_skipped += 1;
+            continue;
+        };
+        if source == target {
+            result.links_skipped += 1;
+            continue;
+        }
+        let link_type_id = match satisfies {
+            Some(id) => id,
+            None => {
+                let id = satisfies_link_type(db, ctx).await?;
+                satisfies = Some(id);
+                id
+            }
+        };
+        let exists = link::Entity::find()
+            .filter(link::Column::SourceObjectId.eq(source))
+            .filter(link::Column::TargetObjectId.eq(target))
+            .filter(link::Column::LinkTypeId.eq(link_type_id))
+            .one(db)
+            .await?
+            .is_some();
+        if exists {
+            result.links_skipped += 1;
+            continue;
+        }
+        let _ = LinkService::create(
+            db,
+            ctx,
+            CreateLinkInput {
+                source_object_id: source,
+                target_object_id: target,
+                link_type_id,
+                attributes: element_id(rel).map(|id| json!({ "sysml_id": id })),
+            },
+        )
+        .await?;
+        result.links_created += 1;
+    }
+
+    Ok(result)
+}
+
+async fn satisfies_link_type(
+    db: &impl ConnectionTrait,
+    ctx: &AuditContext,
+) -> Result<Uuid, CoreError> {
+    let existing = link_type::Entity::find().all(db).await?;
+    if let Some(lt) = existing
+        .iter()
+        .find(|lt| lt.name.eq_ignore_ascii_case(SATISFY_LINK_TYPE))
+    {
+        return Ok(lt.id);
+    }
+    let created = LinkService::create_link_type(
+        db,
+        ctx,
+        CreateLinkTypeInput {
+            name: SATISFY_LINK_TYPE.to_owned(),
+            description: Some("Imported from SysML v2 SatisfyRequirementUsage".to_owned()),
+            suspect_direction: None,
+            suspect_cascade: None,
+            suspect_skip_informative: None,
+            inverse_name: Some("satisfied by".to_owned()),
+            rules: None,
+        },
+    )
+    .await?;
+    Ok(created.id)
+}
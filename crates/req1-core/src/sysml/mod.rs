@@ -0,0 +1,83 @@
+pub mod export;
+pub mod import;
+
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// `references_` entry type under which an object keeps the id of the SysML v2
+/// element it was imported from.
+pub const REFERENCE_TYPE: &str = "sysml";
+
+/// Link type that `SatisfyRequirementUsage` relations map to.
+pub const SATISFY_LINK_TYPE: &str = "satisfies";
+
+/// Result of a SysML v2 import into a module.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct SysmlImportResult {
+    pub objects_created: usize,
+    pub objects_updated: usize,
+    pub objects_unchanged: usize,
+    pub object_types_created: usize,
+    pub links_created: usize,
+    pub links_skipped: usize,
+}
+
+/// Flatten the accepted payload shapes into a list of elements: a bare array, an
+/// `{"elements": [...]}` wrapper, or an API commit whose `change` entries carry
+/// the elements as `payload`.
+pub fn elements(doc: &Value) -> Vec<&Value> {
+    let items = match doc {
+        Value::Array(items) => items.as_slice(),
+        Value::Object(map) => map
+            .get("elements")
+            .or_else(|| map.get("change"))
+            .and_then(Value::as_array)
+            .map_or(&[][..], Vec::as_slice),
+        _ => &[],
+    };
+    items
+        .iter()
+        .map(|item| item.get("payload").unwrap_or(item))
+        .filter(|el| el.is_object())
+        .collect()
+}
+
+/// `sysml` entry in an object's `references_`, if any.
+pub fn sysml_reference(references: &Value) -> Option<&Value> {
+    references.as_array()?.iter().find(|r| {
+        r.get("type").and_then(Value::as_str) == Some(REFERENCE_TYPE)
+            && r.get("path").and_then(Value::as_str).is_some()
+    })
+}
+
+pub fn element_id(el: &Value) -> Option<&str> {
+    el.get("@id").and_then(Value::as_str)
+}
+
+pub fn element_type(el: &Value) -> &str {
+    el.get("@type").and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Id of an `{"@id": ...}` reference, or of the first entry of a list of them.
+pub fn ref_id(value: Option<&Value>) -> Option<&str> {
+    match value? {
+        Value::Array(items) => items.first().and_then(|v| ref_id(Some(v))),
+        Value::String(s) => Some(s),
+        v => element_id(v),
+    }
+}
+
+fn str_field<'a>(el: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|k| el.get(*k).and_then(Value::as_str))
+        .filter(|s| !s.is_empty())
+}
+
+pub fn name(el: &Value) -> Option<&str> {
+    str_field(el, &["declaredName", "name"])
+}
+
+pub fn short_name(el: &Value) -> Option<&str> {
+    str_field(el, &["declaredShortName", "shortName", "reqId"])
+}
@@ -40,33 +40,25 @@ pub async fn validate_attributes(
         }
 
         match def.data_type.as_str() {
-            "string" | "rich_text" | "user_ref" | "date" => {
-                if !value.is_string() {
-                    return Err(CoreError::bad_request(format!(
-                        "attribute '{key}' must be a string"
-                    )));
-                }
+            "string" | "rich_text" | "user_ref" | "date" if !value.is_string() => {
+                return Err(CoreError::bad_request(format!(
+                    "attribute '{key}' must be a string"
+                )));
             }
-            "integer" => {
-                if !value.is_i64() && !value.is_u64() {
-                    return Err(CoreError::bad_request(format!(
-                        "attribute '{key}' must be an integer"
-                    )));
-                }
+            "integer" if !value.is_i64() && !value.is_u64() => {
+                return Err(CoreError::bad_request(format!(
+                    "attribute '{key}' must be an integer"
+                )));
             }
-            "float" => {
-                if !value.is_number() {
-                    return Err(CoreError::bad_request(format!(
-                        "attribute '{key}' must be a number"
-                    )));
-                }
+            "float" if !value.is_number() => {
+                return Err(CoreError::bad_request(format!(
+                    "attribute '{key}' must be a number"
+                )));
             }
-            "bool" => {
-                if !value.is_boolean() {
-                    return Err(CoreError::bad_request(format!(
-                        "attribute '{key}' must be a boolean"
-                    )));
-                }
+            "bool" if !value.is_boolean() => {
+                return Err(CoreError::bad_request(format!(
+                    "attribute '{key}' must be a boolean"
+                )));
             }
             "enum" => {
                 let val_str = value.as_str().ok_or_else(|| {
@@ -174,26 +166,20 @@ pub fn validate_attr_constraints(
 
     if let Some(val) = default_value {
         match data_type {
-            "integer" => {
-                if val.parse::<i64>().is_err() {
-                    return Err(CoreError::bad_request(format!(
-                        "default_value '{val}' is not a valid integer"
-                    )));
-                }
+            "integer" if val.parse::<i64>().is_err() => {
+                return Err(CoreError::bad_request(format!(
+                    "default_value '{val}' is not a valid integer"
+                )));
             }
-            "float" => {
-                if val.parse::<f64>().is_err() {
-                    return Err(CoreError::bad_request(format!(
-                        "default_value '{val}' is not a valid float"
-                    )));
-                }
+            "float" if val.parse::<f64>().is_err() => {
+                return Err(CoreError::bad_request(format!(
+                    "default_value '{val}' is not a valid float"
+                )));
             }
-            "bool" => {
-                if val != "true" && val != "false" {
-                    return Err(CoreError::bad_request(format!(
-                        "default_value '{val}' is not a valid bool (expected 'true' or 'false')"
-                    )));
-                }
+            "bool" if val != "true" && val != "false" => {
+                return Err(CoreError::bad_request(format!(
+                    "default_value '{val}' is not a valid bool (expected 'true' or 'false')"
+                )));
             }
             "enum" => {
                 if let Some(ev) = enum_values {
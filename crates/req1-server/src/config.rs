@@ -1,4 +1,9 @@
 use std::env;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use req1_core::scripting::engine::ScriptLimits;
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -11,6 +16,15 @@ pub struct Config {
     pub build_sha: Option<String>,
     pub jwt_secret: String,
     pub jwt_expiration_hours: u64,
+    /// Directories whose files and git repositories external links may be checked against.
+    pub external_link_roots: Vec<PathBuf>,
+    /// Time, heap and concurrency limits for user scripts.
+    pub script_limits: ScriptLimits,
+    /// Reverse proxies whose `X-Forwarded-For` / `X-Real-IP` headers are believed.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Origins (`https://host[:port]`) of OSLC consumers the delegated dialogs answer to,
+    /// besides req1's own.
+    pub oslc_consumer_origins: Vec<String>,
 }
 
 impl Config {
@@ -34,6 +48,39 @@ impl Config {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(24),
+            external_link_roots: env::var_os("EXTERNAL_LINK_ROOTS")
+                .map(|v| env::split_paths(&v).collect())
+                .unwrap_or_default(),
+            script_limits: script_limits_from_env(),
+            trusted_proxies: env::var("TRUSTED_PROXIES")
+                .map(|v| {
+                    v.split(',')
+                        .filter_map(|ip| ip.trim().parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            oslc_consumer_origins: env::var("OSLC_CONSUMER_ORIGINS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|o| o.trim().trim_end_matches('/').to_owned())
+                        .filter(|o| !o.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
         }
     }
 }
+
+fn script_limits_from_env() -> ScriptLimits {
+    let defaults = ScriptLimits::default();
+    let var = |name: &str| env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+    ScriptLimits {
+        timeout: var("SCRIPT_TIMEOUT_MS").map_or(defaults.timeout, Duration::from_millis),
+        heap_limit: var("SCRIPT_HEAP_LIMIT_MB")
+            .and_then(|mb| usize::try_from(mb * 1024 * 1024).ok())
+            .unwrap_or(defaults.heap_limit),
+        workers: var("SCRIPT_WORKERS")
+            .and_then(|n| usize::try_from(n).ok())
+            .unwrap_or(defaults.workers),
+    }
+}
@@ -67,6 +67,16 @@ impl IntoResponse for AppError {
             CoreError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", msg.clone()),
             CoreError::Forbidden(msg) => (StatusCode::FORBIDDEN, "FORBIDDEN", msg.clone()),
             CoreError::Conflict(msg) => (StatusCode::CONFLICT, "CONFLICT", msg.clone()),
+            CoreError::ScriptTimeout(msg) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "SCRIPT_TIMEOUT",
+                msg.clone(),
+            ),
+            CoreError::ScriptMemoryLimit(msg) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "SCRIPT_MEMORY_LIMIT",
+                msg.clone(),
+            ),
             CoreError::Internal(msg) => {
                 tracing::error!("Internal error: {msg}");
                 (
@@ -1,4 +1,5 @@
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use axum::http::{HeaderValue, Method};
 use sea_orm::Database;
@@ -14,6 +15,7 @@ use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitEx
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+use req1_core::scripting::engine::ScriptEngine;
 use req1_server::config::Config;
 use req1_server::middleware;
 use req1_server::openapi::ApiDoc;
@@ -31,15 +33,25 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     let config = Config::from_env();
+    ScriptEngine::configure(config.script_limits);
     tracing::info!("Connecting to database...");
     let db = Database::connect(&config.database_url).await?;
 
     tracing::info!("Running migrations...");
+    let refingerprint = migration::Migrator::get_pending_migrations(&db)
+        .await?
+        .iter()
+        .any(|m| m.name() == migration::CANONICAL_FINGERPRINT);
     migration::Migrator::up(&db, None).await?;
+    if refingerprint {
+        let changed = req1_core::fingerprint::refingerprint_all(&db).await?;
+        tracing::info!("Recomputed {changed} content fingerprints");
+    }
 
     let state = AppState {
         db,
         config: config.clone(),
+        api: Arc::default(),
     };
 
     scheduler::spawn_scheduler(state.db.clone());
@@ -70,9 +82,12 @@ async fn main() -> anyhow::Result<()> {
     socket.bind(addr)?;
     let listener = socket.listen(1024)?;
     tracing::info!("Listening on {}", config.listen_addr);
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     Ok(())
 }
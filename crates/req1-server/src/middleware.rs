@@ -1,9 +1,17 @@
+use std::net::{IpAddr, SocketAddr};
+
 use axum::{
-    extract::{Request, State},
-    http::{HeaderValue, header},
+    extract::{ConnectInfo, MatchedPath, RawPathParams, Request, State},
+    http::{Extensions, HeaderValue, Method, header},
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use req1_core::auth::AuthUser;
+use req1_core::error::CoreError;
+use req1_core::service::audit::AuditContext;
+use req1_core::service::permission::{Permission, PermissionService, Scope};
+use req1_core::service::scheduler::SchedulerService;
+use uuid::Uuid;
 
 use crate::error::AppError;
 use crate::state::AppState;
@@ -32,6 +40,75 @@ pub async fn cache_control(request: Request, next: Next) -> Response {
     response
 }
 
+/// Client address for audit records: the peer address of the connection, or, when the
+/// peer is a trusted proxy, the nearest untrusted `X-Forwarded-For` hop (else `X-Real-IP`).
+fn client_ip(request: &Request, trusted: &[IpAddr]) -> Option<String> {
+    let peer = peer_ip(request.extensions())?;
+    if !trusted.contains(&peer) {
+        return Some(peer.to_string());
+    }
+
+    let headers = request.headers();
+    let forwarded: Vec<IpAddr> = headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| {
+            v.split(',')
+                .filter_map(|hop| hop.trim().parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    // Hops are appended by each proxy, so walk back from the one that reached us
+    let client = forwarded
+        .iter()
+        .rev()
+        .find(|ip| !trusted.contains(ip))
+        .or_else(|| forwarded.first())
+        .copied()
+        .or_else(|| {
+            headers
+                .get("x-real-ip")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse().ok())
+        });
+    Some(client.unwrap_or(peer).to_string())
+}
+
+fn peer_ip(extensions: &Extensions) -> Option<IpAddr> {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+/// Whether the connection comes from one of `trusted`, so its `X-Forwarded-*` headers
+/// may be believed.
+pub fn from_trusted_proxy(extensions: &Extensions, trusted: &[IpAddr]) -> bool {
+    peer_ip(extensions).is_some_and(|peer| trusted.contains(&peer))
+}
+
+/// Attach an anonymous `AuditContext` to unauthenticated requests (e.g. registration).
+pub async fn audit_context(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let ctx = AuditContext {
+        ip_address: client_ip(&request, &state.config.trusted_proxies),
+        ..AuditContext::default()
+    };
+    let _ = request.extensions_mut().insert(ctx);
+    next.run(request).await
+}
+
+/// Record failed trigger runs of the request once its transaction has ended.
+pub async fn record_script_failures(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    SchedulerService::record_failed_runs(&state.db, next.run(request)).await
+}
+
 pub async fn require_auth(
     State(state): State<AppState>,
     mut request: Request,
@@ -53,9 +130,284 @@ pub async fn require_auth(
 
     match req1_core::service::auth::AuthService::verify_token(token, &state.config.jwt_secret) {
         Ok(auth_user) => {
+            let ctx = AuditContext::new(
+                &auth_user,
+                client_ip(&request, &state.config.trusted_proxies),
+            );
+            let _ = request.extensions_mut().insert(ctx);
             let _ = request.extensions_mut().insert(auth_user);
             next.run(request).await
         }
         Err(_) => AppError::unauthorized("invalid or expired token").into_response(),
     }
 }
+
+/// Nested collections whose `{id}` must belong to the parent named just before it in the
+/// path: (parent path parameter, collection segment, table, parent column).
+const NESTED_TABLES: &[(&str, &str, &str, &str)] = &[
+    ("module_id", "objects", "object", "module_id"),
+    (
+        "module_id",
+        "attribute-definitions",
+        "attribute_definition",
+        "module_id",
+    ),
+    ("module_id", "baselines", "baseline", "module_id"),
+    (
+        "module_id",
+        "change-proposals",
+        "change_proposal",
+        "module_id",
+    ),
+    ("module_id", "diagrams", "diagram", "module_id"),
+    (
+        "module_id",
+        "lifecycle-models",
+        "lifecycle_model",
+        "module_id",
+    ),
+    (
+        "module_id",
+        "review-packages",
+        "review_package",
+        "module_id",
+    ),
+    ("module_id", "scripts", "script", "module_id"),
+    ("module_id", "test-cases", "test_case", "module_id"),
+    ("module_id", "views", "view", "module_id"),
+    ("module_id", "webhooks", "webhook", "module_id"),
+    ("module_id", "permissions", "module_permission", "module_id"),
+    ("workspace_id", "projects", "project", "workspace_id"),
+    ("workspace_id", "dashboards", "dashboard", "workspace_id"),
+    (
+        "workspace_id",
+        "members",
+        "workspace_member",
+        "workspace_id",
+    ),
+    (
+        "dashboard_id",
+        "widgets",
+        "dashboard_widget",
+        "dashboard_id",
+    ),
+    ("object_id", "attachments", "attachment", "object_id"),
+    ("object_id", "comments", "comment", "object_id"),
+    (
+        "package_id",
+        "assignments",
+        "review_assignment",
+        "package_id",
+    ),
+    ("package_id", "comments", "review_comment", "package_id"),
+    (
+        "test_case_id",
+        "executions",
+        "test_execution",
+        "test_case_id",
+    ),
+];
+
+/// POST routes that only compute a result and never mutate the module.
+const READ_ONLY_POSTS: &[&str] = &[
+    "/modules/{module_id}/import/docx/preview",
+    "/modules/{module_id}/reqif/merge/preview",
+    "/modules/{module_id}/scripts/{id}/layout",
+];
+
+/// Routes that name no workspace, project or module in their path. Their handlers check
+/// permissions themselves, filter results to what the caller can read, or only touch the
+/// caller's own data. Any other route that resolves to no scope is refused.
+const GLOBAL_ROUTES: &[&str] = &[
+    "/audit-log",
+    "/auth/change-password",
+    "/auth/me",
+    "/baseline-diff",
+    "/baseline-sets",
+    "/baseline-sets/{id}",
+    "/baseline-sets/{id}/consistency",
+    "/baselines",
+    "/e-signatures",
+    "/e-signatures/entity/{entity_type}/{entity_id}",
+    "/external-links",
+    "/link-types",
+    "/link-types/{id}",
+    "/links",
+    "/mcp",
+    "/modules",
+    "/modules/from-template",
+    "/notifications",
+    "/notifications/read-all",
+    "/notifications/unread-count",
+    "/notifications/{id}/read",
+    "/object-types",
+    "/oslc/catalog",
+    "/project-templates",
+    "/project-templates/{id}",
+    "/project-templates/{id}/instantiate",
+    "/search",
+    "/suspect-links",
+    "/suspect-links/resolve",
+    "/traceability-matrix",
+    "/users",
+    "/users/{id}",
+    "/workspaces",
+];
+
+/// Global catalogs shared by all workspaces: anyone may read them, only admins change them.
+const ADMIN_CATALOGS: &[&str] = &["baseline-sets", "link-types", "project-templates"];
+
+/// Catalog routes that use an entry rather than change it. Their handlers check access to
+/// the target instead.
+const CATALOG_USES: &[&str] = &["/project-templates/{id}/instantiate"];
+
+fn scope_for_param(name: &str, id: Uuid) -> Option<Scope> {
+    match name {
+        "workspace_id" => Some(Scope::Workspace(id)),
+        "project_id" => Some(Scope::Project(id)),
+        "module_id" => Some(Scope::Module(id)),
+        "object_id" => Some(Scope::Object(id)),
+        "package_id" => Some(Scope::ReviewPackage(id)),
+        "test_case_id" => Some(Scope::TestCase(id)),
+        "dashboard_id" => Some(Scope::Dashboard(id)),
+        _ => None,
+    }
+}
+
+fn scope_for_collection(collection: &str, id: Uuid) -> Option<Scope> {
+    match collection {
+        "workspaces" => Some(Scope::Workspace(id)),
+        "projects" => Some(Scope::Project(id)),
+        "modules" => Some(Scope::Module(id)),
+        "objects" | "object-impact" => Some(Scope::Object(id)),
+        "links" => Some(Scope::Link(id)),
+        "external-links" => Some(Scope::ExternalLink(id)),
+        "review-packages" => Some(Scope::ReviewPackage(id)),
+        "test-cases" => Some(Scope::TestCase(id)),
+        "dashboards" => Some(Scope::Dashboard(id)),
+        "baselines" => Some(Scope::Baseline(id)),
+        "script-libraries" => Some(Scope::ScriptLibrary(id)),
+        "object-types" => Some(Scope::ObjectType(id)),
+        _ => None,
+    }
+}
+
+fn is_read_only(method: &Method, route: &str) -> bool {
+    matches!(*method, Method::GET | Method::HEAD)
+        || (*method == Method::POST && READ_ONLY_POSTS.contains(&route))
+}
+
+fn required_permission(method: &Method, route: &str, segments: &[&str]) -> Permission {
+    let read_only = is_read_only(method, route);
+    if segments.contains(&"permissions") {
+        return Permission::Admin;
+    }
+    if segments.contains(&"members") && !read_only {
+        return Permission::Admin;
+    }
+    // Library code runs inside every script that imports it, so only admins change it
+    if segments.contains(&"script-libraries") && !read_only {
+        return Permission::Admin;
+    }
+    match segments {
+        ["workspaces", "{id}"] if !read_only => Permission::Admin,
+        ["modules", "{id}"] if *method == Method::DELETE => Permission::Admin,
+        _ if read_only => Permission::Read,
+        _ => Permission::Write,
+    }
+}
+
+/// Enforce workspace roles and module permissions for every resource named in the
+/// matched route's path. Must run after `require_auth`.
+///
+/// Routes whose scope lives in the request body or query (e.g. `POST /links`) are listed in
+/// `GLOBAL_ROUTES` and check permissions in their handlers instead.
+pub async fn authorize(
+    State(state): State<AppState>,
+    matched: MatchedPath,
+    params: RawPathParams,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(auth_user) = request.extensions().get::<AuthUser>().cloned() else {
+        return AppError::unauthorized("missing authenticated user").into_response();
+    };
+
+    let route = matched.as_str().trim_start_matches("/api/v1");
+    let segments: Vec<&str> = route.split('/').filter(|s| !s.is_empty()).collect();
+    let values: Vec<(&str, Uuid)> = params
+        .iter()
+        .filter_map(|(name, value)| value.parse().ok().map(|id| (name, id)))
+        .collect();
+    let value_of = |name: &str| values.iter().find(|(n, _)| *n == name).map(|(_, id)| *id);
+
+    let global = GLOBAL_ROUTES.contains(&route);
+    if !global && values.len() != params.iter().count() {
+        return AppError::bad_request("invalid id in path").into_response();
+    }
+    if segments.first().is_some_and(|s| ADMIN_CATALOGS.contains(s))
+        && !is_read_only(request.method(), route)
+        && !CATALOG_USES.contains(&route)
+        && let Err(e) = PermissionService::require_admin(&auth_user)
+    {
+        return AppError::from(e).into_response();
+    }
+
+    let needed = required_permission(request.method(), route, &segments);
+    let mut scopes = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) else {
+            continue;
+        };
+        let Some(id) = value_of(name) else {
+            continue;
+        };
+        if name != "id" {
+            scopes.extend(scope_for_param(name, id));
+            continue;
+        }
+        let Some(collection) = i.checked_sub(1).and_then(|j| segments.get(j)) else {
+            continue;
+        };
+        scopes.extend(scope_for_collection(collection, id));
+
+        let parent = i
+            .checked_sub(2)
+            .and_then(|j| segments.get(j))
+            .and_then(|s| s.strip_prefix('{'))
+            .and_then(|s| s.strip_suffix('}'));
+        let nested = parent.and_then(|p| {
+            NESTED_TABLES
+                .iter()
+                .find(|(param, coll, _, _)| *param == p && coll == collection)
+        });
+        if let Some((param, _, table, column)) = nested {
+            let Some(parent_id) = value_of(param) else {
+                continue;
+            };
+            match PermissionService::parent_of(&state.db, table, column, id).await {
+                Ok(Some(owner)) if owner != parent_id => {
+                    return AppError::not_found(format!("{collection} {id} not found"))
+                        .into_response();
+                }
+                Ok(_) => {}
+                Err(e) => return AppError::from(e).into_response(),
+            }
+        }
+    }
+
+    if scopes.is_empty() && !global {
+        return AppError::forbidden(format!("no access rule for {route}")).into_response();
+    }
+
+    for scope in scopes {
+        match PermissionService::require(&state.db, &auth_user, scope, needed).await {
+            Ok(()) => {}
+            // Let the handler report missing resources with its usual 404
+            Err(CoreError::NotFound(_)) => {}
+            Err(e) => return AppError::from(e).into_response(),
+        }
+    }
+
+    next.run(request).await
+}
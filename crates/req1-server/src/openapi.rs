@@ -28,6 +28,17 @@ use crate::routes;
         routes::workspaces::get_workspace,
         routes::workspaces::update_workspace,
         routes::workspaces::delete_workspace,
+        // permissions
+        routes::workspace_members::list_members,
+        routes::workspace_members::create_member,
+        routes::workspace_members::get_member,
+        routes::workspace_members::update_member,
+        routes::workspace_members::delete_member,
+        routes::module_permissions::list_module_permissions,
+        routes::module_permissions::create_module_permission,
+        routes::module_permissions::get_module_permission,
+        routes::module_permissions::update_module_permission,
+        routes::module_permissions::delete_module_permission,
         // projects
         routes::projects::list_projects,
         routes::projects::create_project,
@@ -59,8 +70,32 @@ use crate::routes;
         routes::links::get_link,
         routes::links::update_link,
         routes::links::delete_link,
+        routes::links::list_link_history,
         routes::links::list_link_types,
         routes::links::create_link_type,
+        routes::links::update_link_type,
+        routes::links::list_suspect_links,
+        routes::links::resolve_suspect_links,
+        // external links
+        routes::external_links::list_external_links,
+        routes::external_links::create_external_link,
+        routes::external_links::get_external_link,
+        routes::external_links::update_external_link,
+        routes::external_links::delete_external_link,
+        routes::external_links::check_external_link,
+        routes::external_links::check_module_external_links,
+        // mcp
+        routes::mcp::handle_mcp,
+        // oslc
+        routes::oslc::get_catalog,
+        routes::oslc::get_service_provider,
+        routes::oslc::query_project_requirements,
+        routes::oslc::get_requirement_collection,
+        routes::oslc::query_module_requirements,
+        routes::oslc::create_requirement,
+        routes::oslc::get_requirement,
+        routes::oslc::selection_dialog,
+        routes::oslc::creation_dialog,
         // baselines
         routes::baselines::list_baselines,
         routes::baselines::create_baseline,
@@ -69,12 +104,14 @@ use crate::routes;
         routes::baselines::diff_baselines,
         routes::baselines::diff_baselines_global,
         routes::baselines::list_all_baselines,
+        routes::baselines::restore_baseline,
         // baseline sets
         routes::baseline_sets::list_baseline_sets,
         routes::baseline_sets::create_baseline_set,
         routes::baseline_sets::get_baseline_set,
         routes::baseline_sets::update_baseline_set,
         routes::baseline_sets::delete_baseline_set,
+        routes::baseline_sets::check_baseline_set_consistency,
         // attribute definitions
         routes::attribute_definitions::list_attribute_definitions,
         routes::attribute_definitions::create_attribute_definition,
@@ -142,6 +179,16 @@ use crate::routes;
         routes::scripts::execute_script,
         routes::scripts::batch_layout,
         routes::scripts::list_executions,
+        // script libraries
+        routes::script_libraries::list_workspace_libraries,
+        routes::script_libraries::create_workspace_library,
+        routes::script_libraries::list_project_libraries,
+        routes::script_libraries::create_project_library,
+        routes::script_libraries::get_library,
+        routes::script_libraries::update_library,
+        routes::script_libraries::delete_library,
+        routes::script_libraries::list_versions,
+        routes::script_libraries::activate_version,
         // validation
         routes::validation::validate_module,
         // traceability
@@ -167,6 +214,8 @@ use crate::routes;
         routes::lifecycle::get_lifecycle_model,
         routes::lifecycle::update_lifecycle_model,
         routes::lifecycle::delete_lifecycle_model,
+        routes::lifecycle::module_timeline,
+        routes::lifecycle::object_timeline,
         // publish/import
         routes::publish::publish_module,
         routes::publish::import_csv,
@@ -176,6 +225,11 @@ use crate::routes;
         // reqif
         routes::reqif::import_reqif_handler,
         routes::reqif::export_reqif_handler,
+        routes::reqif::preview_merge_handler,
+        routes::reqif::merge_reqif_handler,
+        // sysml
+        routes::sysml::import_sysml,
+        routes::sysml::export_sysml,
         // tests
         routes::tests::list_test_cases,
         routes::tests::create_test_case,
@@ -234,6 +288,7 @@ use crate::routes;
         entity::audit_log::Model,
         entity::baseline::Model,
         entity::baseline_entry::Model,
+        entity::baseline_link::Model,
         entity::baseline_set::Model,
         entity::change_proposal::Model,
         entity::comment::Model,
@@ -257,12 +312,16 @@ use crate::routes;
         entity::review_package::Model,
         entity::script::Model,
         entity::script_execution::Model,
+        entity::script_library::Model,
+        entity::script_library_version::Model,
         entity::test_case::Model,
         entity::test_execution::Model,
         entity::view::Model,
         entity::webhook::Model,
         entity::workspace::Model,
         entity::workspace_member::Model,
+        entity::module_permission::Model,
+        entity::workspace_member::Model,
         // Core types
         req1_core::PaginatedResponse<serde_json::Value>,
         req1_core::auth::AuthUser,
@@ -272,9 +331,15 @@ use crate::routes;
         req1_core::service::project::UpdateProjectInput,
         req1_core::service::workspace::CreateWorkspaceInput,
         req1_core::service::workspace::UpdateWorkspaceInput,
+        req1_core::service::workspace_member::CreateWorkspaceMemberInput,
+        req1_core::service::workspace_member::UpdateWorkspaceMemberInput,
+        req1_core::service::module_permission::CreateModulePermissionInput,
+        req1_core::service::module_permission::UpdateModulePermissionInput,
+        req1_core::service::permission::Permission,
         req1_core::service::module::CreateModuleInput,
         req1_core::service::module::UpdateModuleInput,
         req1_core::service::module::CreateModuleFromTemplateInput,
+        req1_core::fingerprint::FingerprintPolicy,
         req1_core::service::object::CreateObjectInput,
         req1_core::service::object::UpdateObjectInput,
         req1_core::service::object::MoveObjectInput,
@@ -282,13 +347,35 @@ use crate::routes;
         req1_core::service::link::CreateLinkInput,
         req1_core::service::link::UpdateLinkInput,
         req1_core::service::link::CreateLinkTypeInput,
+        req1_core::service::link::UpdateLinkTypeInput,
+        req1_core::suspect::SuspectReason,
+        req1_core::link_rules::LinkTypeRules,
+        req1_core::service::suspect_queue::SuspectLinkEntry,
+        req1_core::service::suspect_queue::SuspectLinkEnd,
+        req1_core::service::suspect_queue::FieldChange,
+        req1_core::service::suspect_queue::ResolveSuspectsInput,
+        entity::link_history::Model,
+        entity::external_link::Model,
+        req1_core::service::external_link::CreateExternalLinkInput,
+        req1_core::service::external_link::UpdateExternalLinkInput,
+        req1_core::service::external_link::ExternalCheckResult,
         req1_core::service::baseline::CreateBaselineInput,
         req1_core::service::baseline::BaselineWithEntries,
         req1_core::service::baseline::BaselineDiff,
         req1_core::service::baseline::DiffEntry,
         req1_core::service::baseline::DiffModified,
+        req1_core::service::baseline::DiffMoved,
+        req1_core::service::baseline::DiffLink,
+        req1_core::service::baseline::DiffLinkRetyped,
+        req1_core::service::baseline::RestoreBaselineInput,
+        req1_core::service::baseline::RestoreChange,
+        req1_core::service::baseline::RestoreResult,
         req1_core::service::baseline_set::CreateBaselineSetInput,
         req1_core::service::baseline_set::UpdateBaselineSetInput,
+        req1_core::service::baseline_set::BaselineSetConsistency,
+        req1_core::service::baseline_set::SetMember,
+        req1_core::service::baseline_set::ConsistencyIssue,
+        req1_core::service::baseline_set::ModulePairTrace,
         req1_core::service::comment::CreateCommentInput,
         req1_core::service::comment::UpdateCommentInput,
         req1_core::service::app_user::CreateAppUserInput,
@@ -312,6 +399,7 @@ use crate::routes;
         req1_core::service::lifecycle::UpdateLifecycleModelInput,
         req1_core::service::lifecycle::LifecycleState,
         req1_core::service::lifecycle::LifecycleTransition,
+        req1_core::service::lifecycle::StateTimelineEntry,
         req1_core::service::diagram::CreateDiagramInput,
         req1_core::service::diagram::UpdateDiagramInput,
         req1_core::service::dashboard::CreateDashboardInput,
@@ -319,6 +407,9 @@ use crate::routes;
         req1_core::service::dashboard::CreateWidgetInput,
         req1_core::service::dashboard::UpdateWidgetInput,
         req1_core::service::dashboard::WidgetDataEntry,
+        req1_core::service::script_library::CreateScriptLibraryInput,
+        req1_core::service::script_library::UpdateScriptLibraryInput,
+        req1_core::service::script_library::ActivateVersionInput,
         req1_core::service::e_signature::SignInput,
         req1_core::service::project_template::CreateTemplateInput,
         req1_core::service::project_template::UpdateTemplateInput,
@@ -367,6 +458,12 @@ use crate::routes;
         routes::publish::CsvImportResponse,
         routes::publish::XlsxImportResponse,
         routes::reqif::ImportResponse,
+        req1_core::reqif::MergeResult,
+        req1_core::reqif::MergeConflict,
+        req1_core::reqif::ConflictResolution,
+        req1_core::reqif::profile::ExchangeProfile,
+        req1_core::reqif::profile::OwnershipViolation,
+        req1_core::sysml::SysmlImportResult,
         routes::e_signatures::CreateSignatureRequest,
         routes::webhooks::CreateWebhookRequest,
         routes::webhooks::UpdateWebhookRequest,
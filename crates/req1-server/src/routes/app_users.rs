@@ -1,14 +1,17 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::app_user;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse,
+    auth::AuthUser,
     service::app_user::{
         AppUserService, CreateAppUserInput, ListAppUsersFilter, UpdateAppUserInput,
     },
@@ -43,9 +46,16 @@ pub(crate) async fn list_users(
 )]
 pub(crate) async fn create_user(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateAppUserInput>,
 ) -> Result<(axum::http::StatusCode, Json<app_user::Model>), AppError> {
-    let result = AppUserService::create(&state.db, body).await?;
+    if auth_user.role != "admin" {
+        return Err(AppError::forbidden("only admins can manage users"));
+    }
+    let txn = state.db.begin().await?;
+    let result = AppUserService::create(&txn, &audit, body).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -70,10 +80,17 @@ pub(crate) async fn get_user(
 )]
 pub(crate) async fn update_user(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
     Json(body): Json<UpdateAppUserInput>,
 ) -> Result<Json<app_user::Model>, AppError> {
-    let result = AppUserService::update(&state.db, id, body).await?;
+    if auth_user.role != "admin" {
+        return Err(AppError::forbidden("only admins can manage users"));
+    }
+    let txn = state.db.begin().await?;
+    let result = AppUserService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -84,8 +101,15 @@ pub(crate) async fn update_user(
 )]
 pub(crate) async fn delete_user(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    AppUserService::delete(&state.db, id).await?;
+    if auth_user.role != "admin" {
+        return Err(AppError::forbidden("only admins can manage users"));
+    }
+    let txn = state.db.begin().await?;
+    AppUserService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
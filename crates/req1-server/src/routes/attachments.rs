@@ -1,11 +1,12 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     body::Body,
     extract::{Multipart, Path, State},
     http::{HeaderMap, HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use utoipa::ToSchema;
@@ -13,6 +14,7 @@ use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use req1_core::service::attachment::AttachmentService;
+use req1_core::service::audit::AuditContext;
 
 const DEFAULT_UPLOAD_DIR: &str = "./uploads";
 
@@ -57,6 +59,7 @@ pub(crate) async fn list_attachments(
 )]
 pub(crate) async fn upload_attachment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(object_id): Path<Uuid>,
     mut multipart: Multipart,
 ) -> Result<(StatusCode, Json<entity::attachment::Model>), AppError> {
@@ -79,8 +82,10 @@ pub(crate) async fn upload_attachment(
     let upload_dir =
         std::env::var("REQ1_UPLOAD_DIR").unwrap_or_else(|_| DEFAULT_UPLOAD_DIR.to_owned());
 
+    let txn = state.db.begin().await?;
     let result = AttachmentService::create(
-        &state.db,
+        &txn,
+        &audit,
         object_id,
         file_name,
         content_type,
@@ -89,6 +94,7 @@ pub(crate) async fn upload_attachment(
     )
     .await?;
 
+    txn.commit().await?;
     Ok((StatusCode::CREATED, Json(result)))
 }
 
@@ -183,8 +189,11 @@ pub(crate) async fn verify_attachment(
 )]
 pub(crate) async fn delete_attachment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_object_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<StatusCode, AppError> {
-    AttachmentService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    AttachmentService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(StatusCode::NO_CONTENT)
 }
@@ -1,17 +1,21 @@
 #![allow(unused_qualifications)]
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set, TransactionTrait,
+};
 use serde::Deserialize;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::attribute_definition;
+use req1_core::service::audit::{AuditContext, AuditService};
+use req1_core::service::crud;
 use req1_core::{PaginatedResponse, Pagination};
 
 pub fn routes() -> Router<AppState> {
@@ -101,6 +105,7 @@ pub(crate) async fn list_attribute_definitions(
 )]
 pub(crate) async fn create_attribute_definition(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     Json(body): Json<CreateAttributeDefinitionRequest>,
 ) -> Result<(axum::http::StatusCode, Json<attribute_definition::Model>), AppError> {
@@ -154,7 +159,18 @@ pub(crate) async fn create_attribute_definition(
         created_at: Set(now),
     };
 
-    let result = model.insert(&state.db).await?;
+    let txn = state.db.begin().await?;
+    let result = model.insert(&txn).await?;
+    AuditService::record::<attribute_definition::Entity>(
+        &txn,
+        &audit,
+        "create",
+        result.id,
+        None,
+        Some(&result),
+    )
+    .await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -197,6 +213,7 @@ pub(crate) async fn get_attribute_definition(
 )]
 pub(crate) async fn update_attribute_definition(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateAttributeDefinitionRequest>,
 ) -> Result<Json<attribute_definition::Model>, AppError> {
@@ -205,7 +222,7 @@ pub(crate) async fn update_attribute_definition(
         .await?
         .ok_or_else(|| AppError::not_found(format!("attribute definition {id} not found")))?;
 
-    let mut active: attribute_definition::ActiveModel = existing.into();
+    let mut active: attribute_definition::ActiveModel = existing.clone().into();
     if let Some(name) = body.name {
         active.name = Set(name);
     }
@@ -248,7 +265,18 @@ pub(crate) async fn update_attribute_definition(
         active.dependency_mapping = Set(Some(dependency_mapping));
     }
 
-    let result = active.update(&state.db).await?;
+    let txn = state.db.begin().await?;
+    let result = active.update(&txn).await?;
+    AuditService::record::<attribute_definition::Entity>(
+        &txn,
+        &audit,
+        "update",
+        result.id,
+        Some(&existing),
+        Some(&result),
+    )
+    .await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -266,16 +294,13 @@ pub(crate) async fn update_attribute_definition(
 )]
 pub(crate) async fn delete_attribute_definition(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    let result = attribute_definition::Entity::delete_by_id(id)
-        .exec(&state.db)
+    let txn = state.db.begin().await?;
+    crud::delete_by_id::<attribute_definition::Entity>(&txn, &audit, id, "attribute definition")
         .await?;
-    if result.rows_affected == 0 {
-        return Err(AppError::not_found(format!(
-            "attribute definition {id} not found"
-        )));
-    }
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
@@ -3,11 +3,13 @@ use axum::{
     extract::State,
     routing::{get, post},
 };
+use sea_orm::TransactionTrait;
 use serde::Deserialize;
 use utoipa::ToSchema;
 
 use crate::{error::AppError, state::AppState};
 use req1_core::auth::AuthUser;
+use req1_core::service::audit::AuditContext;
 use req1_core::service::auth::AuthService;
 
 pub fn public_routes() -> Router<AppState> {
@@ -50,10 +52,19 @@ pub(crate) struct ChangePasswordRequest {
 )]
 pub(crate) async fn register(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Json(body): Json<RegisterRequest>,
 ) -> Result<(axum::http::StatusCode, Json<entity::app_user::Model>), AppError> {
-    let user =
-        AuthService::register(&state.db, &body.email, &body.password, &body.display_name).await?;
+    let txn = state.db.begin().await?;
+    let user = AuthService::register(
+        &txn,
+        &audit,
+        &body.email,
+        &body.password,
+        &body.display_name,
+    )
+    .await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(user)))
 }
 
@@ -97,15 +108,19 @@ pub(crate) async fn me(Extension(auth_user): Extension<AuthUser>) -> Json<AuthUs
 )]
 pub(crate) async fn change_password(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<ChangePasswordRequest>,
 ) -> Result<axum::http::StatusCode, AppError> {
+    let txn = state.db.begin().await?;
     AuthService::change_password(
-        &state.db,
+        &txn,
+        &audit,
         auth_user.id,
         &body.old_password,
         &body.new_password,
     )
     .await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
@@ -1,15 +1,23 @@
+use std::collections::BTreeSet;
+
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::baseline_set;
+use req1_core::auth::AuthUser;
+use req1_core::service::audit::AuditContext;
+use req1_core::service::permission::{Permission, PermissionService};
 use req1_core::{
     PaginatedResponse, Pagination,
-    service::baseline_set::{BaselineSetService, CreateBaselineSetInput, UpdateBaselineSetInput},
+    service::baseline_set::{
+        BaselineSetConsistency, BaselineSetService, CreateBaselineSetInput, UpdateBaselineSetInput,
+    },
 };
 
 pub fn routes() -> Router<AppState> {
@@ -24,6 +32,10 @@ pub fn routes() -> Router<AppState> {
                 .patch(update_baseline_set)
                 .delete(delete_baseline_set),
         )
+        .route(
+            "/baseline-sets/{id}/consistency",
+            get(check_baseline_set_consistency),
+        )
 }
 
 #[utoipa::path(get, path = "/api/v1/baseline-sets", tag = "BaselineSets",
@@ -46,9 +58,12 @@ pub(crate) async fn list_baseline_sets(
 )]
 pub(crate) async fn create_baseline_set(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Json(body): Json<CreateBaselineSetInput>,
 ) -> Result<(axum::http::StatusCode, Json<baseline_set::Model>), AppError> {
-    let result = BaselineSetService::create(&state.db, body).await?;
+    let txn = state.db.begin().await?;
+    let result = BaselineSetService::create(&txn, &audit, body).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -73,10 +88,13 @@ pub(crate) async fn get_baseline_set(
 )]
 pub(crate) async fn update_baseline_set(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
     Json(body): Json<UpdateBaselineSetInput>,
 ) -> Result<Json<baseline_set::Model>, AppError> {
-    let result = BaselineSetService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = BaselineSetService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -87,8 +105,31 @@ pub(crate) async fn update_baseline_set(
 )]
 pub(crate) async fn delete_baseline_set(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    BaselineSetService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    BaselineSetService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
+
+#[utoipa::path(get, path = "/api/v1/baseline-sets/{id}/consistency", tag = "BaselineSets",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Baseline set ID")),
+    responses((status = 200, body = BaselineSetConsistency), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn check_baseline_set_consistency(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<BaselineSetConsistency>, AppError> {
+    let result = BaselineSetService::check_consistency(&state.db, id).await?;
+    // The report lists objects and links of every member module
+    let modules: BTreeSet<Uuid> = result.members.iter().map(|m| m.module_id).collect();
+    for module_id in modules {
+        PermissionService::require_module(&state.db, &auth_user, module_id, Permission::Read)
+            .await?;
+    }
+    Ok(Json(result))
+}
@@ -1,9 +1,9 @@
 #![allow(unused_qualifications)]
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
-    routing::get,
+    routing::{get, post},
 };
 use sea_orm::TransactionTrait;
 use serde::Deserialize;
@@ -11,10 +11,16 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
-    service::baseline::{
-        BaselineDiff, BaselineService, BaselineWithEntries, CreateBaselineInput, DiffBaselineInput,
+    auth::AuthUser,
+    service::{
+        baseline::{
+            BaselineDiff, BaselineService, BaselineWithEntries, CreateBaselineInput,
+            DiffBaselineInput, RestoreBaselineInput, RestoreResult,
+        },
+        permission::{Permission, PermissionService, Scope},
     },
 };
 
@@ -28,6 +34,10 @@ pub fn routes() -> Router<AppState> {
             "/modules/{module_id}/baselines/{id}",
             get(get_baseline).delete(delete_baseline),
         )
+        .route(
+            "/modules/{module_id}/baselines/{id}/restore",
+            post(restore_baseline),
+        )
         .route("/modules/{module_id}/baseline-diff", get(diff_baselines))
         .route("/baseline-diff", get(diff_baselines_global))
         .route("/baselines", get(list_all_baselines))
@@ -72,12 +82,14 @@ pub(crate) async fn list_baselines(
 )]
 pub(crate) async fn create_baseline(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     Json(body): Json<CreateBaselineRequest>,
 ) -> Result<(axum::http::StatusCode, Json<BaselineWithEntries>), AppError> {
     let txn = state.db.begin().await?;
     let result = BaselineService::create(
         &txn,
+        &audit,
         CreateBaselineInput {
             module_id,
             name: body.name,
@@ -117,12 +129,36 @@ pub(crate) async fn get_baseline(
 )]
 pub(crate) async fn delete_baseline(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    BaselineService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    BaselineService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(post, path = "/api/v1/modules/{module_id}/baselines/{id}/restore", tag = "Baselines",
+    security(("bearer_auth" = [])),
+    params(
+        ("module_id" = Uuid, Path, description = "Module ID"),
+        ("id" = Uuid, Path, description = "Baseline ID"),
+    ),
+    request_body = RestoreBaselineInput,
+    responses((status = 200, body = RestoreResult), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn restore_baseline(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path((module_id, id)): Path<(Uuid, Uuid)>,
+    Json(body): Json<RestoreBaselineInput>,
+) -> Result<Json<RestoreResult>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = BaselineService::restore(&txn, &audit, module_id, id, body).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
+
 #[utoipa::path(get, path = "/api/v1/modules/{module_id}/baseline-diff", tag = "Baselines",
     security(("bearer_auth" = [])),
     params(
@@ -154,8 +190,18 @@ pub(crate) async fn diff_baselines(
 )]
 pub(crate) async fn diff_baselines_global(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(query): Query<DiffQuery>,
 ) -> Result<Json<BaselineDiff>, AppError> {
+    for baseline_id in [query.a, query.b] {
+        PermissionService::require(
+            &state.db,
+            &auth_user,
+            Scope::Baseline(baseline_id),
+            Permission::Read,
+        )
+        .await?;
+    }
     let result = BaselineService::diff(
         &state.db,
         DiffBaselineInput {
@@ -174,8 +220,11 @@ pub(crate) async fn diff_baselines_global(
 )]
 pub(crate) async fn list_all_baselines(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(pagination): Query<Pagination>,
 ) -> Result<Json<PaginatedResponse<entity::baseline::Model>>, AppError> {
-    let result = BaselineService::list_all(&state.db, pagination.offset, pagination.limit).await?;
+    let visible = PermissionService::visible_module_ids(&state.db, &auth_user).await?;
+    let result =
+        BaselineService::list_all(&state.db, visible, pagination.offset, pagination.limit).await?;
     Ok(Json(result))
 }
@@ -1,12 +1,14 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::change_proposal;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
     service::change_proposal::{
@@ -57,11 +59,14 @@ pub(crate) async fn list_change_proposals(
 )]
 pub(crate) async fn create_change_proposal(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     Json(body): Json<CreateChangeProposalInput>,
 ) -> Result<(axum::http::StatusCode, Json<change_proposal::Model>), AppError> {
     let input = CreateChangeProposalInput { module_id, ..body };
-    let result = ChangeProposalService::create(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = ChangeProposalService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -100,10 +105,13 @@ pub(crate) async fn get_change_proposal(
 )]
 pub(crate) async fn update_change_proposal(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateChangeProposalInput>,
 ) -> Result<Json<change_proposal::Model>, AppError> {
-    let result = ChangeProposalService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ChangeProposalService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -121,8 +129,11 @@ pub(crate) async fn update_change_proposal(
 )]
 pub(crate) async fn delete_change_proposal(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    ChangeProposalService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ChangeProposalService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
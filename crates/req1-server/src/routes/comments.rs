@@ -1,12 +1,14 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::comment;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
     service::comment::{CommentService, CreateCommentInput, UpdateCommentInput},
@@ -52,11 +54,14 @@ pub(crate) async fn list_comments(
 )]
 pub(crate) async fn create_comment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(object_id): Path<Uuid>,
     Json(body): Json<CreateCommentInput>,
 ) -> Result<(axum::http::StatusCode, Json<comment::Model>), AppError> {
     let input = CreateCommentInput { object_id, ..body };
-    let result = CommentService::create(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = CommentService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -87,10 +92,13 @@ pub(crate) async fn get_comment(
 )]
 pub(crate) async fn update_comment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_object_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateCommentInput>,
 ) -> Result<Json<comment::Model>, AppError> {
-    let result = CommentService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = CommentService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -104,8 +112,11 @@ pub(crate) async fn update_comment(
 )]
 pub(crate) async fn delete_comment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_object_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    CommentService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    CommentService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
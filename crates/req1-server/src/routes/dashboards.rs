@@ -6,11 +6,13 @@ use axum::{
     response::{IntoResponse, Response},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use std::fmt::Write as _;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use req1_core::auth::AuthUser;
+use req1_core::service::audit::AuditContext;
 use req1_core::service::dashboard::{
     CreateDashboardInput, CreateWidgetInput, DashboardService, UpdateDashboardInput,
     UpdateWidgetInput,
@@ -64,6 +66,7 @@ pub(crate) async fn list_dashboards(
 )]
 pub(crate) async fn create_dashboard(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(workspace_id): Path<Uuid>,
     Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateDashboardInput>,
@@ -73,7 +76,9 @@ pub(crate) async fn create_dashboard(
         created_by: Some(auth_user.id),
         ..body
     };
-    let result = DashboardService::create_dashboard(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = DashboardService::create_dashboard(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((StatusCode::CREATED, Json(result)))
 }
 
@@ -106,10 +111,13 @@ pub(crate) async fn get_dashboard(
 )]
 pub(crate) async fn update_dashboard(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_workspace_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateDashboardInput>,
 ) -> Result<Json<entity::dashboard::Model>, AppError> {
-    let result = DashboardService::update_dashboard(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = DashboardService::update_dashboard(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -124,9 +132,12 @@ pub(crate) async fn update_dashboard(
 )]
 pub(crate) async fn delete_dashboard(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_workspace_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<StatusCode, AppError> {
-    DashboardService::delete_dashboard(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    DashboardService::delete_dashboard(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -151,6 +162,7 @@ pub(crate) async fn list_widgets(
 )]
 pub(crate) async fn create_widget(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(dashboard_id): Path<Uuid>,
     Json(body): Json<CreateWidgetInput>,
 ) -> Result<(StatusCode, Json<entity::dashboard_widget::Model>), AppError> {
@@ -158,7 +170,9 @@ pub(crate) async fn create_widget(
         dashboard_id,
         ..body
     };
-    let result = DashboardService::create_widget(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = DashboardService::create_widget(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((StatusCode::CREATED, Json(result)))
 }
 
@@ -195,10 +209,13 @@ pub(crate) async fn get_widget(
 )]
 pub(crate) async fn update_widget(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_dashboard_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateWidgetInput>,
 ) -> Result<Json<entity::dashboard_widget::Model>, AppError> {
-    let result = DashboardService::update_widget(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = DashboardService::update_widget(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -212,9 +229,12 @@ pub(crate) async fn update_widget(
 )]
 pub(crate) async fn delete_widget(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_dashboard_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<StatusCode, AppError> {
-    DashboardService::delete_widget(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    DashboardService::delete_widget(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
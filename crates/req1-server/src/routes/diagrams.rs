@@ -3,11 +3,13 @@ use axum::{
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use req1_core::PaginatedResponse;
 use req1_core::auth::AuthUser;
+use req1_core::service::audit::AuditContext;
 use req1_core::service::diagram::{
     CreateDiagramInput, DiagramService, ListDiagramsFilter, UpdateDiagramInput,
 };
@@ -51,6 +53,7 @@ pub(crate) async fn list_diagrams(
 )]
 pub(crate) async fn create_diagram(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateDiagramInput>,
@@ -60,7 +63,9 @@ pub(crate) async fn create_diagram(
         created_by: Some(auth_user.id),
         ..body
     };
-    let result = DiagramService::create(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = DiagramService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -91,10 +96,13 @@ pub(crate) async fn get_diagram(
 )]
 pub(crate) async fn update_diagram(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateDiagramInput>,
 ) -> Result<Json<entity::diagram::Model>, AppError> {
-    let result = DiagramService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = DiagramService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -108,8 +116,11 @@ pub(crate) async fn update_diagram(
 )]
 pub(crate) async fn delete_diagram(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    DiagramService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    DiagramService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
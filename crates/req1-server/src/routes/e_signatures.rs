@@ -3,6 +3,7 @@ use axum::{
     extract::{Path, State},
     routing::{get, post},
 };
+use sea_orm::TransactionTrait;
 use serde::Deserialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
@@ -10,7 +11,9 @@ use uuid::Uuid;
 use crate::{error::AppError, state::AppState};
 use entity::e_signature;
 use req1_core::auth::AuthUser;
+use req1_core::service::audit::AuditContext;
 use req1_core::service::e_signature::{ESignatureService, SignInput};
+use req1_core::service::permission::{Permission, PermissionService, Scope};
 
 pub fn routes() -> Router<AppState> {
     Router::new()
@@ -21,6 +24,20 @@ pub fn routes() -> Router<AppState> {
         )
 }
 
+/// Scope a signature's entity belongs to, so signing and listing follow the entity's access.
+fn entity_scope(entity_type: &str, entity_id: Uuid) -> Result<Scope, AppError> {
+    match entity_type {
+        "object" => Ok(Scope::Object(entity_id)),
+        "review_package" => Ok(Scope::ReviewPackage(entity_id)),
+        "baseline" => Ok(Scope::Baseline(entity_id)),
+        "test_case" => Ok(Scope::TestCase(entity_id)),
+        "module" => Ok(Scope::Module(entity_id)),
+        other => Err(AppError::bad_request(format!(
+            "unsupported entity type '{other}'"
+        ))),
+    }
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub(crate) struct CreateSignatureRequest {
     entity_type: String,
@@ -36,22 +53,28 @@ pub(crate) struct CreateSignatureRequest {
 )]
 pub(crate) async fn create_signature(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateSignatureRequest>,
 ) -> Result<(axum::http::StatusCode, Json<e_signature::Model>), AppError> {
+    let scope = entity_scope(&body.entity_type, body.entity_id)?;
+    PermissionService::require(&state.db, &auth_user, scope, Permission::Write).await?;
     let input = SignInput {
         password: body.password,
         meaning: body.meaning,
         ip_address: None,
     };
+    let txn = state.db.begin().await?;
     let result = ESignatureService::sign(
-        &state.db,
+        &txn,
+        &audit,
         auth_user.id,
         &body.entity_type,
         body.entity_id,
         input,
     )
     .await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -66,8 +89,11 @@ pub(crate) async fn create_signature(
 )]
 pub(crate) async fn list_signatures(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path((entity_type, entity_id)): Path<(String, Uuid)>,
 ) -> Result<Json<Vec<e_signature::Model>>, AppError> {
+    let scope = entity_scope(&entity_type, entity_id)?;
+    PermissionService::require(&state.db, &auth_user, scope, Permission::Read).await?;
     let result = ESignatureService::list_for_entity(&state.db, &entity_type, entity_id).await?;
     Ok(Json(result))
 }
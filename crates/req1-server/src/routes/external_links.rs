@@ -0,0 +1,180 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    routing::{get, post},
+};
+use sea_orm::TransactionTrait;
+use uuid::Uuid;
+
+use crate::{error::AppError, state::AppState};
+use entity::external_link;
+use req1_core::external::ExternalCheckers;
+use req1_core::service::audit::AuditContext;
+use req1_core::{
+    PaginatedResponse,
+    auth::AuthUser,
+    service::{
+        external_link::{
+            CreateExternalLinkInput, ExternalCheckResult, ExternalLinkService,
+            ListExternalLinksFilter, UpdateExternalLinkInput,
+        },
+        permission::{Permission, PermissionService},
+    },
+};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/external-links",
+            get(list_external_links).post(create_external_link),
+        )
+        .route(
+            "/external-links/{id}",
+            get(get_external_link)
+                .patch(update_external_link)
+                .delete(delete_external_link),
+        )
+        .route("/external-links/{id}/check", post(check_external_link))
+        .route(
+            "/modules/{module_id}/external-links/check",
+            post(check_module_external_links),
+        )
+}
+
+fn checkers(state: &AppState) -> ExternalCheckers {
+    ExternalCheckers::local(&state.config.external_link_roots)
+}
+
+#[utoipa::path(get, path = "/api/v1/external-links", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(ListExternalLinksFilter),
+    responses((status = 200, body = PaginatedResponse<external_link::Model>))
+)]
+pub(crate) async fn list_external_links(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(filter): Query<ListExternalLinksFilter>,
+) -> Result<Json<PaginatedResponse<external_link::Model>>, AppError> {
+    let mut scoped = false;
+    if let Some(module_id) = filter.module_id {
+        PermissionService::require_module(&state.db, &auth_user, module_id, Permission::Read)
+            .await?;
+        scoped = true;
+    }
+    if let Some(object_id) = filter.source_object_id {
+        PermissionService::require_object(&state.db, &auth_user, object_id, Permission::Read)
+            .await?;
+        scoped = true;
+    }
+    if !scoped && auth_user.role != "admin" {
+        return Err(AppError::forbidden(
+            "listing external links requires a module_id or source_object_id filter",
+        ));
+    }
+    let result = ExternalLinkService::list(&state.db, filter).await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(post, path = "/api/v1/external-links", tag = "Links",
+    security(("bearer_auth" = [])),
+    request_body = CreateExternalLinkInput,
+    responses((status = 201, body = external_link::Model))
+)]
+pub(crate) async fn create_external_link(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(body): Json<CreateExternalLinkInput>,
+) -> Result<(axum::http::StatusCode, Json<external_link::Model>), AppError> {
+    PermissionService::require_object(
+        &state.db,
+        &auth_user,
+        body.source_object_id,
+        Permission::Write,
+    )
+    .await?;
+    let txn = state.db.begin().await?;
+    let result = ExternalLinkService::create(&txn, &audit, &checkers(&state), body).await?;
+    txn.commit().await?;
+    Ok((axum::http::StatusCode::CREATED, Json(result)))
+}
+
+#[utoipa::path(get, path = "/api/v1/external-links/{id}", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "External link ID")),
+    responses((status = 200, body = external_link::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn get_external_link(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<external_link::Model>, AppError> {
+    let result = ExternalLinkService::get(&state.db, id).await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(patch, path = "/api/v1/external-links/{id}", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "External link ID")),
+    request_body = UpdateExternalLinkInput,
+    responses((status = 200, body = external_link::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn update_external_link(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateExternalLinkInput>,
+) -> Result<Json<external_link::Model>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = ExternalLinkService::update(&txn, &audit, &checkers(&state), id, body).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(delete, path = "/api/v1/external-links/{id}", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "External link ID")),
+    responses((status = 204, description = "Deleted"), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn delete_external_link(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(id): Path<Uuid>,
+) -> Result<axum::http::StatusCode, AppError> {
+    let txn = state.db.begin().await?;
+    ExternalLinkService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(post, path = "/api/v1/external-links/{id}/check", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "External link ID")),
+    responses((status = 200, body = ExternalCheckResult), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn check_external_link(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ExternalCheckResult>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = ExternalLinkService::check(&txn, &audit, &checkers(&state), id).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(post, path = "/api/v1/modules/{module_id}/external-links/check", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    responses((status = 200, body = Vec<ExternalCheckResult>))
+)]
+pub(crate) async fn check_module_external_links(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(module_id): Path<Uuid>,
+) -> Result<Json<Vec<ExternalCheckResult>>, AppError> {
+    let txn = state.db.begin().await?;
+    let result =
+        ExternalLinkService::check_module(&txn, &audit, &checkers(&state), module_id).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
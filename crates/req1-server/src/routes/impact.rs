@@ -3,17 +3,20 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use req1_core::auth::AuthUser;
+use req1_core::baseline::Snapshot;
+use req1_core::service::permission::PermissionService;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
-use entity::{link, link_type, object};
+use entity::{baseline_link, link, link_type, object};
 
 pub fn routes() -> Router<AppState> {
     Router::new().route("/object-impact/{id}", get(get_impact))
@@ -23,6 +26,10 @@ pub fn routes() -> Router<AppState> {
 pub(crate) struct ImpactQuery {
     direction: Option<String>,
     max_depth: Option<u32>,
+    /// Traverse the links frozen in this baseline instead of live links
+    baseline_id: Option<Uuid>,
+    /// Traverse the links frozen in every baseline of this set
+    baseline_set_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -52,6 +59,39 @@ pub(crate) struct ImpactResponse {
     edges: Vec<ImpactEdge>,
 }
 
+/// Traversal view of a link, shared by live and baselined links.
+struct TraceLink {
+    source_object_id: Uuid,
+    target_object_id: Uuid,
+    link_type_id: Uuid,
+    suspect: bool,
+}
+
+impl From<link::Model> for TraceLink {
+    fn from(l: link::Model) -> Self {
+        Self {
+            source_object_id: l.source_object_id,
+            target_object_id: l.target_object_id,
+            link_type_id: l.link_type_id,
+            suspect: l.suspect,
+        }
+    }
+}
+
+impl From<baseline_link::Model> for TraceLink {
+    fn from(l: baseline_link::Model) -> Self {
+        Self {
+            source_object_id: l.source_object_id,
+            target_object_id: l.target_object_id,
+            link_type_id: l.link_type_id,
+            suspect: l.suspect,
+        }
+    }
+}
+
+/// `(heading, level, module_id)`
+type ObjectInfo = (Option<String>, String, Uuid);
+
 struct QueueEntry {
     object_id: Uuid,
     depth: u32,
@@ -59,7 +99,7 @@ struct QueueEntry {
 }
 
 /// Given a link and a node, return the neighbor in the given traversal direction.
-fn link_neighbor(lnk: &link::Model, node_id: Uuid, direction: &str) -> Option<Uuid> {
+fn link_neighbor(lnk: &TraceLink, node_id: Uuid, direction: &str) -> Option<Uuid> {
     match direction {
         "forward" | "both" if lnk.source_object_id == node_id => Some(lnk.target_object_id),
         "backward" | "both" if lnk.target_object_id == node_id => Some(lnk.source_object_id),
@@ -77,7 +117,7 @@ fn bfs_traverse(
     root_id: Uuid,
     direction: &str,
     max_depth: u32,
-    all_links: &[link::Model],
+    all_links: &[TraceLink],
 ) -> (BfsResult, BfsEdge) {
     let mut visited: HashSet<Uuid> = HashSet::new();
     let _ = visited.insert(root_id);
@@ -144,6 +184,7 @@ fn bfs_traverse(
 )]
 pub(crate) async fn get_impact(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(root_id): Path<Uuid>,
     Query(query): Query<ImpactQuery>,
 ) -> Result<Json<ImpactResponse>, AppError> {
@@ -157,26 +198,51 @@ pub(crate) async fn get_impact(
         )));
     }
 
-    // Verify root exists
-    let _root = object::Entity::find_by_id(root_id)
-        .one(&state.db)
-        .await?
-        .ok_or_else(|| AppError::not_found(format!("object {root_id} not found")))?;
+    let snapshot = Snapshot::resolve(&state.db, query.baseline_id, query.baseline_set_id).await?;
 
-    let all_links = link::Entity::find().all(&state.db).await?;
+    let (all_links, objects_map): (Vec<TraceLink>, HashMap<Uuid, ObjectInfo>) =
+        if let Some(snapshot) = &snapshot {
+            let objects_map: HashMap<Uuid, ObjectInfo> = snapshot
+                .all_objects(&state.db)
+                .await?
+                .into_iter()
+                .map(|o| (o.id, (o.heading, o.level, o.module_id)))
+                .collect();
+            if !objects_map.contains_key(&root_id) {
+                return Err(AppError::not_found(format!(
+                    "object {root_id} not found in baseline"
+                )));
+            }
+            let links = snapshot.links(&state.db).await?;
+            (
+                links.into_iter().map(TraceLink::from).collect(),
+                objects_map,
+            )
+        } else {
+            // Verify root exists
+            let _root = object::Entity::find_by_id(root_id)
+                .one(&state.db)
+                .await?
+                .ok_or_else(|| AppError::not_found(format!("object {root_id} not found")))?;
+            let links = link::Entity::find().all(&state.db).await?;
+            (
+                links.into_iter().map(TraceLink::from).collect(),
+                HashMap::new(),
+            )
+        };
     let (result_entries, edge_entries) = bfs_traverse(root_id, direction, max_depth, &all_links);
 
     // Load object details for all collected IDs
     let object_ids: Vec<Uuid> = result_entries.iter().map(|(id, _, _)| *id).collect();
-    let objects_map: HashMap<Uuid, object::Model> = if object_ids.is_empty() {
-        HashMap::new()
+    let objects_map: HashMap<Uuid, ObjectInfo> = if snapshot.is_some() || object_ids.is_empty() {
+        objects_map
     } else {
         object::Entity::find()
             .filter(object::Column::Id.is_in(object_ids))
             .all(&state.db)
             .await?
             .into_iter()
-            .map(|o| (o.id, o))
+            .map(|o| (o.id, (o.heading, o.level, o.module_id)))
             .collect()
     };
 
@@ -194,18 +260,28 @@ pub(crate) async fn get_impact(
             .collect()
     };
 
+    // Only report objects of modules the caller can read
+    let visible = PermissionService::visible_module_ids(&state.db, &auth_user).await?;
+    let readable = |id: &Uuid| {
+        *id == root_id
+            || objects_map
+                .get(id)
+                .is_some_and(|(_, _, m)| visible.as_ref().is_none_or(|v| v.contains(m)))
+    };
+
     // Build response
     let impact_objects: Vec<ImpactObject> = result_entries
         .into_iter()
+        .filter(|(id, _, _)| readable(id))
         .filter_map(|(id, depth, lt_id)| {
-            let obj = objects_map.get(&id)?;
+            let (heading, level, module_id) = objects_map.get(&id)?;
             Some(ImpactObject {
-                id: obj.id,
-                heading: obj.heading.clone(),
-                level: obj.level.clone(),
+                id,
+                heading: heading.clone(),
+                level: level.clone(),
                 depth,
                 link_type: lt_id.and_then(|lt| link_types_map.get(&lt).cloned()),
-                module_id: obj.module_id,
+                module_id: *module_id,
             })
         })
         .collect();
@@ -213,6 +289,7 @@ pub(crate) async fn get_impact(
     // Build edges
     let impact_edges: Vec<ImpactEdge> = edge_entries
         .into_iter()
+        .filter(|(src, tgt, _, _)| readable(src) && readable(tgt))
         .map(|(src, tgt, lt_id, suspect)| ImpactEdge {
             source_id: src,
             target_id: tgt,
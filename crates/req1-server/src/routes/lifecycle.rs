@@ -1,13 +1,15 @@
 use axum::{
-    Router,
+    Extension, Router,
     extract::{Path, State},
     routing::{delete, get, patch},
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
+use req1_core::service::audit::AuditContext;
 use req1_core::service::lifecycle::{
-    CreateLifecycleModelInput, LifecycleService, UpdateLifecycleModelInput,
+    CreateLifecycleModelInput, LifecycleService, StateTimelineEntry, UpdateLifecycleModelInput,
 };
 
 pub fn routes() -> Router<AppState> {
@@ -22,6 +24,11 @@ pub fn routes() -> Router<AppState> {
                 .merge(patch(update_lifecycle_model))
                 .merge(delete(delete_lifecycle_model)),
         )
+        .route("/modules/{module_id}/timeline", get(module_timeline))
+        .route(
+            "/modules/{module_id}/objects/{id}/timeline",
+            get(object_timeline),
+        )
 }
 
 #[utoipa::path(get, path = "/api/v1/modules/{module_id}/lifecycle-models", tag = "Lifecycle",
@@ -45,10 +52,13 @@ pub(crate) async fn list_lifecycle_models(
 )]
 pub(crate) async fn create_lifecycle_model(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     axum::Json(input): axum::Json<CreateLifecycleModelInput>,
 ) -> Result<axum::Json<entity::lifecycle_model::Model>, AppError> {
-    let model = LifecycleService::create(&state.db, module_id, input).await?;
+    let txn = state.db.begin().await?;
+    let model = LifecycleService::create(&txn, &audit, module_id, input).await?;
+    txn.commit().await?;
     Ok(axum::Json(model))
 }
 
@@ -85,10 +95,13 @@ pub(crate) async fn get_lifecycle_model(
 )]
 pub(crate) async fn update_lifecycle_model(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     axum::Json(input): axum::Json<UpdateLifecycleModelInput>,
 ) -> Result<axum::Json<entity::lifecycle_model::Model>, AppError> {
-    let model = LifecycleService::update(&state.db, id, input).await?;
+    let txn = state.db.begin().await?;
+    let model = LifecycleService::update(&txn, &audit, id, input).await?;
+    txn.commit().await?;
     Ok(axum::Json(model))
 }
 
@@ -103,8 +116,40 @@ pub(crate) async fn update_lifecycle_model(
 )]
 pub(crate) async fn delete_lifecycle_model(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::Json<()>, AppError> {
-    LifecycleService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    LifecycleService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::Json(()))
 }
+
+#[utoipa::path(get, path = "/api/v1/modules/{module_id}/timeline", tag = "Lifecycle",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    responses((status = 200, body = Vec<StateTimelineEntry>))
+)]
+pub(crate) async fn module_timeline(
+    State(state): State<AppState>,
+    Path(module_id): Path<Uuid>,
+) -> Result<axum::Json<Vec<StateTimelineEntry>>, AppError> {
+    let items = LifecycleService::timeline(&state.db, module_id, None).await?;
+    Ok(axum::Json(items))
+}
+
+#[utoipa::path(get, path = "/api/v1/modules/{module_id}/objects/{id}/timeline", tag = "Lifecycle",
+    security(("bearer_auth" = [])),
+    params(
+        ("module_id" = Uuid, Path, description = "Module ID"),
+        ("id" = Uuid, Path, description = "Object ID"),
+    ),
+    responses((status = 200, body = Vec<StateTimelineEntry>))
+)]
+pub(crate) async fn object_timeline(
+    State(state): State<AppState>,
+    Path((module_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<axum::Json<Vec<StateTimelineEntry>>, AppError> {
+    let items = LifecycleService::timeline(&state.db, module_id, Some(id)).await?;
+    Ok(axum::Json(items))
+}
@@ -1,16 +1,26 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
-use entity::{link, link_type};
+use entity::{link, link_history, link_type};
+use req1_core::service::audit::AuditContext;
 use req1_core::{
-    PaginatedResponse,
-    service::link::{
-        CreateLinkInput, CreateLinkTypeInput, LinkService, ListLinksFilter, UpdateLinkInput,
+    PaginatedResponse, Pagination,
+    auth::AuthUser,
+    service::{
+        link::{
+            CreateLinkInput, CreateLinkTypeInput, LinkService, ListLinksFilter, UpdateLinkInput,
+            UpdateLinkTypeInput,
+        },
+        permission::{Permission, PermissionService, Scope},
+        suspect_queue::{
+            ResolveSuspectsInput, SuspectLinkEntry, SuspectQueueFilter, SuspectQueueService,
+        },
     },
 };
 
@@ -21,7 +31,14 @@ pub fn routes() -> Router<AppState> {
             "/links/{id}",
             get(get_link).patch(update_link).delete(delete_link),
         )
+        .route("/links/{id}/history", get(list_link_history))
         .route("/link-types", get(list_link_types).post(create_link_type))
+        .route("/link-types/{id}", axum::routing::patch(update_link_type))
+        .route("/suspect-links", get(list_suspect_links))
+        .route(
+            "/suspect-links/resolve",
+            axum::routing::post(resolve_suspect_links),
+        )
 }
 
 #[utoipa::path(get, path = "/api/v1/links", tag = "Links",
@@ -31,8 +48,28 @@ pub fn routes() -> Router<AppState> {
 )]
 pub(crate) async fn list_links(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(filter): Query<ListLinksFilter>,
 ) -> Result<Json<PaginatedResponse<link::Model>>, AppError> {
+    let mut scoped = false;
+    if let Some(module_id) = filter.module_id {
+        PermissionService::require_module(&state.db, &auth_user, module_id, Permission::Read)
+            .await?;
+        scoped = true;
+    }
+    for object_id in [filter.source_object_id, filter.target_object_id]
+        .into_iter()
+        .flatten()
+    {
+        PermissionService::require_object(&state.db, &auth_user, object_id, Permission::Read)
+            .await?;
+        scoped = true;
+    }
+    if !scoped && auth_user.role != "admin" {
+        return Err(AppError::forbidden(
+            "listing links requires a module_id, source_object_id or target_object_id filter",
+        ));
+    }
     let result = LinkService::list(&state.db, filter).await?;
     Ok(Json(result))
 }
@@ -44,9 +81,27 @@ pub(crate) async fn list_links(
 )]
 pub(crate) async fn create_link(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateLinkInput>,
 ) -> Result<(axum::http::StatusCode, Json<link::Model>), AppError> {
-    let result = LinkService::create(&state.db, body).await?;
+    PermissionService::require_object(
+        &state.db,
+        &auth_user,
+        body.source_object_id,
+        Permission::Write,
+    )
+    .await?;
+    PermissionService::require_object(
+        &state.db,
+        &auth_user,
+        body.target_object_id,
+        Permission::Read,
+    )
+    .await?;
+    let txn = state.db.begin().await?;
+    let result = LinkService::create(&txn, &audit, body).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -71,10 +126,13 @@ pub(crate) async fn get_link(
 )]
 pub(crate) async fn update_link(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
     Json(body): Json<UpdateLinkInput>,
 ) -> Result<Json<link::Model>, AppError> {
-    let result = LinkService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = LinkService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -85,12 +143,36 @@ pub(crate) async fn update_link(
 )]
 pub(crate) async fn delete_link(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    LinkService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    LinkService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(get, path = "/api/v1/links/{id}/history", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Link ID"), Pagination),
+    responses((status = 200, body = PaginatedResponse<link_history::Model>))
+)]
+pub(crate) async fn list_link_history(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<PaginatedResponse<link_history::Model>>, AppError> {
+    let result = LinkService::history(&state.db, id, pagination.offset, pagination.limit).await?;
+    // A deleted link is no longer a permission scope; check its recorded source instead
+    if LinkService::get(&state.db, id).await.is_err()
+        && let Some(source) = result.items.first().and_then(|h| h.source_object_id)
+    {
+        PermissionService::require_object(&state.db, &auth_user, source, Permission::Read).await?;
+    }
+    Ok(Json(result))
+}
+
 #[utoipa::path(get, path = "/api/v1/link-types", tag = "Links",
     security(("bearer_auth" = [])),
     responses((status = 200, body = Vec<link_type::Model>))
@@ -109,8 +191,65 @@ pub(crate) async fn list_link_types(
 )]
 pub(crate) async fn create_link_type(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Json(body): Json<CreateLinkTypeInput>,
 ) -> Result<(axum::http::StatusCode, Json<link_type::Model>), AppError> {
-    let result = LinkService::create_link_type(&state.db, body).await?;
+    let txn = state.db.begin().await?;
+    let result = LinkService::create_link_type(&txn, &audit, body).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
+
+#[utoipa::path(patch, path = "/api/v1/link-types/{id}", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Link type ID")),
+    request_body = UpdateLinkTypeInput,
+    responses((status = 200, body = link_type::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn update_link_type(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateLinkTypeInput>,
+) -> Result<Json<link_type::Model>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = LinkService::update_link_type(&txn, &audit, id, body).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(get, path = "/api/v1/suspect-links", tag = "Links",
+    security(("bearer_auth" = [])),
+    params(SuspectQueueFilter),
+    responses((status = 200, body = PaginatedResponse<SuspectLinkEntry>))
+)]
+pub(crate) async fn list_suspect_links(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(filter): Query<SuspectQueueFilter>,
+) -> Result<Json<PaginatedResponse<SuspectLinkEntry>>, AppError> {
+    let visible = PermissionService::visible_module_ids(&state.db, &auth_user).await?;
+    let result = SuspectQueueService::list(&state.db, filter, visible).await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(post, path = "/api/v1/suspect-links/resolve", tag = "Links",
+    security(("bearer_auth" = [])),
+    request_body = ResolveSuspectsInput,
+    responses((status = 200, body = Vec<link::Model>), (status = 400, description = "Missing justification or link not suspect"))
+)]
+pub(crate) async fn resolve_suspect_links(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(body): Json<ResolveSuspectsInput>,
+) -> Result<Json<Vec<link::Model>>, AppError> {
+    for id in &body.link_ids {
+        PermissionService::require(&state.db, &auth_user, Scope::Link(*id), Permission::Write)
+            .await?;
+    }
+    let txn = state.db.begin().await?;
+    let result = SuspectQueueService::resolve(&txn, &audit, body).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
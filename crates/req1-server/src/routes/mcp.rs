@@ -0,0 +1,472 @@
+//! Model Context Protocol endpoint (JSON-RPC over streamable HTTP).
+//!
+//! Every tool call is replayed against the REST router with the caller's own
+//! credentials, so assistants go through exactly the authentication and
+//! permission checks of the REST API. Edits to existing requirements are never
+//! applied directly: they become change proposals for a human to approve. There
+//! is no tool to create objects.
+
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+
+use axum::{
+    Extension, Json, Router,
+    body::Body,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, Method, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use sea_orm::EntityTrait;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use entity::object;
+use req1_core::auth::AuthUser;
+
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/mcp", post(handle_mcp).get(sse_not_supported))
+}
+
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Caller identity forwarded into replayed REST requests.
+struct Caller {
+    user: AuthUser,
+    headers: HeaderMap,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+}
+
+#[utoipa::path(post, path = "/api/v1/mcp", tag = "MCP",
+    security(("bearer_auth" = [])),
+    request_body(content = Object, description = "JSON-RPC 2.0 request, notification or batch"),
+    responses(
+        (status = 200, body = Object, description = "JSON-RPC response"),
+        (status = 202, description = "Notification accepted"),
+    )
+)]
+pub(crate) async fn handle_mcp(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let caller = Caller {
+        user,
+        headers,
+        connect_info: connect_info.map(|Extension(c)| c),
+    };
+    let Ok(message) = serde_json::from_slice::<Value>(&body) else {
+        return Json(error_response(Value::Null, PARSE_ERROR, "invalid JSON")).into_response();
+    };
+
+    let response = match message {
+        Value::Array(batch) => {
+            let mut responses = Vec::new();
+            for message in batch {
+                responses.extend(handle_message(&state, &caller, message).await);
+            }
+            (!responses.is_empty()).then(|| Value::Array(responses))
+        }
+        message => handle_message(&state, &caller, message).await,
+    };
+    response.map_or_else(
+        || StatusCode::ACCEPTED.into_response(),
+        |r| Json(r).into_response(),
+    )
+}
+
+/// Server-initiated streams are not offered; clients fall back to plain POST.
+pub(crate) async fn sse_not_supported() -> StatusCode {
+    StatusCode::METHOD_NOT_ALLOWED
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+/// Handle one JSON-RPC message; notifications yield no response.
+async fn handle_message(state: &AppState, caller: &Caller, message: Value) -> Option<Value> {
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            message.get("id").cloned().unwrap_or_default(),
+            INVALID_REQUEST,
+            "missing method",
+        ));
+    };
+    let id = message.get("id").cloned()?;
+    let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(state, caller, &params).await,
+        _ => Err(RpcError {
+            code: METHOD_NOT_FOUND,
+            message: format!("method '{method}' not found"),
+        }),
+    };
+    Some(match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(e) => error_response(id, e.code, &e.message),
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "req1", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Tools read the live requirements database with your permissions. \
+            Edits are submitted as change proposals for review, never applied directly.",
+    })
+}
+
+fn uuid_param() -> Value {
+    json!({ "type": "string", "format": "uuid" })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "list_modules",
+            "description": "List the modules you can read, optionally within one project.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "project_id": uuid_param() },
+            },
+        },
+        {
+            "name": "search_requirements",
+            "description": "Full-text search over requirement headings and bodies, across all readable modules or within one.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "module_id": uuid_param(),
+                    "limit": { "type": "integer", "minimum": 1, "maximum": 200 },
+                },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "get_object",
+            "description": "Fetch one requirement object with its attributes, classification and review state.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "object_id": uuid_param() },
+                "required": ["object_id"],
+            },
+        },
+        {
+            "name": "list_links",
+            "description": "List trace links of an object (both directions) or of a module.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "object_id": uuid_param(), "module_id": uuid_param() },
+            },
+        },
+        {
+            "name": "impact_analysis",
+            "description": "Objects reachable from an object over trace links.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "object_id": uuid_param(),
+                    "direction": { "type": "string", "enum": ["forward", "backward", "both"] },
+                    "max_depth": { "type": "integer", "minimum": 1, "maximum": 20 },
+                },
+                "required": ["object_id"],
+            },
+        },
+        {
+            "name": "validate_module",
+            "description": "Run the module's validation rules and report issues.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "module_id": uuid_param() },
+                "required": ["module_id"],
+            },
+        },
+        {
+            "name": "propose_change",
+            "description": "Propose an edit to a requirement. Creates a draft change proposal in the object's module for a reviewer to approve and apply; the object itself is not modified.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "object_id": uuid_param(),
+                    "heading": { "type": "string" },
+                    "body": { "type": "string" },
+                    "classification": { "type": "string" },
+                    "title": { "type": "string", "description": "Proposal title" },
+                    "rationale": { "type": "string", "description": "Why the change is needed" },
+                },
+                "required": ["object_id"],
+            },
+        },
+    ])
+}
+
+/// Outcome of a replayed REST call: the JSON body, or the API's error message.
+type ApiResult = Result<Value, String>;
+
+async fn call_tool(state: &AppState, caller: &Caller, params: &Value) -> Result<Value, RpcError> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::invalid_params("missing tool name"))?;
+    let args = params
+        .get("arguments")
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    let uuid_arg = |key: &str| -> Result<Option<Uuid>, RpcError> {
+        args.get(key)
+            .and_then(Value::as_str)
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| RpcError::invalid_params(format!("'{key}' must be a UUID")))
+            })
+            .transpose()
+    };
+    let required = |key: &str| -> Result<Uuid, RpcError> {
+        uuid_arg(key)?.ok_or_else(|| RpcError::invalid_params(format!("'{key}' is required")))
+    };
+
+    let outcome = match name {
+        "list_modules" => {
+            let mut uri = "/modules?limit=500".to_owned();
+            if let Some(project_id) = uuid_arg("project_id")? {
+                let _ = write!(uri, "&project_id={project_id}");
+            }
+            api(state, caller, Method::GET, &uri, None).await
+        }
+        "search_requirements" => {
+            let query = args
+                .get("query")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::invalid_params("'query' is required"))?;
+            let limit = args.get("limit").and_then(Value::as_u64).unwrap_or(20);
+            let uri = match uuid_arg("module_id")? {
+                Some(module_id) => format!(
+                    "/modules/{module_id}/objects?search={}&limit={limit}",
+                    encode(query)
+                ),
+                None => format!("/search?q={}&limit={limit}", encode(query)),
+            };
+            api(state, caller, Method::GET, &uri, None).await
+        }
+        "get_object" => get_object(state, caller, required("object_id")?).await,
+        "list_links" => {
+            if let Some(object_id) = uuid_arg("object_id")? {
+                let outgoing = format!("/links?source_object_id={object_id}&limit=500");
+                let incoming = format!("/links?target_object_id={object_id}&limit=500");
+                match (
+                    api(state, caller, Method::GET, &outgoing, None).await,
+                    api(state, caller, Method::GET, &incoming, None).await,
+                ) {
+                    (Ok(out), Ok(inc)) => Ok(json!({
+                        "outgoing": out["items"],
+                        "incoming": inc["items"],
+                    })),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
+                }
+            } else if let Some(module_id) = uuid_arg("module_id")? {
+                let uri = format!("/links?module_id={module_id}&limit=500");
+                api(state, caller, Method::GET, &uri, None).await
+            } else {
+                return Err(RpcError::invalid_params(
+                    "either 'object_id' or 'module_id' is required",
+                ));
+            }
+        }
+        "impact_analysis" => {
+            let object_id = required("object_id")?;
+            let mut uri = format!("/object-impact/{object_id}?");
+            if let Some(direction) = args.get("direction").and_then(Value::as_str) {
+                let _ = write!(uri, "direction={}&", encode(direction));
+            }
+            if let Some(depth) = args.get("max_depth").and_then(Value::as_u64) {
+                let _ = write!(uri, "max_depth={depth}");
+            }
+            api(state, caller, Method::GET, &uri, None).await
+        }
+        "validate_module" => {
+            let uri = format!("/modules/{}/validate", required("module_id")?);
+            api(state, caller, Method::GET, &uri, None).await
+        }
+        "propose_change" => propose_change(state, caller, required("object_id")?, &args).await,
+        _ => {
+            return Err(RpcError::invalid_params(format!("unknown tool '{name}'")));
+        }
+    };
+
+    Ok(match outcome {
+        Ok(value) => json!({
+            "content": [{
+                "type": "text",
+                "text": serde_json::to_string_pretty(&value).unwrap_or_default(),
+            }],
+            "isError": false,
+        }),
+        Err(message) => json!({
+            "content": [{ "type": "text", "text": message }],
+            "isError": true,
+        }),
+    })
+}
+
+async fn get_object(state: &AppState, caller: &Caller, object_id: Uuid) -> ApiResult {
+    // Objects are served under their module; resolve it, then let the REST route
+    // decide whether the caller may read it.
+    let module_id = object::Entity::find_by_id(object_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|o| o.module_id)
+        .ok_or_else(|| format!("object {object_id} not found"))?;
+    let uri = format!("/modules/{module_id}/objects/{object_id}");
+    api(state, caller, Method::GET, &uri, None).await
+}
+
+async fn propose_change(
+    state: &AppState,
+    caller: &Caller,
+    object_id: Uuid,
+    args: &Value,
+) -> ApiResult {
+    let current = get_object(state, caller, object_id).await?;
+    let diff: Vec<Value> = ["heading", "body", "classification"]
+        .into_iter()
+        .filter_map(|field| {
+            let proposed = args.get(field)?.as_str()?;
+            let old = current[field].as_str().unwrap_or_default();
+            (proposed != old).then(|| {
+                json!({
+                    "object_id": object_id,
+                    "field": field,
+                    "old_value": old,
+                    "new_value": proposed,
+                })
+            })
+        })
+        .collect();
+    if diff.is_empty() {
+        return Err("no changes proposed: heading, body and classification match".to_owned());
+    }
+
+    let title = args.get("title").and_then(Value::as_str).map_or_else(
+        || {
+            format!(
+                "Change {} {}",
+                current["level"].as_str().unwrap_or_default(),
+                current["heading"].as_str().unwrap_or_default()
+            )
+            .trim_end()
+            .to_owned()
+        },
+        str::to_owned,
+    );
+    let uri = format!(
+        "/modules/{}/change-proposals",
+        current["module_id"].as_str().unwrap_or_default()
+    );
+    let body = json!({
+        "title": title,
+        "description": args.get("rationale"),
+        "author_id": caller.user.id,
+        "diff_data": diff,
+    });
+    api(state, caller, Method::POST, &uri, Some(body)).await
+}
+
+/// Replay a request against the REST API as the caller.
+async fn api(
+    state: &AppState,
+    caller: &Caller,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> ApiResult {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(format!("/api/v1{uri}"));
+    for name in [header::AUTHORIZATION.as_str(), "x-forwarded-for"] {
+        if let Some(value) = caller.headers.get(name) {
+            builder = builder.header(name, value);
+        }
+    }
+    let body = match body {
+        Some(json) => {
+            builder = builder.header(header::CONTENT_TYPE, "application/json");
+            Body::from(json.to_string())
+        }
+        None => Body::empty(),
+    };
+    let mut request = builder.body(body).map_err(|e| e.to_string())?;
+    if let Some(connect_info) = caller.connect_info {
+        let _ = request.extensions_mut().insert(connect_info);
+    }
+
+    let router = state
+        .api
+        .get_or_init(|| super::router(state.clone()))
+        .clone();
+    let response = match router.oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .map_err(|e| e.to_string())?;
+    let value: Value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    if status.is_success() {
+        Ok(value)
+    } else {
+        let message = value["error"]["message"]
+            .as_str()
+            .unwrap_or_else(|| status.canonical_reason().unwrap_or("request failed"));
+        Err(format!("{}: {message}", status.as_u16()))
+    }
+}
+
+/// Percent-encode a query parameter value.
+fn encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+    out
+}
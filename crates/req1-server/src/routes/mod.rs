@@ -16,14 +16,18 @@ pub mod comments;
 pub mod dashboards;
 pub mod diagrams;
 pub mod e_signatures;
+pub mod external_links;
 pub mod health;
 pub mod impact;
 pub mod lifecycle;
 pub mod links;
+pub mod mcp;
+pub mod module_permissions;
 pub mod modules;
 pub mod notifications;
 pub mod object_types;
 pub mod objects;
+pub mod oslc;
 pub mod project_templates;
 pub mod projects;
 pub mod publish;
@@ -31,18 +35,23 @@ pub mod reqif;
 pub mod review_assignments;
 pub mod review_comments;
 pub mod review_packages;
+pub mod script_libraries;
 pub mod scripts;
+pub mod sysml;
 pub mod tests;
 pub mod traceability;
 pub mod validation;
 pub mod views;
 pub mod webhooks;
+pub mod workspace_members;
 pub mod workspaces;
 
 pub fn router(state: AppState) -> Router {
     let public = Router::new()
         .merge(health::routes())
         .nest("/api/v1", auth::public_routes())
+        .nest("/api/v1", oslc::public_routes())
+        .route_layer(from_fn_with_state(state.clone(), middleware::audit_context))
         .with_state(state.clone());
 
     let protected = Router::new()
@@ -51,10 +60,14 @@ pub fn router(state: AppState) -> Router {
         .nest("/api/v1", workspaces::routes())
         .nest("/api/v1", projects::routes())
         .nest("/api/v1", links::routes())
+        .nest("/api/v1", external_links::routes())
+        .nest("/api/v1", oslc::routes())
+        .nest("/api/v1", mcp::routes())
         .nest("/api/v1", baselines::routes())
         .nest("/api/v1", attribute_definitions::routes())
         .nest("/api/v1", traceability::routes())
         .nest("/api/v1", scripts::routes())
+        .nest("/api/v1", script_libraries::routes())
         .nest("/api/v1", publish::routes())
         .nest("/api/v1", validation::routes())
         .nest("/api/v1", views::routes())
@@ -71,6 +84,7 @@ pub fn router(state: AppState) -> Router {
         .nest("/api/v1", webhooks::routes())
         .nest("/api/v1", lifecycle::routes())
         .nest("/api/v1", reqif::routes())
+        .nest("/api/v1", sysml::routes())
         .nest("/api/v1", tests::routes())
         .nest("/api/v1", auth::protected_routes())
         .nest("/api/v1", audit::routes())
@@ -79,7 +93,14 @@ pub fn router(state: AppState) -> Router {
         .nest("/api/v1", e_signatures::routes())
         .nest("/api/v1", dashboards::routes())
         .nest("/api/v1", project_templates::routes())
+        .nest("/api/v1", workspace_members::routes())
+        .nest("/api/v1", module_permissions::routes())
+        .route_layer(from_fn_with_state(state.clone(), middleware::authorize))
         .route_layer(from_fn_with_state(state.clone(), middleware::require_auth))
+        .route_layer(from_fn_with_state(
+            state.clone(),
+            middleware::record_script_failures,
+        ))
         .with_state(state);
 
     public.merge(protected)
@@ -0,0 +1,125 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    routing::get,
+};
+use sea_orm::TransactionTrait;
+use uuid::Uuid;
+
+use crate::{error::AppError, state::AppState};
+use entity::module_permission;
+use req1_core::service::audit::AuditContext;
+use req1_core::{
+    PaginatedResponse, Pagination,
+    service::module_permission::{
+        CreateModulePermissionInput, ModulePermissionService, UpdateModulePermissionInput,
+    },
+};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/modules/{module_id}/permissions",
+            get(list_module_permissions).post(create_module_permission),
+        )
+        .route(
+            "/modules/{module_id}/permissions/{id}",
+            get(get_module_permission)
+                .patch(update_module_permission)
+                .delete(delete_module_permission),
+        )
+}
+
+#[utoipa::path(get, path = "/api/v1/modules/{module_id}/permissions", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("module_id" = Uuid, Path, description = "Module ID"),
+        Pagination,
+    ),
+    responses((status = 200, body = PaginatedResponse<module_permission::Model>))
+)]
+pub(crate) async fn list_module_permissions(
+    State(state): State<AppState>,
+    Path(module_id): Path<Uuid>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<PaginatedResponse<module_permission::Model>>, AppError> {
+    let result =
+        ModulePermissionService::list(&state.db, module_id, pagination.offset, pagination.limit)
+            .await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(post, path = "/api/v1/modules/{module_id}/permissions", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    request_body = CreateModulePermissionInput,
+    responses((status = 201, body = module_permission::Model), (status = 409, description = "Already granted"))
+)]
+pub(crate) async fn create_module_permission(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(module_id): Path<Uuid>,
+    Json(body): Json<CreateModulePermissionInput>,
+) -> Result<(axum::http::StatusCode, Json<module_permission::Model>), AppError> {
+    let input = CreateModulePermissionInput { module_id, ..body };
+    let txn = state.db.begin().await?;
+    let result = ModulePermissionService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
+    Ok((axum::http::StatusCode::CREATED, Json(result)))
+}
+
+#[utoipa::path(get, path = "/api/v1/modules/{module_id}/permissions/{id}", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("module_id" = Uuid, Path, description = "Module ID"),
+        ("id" = Uuid, Path, description = "Permission grant ID"),
+    ),
+    responses((status = 200, body = module_permission::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn get_module_permission(
+    State(state): State<AppState>,
+    Path((_module_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<module_permission::Model>, AppError> {
+    let result = ModulePermissionService::get(&state.db, id).await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(patch, path = "/api/v1/modules/{module_id}/permissions/{id}", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("module_id" = Uuid, Path, description = "Module ID"),
+        ("id" = Uuid, Path, description = "Permission grant ID"),
+    ),
+    request_body = UpdateModulePermissionInput,
+    responses((status = 200, body = module_permission::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn update_module_permission(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path((_module_id, id)): Path<(Uuid, Uuid)>,
+    Json(body): Json<UpdateModulePermissionInput>,
+) -> Result<Json<module_permission::Model>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = ModulePermissionService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(delete, path = "/api/v1/modules/{module_id}/permissions/{id}", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("module_id" = Uuid, Path, description = "Module ID"),
+        ("id" = Uuid, Path, description = "Permission grant ID"),
+    ),
+    responses((status = 204, description = "Revoked"), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn delete_module_permission(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path((_module_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<axum::http::StatusCode, AppError> {
+    let txn = state.db.begin().await?;
+    ModulePermissionService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
@@ -1,17 +1,23 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::module;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse,
-    service::module::{
-        CreateModuleFromTemplateInput, CreateModuleInput, ListModulesFilter, ModuleService,
-        UpdateModuleInput,
+    auth::AuthUser,
+    service::{
+        module::{
+            CreateModuleFromTemplateInput, CreateModuleInput, ListModulesFilter, ModuleService,
+            UpdateModuleInput,
+        },
+        permission::{Permission, PermissionService},
     },
 };
 
@@ -35,9 +41,11 @@ pub fn routes() -> Router<AppState> {
 )]
 pub(crate) async fn list_modules(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(filter): Query<ListModulesFilter>,
 ) -> Result<Json<PaginatedResponse<module::Model>>, AppError> {
-    let result = ModuleService::list(&state.db, filter).await?;
+    let visible = PermissionService::visible_module_ids(&state.db, &auth_user).await?;
+    let result = ModuleService::list(&state.db, filter, visible).await?;
     Ok(Json(result))
 }
 
@@ -48,9 +56,15 @@ pub(crate) async fn list_modules(
 )]
 pub(crate) async fn create_module(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateModuleInput>,
 ) -> Result<(axum::http::StatusCode, Json<module::Model>), AppError> {
-    let result = ModuleService::create(&state.db, body).await?;
+    PermissionService::require_project(&state.db, &auth_user, body.project_id, Permission::Write)
+        .await?;
+    let txn = state.db.begin().await?;
+    let result = ModuleService::create(&txn, &audit, body).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -75,10 +89,13 @@ pub(crate) async fn get_module(
 )]
 pub(crate) async fn update_module(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
     Json(body): Json<UpdateModuleInput>,
 ) -> Result<Json<module::Model>, AppError> {
-    let result = ModuleService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ModuleService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -89,9 +106,12 @@ pub(crate) async fn update_module(
 )]
 pub(crate) async fn delete_module(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    ModuleService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ModuleService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
@@ -102,8 +122,21 @@ pub(crate) async fn delete_module(
 )]
 pub(crate) async fn create_module_from_template(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateModuleFromTemplateInput>,
 ) -> Result<(axum::http::StatusCode, Json<module::Model>), AppError> {
-    let result = ModuleService::create_from_template(&state.db, body).await?;
+    PermissionService::require_project(&state.db, &auth_user, body.project_id, Permission::Write)
+        .await?;
+    PermissionService::require_module(
+        &state.db,
+        &auth_user,
+        body.template_module_id,
+        Permission::Read,
+    )
+    .await?;
+    let txn = state.db.begin().await?;
+    let result = ModuleService::create_from_template(&txn, &audit, body).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
@@ -1,15 +1,19 @@
 #![allow(unused_qualifications)]
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use utoipa::IntoParams;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::object_type;
+use req1_core::auth::AuthUser;
+use req1_core::service::audit::AuditContext;
+use req1_core::service::permission::{Permission, PermissionService};
 use req1_core::{
     PaginatedResponse,
     service::object_type::{CreateObjectTypeInput, ObjectTypeService, UpdateObjectTypeInput},
@@ -49,8 +53,11 @@ const fn default_limit() -> u64 {
 )]
 pub(crate) async fn list_object_types(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(query): Query<ListObjectTypesQuery>,
 ) -> Result<Json<PaginatedResponse<object_type::Model>>, AppError> {
+    PermissionService::require_module(&state.db, &auth_user, query.module_id, Permission::Read)
+        .await?;
     let result =
         ObjectTypeService::list(&state.db, query.module_id, query.offset, query.limit).await?;
     Ok(Json(result))
@@ -63,9 +70,15 @@ pub(crate) async fn list_object_types(
 )]
 pub(crate) async fn create_object_type(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateObjectTypeInput>,
 ) -> Result<(axum::http::StatusCode, Json<object_type::Model>), AppError> {
-    let result = ObjectTypeService::create(&state.db, body).await?;
+    PermissionService::require_module(&state.db, &auth_user, body.module_id, Permission::Write)
+        .await?;
+    let txn = state.db.begin().await?;
+    let result = ObjectTypeService::create(&txn, &audit, body).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -90,10 +103,13 @@ pub(crate) async fn get_object_type(
 )]
 pub(crate) async fn update_object_type(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
     Json(body): Json<UpdateObjectTypeInput>,
 ) -> Result<Json<object_type::Model>, AppError> {
-    let result = ObjectTypeService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ObjectTypeService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -104,8 +120,11 @@ pub(crate) async fn update_object_type(
 )]
 pub(crate) async fn delete_object_type(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    ObjectTypeService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ObjectTypeService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
@@ -8,16 +8,20 @@ use sea_orm::{
     ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait,
 };
 use serde::Deserialize;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::object_history;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
-    service::object::{
-        CreateObjectInput, GlobalSearchResult, ListObjectsFilter, MoveObjectInput, ObjectService,
-        UpdateObjectInput,
+    service::{
+        object::{
+            CreateObjectInput, GlobalSearchResult, ListObjectsFilter, MoveObjectInput,
+            ObjectService, UpdateObjectInput,
+        },
+        permission::PermissionService,
     },
 };
 
@@ -76,13 +80,13 @@ pub(crate) async fn list_objects(
 )]
 pub(crate) async fn create_object(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
-    Extension(_auth_user): Extension<AuthUser>,
     Json(body): Json<CreateObjectInput>,
 ) -> Result<(axum::http::StatusCode, Json<entity::object::Model>), AppError> {
     let txn = state.db.begin().await?;
     let input = CreateObjectInput { module_id, ..body };
-    let result = ObjectService::create(&txn, input).await?;
+    let result = ObjectService::create(&txn, &audit, input).await?;
     txn.commit().await?;
 
     Ok((axum::http::StatusCode::CREATED, Json(result)))
@@ -115,11 +119,12 @@ pub(crate) async fn get_object(
 )]
 pub(crate) async fn update_object(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateObjectInput>,
 ) -> Result<Json<entity::object::Model>, AppError> {
     let txn = state.db.begin().await?;
-    let _ = ObjectService::update(&txn, id, body).await?;
+    let _ = ObjectService::update(&txn, &audit, id, body).await?;
     txn.commit().await?;
 
     let result = ObjectService::get(&state.db, id).await?;
@@ -136,10 +141,11 @@ pub(crate) async fn update_object(
 )]
 pub(crate) async fn delete_object(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
     let txn = state.db.begin().await?;
-    ObjectService::delete(&txn, id).await?;
+    ObjectService::delete(&txn, &audit, id).await?;
     txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
@@ -155,21 +161,33 @@ pub(crate) async fn delete_object(
 )]
 pub(crate) async fn move_object(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<MoveObjectInput>,
 ) -> Result<Json<entity::object::Model>, AppError> {
     let txn = state.db.begin().await?;
-    let result = ObjectService::move_object(&txn, module_id, id, body).await?;
+    let result = ObjectService::move_object(&txn, &audit, module_id, id, body).await?;
     txn.commit().await?;
     Ok(Json(result))
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub(crate) struct HistoryFilter {
+    /// User id, or `script:<name>` for script-originated changes
+    actor: Option<String>,
+    #[param(value_type = Option<String>)]
+    changed_after: Option<chrono::DateTime<chrono::FixedOffset>>,
+    #[param(value_type = Option<String>)]
+    changed_before: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
 #[utoipa::path(get, path = "/api/v1/modules/{module_id}/objects/{id}/history", tag = "Objects",
     security(("bearer_auth" = [])),
     params(
         ("module_id" = Uuid, Path, description = "Module ID"),
         ("id" = Uuid, Path, description = "Object ID"),
         Pagination,
+        HistoryFilter,
     ),
     responses((status = 200, body = PaginatedResponse<object_history::Model>))
 )]
@@ -177,9 +195,19 @@ pub(crate) async fn list_object_history(
     State(state): State<AppState>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Query(pagination): Query<Pagination>,
+    Query(filter): Query<HistoryFilter>,
 ) -> Result<Json<PaginatedResponse<object_history::Model>>, AppError> {
-    let paginator = object_history::Entity::find()
-        .filter(object_history::Column::ObjectId.eq(id))
+    let mut select = object_history::Entity::find().filter(object_history::Column::ObjectId.eq(id));
+    if let Some(actor) = filter.actor {
+        select = select.filter(object_history::Column::Actor.eq(actor));
+    }
+    if let Some(after) = filter.changed_after {
+        select = select.filter(object_history::Column::ChangedAt.gte(after));
+    }
+    if let Some(before) = filter.changed_before {
+        select = select.filter(object_history::Column::ChangedAt.lt(before));
+    }
+    let paginator = select
         .order_by(object_history::Column::Version, Order::Desc)
         .paginate(&state.db, pagination.limit);
     let total = paginator.num_items().await?;
@@ -220,9 +248,11 @@ pub(crate) struct SearchResponse {
 )]
 pub(crate) async fn search_global(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(query): Query<SearchQuery>,
 ) -> Result<Json<SearchResponse>, AppError> {
-    let results = ObjectService::search_global(&state.db, &query.q, query.limit).await?;
+    let visible = PermissionService::visible_module_ids(&state.db, &auth_user).await?;
+    let results = ObjectService::search_global(&state.db, &query.q, query.limit, visible).await?;
     Ok(Json(SearchResponse { items: results }))
 }
 
@@ -236,10 +266,11 @@ pub(crate) async fn search_global(
 )]
 pub(crate) async fn sync_placeholder(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<Json<entity::object::Model>, AppError> {
     let txn = state.db.begin().await?;
-    let result = ObjectService::sync_placeholder(&txn, id).await?;
+    let result = ObjectService::sync_placeholder(&txn, &audit, id).await?;
     txn.commit().await?;
     Ok(Json(result))
 }
@@ -254,9 +285,12 @@ pub(crate) async fn sync_placeholder(
 )]
 pub(crate) async fn break_placeholder_link(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<Json<entity::object::Model>, AppError> {
-    let result = ObjectService::break_placeholder_link(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    let result = ObjectService::break_placeholder_link(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -272,10 +306,11 @@ pub(crate) struct SyncAllResponse {
 )]
 pub(crate) async fn sync_all_placeholders(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
 ) -> Result<Json<SyncAllResponse>, AppError> {
     let txn = state.db.begin().await?;
-    let synced = ObjectService::sync_all_placeholders(&txn, module_id).await?;
+    let synced = ObjectService::sync_all_placeholders(&txn, &audit, module_id).await?;
     txn.commit().await?;
     Ok(Json(SyncAllResponse { synced }))
 }
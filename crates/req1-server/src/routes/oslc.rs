@@ -0,0 +1,374 @@
+use std::convert::Infallible;
+
+use axum::{
+    Extension, Router,
+    extract::{FromRequestParts, OriginalUri, Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header, request::Parts},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+};
+use sea_orm::TransactionTrait;
+use uuid::Uuid;
+
+use crate::{error::AppError, middleware, state::AppState};
+use req1_core::auth::AuthUser;
+use req1_core::oslc::{self, OslcQuery, QueryScope, Urls, rdf::RdfFormat, rdf::Resource};
+use req1_core::service::audit::AuditContext;
+use req1_core::service::permission::PermissionService;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/oslc/catalog", get(get_catalog))
+        .route("/oslc/projects/{project_id}", get(get_service_provider))
+        .route(
+            "/oslc/projects/{project_id}/requirements",
+            get(query_project_requirements),
+        )
+        .route("/oslc/modules/{module_id}", get(get_requirement_collection))
+        .route(
+            "/oslc/modules/{module_id}/requirements",
+            get(query_module_requirements).post(create_requirement),
+        )
+        .route("/oslc/requirements/{object_id}", get(get_requirement))
+}
+
+/// Delegated dialogs authenticate from the browser's stored session, so the pages
+/// themselves are served without a token.
+pub fn public_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/oslc/projects/{project_id}/selector",
+            get(selection_dialog),
+        )
+        .route("/oslc/modules/{module_id}/creator", get(creation_dialog))
+}
+
+/// Scheme and authority the client used. A reverse proxy's forwarded headers are only
+/// honoured when the connection comes from one of the trusted proxies.
+pub(crate) struct BaseUrls(Urls);
+
+impl FromRequestParts<AppState> for BaseUrls {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Infallible> {
+        let proxied =
+            middleware::from_trusted_proxy(&parts.extensions, &state.config.trusted_proxies);
+        let header = |name: &str| parts.headers.get(name).and_then(|v| v.to_str().ok());
+        let forwarded = |name: &str| header(name).filter(|_| proxied);
+        let scheme = forwarded("x-forwarded-proto").unwrap_or("http");
+        let host = forwarded("x-forwarded-host")
+            .or_else(|| header(header::HOST.as_str()))
+            .unwrap_or("localhost");
+        Ok(Self(Urls::new(format!("{scheme}://{host}"))))
+    }
+}
+
+fn rdf_response(headers: &HeaderMap, status: StatusCode, graph: &[Resource]) -> Response {
+    let format = RdfFormat::from_accept(headers.get(header::ACCEPT).and_then(|v| v.to_str().ok()));
+    let mut response_headers = HeaderMap::new();
+    let _ = response_headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+    let _ = response_headers.insert("oslc-core-version", HeaderValue::from_static("2.0"));
+    (status, response_headers, format.write(graph)).into_response()
+}
+
+#[utoipa::path(get, path = "/api/v1/oslc/catalog", tag = "OSLC",
+    security(("bearer_auth" = [])),
+    responses((status = 200, description = "Service provider catalog (RDF/XML, Turtle or JSON-LD)"))
+)]
+pub(crate) async fn get_catalog(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    BaseUrls(urls): BaseUrls,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let workspaces = PermissionService::visible_workspace_ids(&state.db, &auth_user).await?;
+    let graph = oslc::catalog(&state.db, &urls, workspaces).await?;
+    Ok(rdf_response(&headers, StatusCode::OK, &graph))
+}
+
+#[utoipa::path(get, path = "/api/v1/oslc/projects/{project_id}", tag = "OSLC",
+    security(("bearer_auth" = [])),
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses((status = 200, description = "Service provider"), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn get_service_provider(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(project_id): Path<Uuid>,
+    BaseUrls(urls): BaseUrls,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let visible = PermissionService::visible_module_ids(&state.db, &auth_user).await?;
+    let provider = oslc::service_provider(&state.db, &urls, project_id, visible).await?;
+    Ok(rdf_response(&headers, StatusCode::OK, &[provider]))
+}
+
+#[utoipa::path(get, path = "/api/v1/oslc/projects/{project_id}/requirements", tag = "OSLC",
+    security(("bearer_auth" = [])),
+    params(("project_id" = Uuid, Path, description = "Project ID"), OslcQuery),
+    responses((status = 200, description = "Query results"), (status = 400, description = "Invalid query"))
+)]
+pub(crate) async fn query_project_requirements(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(project_id): Path<Uuid>,
+    Query(params): Query<OslcQuery>,
+    OriginalUri(uri): OriginalUri,
+    BaseUrls(urls): BaseUrls,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let visible = PermissionService::visible_module_ids(&state.db, &auth_user).await?;
+    let request_uri = format!("{}{uri}", urls.base());
+    let graph = oslc::query(
+        &state.db,
+        &urls,
+        QueryScope::Project(project_id),
+        visible,
+        &params,
+        &request_uri,
+    )
+    .await?;
+    Ok(rdf_response(&headers, StatusCode::OK, &graph))
+}
+
+#[utoipa::path(get, path = "/api/v1/oslc/modules/{module_id}", tag = "OSLC",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    responses((status = 200, description = "Requirement collection"), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn get_requirement_collection(
+    State(state): State<AppState>,
+    Path(module_id): Path<Uuid>,
+    BaseUrls(urls): BaseUrls,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let collection = oslc::collection(&state.db, &urls, module_id).await?;
+    Ok(rdf_response(&headers, StatusCode::OK, &[collection]))
+}
+
+#[utoipa::path(get, path = "/api/v1/oslc/modules/{module_id}/requirements", tag = "OSLC",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID"), OslcQuery),
+    responses((status = 200, description = "Query results"), (status = 400, description = "Invalid query"))
+)]
+pub(crate) async fn query_module_requirements(
+    State(state): State<AppState>,
+    Path(module_id): Path<Uuid>,
+    Query(params): Query<OslcQuery>,
+    OriginalUri(uri): OriginalUri,
+    BaseUrls(urls): BaseUrls,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let request_uri = format!("{}{uri}", urls.base());
+    let graph = oslc::query(
+        &state.db,
+        &urls,
+        QueryScope::Module(module_id),
+        None,
+        &params,
+        &request_uri,
+    )
+    .await?;
+    Ok(rdf_response(&headers, StatusCode::OK, &graph))
+}
+
+#[utoipa::path(post, path = "/api/v1/oslc/modules/{module_id}/requirements", tag = "OSLC",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    request_body(content = Object, description = "JSON-LD oslc_rm:Requirement", content_type = "application/ld+json"),
+    responses((status = 201, description = "Created requirement"))
+)]
+pub(crate) async fn create_requirement(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(module_id): Path<Uuid>,
+    BaseUrls(urls): BaseUrls,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Response, AppError> {
+    // JSON-LD arrives as application/ld+json, which the Json extractor rejects
+    let body: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|e| AppError::bad_request(format!("invalid JSON-LD: {e}")))?;
+    let txn = state.db.begin().await?;
+    let created = oslc::create_requirement(&txn, &audit, &urls, module_id, &body).await?;
+    txn.commit().await?;
+
+    let mut response = rdf_response(
+        &headers,
+        StatusCode::CREATED,
+        std::slice::from_ref(&created),
+    );
+    if let Some(location) = created
+        .about
+        .as_deref()
+        .and_then(|a| HeaderValue::from_str(a).ok())
+    {
+        let _ = response.headers_mut().insert(header::LOCATION, location);
+    }
+    Ok(response)
+}
+
+#[utoipa::path(get, path = "/api/v1/oslc/requirements/{object_id}", tag = "OSLC",
+    security(("bearer_auth" = [])),
+    params(("object_id" = Uuid, Path, description = "Object ID")),
+    responses((status = 200, description = "Requirement"), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn get_requirement(
+    State(state): State<AppState>,
+    Path(object_id): Path<Uuid>,
+    BaseUrls(urls): BaseUrls,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let requirement = oslc::requirement(&state.db, &urls, object_id).await?;
+    Ok(rdf_response(&headers, StatusCode::OK, &[requirement]))
+}
+
+const DIALOG_SCRIPT: &str = r##"
+const token = localStorage.getItem("token");
+const status = document.getElementById("status");
+if (!token) status.textContent = "Sign in to req1 in this browser first.";
+
+const consumers = [window.location.origin, ...CONSUMER_ORIGINS];
+
+// Only answer http(s) pages of req1 itself or a configured consumer
+function consumerUrl(url) {
+  try {
+    const parsed = new URL(url);
+    return ["http:", "https:"].includes(parsed.protocol) && consumers.includes(parsed.origin);
+  } catch {
+    return false;
+  }
+}
+
+function respond(results) {
+  const message = JSON.stringify({ "oslc:results": results });
+  if (window.location.hash === "#oslc-core-windowName-1.0") {
+    const returnUrl = window.name;
+    if (!consumerUrl(returnUrl)) {
+      status.textContent = "The requesting application is not a configured OSLC consumer.";
+      return;
+    }
+    window.name = message;
+    window.location.href = returnUrl;
+  } else {
+    // Messages addressed to another origin than the receiver's are dropped
+    const target = window.opener || window.parent;
+    for (const origin of consumers) target.postMessage("oslc-response:" + message, origin);
+  }
+}
+
+function text(value) {
+  if (Array.isArray(value)) value = value[0];
+  if (value && typeof value === "object") value = value["@value"];
+  return value || "";
+}
+"##;
+
+const DIALOG_STYLE: &str = "body{font-family:sans-serif;margin:12px}\
+    ul{list-style:none;padding:0;max-height:340px;overflow:auto}\
+    li{padding:4px;cursor:pointer}li:hover{background:#eef}\
+    input,textarea{width:100%;box-sizing:border-box;margin-bottom:8px}";
+
+fn dialog_page(state: &AppState, title: &str, body: &str, script: &str) -> Html<String> {
+    let consumers = serde_json::to_string(&state.config.oslc_consumer_origins)
+        .unwrap_or_else(|_| "[]".to_owned());
+    Html(format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\
+         <style>{DIALOG_STYLE}</style></head><body>\n{body}\n<p id=\"status\"></p>\n\
+         <script>const CONSUMER_ORIGINS = {consumers};{DIALOG_SCRIPT}{script}</script>\n\
+         </body></html>\n"
+    ))
+}
+
+#[utoipa::path(get, path = "/api/v1/oslc/projects/{project_id}/selector", tag = "OSLC",
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses((status = 200, description = "Delegated selection dialog (HTML)"))
+)]
+pub(crate) async fn selection_dialog(
+    State(state): State<AppState>,
+    Path(project_id): Path<Uuid>,
+) -> Html<String> {
+    let script = format!(
+        r#"
+const list = document.getElementById("results");
+let requirements = [];
+async function load() {{
+  const res = await fetch("/api/v1/oslc/projects/{project_id}/requirements?oslc.select=dcterms:title,oslc:shortTitle", {{
+    headers: {{ Accept: "application/ld+json", Authorization: "Bearer " + token }},
+  }});
+  if (!res.ok) {{ status.textContent = "Could not load requirements (" + res.status + ")"; return; }}
+  const doc = await res.json();
+  requirements = (doc["@graph"] || [doc]).filter((n) => (n["@type"] || []).includes("oslc_rm:Requirement"));
+  render();
+}}
+function render() {{
+  const term = document.getElementById("search").value.toLowerCase();
+  list.replaceChildren();
+  for (const r of requirements) {{
+    const label = (text(r["oslc:shortTitle"]) + " " + text(r["dcterms:title"])).trim();
+    if (term && !label.toLowerCase().includes(term)) continue;
+    const item = document.createElement("li");
+    item.textContent = label;
+    item.onclick = () => respond([{{ "oslc:label": label, "rdf:resource": r["@id"] }}]);
+    list.appendChild(item);
+  }}
+}}
+document.getElementById("search").oninput = render;
+document.getElementById("cancel").onclick = () => respond([]);
+if (token) load();
+"#
+    );
+    dialog_page(
+        &state,
+        "Select requirement",
+        "<input id=\"search\" placeholder=\"Filter requirements\">\n<ul id=\"results\"></ul>\n\
+         <button id=\"cancel\">Cancel</button>",
+        &script,
+    )
+}
+
+#[utoipa::path(get, path = "/api/v1/oslc/modules/{module_id}/creator", tag = "OSLC",
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    responses((status = 200, description = "Delegated creation dialog (HTML)"))
+)]
+pub(crate) async fn creation_dialog(
+    State(state): State<AppState>,
+    Path(module_id): Path<Uuid>,
+) -> Html<String> {
+    let script = format!(
+        r#"
+document.getElementById("form").onsubmit = async (e) => {{
+  e.preventDefault();
+  const res = await fetch("/api/v1/oslc/modules/{module_id}/requirements", {{
+    method: "POST",
+    headers: {{
+      Accept: "application/ld+json",
+      "Content-Type": "application/ld+json",
+      Authorization: "Bearer " + token,
+    }},
+    body: JSON.stringify({{
+      "@type": "oslc_rm:Requirement",
+      "dcterms:title": document.getElementById("title").value,
+      "dcterms:description": document.getElementById("description").value,
+    }}),
+  }});
+  if (!res.ok) {{ status.textContent = "Could not create requirement (" + res.status + ")"; return; }}
+  const created = await res.json();
+  respond([{{ "oslc:label": text(created["dcterms:title"]), "rdf:resource": created["@id"] }}]);
+}};
+document.getElementById("cancel").onclick = () => respond([]);
+"#
+    );
+    dialog_page(
+        &state,
+        "Create requirement",
+        "<form id=\"form\">\n<input id=\"title\" placeholder=\"Title\" required>\n\
+         <textarea id=\"description\" rows=\"8\" placeholder=\"Description\"></textarea>\n\
+         <button type=\"submit\">Create</button> <button type=\"button\" id=\"cancel\">Cancel</button>\n\
+         </form>",
+        &script,
+    )
+}
@@ -4,10 +4,13 @@ use axum::{
     http::StatusCode,
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use req1_core::auth::AuthUser;
+use req1_core::service::audit::AuditContext;
+use req1_core::service::permission::{Permission, PermissionService};
 use req1_core::service::project_template::{
     CreateTemplateInput, InstantiateInput, ProjectTemplateService, UpdateTemplateInput,
 };
@@ -48,11 +51,14 @@ pub(crate) async fn list_templates(
 )]
 pub(crate) async fn create_template(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Extension(auth_user): Extension<AuthUser>,
     Json(mut body): Json<CreateTemplateInput>,
 ) -> Result<(StatusCode, Json<entity::project_template::Model>), AppError> {
     body.created_by = Some(auth_user.id);
-    let result = ProjectTemplateService::create(&state.db, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ProjectTemplateService::create(&txn, &audit, body).await?;
+    txn.commit().await?;
     Ok((StatusCode::CREATED, Json(result)))
 }
 
@@ -83,10 +89,13 @@ pub(crate) async fn get_template(
 )]
 pub(crate) async fn update_template(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
     Json(body): Json<UpdateTemplateInput>,
 ) -> Result<Json<entity::project_template::Model>, AppError> {
-    let result = ProjectTemplateService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ProjectTemplateService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -97,9 +106,12 @@ pub(crate) async fn update_template(
 )]
 pub(crate) async fn delete_template(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, AppError> {
-    ProjectTemplateService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ProjectTemplateService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -112,6 +124,8 @@ pub(crate) async fn delete_template(
 )]
 pub(crate) async fn instantiate_template(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
     Json(body): Json<InstantiateInput>,
 ) -> Result<
@@ -121,6 +135,15 @@ pub(crate) async fn instantiate_template(
     ),
     AppError,
 > {
-    let result = ProjectTemplateService::instantiate(&state.db, id, body).await?;
+    PermissionService::require_workspace(
+        &state.db,
+        &auth_user,
+        body.workspace_id,
+        Permission::Write,
+    )
+    .await?;
+    let txn = state.db.begin().await?;
+    let result = ProjectTemplateService::instantiate(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok((StatusCode::CREATED, Json(result)))
 }
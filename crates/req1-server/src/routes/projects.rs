@@ -1,12 +1,14 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::project;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
     service::project::{CreateProjectInput, ProjectService, UpdateProjectInput},
@@ -52,6 +54,7 @@ pub(crate) async fn list_projects(
 )]
 pub(crate) async fn create_project(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(workspace_id): Path<Uuid>,
     Json(body): Json<CreateProjectInput>,
 ) -> Result<(axum::http::StatusCode, Json<project::Model>), AppError> {
@@ -59,7 +62,9 @@ pub(crate) async fn create_project(
         workspace_id,
         ..body
     };
-    let result = ProjectService::create(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = ProjectService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -90,10 +95,13 @@ pub(crate) async fn get_project(
 )]
 pub(crate) async fn update_project(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_workspace_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateProjectInput>,
 ) -> Result<Json<project::Model>, AppError> {
-    let result = ProjectService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ProjectService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -107,8 +115,11 @@ pub(crate) async fn update_project(
 )]
 pub(crate) async fn delete_project(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_workspace_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    ProjectService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ProjectService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
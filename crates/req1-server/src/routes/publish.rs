@@ -1,18 +1,20 @@
 #![allow(unused_qualifications)]
 
 use axum::{
-    Router,
+    Extension, Router,
     body::Body,
     extract::{Multipart, Path, Query, State},
     http::{HeaderMap, HeaderValue, StatusCode, header},
     response::{Html, IntoResponse, Response},
     routing::{get, post},
 };
+use sea_orm::TransactionTrait;
 use serde::Deserialize;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
+use req1_core::service::audit::AuditContext;
 use req1_core::service::csv_import::CsvImportService;
 use req1_core::service::docx_import::{DocxImportInput, DocxImportService};
 use req1_core::service::publish::PublishService;
@@ -149,10 +151,13 @@ pub(crate) struct CsvImportResponse {
 )]
 pub(crate) async fn import_csv(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     body: String,
 ) -> Result<axum::Json<CsvImportResponse>, AppError> {
-    let result = CsvImportService::import_csv(&state.db, module_id, &body).await?;
+    let txn = state.db.begin().await?;
+    let result = CsvImportService::import_csv(&txn, &audit, module_id, &body).await?;
+    txn.commit().await?;
     Ok(axum::Json(CsvImportResponse {
         objects_created: result.objects_created,
     }))
@@ -172,10 +177,13 @@ pub(crate) struct XlsxImportResponse {
 )]
 pub(crate) async fn import_xlsx(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     body: axum::body::Bytes,
 ) -> Result<axum::Json<XlsxImportResponse>, AppError> {
-    let result = XlsxImportService::import_xlsx(&state.db, module_id, &body).await?;
+    let txn = state.db.begin().await?;
+    let result = XlsxImportService::import_xlsx(&txn, &audit, module_id, &body).await?;
+    txn.commit().await?;
     Ok(axum::Json(XlsxImportResponse {
         objects_created: result.objects_created,
         objects_updated: result.objects_updated,
@@ -205,6 +213,7 @@ pub(crate) async fn preview_docx(
 )]
 pub(crate) async fn import_docx(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     mut multipart: Multipart,
 ) -> Result<
@@ -252,6 +261,8 @@ pub(crate) async fn import_docx(
         }
     };
 
-    let result = DocxImportService::import_docx(&state.db, module_id, &data, input).await?;
+    let txn = state.db.begin().await?;
+    let result = DocxImportService::import_docx(&txn, &audit, module_id, &data, input).await?;
+    txn.commit().await?;
     Ok((StatusCode::CREATED, axum::Json(result)))
 }
@@ -3,7 +3,7 @@
 use std::io::Cursor;
 
 use axum::{
-    Router,
+    Extension, Router,
     body::Body,
     extract::{Multipart, Path, Query, State},
     http::{HeaderMap, HeaderValue, StatusCode, header},
@@ -14,6 +14,11 @@ use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use req1_core::auth::AuthUser;
+use req1_core::reqif::{ConflictResolution, MergeResult};
+use req1_core::service::audit::AuditContext;
+use req1_reqif::ReqIf;
+
 use crate::{error::AppError, state::AppState};
 
 pub fn routes() -> Router<AppState> {
@@ -26,6 +31,14 @@ pub fn routes() -> Router<AppState> {
             "/modules/{module_id}/reqif/export",
             get(export_reqif_handler),
         )
+        .route(
+            "/modules/{module_id}/reqif/merge/preview",
+            post(preview_merge_handler),
+        )
+        .route(
+            "/modules/{module_id}/reqif/merge",
+            post(merge_reqif_handler),
+        )
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -46,9 +59,29 @@ pub(crate) struct ImportResponse {
 )]
 pub(crate) async fn import_reqif_handler(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(project_id): Path<Uuid>,
     mut multipart: Multipart,
 ) -> Result<Response, AppError> {
+    let doc = read_reqif_upload(&mut multipart).await?;
+
+    let result =
+        req1_core::reqif::import::import_reqif(&state.db, &audit, project_id, &doc).await?;
+
+    let response = ImportResponse {
+        module_id: result.module_id,
+        objects_created: result.objects_created,
+        links_created: result.links_created,
+        attribute_definitions_created: result.attribute_definitions_created,
+        object_types_created: result.object_types_created,
+        link_types_created: result.link_types_created,
+    };
+
+    Ok((StatusCode::CREATED, axum::Json(response)).into_response())
+}
+
+/// Parse the first multipart field as a `.reqif` document or `.reqifz` archive.
+async fn read_reqif_upload(multipart: &mut Multipart) -> Result<ReqIf, AppError> {
     let field = multipart
         .next_field()
         .await
@@ -63,29 +96,75 @@ pub(crate) async fn import_reqif_handler(
 
     let is_reqifz = filename.ends_with(".reqifz");
 
-    let doc = if is_reqifz {
+    if is_reqifz {
         let cursor = Cursor::new(&data);
         req1_reqif::from_reqifz(cursor)
-            .map_err(|e| AppError::bad_request(format!("invalid reqifz: {e}")))?
+            .map_err(|e| AppError::bad_request(format!("invalid reqifz: {e}")))
     } else {
         let xml = std::str::from_utf8(&data)
             .map_err(|e| AppError::bad_request(format!("invalid UTF-8: {e}")))?;
         req1_reqif::from_xml_str(xml)
-            .map_err(|e| AppError::bad_request(format!("invalid reqif XML: {e}")))?
-    };
+            .map_err(|e| AppError::bad_request(format!("invalid reqif XML: {e}")))
+    }
+}
 
-    let result = req1_core::reqif::import::import_reqif(&state.db, project_id, &doc).await?;
+#[utoipa::path(post, path = "/api/v1/modules/{module_id}/reqif/merge/preview", tag = "ReqIF",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    request_body(content_type = "multipart/form-data", content = String),
+    responses((status = 200, body = MergeResult))
+)]
+pub(crate) async fn preview_merge_handler(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(user): Extension<AuthUser>,
+    Path(module_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<axum::Json<MergeResult>, AppError> {
+    let doc = read_reqif_upload(&mut multipart).await?;
+    let result =
+        req1_core::reqif::merge::preview_merge(&state.db, &audit, &user, module_id, &doc).await?;
+    Ok(axum::Json(result))
+}
 
-    let response = ImportResponse {
-        module_id: result.module_id,
-        objects_created: result.objects_created,
-        links_created: result.links_created,
-        attribute_definitions_created: result.attribute_definitions_created,
-        object_types_created: result.object_types_created,
-        link_types_created: result.link_types_created,
-    };
+#[derive(Debug, Deserialize, IntoParams)]
+pub(crate) struct MergeQuery {
+    /// What to do with fields changed both locally and in the document.
+    #[serde(default)]
+    on_conflict: ConflictResolution,
+}
 
-    Ok((StatusCode::CREATED, axum::Json(response)).into_response())
+#[utoipa::path(post, path = "/api/v1/modules/{module_id}/reqif/merge", tag = "ReqIF",
+    security(("bearer_auth" = [])),
+    params(
+        ("module_id" = Uuid, Path, description = "Module ID"),
+        MergeQuery,
+    ),
+    request_body(content_type = "multipart/form-data", content = String),
+    responses(
+        (status = 200, body = MergeResult),
+        (status = 409, description = "Conflicts with local edits and on_conflict=fail"),
+    )
+)]
+pub(crate) async fn merge_reqif_handler(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(user): Extension<AuthUser>,
+    Path(module_id): Path<Uuid>,
+    Query(query): Query<MergeQuery>,
+    mut multipart: Multipart,
+) -> Result<axum::Json<MergeResult>, AppError> {
+    let doc = read_reqif_upload(&mut multipart).await?;
+    let result = req1_core::reqif::merge::merge_reqif(
+        &state.db,
+        &audit,
+        &user,
+        module_id,
+        &doc,
+        query.on_conflict,
+    )
+    .await?;
+    Ok(axum::Json(result))
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
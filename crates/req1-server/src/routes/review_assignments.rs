@@ -1,12 +1,14 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::review_assignment;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
     service::review_assignment::{
@@ -55,11 +57,14 @@ pub(crate) async fn list_assignments(
 )]
 pub(crate) async fn create_assignment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(package_id): Path<Uuid>,
     Json(body): Json<CreateReviewAssignmentInput>,
 ) -> Result<(axum::http::StatusCode, Json<review_assignment::Model>), AppError> {
     let input = CreateReviewAssignmentInput { package_id, ..body };
-    let result = ReviewAssignmentService::create(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = ReviewAssignmentService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -90,10 +95,13 @@ pub(crate) async fn get_assignment(
 )]
 pub(crate) async fn update_assignment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_package_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateReviewAssignmentInput>,
 ) -> Result<Json<review_assignment::Model>, AppError> {
-    let result = ReviewAssignmentService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ReviewAssignmentService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -107,8 +115,11 @@ pub(crate) async fn update_assignment(
 )]
 pub(crate) async fn delete_assignment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_package_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    ReviewAssignmentService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ReviewAssignmentService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
@@ -1,12 +1,14 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::review_comment;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
     service::review_comment::{
@@ -55,11 +57,14 @@ pub(crate) async fn list_review_comments(
 )]
 pub(crate) async fn create_review_comment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(package_id): Path<Uuid>,
     Json(body): Json<CreateReviewCommentInput>,
 ) -> Result<(axum::http::StatusCode, Json<review_comment::Model>), AppError> {
     let input = CreateReviewCommentInput { package_id, ..body };
-    let result = ReviewCommentService::create(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = ReviewCommentService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -90,10 +95,13 @@ pub(crate) async fn get_review_comment(
 )]
 pub(crate) async fn update_review_comment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_package_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateReviewCommentInput>,
 ) -> Result<Json<review_comment::Model>, AppError> {
-    let result = ReviewCommentService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ReviewCommentService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -107,8 +115,11 @@ pub(crate) async fn update_review_comment(
 )]
 pub(crate) async fn delete_review_comment(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_package_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    ReviewCommentService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ReviewCommentService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
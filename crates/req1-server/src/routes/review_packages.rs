@@ -3,6 +3,7 @@ use axum::{
     extract::{Path, Query, State},
     routing::{get, post},
 };
+use sea_orm::TransactionTrait;
 use serde::Deserialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
@@ -10,6 +11,7 @@ use uuid::Uuid;
 use crate::{error::AppError, state::AppState};
 use entity::review_package;
 use req1_core::auth::AuthUser;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
     service::review_package::{
@@ -66,11 +68,14 @@ pub(crate) async fn list_review_packages(
 )]
 pub(crate) async fn create_review_package(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     Json(body): Json<CreateReviewPackageInput>,
 ) -> Result<(axum::http::StatusCode, Json<review_package::Model>), AppError> {
     let input = CreateReviewPackageInput { module_id, ..body };
-    let result = ReviewPackageService::create(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = ReviewPackageService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -101,10 +106,13 @@ pub(crate) async fn get_review_package(
 )]
 pub(crate) async fn update_review_package(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateReviewPackageInput>,
 ) -> Result<Json<review_package::Model>, AppError> {
-    let result = ReviewPackageService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ReviewPackageService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -118,9 +126,12 @@ pub(crate) async fn update_review_package(
 )]
 pub(crate) async fn delete_review_package(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    ReviewPackageService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ReviewPackageService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
@@ -155,6 +166,7 @@ pub(crate) struct TransitionRequest {
 )]
 pub(crate) async fn transition_status(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Extension(auth_user): Extension<AuthUser>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<TransitionRequest>,
@@ -168,13 +180,16 @@ pub(crate) async fn transition_status(
         _ => None,
     };
 
+    let txn = state.db.begin().await?;
     let result = ReviewPackageService::transition_status(
-        &state.db,
+        &txn,
+        &audit,
         id,
         &body.status,
         auth_user.id,
         sign_input,
     )
     .await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
@@ -0,0 +1,188 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use sea_orm::TransactionTrait;
+use uuid::Uuid;
+
+use crate::{error::AppError, state::AppState};
+use req1_core::service::audit::AuditContext;
+use req1_core::service::script_library::{
+    ActivateVersionInput, CreateScriptLibraryInput, ScriptLibraryService, UpdateScriptLibraryInput,
+};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/workspaces/{workspace_id}/script-libraries",
+            get(list_workspace_libraries).post(create_workspace_library),
+        )
+        .route(
+            "/projects/{project_id}/script-libraries",
+            get(list_project_libraries).post(create_project_library),
+        )
+        .route(
+            "/script-libraries/{id}",
+            get(get_library)
+                .patch(update_library)
+                .delete(delete_library),
+        )
+        .route("/script-libraries/{id}/versions", get(list_versions))
+        .route("/script-libraries/{id}/activate", post(activate_version))
+}
+
+#[utoipa::path(get, path = "/api/v1/workspaces/{workspace_id}/script-libraries",
+    tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("workspace_id" = Uuid, Path, description = "Workspace ID")),
+    responses((status = 200, body = Vec<entity::script_library::Model>))
+)]
+pub(crate) async fn list_workspace_libraries(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<Json<Vec<entity::script_library::Model>>, AppError> {
+    let items = ScriptLibraryService::list_for_workspace(&state.db, workspace_id).await?;
+    Ok(Json(items))
+}
+
+#[utoipa::path(post, path = "/api/v1/workspaces/{workspace_id}/script-libraries",
+    tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("workspace_id" = Uuid, Path, description = "Workspace ID")),
+    request_body = CreateScriptLibraryInput,
+    responses((status = 201, body = entity::script_library::Model))
+)]
+pub(crate) async fn create_workspace_library(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(workspace_id): Path<Uuid>,
+    Json(body): Json<CreateScriptLibraryInput>,
+) -> Result<(StatusCode, Json<entity::script_library::Model>), AppError> {
+    let input = CreateScriptLibraryInput {
+        workspace_id: Some(workspace_id),
+        project_id: None,
+        ..body
+    };
+    let txn = state.db.begin().await?;
+    let result = ScriptLibraryService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+#[utoipa::path(get, path = "/api/v1/projects/{project_id}/script-libraries",
+    tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    responses((status = 200, body = Vec<entity::script_library::Model>))
+)]
+pub(crate) async fn list_project_libraries(
+    State(state): State<AppState>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<Vec<entity::script_library::Model>>, AppError> {
+    let items = ScriptLibraryService::list_for_project(&state.db, project_id).await?;
+    Ok(Json(items))
+}
+
+#[utoipa::path(post, path = "/api/v1/projects/{project_id}/script-libraries",
+    tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("project_id" = Uuid, Path, description = "Project ID")),
+    request_body = CreateScriptLibraryInput,
+    responses((status = 201, body = entity::script_library::Model))
+)]
+pub(crate) async fn create_project_library(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(project_id): Path<Uuid>,
+    Json(body): Json<CreateScriptLibraryInput>,
+) -> Result<(StatusCode, Json<entity::script_library::Model>), AppError> {
+    let input = CreateScriptLibraryInput {
+        workspace_id: None,
+        project_id: Some(project_id),
+        ..body
+    };
+    let txn = state.db.begin().await?;
+    let result = ScriptLibraryService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+#[utoipa::path(get, path = "/api/v1/script-libraries/{id}", tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Script library ID")),
+    responses((status = 200, body = entity::script_library::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn get_library(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<entity::script_library::Model>, AppError> {
+    let result = ScriptLibraryService::get(&state.db, id).await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(patch, path = "/api/v1/script-libraries/{id}", tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Script library ID")),
+    request_body = UpdateScriptLibraryInput,
+    responses((status = 200, body = entity::script_library::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn update_library(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateScriptLibraryInput>,
+) -> Result<Json<entity::script_library::Model>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = ScriptLibraryService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(delete, path = "/api/v1/script-libraries/{id}", tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Script library ID")),
+    responses((status = 204, description = "Deleted"), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn delete_library(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let txn = state.db.begin().await?;
+    ScriptLibraryService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(get, path = "/api/v1/script-libraries/{id}/versions", tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Script library ID")),
+    responses((status = 200, body = Vec<entity::script_library_version::Model>))
+)]
+pub(crate) async fn list_versions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<entity::script_library_version::Model>>, AppError> {
+    let items = ScriptLibraryService::list_versions(&state.db, id).await?;
+    Ok(Json(items))
+}
+
+#[utoipa::path(post, path = "/api/v1/script-libraries/{id}/activate", tag = "Script Libraries",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "Script library ID")),
+    request_body = ActivateVersionInput,
+    responses((status = 200, body = entity::script_library::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn activate_version(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<ActivateVersionInput>,
+) -> Result<Json<entity::script_library::Model>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = ScriptLibraryService::activate(&txn, &audit, id, body.version).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
@@ -1,10 +1,10 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -14,7 +14,13 @@ use crate::{error::AppError, state::AppState};
 use entity::{object, script};
 use req1_core::PaginatedResponse;
 use req1_core::Pagination;
-use req1_core::scripting::engine::{Mutation, ScriptEngine, ScriptObject, TriggerContext};
+use req1_core::auth::AuthUser;
+use req1_core::error::CoreError;
+use req1_core::scripting::apply::apply_mutations;
+use req1_core::scripting::engine::{ScriptEngine, ScriptObject, TriggerContext};
+use req1_core::scripting::world::WorldAccess;
+use req1_core::service::audit::{AuditContext, AuditService};
+use req1_core::service::crud;
 use req1_core::service::object::load_world;
 use req1_core::service::scheduler::SchedulerService;
 
@@ -114,6 +120,7 @@ pub(crate) async fn list_scripts(
 )]
 pub(crate) async fn create_script(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     Json(body): Json<CreateScriptRequest>,
 ) -> Result<(axum::http::StatusCode, Json<script::Model>), AppError> {
@@ -166,7 +173,11 @@ pub(crate) async fn create_script(
         updated_at: Set(now),
     };
 
-    let result = model.insert(&state.db).await?;
+    let txn = state.db.begin().await?;
+    let result = model.insert(&txn).await?;
+    AuditService::record::<script::Entity>(&txn, &audit, "create", result.id, None, Some(&result))
+        .await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -200,6 +211,7 @@ pub(crate) async fn get_script(
 )]
 pub(crate) async fn update_script(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateScriptRequest>,
 ) -> Result<Json<script::Model>, AppError> {
@@ -240,7 +252,7 @@ pub(crate) async fn update_script(
         }
     }
 
-    let mut active: script::ActiveModel = existing.into();
+    let mut active: script::ActiveModel = existing.clone().into();
     if let Some(name) = body.name {
         active.name = Set(name);
     }
@@ -267,7 +279,18 @@ pub(crate) async fn update_script(
     }
     active.updated_at = Set(chrono::Utc::now().fixed_offset());
 
-    let result = active.update(&state.db).await?;
+    let txn = state.db.begin().await?;
+    let result = active.update(&txn).await?;
+    AuditService::record::<script::Entity>(
+        &txn,
+        &audit,
+        "update",
+        result.id,
+        Some(&existing),
+        Some(&result),
+    )
+    .await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -281,12 +304,12 @@ pub(crate) async fn update_script(
 )]
 pub(crate) async fn delete_script(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    let result = script::Entity::delete_by_id(id).exec(&state.db).await?;
-    if result.rows_affected == 0 {
-        return Err(AppError::not_found(format!("script {id} not found")));
-    }
+    let txn = state.db.begin().await?;
+    crud::delete_by_id::<script::Entity>(&txn, &audit, id, "script").await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
@@ -301,6 +324,7 @@ pub(crate) async fn delete_script(
 )]
 pub(crate) async fn test_script(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path((module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<TestScriptRequest>,
 ) -> Result<Json<serde_json::Value>, AppError> {
@@ -310,6 +334,7 @@ pub(crate) async fn test_script(
         .ok_or_else(|| AppError::not_found(format!("script {id} not found")))?;
 
     let world = load_world(&state.db, module_id).await?;
+    let access = WorldAccess::new(&state.db, Some(auth_user.id), module_id);
 
     let result = match s.script_type.as_str() {
         "trigger" => {
@@ -323,7 +348,7 @@ pub(crate) async fn test_script(
                 hook_point: hook,
                 object: obj,
             };
-            let r = ScriptEngine::run_trigger(&s.source_code, &world, &ctx)?;
+            let r = ScriptEngine::run_trigger(&s.source_code, &world, &ctx, &access).await?;
             serde_json::json!({
                 "script_type": "trigger",
                 "rejected": r.rejected,
@@ -335,14 +360,14 @@ pub(crate) async fn test_script(
             let obj = body.object.ok_or_else(|| {
                 AppError::bad_request("object is required for layout test".to_owned())
             })?;
-            let r = ScriptEngine::run_layout(&s.source_code, &world, &obj)?;
+            let r = ScriptEngine::run_layout(&s.source_code, &world, &obj, &access).await?;
             serde_json::json!({
                 "script_type": "layout",
                 "value": r.value,
             })
         }
         "action" => {
-            let r = ScriptEngine::run_action(&s.source_code, &world)?;
+            let r = ScriptEngine::run_action(&s.source_code, &world, &access).await?;
             serde_json::json!({
                 "script_type": "action",
                 "output": r.output,
@@ -369,6 +394,7 @@ pub(crate) async fn test_script(
 )]
 pub(crate) async fn execute_script(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<Json<ExecuteResult>, AppError> {
     let s = script::Entity::find_by_id(id)
@@ -383,16 +409,42 @@ pub(crate) async fn execute_script(
     }
 
     let world = load_world(&state.db, module_id).await?;
-    let result = ScriptEngine::run_action(&s.source_code, &world)?;
-
+    let access = WorldAccess::new(&state.db, audit.user_id, module_id);
+    let execution = SchedulerService::record_execution_start(&state.db, s.id).await?;
+    let result = match ScriptEngine::run_action(&s.source_code, &world, &access).await {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = SchedulerService::record_execution_failure(&state.db, execution, &e).await;
+            return Err(e.into());
+        }
+    };
     let mutation_count = result.mutations.len();
-
     if !result.mutations.is_empty() {
-        let txn = state.db.begin().await?;
-        apply_action_mutations(&txn, &result.mutations).await?;
-        txn.commit().await?;
+        let applied = async {
+            let txn = state.db.begin().await?;
+            apply_mutations(
+                &txn,
+                &audit.for_script(&s.name),
+                &access,
+                module_id,
+                &result.mutations,
+            )
+            .await?;
+            txn.commit().await?;
+            Ok::<_, CoreError>(())
+        }
+        .await;
+        if let Err(e) = applied {
+            let _ = SchedulerService::record_execution_failure(&state.db, execution, &e).await;
+            return Err(e.into());
+        }
     }
 
+    let output = (!result.output.is_empty()).then(|| result.output.join("\n"));
+    let _ =
+        SchedulerService::record_execution_finish(&state.db, execution, "success", output, None)
+            .await?;
+
     Ok(Json(ExecuteResult {
         output: result.output,
         mutations_applied: mutation_count,
@@ -420,6 +472,7 @@ pub(crate) struct BatchLayoutResponse {
 )]
 pub(crate) async fn batch_layout(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path((module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<Json<BatchLayoutResponse>, AppError> {
     let s = script::Entity::find_by_id(id)
@@ -434,19 +487,34 @@ pub(crate) async fn batch_layout(
     }
 
     let world = load_world(&state.db, module_id).await?;
+    let access = WorldAccess::new(&state.db, Some(auth_user.id), module_id);
 
-    let mut results = Vec::new();
-    for obj in &world.objects {
-        let layout_result = ScriptEngine::run_layout(&s.source_code, &world, obj)?;
-        let oid = obj
-            .id
-            .parse::<Uuid>()
-            .map_err(|e| AppError::internal(format!("invalid object UUID: {e}")))?;
-        results.push(LayoutEntry {
-            object_id: oid,
-            value: layout_result.value,
-        });
-    }
+    let objects = object::Entity::find()
+        .filter(object::Column::ModuleId.eq(module_id))
+        .filter(object::Column::DeletedAt.is_null())
+        .order_by_asc(object::Column::Position)
+        .all(&state.db)
+        .await?;
+    let object_ids: Vec<Uuid> = objects.iter().map(|o| o.id).collect();
+    let script_objects: Vec<ScriptObject> = objects.into_iter().map(ScriptObject::from).collect();
+
+    let started_at = chrono::Utc::now().fixed_offset();
+    let values =
+        match ScriptEngine::run_layouts(&s.source_code, &world, &script_objects, &access).await {
+            Ok(values) => values,
+            Err(e) => {
+                let _ = SchedulerService::record_failed_run(&state.db, s.id, started_at, &e).await;
+                return Err(e.into());
+            }
+        };
+    let results = object_ids
+        .into_iter()
+        .zip(values)
+        .map(|(object_id, r)| LayoutEntry {
+            object_id,
+            value: r.value,
+        })
+        .collect();
 
     Ok(Json(BatchLayoutResponse { results }))
 }
@@ -470,47 +538,3 @@ pub(crate) async fn list_executions(
             .await?;
     Ok(Json(result))
 }
-
-async fn apply_action_mutations(
-    db: &impl ConnectionTrait,
-    mutations: &[Mutation],
-) -> Result<(), AppError> {
-    use std::collections::HashMap;
-
-    let mut grouped: HashMap<Uuid, Vec<(&String, &serde_json::Value)>> = HashMap::new();
-    for m in mutations {
-        match m {
-            Mutation::SetAttribute {
-                object_id,
-                key,
-                value,
-            } => {
-                grouped.entry(*object_id).or_default().push((key, value));
-            }
-        }
-    }
-
-    for (oid, changes) in &grouped {
-        let obj = object::Entity::find_by_id(*oid)
-            .one(db)
-            .await?
-            .ok_or_else(|| AppError::not_found(format!("object {oid} not found")))?;
-
-        let mut attrs = obj
-            .attributes
-            .clone()
-            .unwrap_or(serde_json::Value::Object(serde_json::Map::default()));
-        for (key, value) in changes {
-            if let Some(map) = attrs.as_object_mut() {
-                let _ = map.insert((*key).clone(), (*value).clone());
-            }
-        }
-
-        let mut active: object::ActiveModel = obj.into();
-        active.attributes = Set(Some(attrs));
-        active.updated_at = Set(chrono::Utc::now().fixed_offset());
-        let _ = active.update(db).await?;
-    }
-
-    Ok(())
-}
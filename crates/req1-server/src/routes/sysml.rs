@@ -0,0 +1,49 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    routing::{get, post},
+};
+use sea_orm::TransactionTrait;
+use uuid::Uuid;
+
+use req1_core::service::audit::AuditContext;
+use req1_core::sysml::SysmlImportResult;
+
+use crate::{error::AppError, state::AppState};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/modules/{module_id}/sysml/import", post(import_sysml))
+        .route("/modules/{module_id}/sysml/export", get(export_sysml))
+}
+
+#[utoipa::path(post, path = "/api/v1/modules/{module_id}/sysml/import", tag = "SysML",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    request_body(content = Object, description = "SysML v2 API elements: an array, `{\"elements\": [...]}` or a commit"),
+    responses((status = 200, body = SysmlImportResult), (status = 400, description = "No requirement elements"))
+)]
+pub(crate) async fn import_sysml(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(module_id): Path<Uuid>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Json<SysmlImportResult>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = req1_core::sysml::import::import_sysml(&txn, &audit, module_id, &body).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(get, path = "/api/v1/modules/{module_id}/sysml/export", tag = "SysML",
+    security(("bearer_auth" = [])),
+    params(("module_id" = Uuid, Path, description = "Module ID")),
+    responses((status = 200, body = Vec<Object>, description = "SysML v2 API elements"), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn export_sysml(
+    State(state): State<AppState>,
+    Path(module_id): Path<Uuid>,
+) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    let elements = req1_core::sysml::export::export_sysml(&state.db, module_id).await?;
+    Ok(Json(elements))
+}
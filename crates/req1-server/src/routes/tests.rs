@@ -1,12 +1,14 @@
 use axum::{
-    Router,
+    Extension, Router,
     extract::{Path, State},
     routing::{delete, get, patch},
 };
+use sea_orm::TransactionTrait;
 use utoipa::IntoParams;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
+use req1_core::service::audit::AuditContext;
 use req1_core::service::test::{
     CreateTestCaseInput, CreateTestExecutionInput, TestService, UpdateTestCaseInput,
     UpdateTestExecutionInput,
@@ -68,10 +70,13 @@ pub(crate) async fn list_test_cases(
 )]
 pub(crate) async fn create_test_case(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     axum::Json(input): axum::Json<CreateTestCaseInput>,
 ) -> Result<axum::Json<entity::test_case::Model>, AppError> {
-    let model = TestService::create_test_case(&state.db, module_id, input).await?;
+    let txn = state.db.begin().await?;
+    let model = TestService::create_test_case(&txn, &audit, module_id, input).await?;
+    txn.commit().await?;
     Ok(axum::Json(model))
 }
 
@@ -108,10 +113,13 @@ pub(crate) async fn get_test_case(
 )]
 pub(crate) async fn update_test_case(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     axum::Json(input): axum::Json<UpdateTestCaseInput>,
 ) -> Result<axum::Json<entity::test_case::Model>, AppError> {
-    let model = TestService::update_test_case(&state.db, id, input).await?;
+    let txn = state.db.begin().await?;
+    let model = TestService::update_test_case(&txn, &audit, id, input).await?;
+    txn.commit().await?;
     Ok(axum::Json(model))
 }
 
@@ -125,9 +133,12 @@ pub(crate) async fn update_test_case(
 )]
 pub(crate) async fn delete_test_case(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::Json<()>, AppError> {
-    TestService::delete_test_case(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    TestService::delete_test_case(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::Json(()))
 }
 
@@ -152,10 +163,13 @@ pub(crate) async fn list_test_executions(
 )]
 pub(crate) async fn create_test_execution(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(test_case_id): Path<Uuid>,
     axum::Json(input): axum::Json<CreateTestExecutionInput>,
 ) -> Result<axum::Json<entity::test_execution::Model>, AppError> {
-    let model = TestService::create_test_execution(&state.db, test_case_id, input).await?;
+    let txn = state.db.begin().await?;
+    let model = TestService::create_test_execution(&txn, &audit, test_case_id, input).await?;
+    txn.commit().await?;
     Ok(axum::Json(model))
 }
 
@@ -192,10 +206,13 @@ pub(crate) async fn get_test_execution(
 )]
 pub(crate) async fn update_test_execution(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_test_case_id, id)): Path<(Uuid, Uuid)>,
     axum::Json(input): axum::Json<UpdateTestExecutionInput>,
 ) -> Result<axum::Json<entity::test_execution::Model>, AppError> {
-    let model = TestService::update_test_execution(&state.db, id, input).await?;
+    let txn = state.db.begin().await?;
+    let model = TestService::update_test_execution(&txn, &audit, id, input).await?;
+    txn.commit().await?;
     Ok(axum::Json(model))
 }
 
@@ -209,9 +226,12 @@ pub(crate) async fn update_test_execution(
 )]
 pub(crate) async fn delete_test_execution(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_test_case_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::Json<()>, AppError> {
-    TestService::delete_test_execution(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    TestService::delete_test_execution(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::Json(()))
 }
 
@@ -3,10 +3,13 @@
 use std::collections::HashSet;
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use req1_core::auth::AuthUser;
+use req1_core::baseline::{FrozenObject, Snapshot};
+use req1_core::service::permission::{Permission, PermissionService};
 use sea_orm::{ColumnTrait, EntityTrait, Order, QueryFilter, QueryOrder};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
@@ -27,6 +30,41 @@ pub(crate) struct TraceabilityMatrixQuery {
     source_module_id: Uuid,
     target_module_id: Uuid,
     link_type_id: Option<Uuid>,
+    /// Evaluate against this baseline; it must cover both modules
+    baseline_id: Option<Uuid>,
+    /// Evaluate against the baselines of this set
+    baseline_set_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub(crate) struct CoverageQuery {
+    /// Evaluate against this baseline of the module
+    baseline_id: Option<Uuid>,
+    /// Evaluate against the module's baseline in this set
+    baseline_set_id: Option<Uuid>,
+}
+
+/// `(link_id, source_object_id, target_object_id, suspect)`
+type TraceLink = (Uuid, Uuid, Uuid, bool);
+
+impl From<object::Model> for MatrixObject {
+    fn from(o: object::Model) -> Self {
+        Self {
+            id: o.id,
+            heading: o.heading,
+            position: o.position,
+        }
+    }
+}
+
+impl From<FrozenObject> for MatrixObject {
+    fn from(o: FrozenObject) -> Self {
+        Self {
+            id: o.id,
+            heading: o.heading,
+            position: o.position,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -58,8 +96,77 @@ pub(crate) struct TraceabilityMatrixResponse {
 )]
 pub(crate) async fn get_traceability_matrix(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(params): Query<TraceabilityMatrixQuery>,
 ) -> Result<Json<TraceabilityMatrixResponse>, AppError> {
+    for module_id in [params.source_module_id, params.target_module_id] {
+        PermissionService::require_module(&state.db, &auth_user, module_id, Permission::Read)
+            .await?;
+    }
+
+    let snapshot = Snapshot::resolve(&state.db, params.baseline_id, params.baseline_set_id).await?;
+
+    let (source_objects, target_objects, links): (
+        Vec<MatrixObject>,
+        Vec<MatrixObject>,
+        Vec<TraceLink>,
+    ) = if let Some(snapshot) = &snapshot {
+        let source_objects = snapshot.objects(&state.db, params.source_module_id).await?;
+        let target_objects = snapshot.objects(&state.db, params.target_module_id).await?;
+        let links = snapshot
+            .links(&state.db)
+            .await?
+            .into_iter()
+            .filter(|l| params.link_type_id.is_none_or(|lt| l.link_type_id == lt))
+            .map(|l| (l.link_id, l.source_object_id, l.target_object_id, l.suspect))
+            .collect();
+        (
+            source_objects.into_iter().map(MatrixObject::from).collect(),
+            target_objects.into_iter().map(MatrixObject::from).collect(),
+            links,
+        )
+    } else {
+        live_matrix_data(&state, &params).await?
+    };
+
+    let source_set: HashSet<Uuid> = source_objects.iter().map(|o| o.id).collect();
+    let target_set: HashSet<Uuid> = target_objects.iter().map(|o| o.id).collect();
+
+    // Normalize direction: source always from source_module, target from target_module
+    let cells: Vec<MatrixCell> = links
+        .into_iter()
+        .filter_map(|(link_id, src, tgt, suspect)| {
+            if source_set.contains(&src) && target_set.contains(&tgt) {
+                Some(MatrixCell {
+                    source_id: src,
+                    target_id: tgt,
+                    link_id,
+                    suspect,
+                })
+            } else if source_set.contains(&tgt) && target_set.contains(&src) {
+                Some(MatrixCell {
+                    source_id: tgt,
+                    target_id: src,
+                    link_id,
+                    suspect,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    Ok(Json(TraceabilityMatrixResponse {
+        source_objects,
+        target_objects,
+        cells,
+    }))
+}
+
+async fn live_matrix_data(
+    state: &AppState,
+    params: &TraceabilityMatrixQuery,
+) -> Result<(Vec<MatrixObject>, Vec<MatrixObject>, Vec<TraceLink>), AppError> {
     // 1. Fetch source objects ordered by position
     let source_objects: Vec<object::Model> = object::Entity::find()
         .filter(object::Column::ModuleId.eq(params.source_module_id))
@@ -83,68 +190,26 @@ pub(crate) async fn get_traceability_matrix(
             .is_in(source_ids.clone())
             .and(link::Column::TargetObjectId.is_in(target_ids.clone()))
             .or(link::Column::SourceObjectId
-                .is_in(target_ids.clone())
-                .and(link::Column::TargetObjectId.is_in(source_ids.clone()))),
+                .is_in(target_ids)
+                .and(link::Column::TargetObjectId.is_in(source_ids))),
     );
 
     if let Some(lt) = params.link_type_id {
         link_query = link_query.filter(link::Column::LinkTypeId.eq(lt));
     }
 
-    let links = link_query.all(&state.db).await?;
-
-    // Build set for fast membership check
-    let source_set: HashSet<Uuid> = source_ids.into_iter().collect();
-    let target_set: HashSet<Uuid> = target_ids.into_iter().collect();
-
-    // 4. Normalize direction: source always from source_module, target from target_module
-    let cells: Vec<MatrixCell> = links
+    let links = link_query
+        .all(&state.db)
+        .await?
         .into_iter()
-        .filter_map(|l| {
-            if source_set.contains(&l.source_object_id) && target_set.contains(&l.target_object_id)
-            {
-                Some(MatrixCell {
-                    source_id: l.source_object_id,
-                    target_id: l.target_object_id,
-                    link_id: l.id,
-                    suspect: l.suspect,
-                })
-            } else if source_set.contains(&l.target_object_id)
-                && target_set.contains(&l.source_object_id)
-            {
-                Some(MatrixCell {
-                    source_id: l.target_object_id,
-                    target_id: l.source_object_id,
-                    link_id: l.id,
-                    suspect: l.suspect,
-                })
-            } else {
-                None
-            }
-        })
+        .map(|l| (l.id, l.source_object_id, l.target_object_id, l.suspect))
         .collect();
 
-    let resp = TraceabilityMatrixResponse {
-        source_objects: source_objects
-            .into_iter()
-            .map(|o| MatrixObject {
-                id: o.id,
-                heading: o.heading,
-                position: o.position,
-            })
-            .collect(),
-        target_objects: target_objects
-            .into_iter()
-            .map(|o| MatrixObject {
-                id: o.id,
-                heading: o.heading,
-                position: o.position,
-            })
-            .collect(),
-        cells,
-    };
-
-    Ok(Json(resp))
+    Ok((
+        source_objects.into_iter().map(MatrixObject::from).collect(),
+        target_objects.into_iter().map(MatrixObject::from).collect(),
+        links,
+    ))
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -160,21 +225,37 @@ pub(crate) struct CoverageResponse {
 
 #[utoipa::path(get, path = "/api/v1/modules/{module_id}/coverage", tag = "Traceability",
     security(("bearer_auth" = [])),
-    params(("module_id" = Uuid, Path, description = "Module ID")),
+    params(
+        ("module_id" = Uuid, Path, description = "Module ID"),
+        CoverageQuery,
+    ),
     responses((status = 200, body = CoverageResponse))
 )]
 #[allow(clippy::cast_precision_loss)]
 pub(crate) async fn get_coverage(
     State(state): State<AppState>,
     Path(module_id): Path<Uuid>,
+    Query(params): Query<CoverageQuery>,
 ) -> Result<Json<CoverageResponse>, AppError> {
-    let objects = object::Entity::find()
-        .filter(object::Column::ModuleId.eq(module_id))
-        .filter(object::Column::DeletedAt.is_null())
-        .all(&state.db)
-        .await?;
+    let snapshot = Snapshot::resolve(&state.db, params.baseline_id, params.baseline_set_id).await?;
 
-    let object_ids: HashSet<Uuid> = objects.iter().map(|o| o.id).collect();
+    let object_ids: HashSet<Uuid> = if let Some(snapshot) = &snapshot {
+        snapshot
+            .objects(&state.db, module_id)
+            .await?
+            .into_iter()
+            .map(|o| o.id)
+            .collect()
+    } else {
+        object::Entity::find()
+            .filter(object::Column::ModuleId.eq(module_id))
+            .filter(object::Column::DeletedAt.is_null())
+            .all(&state.db)
+            .await?
+            .into_iter()
+            .map(|o| o.id)
+            .collect()
+    };
     let total = object_ids.len() as u64;
 
     if total == 0 {
@@ -189,25 +270,38 @@ pub(crate) async fn get_coverage(
         }));
     }
 
-    let id_vec: Vec<Uuid> = object_ids.iter().copied().collect();
-    let links = link::Entity::find()
-        .filter(
-            link::Column::SourceObjectId
-                .is_in(id_vec.clone())
-                .or(link::Column::TargetObjectId.is_in(id_vec)),
-        )
-        .all(&state.db)
-        .await?;
+    // `(source_object_id, target_object_id)`
+    let links: Vec<(Uuid, Uuid)> = if let Some(snapshot) = &snapshot {
+        snapshot
+            .links(&state.db)
+            .await?
+            .into_iter()
+            .map(|l| (l.source_object_id, l.target_object_id))
+            .collect()
+    } else {
+        let id_vec: Vec<Uuid> = object_ids.iter().copied().collect();
+        link::Entity::find()
+            .filter(
+                link::Column::SourceObjectId
+                    .is_in(id_vec.clone())
+                    .or(link::Column::TargetObjectId.is_in(id_vec)),
+            )
+            .all(&state.db)
+            .await?
+            .into_iter()
+            .map(|l| (l.source_object_id, l.target_object_id))
+            .collect()
+    };
 
     let mut has_downstream: HashSet<Uuid> = HashSet::new(); // objects appearing as source
     let mut has_upstream: HashSet<Uuid> = HashSet::new(); // objects appearing as target
 
-    for lnk in &links {
-        if object_ids.contains(&lnk.source_object_id) {
-            let _ = has_downstream.insert(lnk.source_object_id);
+    for (source_id, target_id) in links {
+        if object_ids.contains(&source_id) {
+            let _ = has_downstream.insert(source_id);
         }
-        if object_ids.contains(&lnk.target_object_id) {
-            let _ = has_upstream.insert(lnk.target_object_id);
+        if object_ids.contains(&target_id) {
+            let _ = has_upstream.insert(target_id);
         }
     }
 
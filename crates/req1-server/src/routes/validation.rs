@@ -1,11 +1,12 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, State},
     routing::get,
 };
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
+use req1_core::auth::AuthUser;
 use req1_core::service::validation_service::{ValidationReport, ValidationService};
 
 pub fn routes() -> Router<AppState> {
@@ -19,8 +20,9 @@ pub fn routes() -> Router<AppState> {
 )]
 pub(crate) async fn validate_module(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Path(module_id): Path<Uuid>,
 ) -> Result<Json<ValidationReport>, AppError> {
-    let report = ValidationService::validate(&state.db, module_id).await?;
+    let report = ValidationService::validate(&state.db, module_id, Some(auth_user.id)).await?;
     Ok(Json(report))
 }
@@ -1,12 +1,14 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::view;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
     service::view::{CreateViewInput, UpdateViewInput, ViewService},
@@ -50,11 +52,14 @@ pub(crate) async fn list_views(
 )]
 pub(crate) async fn create_view(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     Json(body): Json<CreateViewInput>,
 ) -> Result<(axum::http::StatusCode, Json<view::Model>), AppError> {
     let input = CreateViewInput { module_id, ..body };
-    let result = ViewService::create(&state.db, input).await?;
+    let txn = state.db.begin().await?;
+    let result = ViewService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -85,10 +90,13 @@ pub(crate) async fn get_view(
 )]
 pub(crate) async fn update_view(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateViewInput>,
 ) -> Result<Json<view::Model>, AppError> {
-    let result = ViewService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = ViewService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -102,8 +110,11 @@ pub(crate) async fn update_view(
 )]
 pub(crate) async fn delete_view(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    ViewService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    ViewService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
@@ -1,14 +1,16 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, State},
     http::StatusCode,
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use serde::Deserialize;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
+use req1_core::service::audit::AuditContext;
 use req1_core::service::webhook::{CreateWebhookInput, UpdateWebhookInput, WebhookService};
 
 pub fn routes() -> Router<AppState> {
@@ -64,11 +66,14 @@ pub(crate) async fn list_webhooks(
 )]
 pub(crate) async fn create_webhook(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(module_id): Path<Uuid>,
     Json(body): Json<CreateWebhookRequest>,
 ) -> Result<(StatusCode, Json<entity::webhook::Model>), AppError> {
+    let txn = state.db.begin().await?;
     let result = WebhookService::create(
-        &state.db,
+        &txn,
+        &audit,
         CreateWebhookInput {
             module_id,
             name: body.name,
@@ -79,6 +84,7 @@ pub(crate) async fn create_webhook(
         },
     )
     .await?;
+    txn.commit().await?;
     Ok((StatusCode::CREATED, Json(result)))
 }
 
@@ -109,11 +115,14 @@ pub(crate) async fn get_webhook(
 )]
 pub(crate) async fn update_webhook(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
     Json(body): Json<UpdateWebhookRequest>,
 ) -> Result<Json<entity::webhook::Model>, AppError> {
+    let txn = state.db.begin().await?;
     let result = WebhookService::update(
-        &state.db,
+        &txn,
+        &audit,
         id,
         UpdateWebhookInput {
             name: body.name,
@@ -124,6 +133,7 @@ pub(crate) async fn update_webhook(
         },
     )
     .await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -137,8 +147,11 @@ pub(crate) async fn update_webhook(
 )]
 pub(crate) async fn delete_webhook(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path((_module_id, id)): Path<(Uuid, Uuid)>,
 ) -> Result<StatusCode, AppError> {
-    WebhookService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    WebhookService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(StatusCode::NO_CONTENT)
 }
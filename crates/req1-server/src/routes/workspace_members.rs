@@ -0,0 +1,126 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    routing::get,
+};
+use sea_orm::TransactionTrait;
+use uuid::Uuid;
+
+use crate::{error::AppError, state::AppState};
+use entity::workspace_member;
+use req1_core::service::audit::AuditContext;
+use req1_core::{
+    PaginatedResponse, Pagination,
+    service::workspace_member::{
+        CreateWorkspaceMemberInput, UpdateWorkspaceMemberInput, WorkspaceMemberService,
+    },
+};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/workspaces/{workspace_id}/members",
+            get(list_members).post(create_member),
+        )
+        .route(
+            "/workspaces/{workspace_id}/members/{id}",
+            get(get_member).patch(update_member).delete(delete_member),
+        )
+}
+
+#[utoipa::path(get, path = "/api/v1/workspaces/{workspace_id}/members", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("workspace_id" = Uuid, Path, description = "Workspace ID"),
+        Pagination,
+    ),
+    responses((status = 200, body = PaginatedResponse<workspace_member::Model>))
+)]
+pub(crate) async fn list_members(
+    State(state): State<AppState>,
+    Path(workspace_id): Path<Uuid>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<PaginatedResponse<workspace_member::Model>>, AppError> {
+    let result =
+        WorkspaceMemberService::list(&state.db, workspace_id, pagination.offset, pagination.limit)
+            .await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(post, path = "/api/v1/workspaces/{workspace_id}/members", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(("workspace_id" = Uuid, Path, description = "Workspace ID")),
+    request_body = CreateWorkspaceMemberInput,
+    responses((status = 201, body = workspace_member::Model), (status = 409, description = "Already a member"))
+)]
+pub(crate) async fn create_member(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path(workspace_id): Path<Uuid>,
+    Json(body): Json<CreateWorkspaceMemberInput>,
+) -> Result<(axum::http::StatusCode, Json<workspace_member::Model>), AppError> {
+    let input = CreateWorkspaceMemberInput {
+        workspace_id,
+        ..body
+    };
+    let txn = state.db.begin().await?;
+    let result = WorkspaceMemberService::create(&txn, &audit, input).await?;
+    txn.commit().await?;
+    Ok((axum::http::StatusCode::CREATED, Json(result)))
+}
+
+#[utoipa::path(get, path = "/api/v1/workspaces/{workspace_id}/members/{id}", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("workspace_id" = Uuid, Path, description = "Workspace ID"),
+        ("id" = Uuid, Path, description = "Membership ID"),
+    ),
+    responses((status = 200, body = workspace_member::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn get_member(
+    State(state): State<AppState>,
+    Path((_workspace_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<workspace_member::Model>, AppError> {
+    let result = WorkspaceMemberService::get(&state.db, id).await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(patch, path = "/api/v1/workspaces/{workspace_id}/members/{id}", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("workspace_id" = Uuid, Path, description = "Workspace ID"),
+        ("id" = Uuid, Path, description = "Membership ID"),
+    ),
+    request_body = UpdateWorkspaceMemberInput,
+    responses((status = 200, body = workspace_member::Model), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn update_member(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path((_workspace_id, id)): Path<(Uuid, Uuid)>,
+    Json(body): Json<UpdateWorkspaceMemberInput>,
+) -> Result<Json<workspace_member::Model>, AppError> {
+    let txn = state.db.begin().await?;
+    let result = WorkspaceMemberService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(delete, path = "/api/v1/workspaces/{workspace_id}/members/{id}", tag = "Permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("workspace_id" = Uuid, Path, description = "Workspace ID"),
+        ("id" = Uuid, Path, description = "Membership ID"),
+    ),
+    responses((status = 204, description = "Removed"), (status = 404, description = "Not found"))
+)]
+pub(crate) async fn delete_member(
+    State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Path((_workspace_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<axum::http::StatusCode, AppError> {
+    let txn = state.db.begin().await?;
+    WorkspaceMemberService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
@@ -1,15 +1,22 @@
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
     routing::get,
 };
+use sea_orm::TransactionTrait;
 use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use entity::workspace;
+use req1_core::service::audit::AuditContext;
 use req1_core::{
     PaginatedResponse, Pagination,
-    service::workspace::{CreateWorkspaceInput, UpdateWorkspaceInput, WorkspaceService},
+    auth::AuthUser,
+    service::{
+        permission::PermissionService,
+        workspace::{CreateWorkspaceInput, UpdateWorkspaceInput, WorkspaceService},
+        workspace_member::{CreateWorkspaceMemberInput, WorkspaceMemberService},
+    },
 };
 
 pub fn routes() -> Router<AppState> {
@@ -30,9 +37,13 @@ pub fn routes() -> Router<AppState> {
 )]
 pub(crate) async fn list_workspaces(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
     Query(pagination): Query<Pagination>,
 ) -> Result<Json<PaginatedResponse<workspace::Model>>, AppError> {
-    let result = WorkspaceService::list(&state.db, pagination.offset, pagination.limit).await?;
+    let visible = PermissionService::visible_workspace_ids(&state.db, &auth_user).await?;
+    let result =
+        WorkspaceService::list_visible(&state.db, visible, pagination.offset, pagination.limit)
+            .await?;
     Ok(Json(result))
 }
 
@@ -43,9 +54,24 @@ pub(crate) async fn list_workspaces(
 )]
 pub(crate) async fn create_workspace(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
+    Extension(auth_user): Extension<AuthUser>,
     Json(body): Json<CreateWorkspaceInput>,
 ) -> Result<(axum::http::StatusCode, Json<workspace::Model>), AppError> {
-    let result = WorkspaceService::create(&state.db, body).await?;
+    let txn = state.db.begin().await?;
+    let result = WorkspaceService::create(&txn, &audit, body).await?;
+    // The creator administers the new workspace
+    let _ = WorkspaceMemberService::create(
+        &txn,
+        &audit,
+        CreateWorkspaceMemberInput {
+            workspace_id: result.id,
+            user_id: auth_user.id,
+            role: Some("admin".to_owned()),
+        },
+    )
+    .await?;
+    txn.commit().await?;
     Ok((axum::http::StatusCode::CREATED, Json(result)))
 }
 
@@ -70,10 +96,13 @@ pub(crate) async fn get_workspace(
 )]
 pub(crate) async fn update_workspace(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
     Json(body): Json<UpdateWorkspaceInput>,
 ) -> Result<Json<workspace::Model>, AppError> {
-    let result = WorkspaceService::update(&state.db, id, body).await?;
+    let txn = state.db.begin().await?;
+    let result = WorkspaceService::update(&txn, &audit, id, body).await?;
+    txn.commit().await?;
     Ok(Json(result))
 }
 
@@ -84,8 +113,11 @@ pub(crate) async fn update_workspace(
 )]
 pub(crate) async fn delete_workspace(
     State(state): State<AppState>,
+    Extension(audit): Extension<AuditContext>,
     Path(id): Path<Uuid>,
 ) -> Result<axum::http::StatusCode, AppError> {
-    WorkspaceService::delete(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    WorkspaceService::delete(&txn, &audit, id).await?;
+    txn.commit().await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
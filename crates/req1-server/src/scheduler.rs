@@ -1,8 +1,12 @@
-use sea_orm::DatabaseConnection;
+use sea_orm::{DatabaseConnection, TransactionTrait};
 use std::time::Duration;
 use tokio::time;
 
-use req1_core::scripting::engine::ScriptEngine;
+use req1_core::error::CoreError;
+use req1_core::scripting::apply::apply_mutations;
+use req1_core::scripting::engine::{Mutation, ScriptEngine};
+use req1_core::scripting::world::WorldAccess;
+use req1_core::service::audit::AuditContext;
 use req1_core::service::object::load_world;
 use req1_core::service::scheduler::SchedulerService;
 
@@ -19,7 +23,8 @@ pub fn spawn_scheduler(db: DatabaseConnection) {
     }));
 }
 
-async fn run_due_scripts(db: &DatabaseConnection) -> Result<(), Box<dyn std::error::Error>> {
+/// Run every enabled scheduled script whose next run is due.
+pub async fn run_due_scripts(db: &DatabaseConnection) -> Result<(), Box<dyn std::error::Error>> {
     let due_scripts = SchedulerService::find_due_scripts(db).await?;
 
     for script in due_scripts {
@@ -47,7 +52,18 @@ async fn run_due_scripts(db: &DatabaseConnection) -> Result<(), Box<dyn std::err
             }
         };
 
-        match ScriptEngine::run_action(&source, &world) {
+        let access = WorldAccess::new(db, None, module_id);
+        let result = match ScriptEngine::run_action(&source, &world, &access).await {
+            Ok(result) => SchedulerService::record_failed_runs(
+                db,
+                apply_scheduled(db, &script.name, &access, module_id, &result.mutations),
+            )
+            .await
+            .map(|()| result),
+            Err(e) => Err(e),
+        };
+
+        match result {
             Ok(result) => {
                 let output_text = if result.output.is_empty() {
                     None
@@ -65,14 +81,7 @@ async fn run_due_scripts(db: &DatabaseConnection) -> Result<(), Box<dyn std::err
                 .await;
             }
             Err(e) => {
-                let _ = SchedulerService::record_execution_finish(
-                    db,
-                    execution,
-                    "error",
-                    None,
-                    Some(e.to_string()),
-                )
-                .await;
+                let _ = SchedulerService::record_execution_failure(db, execution, &e).await;
             }
         }
 
@@ -81,3 +90,27 @@ async fn run_due_scripts(db: &DatabaseConnection) -> Result<(), Box<dyn std::err
 
     Ok(())
 }
+
+/// Apply a scheduled run's mutations in one transaction, attributed to the script.
+async fn apply_scheduled(
+    db: &DatabaseConnection,
+    script_name: &str,
+    access: &WorldAccess<'_, DatabaseConnection>,
+    module_id: uuid::Uuid,
+    mutations: &[Mutation],
+) -> Result<(), CoreError> {
+    if mutations.is_empty() {
+        return Ok(());
+    }
+    let txn = db.begin().await?;
+    apply_mutations(
+        &txn,
+        &AuditContext::system().for_script(script_name),
+        access,
+        module_id,
+        mutations,
+    )
+    .await?;
+    txn.commit().await?;
+    Ok(())
+}
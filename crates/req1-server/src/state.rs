@@ -1,3 +1,6 @@
+use std::sync::{Arc, OnceLock};
+
+use axum::Router;
 use sea_orm::DatabaseConnection;
 
 use crate::config::Config;
@@ -7,4 +10,6 @@ use crate::config::Config;
 pub struct AppState {
     pub db: DatabaseConnection,
     pub config: Config,
+    /// The REST router, built on first use, that MCP tool calls are replayed against.
+    pub api: Arc<OnceLock<Router>>,
 }
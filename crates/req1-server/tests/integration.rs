@@ -16,17 +16,23 @@ mod integration {
     pub mod common;
 
     mod attributes;
+    mod audit;
     mod baselines;
     mod dashboards;
     mod docx_import;
     mod health;
     mod impact;
     mod links;
+    mod mcp;
     mod modules;
     mod objects;
+    mod oslc;
+    mod permissions;
     mod publish;
+    mod reqif_merge;
     mod reviews;
     mod scripts;
+    mod sysml;
     mod templates;
     mod users;
     mod workspaces;
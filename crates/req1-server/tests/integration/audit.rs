@@ -0,0 +1,127 @@
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use super::common::{admin_client, api, create_module, spawn_server};
+
+#[tokio::test]
+async fn test_mutations_are_audited() {
+    let base = spawn_server().await;
+    let client = admin_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+
+    let me: Value = client
+        .get(format!("{}/auth/me", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let obj: Value = client
+        .post(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .header("x-forwarded-for", "203.0.113.7, 10.0.0.1")
+        .json(&json!({"heading": "Audited"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let obj_id = obj["id"].as_str().unwrap();
+
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{obj_id}", api(&base)))
+        .json(&json!({"heading": "Audited (edited)"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .get(format!("{}/audit-log?entity_id={obj_id}", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let log: Value = res.json().await.unwrap();
+    let items = log["items"].as_array().unwrap();
+
+    let create = items.iter().find(|i| i["action"] == "create").unwrap();
+    assert_eq!(create["entity_type"], "object");
+    assert_eq!(create["user_id"], me["id"]);
+    assert_eq!(create["ip_address"], "203.0.113.7");
+    assert!(create["details"]["before"].is_null());
+    assert_eq!(create["details"]["after"]["heading"], "Audited");
+
+    let update = items.iter().find(|i| i["action"] == "update").unwrap();
+    assert_eq!(update["details"]["before"]["heading"], "Audited");
+    assert_eq!(update["details"]["after"]["heading"], "Audited (edited)");
+
+    let res = client
+        .get(format!(
+            "{}/audit-log?entity_id={mod_id}&action=create",
+            api(&base)
+        ))
+        .send()
+        .await
+        .unwrap();
+    let log: Value = res.json().await.unwrap();
+    assert_eq!(log["items"].as_array().unwrap().len(), 1);
+    assert_eq!(log["items"][0]["entity_type"], "module");
+}
+
+#[tokio::test]
+async fn test_webhook_secret_is_not_audited() {
+    let base = spawn_server().await;
+    let client = admin_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let hooks_url = format!("{}/modules/{mod_id}/webhooks", api(&base));
+
+    let res = client
+        .post(&hooks_url)
+        .json(&json!({
+            "name": "CI",
+            "url": "https://ci.example/hook",
+            "secret": "s3cret-create",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let hook: Value = res.json().await.unwrap();
+    let hook_id = hook["id"].as_str().unwrap();
+
+    let res = client
+        .patch(format!("{hooks_url}/{hook_id}"))
+        .json(&json!({"secret": "s3cret-update"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = client
+        .delete(format!("{hooks_url}/{hook_id}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let log: Value = client
+        .get(format!("{}/audit-log?entity_id={hook_id}", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = log["items"].as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    for item in items {
+        let details = item["details"].to_string();
+        assert!(!details.contains("s3cret"), "{}", item["action"]);
+    }
+    let create = items.iter().find(|i| i["action"] == "create").unwrap();
+    assert_eq!(create["details"]["after"]["secret"], "***");
+}
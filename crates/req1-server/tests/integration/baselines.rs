@@ -1,7 +1,10 @@
 use axum::http::StatusCode;
 use serde_json::{Value, json};
 
-use super::common::{api, authed_client, create_module, spawn_server};
+use super::common::{
+    admin_client, api, authed_client, authed_client_as, create_link_type, create_module,
+    create_object, spawn_server,
+};
 
 #[tokio::test]
 async fn test_baseline_crud() {
@@ -137,11 +140,141 @@ async fn test_baseline_diff() {
 }
 
 #[tokio::test]
-async fn test_baseline_set_crud() {
+async fn test_baseline_diff_links_and_structure() {
     let base = spawn_server().await;
     let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let bl_url = format!("{}/modules/{mod_id}/baselines", api(&base));
+    let lt_id = create_link_type(&base).await;
+
+    let parent = create_object(&client, &base, mod_id, "Parent").await;
+    let parent_id = parent["id"].as_str().unwrap();
+    let child = create_object(&client, &base, mod_id, "Child").await;
+    let child_id = child["id"].as_str().unwrap();
+
+    let snapshot = |name: &'static str| {
+        let client = client.clone();
+        let bl_url = bl_url.clone();
+        async move {
+            let bl: Value = client
+                .post(&bl_url)
+                .json(&json!({"name": name}))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            bl
+        }
+    };
+
+    let bl_a = snapshot("before").await;
+    assert_eq!(bl_a["links"].as_array().unwrap().len(), 0);
+
+    let link: Value = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": parent_id,
+            "target_object_id": child_id,
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap();
+
+    let res = client
+        .patch(format!(
+            "{}/modules/{mod_id}/objects/{child_id}",
+            api(&base)
+        ))
+        .json(&json!({"parent_id": parent_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let bl_b = snapshot("after").await;
+    assert_eq!(bl_b["links"].as_array().unwrap().len(), 1);
+    assert_eq!(bl_b["links"][0]["suspect"], false);
+
+    let diff: Value = client
+        .get(format!(
+            "{}/modules/{mod_id}/baseline-diff?a={}&b={}",
+            api(&base),
+            bl_a["id"].as_str().unwrap(),
+            bl_b["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(diff["links_added"].as_array().unwrap().len(), 1);
+    assert_eq!(diff["links_added"][0]["link_id"], link_id);
+    assert_eq!(diff["links_removed"].as_array().unwrap().len(), 0);
+    let moved = diff["moved"].as_array().unwrap();
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved[0]["object_id"], child_id);
+    assert!(moved[0]["parent_id_a"].is_null());
+    assert_eq!(moved[0]["parent_id_b"], parent_id);
+
+    let res = client
+        .delete(format!("{}/links/{link_id}", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let bl_c = snapshot("unlinked").await;
+    let diff: Value = client
+        .get(format!(
+            "{}/modules/{mod_id}/baseline-diff?a={}&b={}",
+            api(&base),
+            bl_b["id"].as_str().unwrap(),
+            bl_c["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(diff["links_removed"].as_array().unwrap().len(), 1);
+    assert_eq!(diff["moved"].as_array().unwrap().len(), 0);
+
+    // The frozen link graph survives the link's deletion
+    let res = client
+        .get(format!("{bl_url}/{}", bl_b["id"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    let bl_b: Value = res.json().await.unwrap();
+    assert_eq!(bl_b["links"][0]["link_id"], link_id);
+}
+
+#[tokio::test]
+async fn test_baseline_set_crud() {
+    let base = spawn_server().await;
+    let client = admin_client(&base).await;
     let url = format!("{}/baseline-sets", api(&base));
 
+    // Baseline sets span workspaces, so only admins manage them
+    let res = authed_client(&base)
+        .await
+        .post(&url)
+        .json(&json!({"name": "Release 1.0", "version": "1.0.0"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
     let res = client
         .post(&url)
         .json(&json!({
@@ -193,3 +326,769 @@ async fn test_baseline_set_crud() {
     let res = client.get(format!("{url}/{bs_id}")).send().await.unwrap();
     assert_eq!(res.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_traceability_against_baseline() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let lt_id = create_link_type(&base).await;
+
+    let src = create_object(&client, &base, mod_id, "Source").await;
+    let src_id = src["id"].as_str().unwrap();
+    let tgt = create_object(&client, &base, mod_id, "Target").await;
+    let tgt_id = tgt["id"].as_str().unwrap();
+
+    let link: Value = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": src_id,
+            "target_object_id": tgt_id,
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap();
+
+    let bl: Value = client
+        .post(format!("{}/modules/{mod_id}/baselines", api(&base)))
+        .json(&json!({"name": "frozen"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let bl_id = bl["id"].as_str().unwrap();
+
+    let res = client
+        .delete(format!("{}/links/{link_id}", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{src_id}", api(&base)))
+        .json(&json!({"heading": "Source (renamed)"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let get = |url: String| {
+        let client = client.clone();
+        async move {
+            let res = client.get(url).send().await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            res.json::<Value>().await.unwrap()
+        }
+    };
+
+    let coverage_url = format!("{}/modules/{mod_id}/coverage", api(&base));
+    let live = get(coverage_url.clone()).await;
+    assert_eq!(live["with_any_link"], 0);
+    let frozen = get(format!("{coverage_url}?baseline_id={bl_id}")).await;
+    assert_eq!(frozen["total_objects"], 2);
+    assert_eq!(frozen["with_any_link"], 2);
+
+    let matrix = get(format!(
+        "{}/traceability-matrix?source_module_id={mod_id}&target_module_id={mod_id}&baseline_id={bl_id}",
+        api(&base)
+    ))
+    .await;
+    assert_eq!(matrix["cells"].as_array().unwrap().len(), 1);
+    assert_eq!(matrix["cells"][0]["link_id"], link_id);
+    let source = matrix["source_objects"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|o| o["id"] == src_id)
+        .unwrap();
+    assert_eq!(source["heading"], "Source");
+
+    let impact_url = format!("{}/object-impact/{src_id}", api(&base));
+    let live = get(impact_url.clone()).await;
+    assert_eq!(live["objects"].as_array().unwrap().len(), 0);
+    let frozen = get(format!("{impact_url}?baseline_id={bl_id}")).await;
+    assert_eq!(frozen["objects"].as_array().unwrap().len(), 1);
+    assert_eq!(frozen["objects"][0]["id"], tgt_id);
+
+    let res = client
+        .get(format!(
+            "{coverage_url}?baseline_id={bl_id}&baseline_set_id={bl_id}"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_baseline_set_consistency() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, mod_a) = create_module(&client, &base).await;
+    let (_ws, _proj, mod_b) = create_module(&client, &base).await;
+    let mod_a = mod_a["id"].as_str().unwrap().to_owned();
+    let mod_b = mod_b["id"].as_str().unwrap().to_owned();
+    let lt_id = create_link_type(&base).await;
+
+    let obj_a = create_object(&client, &base, &mod_a, "System req").await;
+    let obj_b = create_object(&client, &base, &mod_b, "Software req").await;
+    let obj_b_id = obj_b["id"].as_str().unwrap();
+    let res = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": obj_a["id"],
+            "target_object_id": obj_b_id,
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let set: Value = admin_client(&base)
+        .await
+        .post(format!("{}/baseline-sets", api(&base)))
+        .json(&json!({"name": "System release", "version": "1.0.0"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let set_id = set["id"].as_str().unwrap();
+
+    let add_baseline = |module_id: String, name: &'static str| {
+        let client = client.clone();
+        let url = format!("{}/modules/{module_id}/baselines", api(&base));
+        async move {
+            let res = client
+                .post(url)
+                .json(&json!({"name": name, "baseline_set_id": set_id}))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::CREATED);
+            res.json::<Value>().await.unwrap()
+        }
+    };
+    let check = || {
+        let client = client.clone();
+        let url = format!("{}/baseline-sets/{set_id}/consistency", api(&base));
+        async move {
+            let res = client.get(url).send().await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            res.json::<Value>().await.unwrap()
+        }
+    };
+
+    let bl_a1 = add_baseline(mod_a.clone(), "A 1").await;
+    let report = check().await;
+    assert_eq!(report["consistent"], false);
+    assert_eq!(report["missing_modules"], json!([mod_b]));
+    assert_eq!(report["issues"][0]["kind"], "module_missing");
+
+    let res = client
+        .patch(format!("{}/modules/{mod_b}/objects/{obj_b_id}", api(&base)))
+        .json(&json!({"heading": "Software req (rev B)"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let _ = add_baseline(mod_b.clone(), "B 1").await;
+
+    let report = check().await;
+    assert_eq!(report["consistent"], false);
+    let issues = report["issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0]["kind"], "version_mismatch");
+    assert_eq!(issues[0]["object_id"], obj_b_id);
+    assert_eq!(issues[0]["expected_version"], 1);
+    assert_eq!(issues[0]["baselined_version"], 2);
+
+    let bl_a2 = add_baseline(mod_a.clone(), "A 2").await;
+    let report = check().await;
+    assert_eq!(report["issues"].as_array().unwrap().len(), 0);
+    assert_eq!(report["consistent"], false);
+    let superseded = report["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["baseline_id"] == bl_a1["id"])
+        .unwrap();
+    assert_eq!(superseded["superseded_in_set"], true);
+    assert_eq!(superseded["superseded_by"], bl_a2["id"]);
+
+    let pairs = report["module_pairs"].as_array().unwrap();
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0]["source_module_id"], mod_a.as_str());
+    assert_eq!(pairs[0]["target_module_id"], mod_b.as_str());
+    assert_eq!(pairs[0]["links"], 1);
+    assert_eq!(pairs[0]["inconsistent_links"], 0);
+
+    // The report needs read access to every member module
+    let stranger = authed_client_as(
+        &base,
+        &format!("set-outsider-{}@test.com", uuid::Uuid::now_v7()),
+    )
+    .await;
+    let res = stranger
+        .get(format!("{}/baseline-sets/{set_id}/consistency", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_baseline_diff_reports_moves() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj_url = format!("{}/modules/{mod_id}/objects", api(&base));
+    let bl_url = format!("{}/modules/{mod_id}/baselines", api(&base));
+
+    let mut ids = Vec::new();
+    for (heading, position) in [("Chapter", 0), ("Requirement", 1)] {
+        let obj: Value = client
+            .post(&obj_url)
+            .json(&json!({"heading": heading, "position": position}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(obj["id"].as_str().unwrap().to_owned());
+    }
+    let (chapter_id, req_id) = (&ids[0], &ids[1]);
+
+    let bl_a: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "flat"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let res = client
+        .post(format!("{obj_url}/{req_id}/move"))
+        .json(&json!({"action": "indent"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let history: Value = client
+        .get(format!("{obj_url}/{req_id}/history"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let latest = &history["items"][0];
+    assert_eq!(latest["change_type"], "move");
+    assert_eq!(latest["version"], 2);
+    assert_eq!(latest["parent_id"], chapter_id.as_str());
+    assert_eq!(latest["level"], "1.1");
+
+    let bl_b: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "nested"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let diff: Value = client
+        .get(format!(
+            "{}/modules/{mod_id}/baseline-diff?a={}&b={}",
+            api(&base),
+            bl_a["id"].as_str().unwrap(),
+            bl_b["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(diff["modified"].as_array().unwrap().len(), 0);
+    let moved = diff["moved"].as_array().unwrap();
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved[0]["object_id"], req_id.as_str());
+    assert_eq!(moved[0]["level_a"], "2");
+    assert_eq!(moved[0]["level_b"], "1.1");
+}
+
+#[tokio::test]
+async fn test_baseline_diff_insert_reports_no_moves() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj_url = format!("{}/modules/{mod_id}/objects", api(&base));
+    let bl_url = format!("{}/modules/{mod_id}/baselines", api(&base));
+
+    for (heading, position) in [("First", 0), ("Second", 1), ("Third", 2)] {
+        let res = client
+            .post(&obj_url)
+            .json(&json!({"heading": heading, "position": position}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+    }
+    let bl_a: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "before"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Inserting at the top shifts the position of every existing sibling
+    let inserted = create_object(&client, &base, mod_id, "Inserted").await;
+    let inserted_id = inserted["id"].as_str().unwrap();
+    let res = client
+        .post(format!("{obj_url}/{inserted_id}/move"))
+        .json(&json!({"action": "move_to", "parent_id": null, "position": 0}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let bl_b: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "after"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let diff: Value = client
+        .get(format!(
+            "{}/modules/{mod_id}/baseline-diff?a={}&b={}",
+            api(&base),
+            bl_a["id"].as_str().unwrap(),
+            bl_b["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(diff["added"].as_array().unwrap().len(), 1);
+    assert_eq!(diff["added"][0]["object_id"], inserted_id);
+    assert_eq!(diff["modified"].as_array().unwrap().len(), 0);
+    assert_eq!(diff["moved"].as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_restore_from_baseline() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj_url = format!("{}/modules/{mod_id}/objects", api(&base));
+    let bl_url = format!("{}/modules/{mod_id}/baselines", api(&base));
+
+    let obj = create_object(&client, &base, mod_id, "Original").await;
+    let obj_id = obj["id"].as_str().unwrap();
+    let bl: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "good"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let restore_url = format!("{bl_url}/{}/restore", bl["id"].as_str().unwrap());
+
+    let res = client
+        .patch(format!("{obj_url}/{obj_id}"))
+        .json(&json!({"heading": "Broken by import"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let imported = create_object(&client, &base, mod_id, "Imported").await;
+    let imported_id = imported["id"].as_str().unwrap();
+
+    let res = client
+        .post(&restore_url)
+        .json(&json!({"dry_run": true}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let plan: Value = res.json().await.unwrap();
+    assert_eq!(plan["dry_run"], true);
+    let changes = plan["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 2);
+    let revert = changes.iter().find(|c| c["object_id"] == obj_id).unwrap();
+    assert_eq!(revert["actions"], json!(["revert"]));
+    assert_eq!(revert["heading"], "Original");
+    let remove = changes
+        .iter()
+        .find(|c| c["object_id"] == imported_id)
+        .unwrap();
+    assert_eq!(remove["actions"], json!(["remove"]));
+
+    let current: Value = client
+        .get(format!("{obj_url}/{obj_id}"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(current["heading"], "Broken by import");
+
+    let res = client
+        .post(&restore_url)
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let restored: Value = client
+        .get(format!("{obj_url}/{obj_id}"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(restored["heading"], "Original");
+    assert_eq!(restored["current_version"], 3);
+
+    let history: Value = client
+        .get(format!("{obj_url}/{obj_id}/history"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(history["items"][0]["change_type"], "restore");
+
+    let list: Value = client
+        .get(&obj_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = list["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], obj_id);
+
+    let res = client
+        .post(&restore_url)
+        .json(&json!({"object_ids": [imported_id]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_restore_partial_edit_and_position() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj_url = format!("{}/modules/{mod_id}/objects", api(&base));
+    let bl_url = format!("{}/modules/{mod_id}/baselines", api(&base));
+
+    let mut ids = Vec::new();
+    for (position, heading) in ["Alpha", "Beta", "Gamma"].into_iter().enumerate() {
+        let obj: Value = client
+            .post(&obj_url)
+            .json(&json!({
+                "heading": heading,
+                "body": format!("{heading} body"),
+                "position": position,
+                "attributes": {"priority": "high", "owner": "alice"},
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(obj["id"].as_str().unwrap().to_owned());
+    }
+    let alpha_url = format!("{obj_url}/{}", ids[0]);
+
+    // The baselined versions replaced the attribute map
+    for (id, owner) in [(&ids[0], "bob"), (&ids[2], "dave")] {
+        let res = client
+            .patch(format!("{obj_url}/{id}"))
+            .json(&json!({"attributes": {"owner": owner}}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+    let bl: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "good"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let restore_url = format!("{bl_url}/{}/restore", bl["id"].as_str().unwrap());
+
+    let res = client
+        .patch(&alpha_url)
+        .json(&json!({"heading": "Alpha (edited)", "attributes": {"owner": "carol"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = client
+        .post(format!("{obj_url}/{}/move", ids[1]))
+        .json(&json!({"action": "up"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Gamma is unchanged since the baseline
+    let plan: Value = client
+        .post(&restore_url)
+        .json(&json!({"dry_run": true}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(
+        !plan["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|c| c["object_id"] == ids[2].as_str())
+    );
+
+    let res = client
+        .post(&restore_url)
+        .json(&json!({"object_ids": [ids[0]]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let result: Value = res.json().await.unwrap();
+    assert_eq!(result["changes"][0]["actions"], json!(["revert", "move"]));
+
+    let alpha: Value = client
+        .get(&alpha_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(alpha["heading"], "Alpha");
+    assert_eq!(alpha["body"], "Alpha body");
+    assert_eq!(alpha["attributes"], json!({"owner": "bob"}));
+
+    let list: Value = client
+        .get(&obj_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let mut positions: Vec<(i64, String)> = list["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| {
+            (
+                o["position"].as_i64().unwrap(),
+                o["id"].as_str().unwrap().to_owned(),
+            )
+        })
+        .collect();
+    positions.sort();
+    assert_eq!(
+        positions,
+        vec![
+            (0, ids[0].clone()),
+            (1, ids[1].clone()),
+            (2, ids[2].clone()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_restore_keeps_deleted_attribute_deleted() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj_url = format!("{}/modules/{mod_id}/objects", api(&base));
+    let bl_url = format!("{}/modules/{mod_id}/baselines", api(&base));
+
+    let obj: Value = client
+        .post(&obj_url)
+        .json(&json!({
+            "heading": "Req",
+            "attributes": {"priority": "high", "owner": "alice"},
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let url = format!("{obj_url}/{}", obj["id"].as_str().unwrap());
+
+    // Drop "priority" before the baseline
+    let res = client
+        .patch(&url)
+        .json(&json!({"attributes": {"owner": "bob"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bl: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "good"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let res = client
+        .patch(&url)
+        .json(&json!({"attributes": {"priority": "low", "owner": "carol"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .post(format!("{bl_url}/{}/restore", bl["id"].as_str().unwrap()))
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let restored: Value = client.get(&url).send().await.unwrap().json().await.unwrap();
+    assert_eq!(restored["attributes"], json!({"owner": "bob"}));
+}
+
+#[tokio::test]
+async fn test_baseline_diff_move_after_partial_edit() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj_url = format!("{}/modules/{mod_id}/objects", api(&base));
+    let bl_url = format!("{}/modules/{mod_id}/baselines", api(&base));
+
+    let mut ids = Vec::new();
+    for (heading, position) in [("Chapter", 0), ("Requirement", 1)] {
+        let obj: Value = client
+            .post(&obj_url)
+            .json(&json!({
+                "heading": heading,
+                "body": format!("{heading} body"),
+                "position": position,
+                "attributes": {"owner": "alice"},
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(obj["id"].as_str().unwrap().to_owned());
+    }
+    let req_id = &ids[1];
+
+    // The baselined version's history row carries the attributes only
+    let res = client
+        .patch(format!("{obj_url}/{req_id}"))
+        .json(&json!({"attributes": {"owner": "bob"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bl_a: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "flat"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let res = client
+        .post(format!("{obj_url}/{req_id}/move"))
+        .json(&json!({"action": "indent"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bl_b: Value = client
+        .post(&bl_url)
+        .json(&json!({"name": "nested"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let diff: Value = client
+        .get(format!(
+            "{}/modules/{mod_id}/baseline-diff?a={}&b={}",
+            api(&base),
+            bl_a["id"].as_str().unwrap(),
+            bl_b["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(diff["modified"].as_array().unwrap().len(), 0);
+    let moved = diff["moved"].as_array().unwrap();
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved[0]["object_id"], req_id.as_str());
+}
@@ -3,11 +3,13 @@
 
 use axum::http::StatusCode;
 use reqwest::Client;
-use sea_orm::Database;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection};
 use sea_orm_migration::MigratorTrait;
 use serde_json::{Value, json};
 use std::fmt::Write;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tower_http::cors::CorsLayer;
 
@@ -15,15 +17,31 @@ use req1_server::config::Config;
 use req1_server::routes;
 use req1_server::state::AppState;
 
+use req1_core::scripting::engine::{ScriptEngine, ScriptLimits};
+use req1_core::service::audit::AuditContext;
+use req1_core::service::auth::AuthService;
 use req1_core::service::project_template::ProjectTemplateService;
 
-/// Spin up a test server on a random port and return its base URL.
-pub async fn spawn_server() -> String {
-    let _ = dotenvy::dotenv();
+/// Global admin account created by `spawn_server`, used by `admin_client`.
+pub const ADMIN_EMAIL: &str = "admin-test@example.com";
 
-    let database_url = std::env::var("TEST_DATABASE_URL")
+fn database_url() -> String {
+    let _ = dotenvy::dotenv();
+    std::env::var("TEST_DATABASE_URL")
         .or_else(|_| std::env::var("DATABASE_URL"))
-        .expect("DATABASE_URL or TEST_DATABASE_URL must be set");
+        .expect("DATABASE_URL or TEST_DATABASE_URL must be set")
+}
+
+/// Direct connection to the test database, for driving background jobs.
+pub async fn connect_db() -> DatabaseConnection {
+    Database::connect(&database_url())
+        .await
+        .expect("failed to connect to test database")
+}
+
+/// Spin up a test server on a random port and return its base URL.
+pub async fn spawn_server() -> String {
+    let database_url = database_url();
 
     let db = Database::connect(&database_url)
         .await
@@ -35,6 +53,21 @@ pub async fn spawn_server() -> String {
 
     let _ = ProjectTemplateService::seed_builtins(&db).await;
 
+    let _ = AuthService::register(
+        &db,
+        &AuditContext::system(),
+        ADMIN_EMAIL,
+        "password123",
+        "Test Admin",
+    )
+    .await;
+    let _ = db
+        .execute_unprepared(&format!(
+            "UPDATE app_user SET role = 'admin' WHERE email = '{ADMIN_EMAIL}'"
+        ))
+        .await
+        .expect("failed to promote test admin");
+
     let config = Config {
         database_url,
         redis_url: None,
@@ -44,9 +77,22 @@ pub async fn spawn_server() -> String {
         build_sha: None,
         jwt_secret: "test-secret".to_string(),
         jwt_expiration_hours: 24,
+        external_link_roots: vec![std::env::temp_dir()],
+        script_limits: ScriptLimits {
+            timeout: Duration::from_secs(1),
+            ..ScriptLimits::default()
+        },
+        // Test clients connect from loopback and pose as one proxy hop via 10.0.0.1
+        trusted_proxies: vec![[127, 0, 0, 1].into(), [10, 0, 0, 1].into()],
+        oslc_consumer_origins: vec!["https://consumer.example".to_owned()],
     };
+    ScriptEngine::configure(config.script_limits);
 
-    let state = AppState { db, config };
+    let state = AppState {
+        db,
+        config,
+        api: Arc::default(),
+    };
     let app = routes::router(state).layer(CorsLayer::permissive());
 
     let listener = TcpListener::bind("127.0.0.1:0")
@@ -55,7 +101,11 @@ pub async fn spawn_server() -> String {
     let addr: SocketAddr = listener.local_addr().expect("failed to get local addr");
 
     let _ = tokio::spawn(async move {
-        let _ = axum::serve(listener, app).await;
+        let _ = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await;
     });
 
     format!("http://{addr}")
@@ -68,13 +118,24 @@ pub fn api(base: &str) -> String {
 /// Register a test user, log in, and return a reqwest Client with the Bearer
 /// token set as a default header so every request is authenticated.
 pub async fn authed_client(base: &str) -> Client {
+    authed_client_as(base, "test@example.com").await
+}
+
+/// Log in as the global admin created by `spawn_server`.
+pub async fn admin_client(base: &str) -> Client {
+    authed_client_as(base, ADMIN_EMAIL).await
+}
+
+/// Like `authed_client`, but for the given email. The user is registered
+/// with the default `viewer` role if it does not exist yet.
+pub async fn authed_client_as(base: &str, email: &str) -> Client {
     let anon = Client::new();
     let url = format!("{}/auth", api(base));
 
     let _ = anon
         .post(format!("{url}/register"))
         .json(&json!({
-            "email": "test@example.com",
+            "email": email,
             "password": "password123",
             "display_name": "Test User"
         }))
@@ -85,7 +146,7 @@ pub async fn authed_client(base: &str) -> Client {
     let res = anon
         .post(format!("{url}/login"))
         .json(&json!({
-            "email": "test@example.com",
+            "email": email,
             "password": "password123"
         }))
         .send()
@@ -177,8 +238,10 @@ pub async fn create_two_objects(client: &Client, base: &str) -> (String, String,
     )
 }
 
-pub async fn create_link_type(client: &Client, base: &str) -> String {
-    let lt: Value = client
+/// Create a link type as the global admin; link types are a global catalog.
+pub async fn create_link_type(base: &str) -> String {
+    let lt: Value = admin_client(base)
+        .await
         .post(format!("{}/link-types", api(base)))
         .json(&json!({"name": format!("lt-{}", uuid::Uuid::now_v7())}))
         .send()
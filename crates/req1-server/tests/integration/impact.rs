@@ -2,7 +2,7 @@ use axum::http::StatusCode;
 use serde_json::{Value, json};
 
 use super::common::{
-    api, authed_client, create_link_type, create_module, create_object, spawn_server,
+    admin_client, api, authed_client, create_link_type, create_module, create_object, spawn_server,
 };
 
 #[tokio::test]
@@ -20,7 +20,7 @@ async fn test_impact_analysis() {
     let obj2_id = obj2["id"].as_str().unwrap();
     let obj3_id = obj3["id"].as_str().unwrap();
 
-    let lt_id = create_link_type(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
 
     // A -> B -> C
     let _ = client
@@ -64,6 +64,109 @@ async fn test_impact_analysis() {
     assert_eq!(edges.len(), 2);
 }
 
+#[tokio::test]
+async fn test_impact_hides_unreadable_modules() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let admin = admin_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let (_ws, _proj, secret) = create_module(&admin, &base).await;
+    let root = create_object(&client, &base, module["id"].as_str().unwrap(), "ROOT").await;
+    let hidden = create_object(&admin, &base, secret["id"].as_str().unwrap(), "SECRET").await;
+    let root_id = root["id"].as_str().unwrap();
+
+    let lt_id = create_link_type(&base).await;
+    let res = admin
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": root_id,
+            "target_object_id": hidden["id"],
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let impact: Value = client
+        .get(format!("{}/object-impact/{root_id}", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(impact["objects"].as_array().unwrap().is_empty());
+    assert!(impact["edges"].as_array().unwrap().is_empty());
+
+    let impact: Value = admin
+        .get(format!("{}/object-impact/{root_id}", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(impact["objects"][0]["heading"], "SECRET");
+}
+
+#[tokio::test]
+async fn test_impact_baseline_heading_after_partial_edit() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj1 = create_object(&client, &base, mod_id, "IMPACT-A").await;
+    let obj2 = create_object(&client, &base, mod_id, "IMPACT-B").await;
+    let obj1_id = obj1["id"].as_str().unwrap();
+    let obj2_id = obj2["id"].as_str().unwrap();
+
+    let lt_id = create_link_type(&base).await;
+    let _ = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": obj1_id,
+            "target_object_id": obj2_id,
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // The frozen version of B only changed an attribute
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{obj2_id}", api(&base)))
+        .json(&json!({"attributes": {"status": "draft"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bl: Value = client
+        .post(format!("{}/modules/{mod_id}/baselines", api(&base)))
+        .json(&json!({"name": "frozen"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let impact: Value = client
+        .get(format!(
+            "{}/object-impact/{obj1_id}?direction=forward&baseline_id={}",
+            api(&base),
+            bl["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(impact["objects"][0]["id"], obj2_id);
+    assert_eq!(impact["objects"][0]["heading"], "IMPACT-B");
+}
+
 #[tokio::test]
 async fn test_impact_invalid_direction() {
     let base = spawn_server().await;
@@ -112,7 +215,7 @@ async fn test_coverage_metrics() {
     let obj2_id = obj2["id"].as_str().unwrap();
 
     // Link A -> B
-    let lt_id = create_link_type(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
     let _ = client
         .post(format!("{}/links", api(&base)))
         .json(&json!({
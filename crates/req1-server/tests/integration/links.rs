@@ -1,16 +1,27 @@
 use axum::http::StatusCode;
+use sea_orm::ConnectionTrait;
 use serde_json::{Value, json};
 
 use super::common::{
-    api, authed_client, create_link_type, create_project, create_two_objects, spawn_server,
+    admin_client, api, authed_client, connect_db, create_link_type, create_object, create_project,
+    create_two_objects, spawn_server,
 };
 
 #[tokio::test]
 async fn test_link_type_crud() {
     let base = spawn_server().await;
-    let client = authed_client(&base).await;
+    let client = admin_client(&base).await;
     let url = format!("{}/link-types", api(&base));
 
+    let res = authed_client(&base)
+        .await
+        .post(&url)
+        .json(&json!({"name": "satisfies"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
     let res = client
         .post(&url)
         .json(&json!({"name": "satisfies", "description": "Requirement satisfies another"}))
@@ -37,7 +48,7 @@ async fn test_link_crud() {
     let base = spawn_server().await;
     let client = authed_client(&base).await;
     let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
-    let lt_id = create_link_type(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
     let url = format!("{}/links", api(&base));
 
     let res = client
@@ -100,7 +111,7 @@ async fn test_link_self_reference_rejected() {
     let base = spawn_server().await;
     let client = authed_client(&base).await;
     let (_mod_id, obj1_id, _obj2_id) = create_two_objects(&client, &base).await;
-    let lt_id = create_link_type(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
 
     let res = client
         .post(format!("{}/links", api(&base)))
@@ -120,7 +131,7 @@ async fn test_link_duplicate_rejected() {
     let base = spawn_server().await;
     let client = authed_client(&base).await;
     let (_mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
-    let lt_id = create_link_type(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
     let url = format!("{}/links", api(&base));
 
     let payload = json!({
@@ -141,7 +152,7 @@ async fn test_suspect_flag_on_object_update() {
     let base = spawn_server().await;
     let client = authed_client(&base).await;
     let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
-    let lt_id = create_link_type(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
 
     let link: Value = client
         .post(format!("{}/links", api(&base)))
@@ -174,6 +185,491 @@ async fn test_suspect_flag_on_object_update() {
     assert_eq!(updated_link["suspect"], true);
 }
 
+#[tokio::test]
+async fn test_fingerprint_policy_ignores_excluded_attributes() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
+
+    for name in ["owner", "priority"] {
+        let res = client
+            .post(format!(
+                "{}/modules/{mod_id}/attribute-definitions",
+                api(&base)
+            ))
+            .json(&json!({"name": name, "data_type": "string"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+    }
+    let res = client
+        .patch(format!("{}/modules/{mod_id}", api(&base)))
+        .json(&json!({"fingerprint_policy": {"exclude": ["owner"]}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let module: Value = res.json().await.unwrap();
+    assert_eq!(module["fingerprint_policy"]["exclude"], json!(["owner"]));
+
+    let link: Value = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": obj1_id,
+            "target_object_id": obj2_id,
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let link_url = format!("{}/links/{}", api(&base), link["id"].as_str().unwrap());
+    let obj_url = format!("{}/modules/{mod_id}/objects/{obj1_id}", api(&base));
+
+    let res = client
+        .patch(&obj_url)
+        .json(&json!({"attributes": {"owner": "alice"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let link: Value = client
+        .get(&link_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(link["suspect"], false);
+
+    let res = client
+        .patch(&obj_url)
+        .json(&json!({"attributes": {"owner": "bob", "priority": "high"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let link: Value = client
+        .get(&link_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(link["suspect"], true);
+}
+
+#[tokio::test]
+async fn test_suspect_rules_per_link_type() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
+    let obj3 = create_object(&client, &base, &mod_id, "Third").await;
+    let obj3_id = obj3["id"].as_str().unwrap();
+    let lt_id = create_link_type(&base).await;
+    let admin = admin_client(&base).await;
+
+    let res = admin
+        .patch(format!("{}/link-types/{lt_id}", api(&base)))
+        .json(&json!({"suspect_direction": "sideways"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let res = admin
+        .patch(format!("{}/link-types/{lt_id}", api(&base)))
+        .json(&json!({"suspect_direction": "forward", "suspect_cascade": true}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let mut link_urls = Vec::new();
+    for (src, tgt) in [
+        (obj1_id.as_str(), obj2_id.as_str()),
+        (obj2_id.as_str(), obj3_id),
+    ] {
+        let link: Value = client
+            .post(format!("{}/links", api(&base)))
+            .json(&json!({
+                "source_object_id": src,
+                "target_object_id": tgt,
+                "link_type_id": lt_id,
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        link_urls.push(format!(
+            "{}/links/{}",
+            api(&base),
+            link["id"].as_str().unwrap()
+        ));
+    }
+    let get_link = |url: String| {
+        let client = client.clone();
+        async move {
+            client
+                .get(url)
+                .send()
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    // obj2 is the target of the first link and the source of the second
+    let obj2: Value = client
+        .patch(format!("{}/modules/{mod_id}/objects/{obj2_id}", api(&base)))
+        .json(&json!({"heading": "Changed"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let first = get_link(link_urls[0].clone()).await;
+    assert_eq!(first["suspect"], false);
+    let second = get_link(link_urls[1].clone()).await;
+    assert_eq!(second["suspect"], true);
+    assert_eq!(second["suspect_reason"]["object_id"], obj2_id.as_str());
+    assert_eq!(second["suspect_reason"]["version"], obj2["current_version"]);
+    assert_eq!(second["suspect_reason"]["changed"], json!(["heading"]));
+
+    let resolved: Value = client
+        .patch(&link_urls[1])
+        .json(&json!({"suspect": false}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(resolved["suspect_reason"], Value::Null);
+
+    // A source change flags the first link and cascades through obj2 to the second
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{obj1_id}", api(&base)))
+        .json(&json!({"body": "new body"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let first = get_link(link_urls[0].clone()).await;
+    assert_eq!(first["suspect"], true);
+    assert_eq!(first["suspect_reason"]["changed"], json!(["body"]));
+    let second = get_link(link_urls[1].clone()).await;
+    assert_eq!(second["suspect"], true);
+    assert_eq!(second["suspect_reason"]["object_id"], obj1_id.as_str());
+    assert_eq!(second["suspect_reason"]["via_link_id"], first["id"]);
+}
+
+#[tokio::test]
+async fn test_suspect_queue_bulk_resolve() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
+
+    let link: Value = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": obj1_id,
+            "target_object_id": obj2_id,
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap();
+
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{obj1_id}", api(&base)))
+        .json(&json!({"heading": "REQ-SRC v2"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let queue_url = format!("{}/suspect-links?module_id={mod_id}", api(&base));
+    let queue: Value = client
+        .get(&queue_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(queue["total"], 1);
+    let entry = &queue["items"][0];
+    assert_eq!(entry["id"], link_id);
+    assert_eq!(
+        entry["source"]["changes"],
+        json!([{"field": "heading", "before": "REQ-SRC", "after": "REQ-SRC v2"}])
+    );
+    assert_eq!(entry["target"]["changes"], json!([]));
+
+    let aged: Value = client
+        .get(format!("{queue_url}&min_age_days=1"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(aged["total"], 0);
+
+    let resolve_url = format!("{}/suspect-links/resolve", api(&base));
+    let res = client
+        .post(&resolve_url)
+        .json(&json!({"link_ids": [link_id], "justification": "  "}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = client
+        .post(&resolve_url)
+        .json(&json!({"link_ids": [link_id], "justification": "Wording only"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let resolved: Value = res.json().await.unwrap();
+    assert_eq!(resolved[0]["suspect"], false);
+
+    let queue: Value = client
+        .get(&queue_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(queue["total"], 0);
+
+    let res = client
+        .post(&resolve_url)
+        .json(&json!({"link_ids": [link_id], "justification": "again"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_link_history() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
+    let other_lt_id = create_link_type(&base).await;
+
+    let link: Value = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": obj1_id,
+            "target_object_id": obj2_id,
+            "link_type_id": lt_id,
+            "attributes": {"rationale": "initial"},
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(link["version"], 1);
+    let link_url = format!("{}/links/{}", api(&base), link["id"].as_str().unwrap());
+
+    let res = client
+        .patch(&link_url)
+        .json(&json!({"attributes": {"rationale": "revised"}, "link_type_id": other_lt_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{obj1_id}", api(&base)))
+        .json(&json!({"heading": "REQ-SRC v2"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = client
+        .patch(&link_url)
+        .json(&json!({"suspect": false}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = client.delete(&link_url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    // History is kept after the link itself is gone
+    let history: Value = client
+        .get(format!("{link_url}/history"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = history["items"].as_array().unwrap();
+    let kinds: Vec<&str> = items
+        .iter()
+        .map(|h| h["change_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["delete", "resolve", "suspect", "update", "create"]);
+    let versions: Vec<i64> = items
+        .iter()
+        .map(|h| h["version"].as_i64().unwrap())
+        .collect();
+    assert_eq!(versions, [5, 4, 3, 2, 1]);
+
+    let update = &items[3];
+    assert_eq!(update["old_attributes"], json!({"rationale": "initial"}));
+    assert_eq!(update["new_attributes"], json!({"rationale": "revised"}));
+    assert_eq!(update["old_link_type_id"], lt_id.as_str());
+    assert_eq!(update["link_type_id"], other_lt_id.as_str());
+    assert!(update["actor"].is_string());
+    assert_eq!(items[2]["suspect_reason"]["changed"], json!(["heading"]));
+}
+
+#[tokio::test]
+async fn test_link_type_rules() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
+    let obj3 = create_object(&client, &base, &mod_id, "REQ-3").await;
+    let obj3_id = obj3["id"].as_str().unwrap();
+    let (other_mod_id, other_id, _) = create_two_objects(&client, &base).await;
+    let admin = admin_client(&base).await;
+
+    let res = admin
+        .post(format!("{}/link-types", api(&base)))
+        .json(&json!({
+            "name": format!("refines-{}", uuid::Uuid::now_v7()),
+            "rules": {"min_outgoing": 2, "max_outgoing": 1},
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let lt: Value = admin
+        .post(format!("{}/link-types", api(&base)))
+        .json(&json!({
+            "name": format!("refines-{}", uuid::Uuid::now_v7()),
+            "inverse_name": "refined by",
+            "rules": {
+                "source_module_ids": [mod_id],
+                "target_module_ids": [mod_id],
+                "min_outgoing": 1,
+                "max_outgoing": 1,
+                "allow_cycles": false,
+            },
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(lt["inverse_name"], "refined by");
+    let lt_id = lt["id"].as_str().unwrap();
+
+    let create = |src: String, tgt: String| {
+        let client = client.clone();
+        let url = format!("{}/links", api(&base));
+        let lt_id = lt_id.to_owned();
+        async move {
+            client
+                .post(url)
+                .json(&json!({
+                    "source_object_id": src,
+                    "target_object_id": tgt,
+                    "link_type_id": lt_id,
+                }))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+
+    assert_eq!(
+        create(obj1_id.clone(), obj2_id.clone()).await,
+        StatusCode::CREATED
+    );
+    // Second outgoing link exceeds max_outgoing
+    assert_eq!(
+        create(obj1_id.clone(), obj3_id.to_owned()).await,
+        StatusCode::BAD_REQUEST
+    );
+    // Target outside the allowed modules
+    assert_eq!(
+        create(obj2_id.clone(), other_id.clone()).await,
+        StatusCode::BAD_REQUEST
+    );
+    // Closing the loop back to obj1
+    assert_eq!(
+        create(obj2_id.clone(), obj1_id.clone()).await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        create(obj2_id.clone(), obj3_id.to_owned()).await,
+        StatusCode::CREATED
+    );
+
+    // obj3 has no outgoing link although the type requires one
+    let report: Value = client
+        .get(format!("{}/modules/{mod_id}/validate", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let cardinality: Vec<&Value> = report["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|i| i["rule"] == "link_cardinality")
+        .collect();
+    assert_eq!(cardinality.len(), 1);
+    assert_eq!(cardinality[0]["object_id"], obj3_id);
+
+    let report: Value = client
+        .get(format!("{}/modules/{other_mod_id}/validate", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(
+        !report["issues"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|i| i["rule"] == "link_cardinality")
+    );
+}
+
 #[tokio::test]
 async fn test_traceability_matrix() {
     let base = spawn_server().await;
@@ -222,7 +718,7 @@ async fn test_traceability_matrix() {
         .await
         .unwrap();
 
-    let lt_id = create_link_type(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
     let _ = client
         .post(format!("{}/links", api(&base)))
         .json(&json!({
@@ -251,3 +747,317 @@ async fn test_traceability_matrix() {
 
     let _ = ws;
 }
+
+#[tokio::test]
+async fn test_external_link_suspect_tracking() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (mod_id, obj1_id, _) = create_two_objects(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
+
+    let path = std::env::temp_dir().join(format!("req1-ext-{}.txt", uuid::Uuid::now_v7()));
+    std::fs::write(&path, "interface v1").unwrap();
+    let uri = format!("file://{}", path.display());
+
+    let res = client
+        .post(format!("{}/external-links", api(&base)))
+        .json(&json!({"source_object_id": obj1_id, "link_type_id": lt_id, "uri": "no-scheme"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = client
+        .post(format!("{}/external-links", api(&base)))
+        .json(&json!({
+            "source_object_id": obj1_id,
+            "link_type_id": lt_id,
+            "uri": uri,
+            "label": "ICD",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let ext: Value = res.json().await.unwrap();
+    let ext_url = format!(
+        "{}/external-links/{}",
+        api(&base),
+        ext["id"].as_str().unwrap()
+    );
+    let first_hash = ext["content_hash"].as_str().unwrap().to_owned();
+    assert_eq!(ext["suspect"], false);
+
+    let check = |client: reqwest::Client, url: String| async move {
+        client
+            .post(format!("{url}/check"))
+            .send()
+            .await
+            .unwrap()
+            .json::<Value>()
+            .await
+            .unwrap()
+    };
+    let result = check(client.clone(), ext_url.clone()).await;
+    assert_eq!(result["status"], "unchanged");
+
+    std::fs::write(&path, "interface v2").unwrap();
+    let result = check(client.clone(), ext_url.clone()).await;
+    assert_eq!(result["status"], "changed");
+    let ext: Value = client
+        .get(&ext_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(ext["suspect"], true);
+    assert_eq!(ext["suspect_reason"]["expected_hash"], first_hash.as_str());
+
+    // Clearing re-baselines on the resource's current content
+    let ext: Value = client
+        .patch(&ext_url)
+        .json(&json!({"suspect": false}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(ext["suspect"], false);
+    assert_ne!(ext["content_hash"].as_str().unwrap(), first_hash);
+
+    // Editing the source object flags the link as well
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{obj1_id}", api(&base)))
+        .json(&json!({"body": "changed body"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let ext: Value = client
+        .get(&ext_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(ext["suspect"], true);
+    assert_eq!(ext["suspect_reason"]["object_id"], obj1_id.as_str());
+
+    let list: Value = client
+        .get(format!(
+            "{}/external-links?module_id={mod_id}&suspect=true",
+            api(&base)
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(list["total"], 1);
+    let res = client
+        .get(format!("{}/external-links?limit=0", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Both flags are audited, the check's and the object edit's
+    let log: Value = client
+        .get(format!(
+            "{}/audit-log?entity_id={}&action=suspect",
+            api(&base),
+            ext["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(log["items"].as_array().unwrap().len(), 2);
+
+    // Paths outside the roots fail alike, whether or not they exist
+    let escape = std::env::temp_dir().join("..").join("etc");
+    for outside in [
+        "file:///etc".to_owned(),
+        format!("file:///req1-missing-{}", uuid::Uuid::now_v7()),
+        format!("file://{}", escape.display()),
+    ] {
+        let ext: Value = client
+            .post(format!("{}/external-links", api(&base)))
+            .json(&json!({"source_object_id": obj1_id, "link_type_id": lt_id, "uri": outside}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            ext["check_error"],
+            "path is outside the configured external link roots"
+        );
+    }
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_refingerprint_formats_numbers_like_saves() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
+    let lt_id = create_link_type(&base).await;
+    let obj_url = format!("{}/modules/{mod_id}/objects/{obj1_id}", api(&base));
+
+    let saved: Value = client
+        .patch(&obj_url)
+        .json(&json!({"attributes": {"weight": 1.5, "count": 12_345_678_901_234_567_890_u64}}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let fingerprint = saved["content_fingerprint"].as_str().unwrap().to_owned();
+    let link: Value = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": obj1_id,
+            "target_object_id": obj2_id,
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let link_id = link["id"].as_str().unwrap();
+
+    // Postgres keeps the number as written, while saves hash the parsed value
+    let db = connect_db().await;
+    let _ = db
+        .execute_unprepared(&format!(
+            r#"UPDATE object SET content_fingerprint = 'stale',
+                attributes = '{{"weight": 1.50, "count": 12345678901234567890}}'::jsonb
+            WHERE id = '{obj1_id}'"#
+        ))
+        .await
+        .unwrap();
+    let _ = db
+        .execute_unprepared(&format!(
+            "UPDATE link SET source_fingerprint = 'stale' WHERE id = '{link_id}'"
+        ))
+        .await
+        .unwrap();
+    let changed = req1_core::fingerprint::refingerprint_module(&db, mod_id.parse().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(changed, 1);
+
+    let obj: Value = client
+        .get(&obj_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(obj["content_fingerprint"], fingerprint.as_str());
+    let link: Value = client
+        .get(format!("{}/links/{link_id}", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(link["source_fingerprint"], fingerprint.as_str());
+    assert_eq!(link["suspect"], false);
+}
+
+#[tokio::test]
+async fn test_suspect_flag_on_trigger_edit() {
+    // An excluded attribute alone keeps the link clean, but a trigger's heading
+    // edit on top of it changes the fingerprint, before or after the save
+    for hook_point in ["pre_save", "post_save"] {
+        let base = spawn_server().await;
+        let client = authed_client(&base).await;
+        let (mod_id, obj1_id, obj2_id) = create_two_objects(&client, &base).await;
+        let lt_id = create_link_type(&base).await;
+
+        let res = client
+            .post(format!(
+                "{}/modules/{mod_id}/attribute-definitions",
+                api(&base)
+            ))
+            .json(&json!({"name": "owner", "data_type": "string"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let res = client
+            .patch(format!("{}/modules/{mod_id}", api(&base)))
+            .json(&json!({"fingerprint_policy": {"exclude": ["owner"]}}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let link: Value = client
+            .post(format!("{}/links", api(&base)))
+            .json(&json!({
+                "source_object_id": obj1_id,
+                "target_object_id": obj2_id,
+                "link_type_id": lt_id,
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let link_url = format!("{}/links/{}", api(&base), link["id"].as_str().unwrap());
+
+        let res = client
+            .post(format!("{}/modules/{mod_id}/scripts", api(&base)))
+            .json(&json!({
+                "name": "Owner heading",
+                "script_type": "trigger",
+                "hook_point": hook_point,
+                "source_code": "req1.setHeading(context.object.id, 'Owned');",
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let res = client
+            .patch(format!("{}/modules/{mod_id}/objects/{obj1_id}", api(&base)))
+            .json(&json!({"attributes": {"owner": "alice"}}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let link: Value = client
+            .get(&link_url)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(link["suspect"], true, "{hook_point}");
+        assert_eq!(
+            link["suspect_reason"]["changed"],
+            json!(["heading"]),
+            "{hook_point}"
+        );
+    }
+}
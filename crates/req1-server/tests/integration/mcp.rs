@@ -0,0 +1,173 @@
+use axum::http::StatusCode;
+use reqwest::Client;
+use serde_json::{Value, json};
+
+use super::common::{
+    api, authed_client, authed_client_as, create_module, create_object, spawn_server,
+};
+
+async fn rpc(client: &Client, base: &str, method: &str, params: Value) -> Value {
+    let res = client
+        .post(format!("{}/mcp", api(base)))
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    res.json().await.unwrap()
+}
+
+async fn call(client: &Client, base: &str, tool: &str, arguments: Value) -> (bool, Value) {
+    let res = rpc(
+        client,
+        base,
+        "tools/call",
+        json!({"name": tool, "arguments": arguments}),
+    )
+    .await;
+    let result = &res["result"];
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let is_error = result["isError"].as_bool().unwrap();
+    let value = if is_error {
+        json!(text)
+    } else {
+        serde_json::from_str(text).unwrap()
+    };
+    (is_error, value)
+}
+
+#[tokio::test]
+async fn test_mcp_tools_respect_permissions_and_propose_changes() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj = create_object(&client, &base, mod_id, "Braking distance").await;
+    let obj_id = obj["id"].as_str().unwrap();
+
+    // Unauthenticated calls are rejected like any other API call
+    let res = Client::new()
+        .post(format!("{}/mcp", api(&base)))
+        .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let init = rpc(
+        &client,
+        &base,
+        "initialize",
+        json!({"protocolVersion": "2025-03-26", "capabilities": {}, "clientInfo": {"name": "test"}}),
+    )
+    .await;
+    assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+    assert_eq!(init["result"]["serverInfo"]["name"], "req1");
+
+    let res = client
+        .post(format!("{}/mcp", api(&base)))
+        .json(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+
+    let tools = rpc(&client, &base, "tools/list", json!({})).await;
+    let names: Vec<&str> = tools["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    for expected in [
+        "search_requirements",
+        "get_object",
+        "list_links",
+        "impact_analysis",
+        "validate_module",
+        "propose_change",
+    ] {
+        assert!(names.contains(&expected), "missing tool {expected}");
+    }
+    assert!(!names.contains(&"create_object"));
+
+    let (is_error, found) = call(
+        &client,
+        &base,
+        "search_requirements",
+        json!({"query": "braking", "module_id": mod_id}),
+    )
+    .await;
+    assert!(!is_error);
+    assert_eq!(found["items"][0]["id"], obj_id);
+
+    let (is_error, fetched) =
+        call(&client, &base, "get_object", json!({"object_id": obj_id})).await;
+    assert!(!is_error);
+    assert_eq!(fetched["heading"], "Braking distance");
+
+    let (is_error, links) = call(&client, &base, "list_links", json!({"object_id": obj_id})).await;
+    assert!(!is_error);
+    assert_eq!(links["outgoing"], json!([]));
+
+    let (is_error, _) = call(
+        &client,
+        &base,
+        "impact_analysis",
+        json!({"object_id": obj_id}),
+    )
+    .await;
+    assert!(!is_error);
+    let (is_error, _) = call(
+        &client,
+        &base,
+        "validate_module",
+        json!({"module_id": mod_id}),
+    )
+    .await;
+    assert!(!is_error);
+
+    // Writes become draft change proposals; the object is untouched
+    let (is_error, proposal) = call(
+        &client,
+        &base,
+        "propose_change",
+        json!({"object_id": obj_id, "body": "Stop within 40 m", "rationale": "Spec was empty"}),
+    )
+    .await;
+    assert!(!is_error);
+    assert_eq!(proposal["status"], "draft");
+    assert_eq!(proposal["module_id"], mod_id);
+    assert_eq!(proposal["diff_data"][0]["field"], "body");
+    assert_eq!(proposal["diff_data"][0]["new_value"], "Stop within 40 m");
+    let (_, fetched) = call(&client, &base, "get_object", json!({"object_id": obj_id})).await;
+    assert!(fetched["body"].is_null());
+
+    // Objects cannot be created bypassing review
+    let res = rpc(
+        &client,
+        &base,
+        "tools/call",
+        json!({"name": "create_object", "arguments": {"module_id": mod_id, "heading": "Planted"}}),
+    )
+    .await;
+    assert_eq!(res["error"]["code"], -32602);
+
+    // A user without access to the workspace sees the REST API's refusal
+    let stranger = authed_client_as(&base, &format!("mcp-{}@test.com", uuid::Uuid::now_v7())).await;
+    let (is_error, message) =
+        call(&stranger, &base, "get_object", json!({"object_id": obj_id})).await;
+    assert!(is_error);
+    assert!(message.as_str().unwrap().starts_with("403"));
+    let (is_error, _) = call(
+        &stranger,
+        &base,
+        "propose_change",
+        json!({"object_id": obj_id, "heading": "Hijacked"}),
+    )
+    .await;
+    assert!(is_error);
+
+    let res = rpc(&client, &base, "tools/call", json!({"name": "drop_tables"})).await;
+    assert_eq!(res["error"]["code"], -32602);
+}
@@ -50,6 +50,13 @@ async fn test_object_crud() {
     assert_eq!(res.status(), StatusCode::OK);
     let history: Value = res.json().await.unwrap();
     assert_eq!(history["items"].as_array().unwrap().len(), 2);
+    assert!(
+        history["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|h| h["changed_by"].is_string() && h["actor"] == h["changed_by"])
+    );
 
     let res = client
         .delete(format!("{url}/{obj_id}"))
@@ -352,3 +359,77 @@ async fn test_comment_not_found() {
         .unwrap();
     assert_eq!(res.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_lifecycle_state_timeline() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+
+    let lc: Value = client
+        .post(format!("{}/modules/{mod_id}/lifecycle-models", api(&base)))
+        .json(&json!({
+            "name": "Approval",
+            "initial_state": "draft",
+            "states": [{"name": "draft"}, {"name": "approved"}],
+            "transitions": [{"from": "draft", "to": "approved"}],
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let obj: Value = client
+        .post(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .json(&json!({"heading": "REQ-LC", "lifecycle_model_id": lc["id"]}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let obj_id = obj["id"].as_str().unwrap();
+    assert_eq!(obj["lifecycle_state"], "draft");
+
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{obj_id}", api(&base)))
+        .json(&json!({
+            "lifecycle_state": "approved",
+            "signature": {"password": "password123", "meaning": "approved"},
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .get(format!(
+            "{}/modules/{mod_id}/objects/{obj_id}/timeline",
+            api(&base)
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let timeline: Value = res.json().await.unwrap();
+    let entries = timeline.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["change_type"], "create");
+    assert_eq!(entries[0]["to_state"], "draft");
+    assert_eq!(entries[1]["change_type"], "state_transition");
+    assert_eq!(entries[1]["from_state"], "draft");
+    assert_eq!(entries[1]["to_state"], "approved");
+    assert_eq!(entries[1]["e_signature"]["meaning"], "approved");
+    assert!(entries[1]["changed_by"].is_string());
+
+    let res = client
+        .get(format!("{}/modules/{mod_id}/timeline", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    let module_timeline: Value = res.json().await.unwrap();
+    assert_eq!(module_timeline.as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,224 @@
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use super::common::{
+    api, authed_client, authed_client_as, create_module, create_object, spawn_server,
+};
+
+#[tokio::test]
+async fn test_oslc_provider_and_query() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, project, module) = create_module(&client, &base).await;
+    let project_id = project["id"].as_str().unwrap();
+    let mod_id = module["id"].as_str().unwrap();
+    let obj = create_object(&client, &base, mod_id, "Braking distance").await;
+    let _ = create_object(&client, &base, mod_id, "Steering torque").await;
+
+    let res = client
+        .get(format!("{}/oslc/catalog", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "application/rdf+xml");
+    let xml = res.text().await.unwrap();
+    assert!(xml.contains("ServiceProviderCatalog"));
+    assert!(xml.contains(&format!("/oslc/projects/{project_id}")));
+
+    let provider = client
+        .get(format!("{}/oslc/projects/{project_id}", api(&base)))
+        .header("Accept", "text/turtle")
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(provider.contains("oslc:queryCapability"));
+    assert!(provider.contains("oslc:selectionDialog"));
+    assert!(provider.contains(&format!("/oslc/modules/{mod_id}/creator")));
+
+    let requirement: Value = client
+        .get(format!(
+            "{}/oslc/requirements/{}",
+            api(&base),
+            obj["id"].as_str().unwrap()
+        ))
+        .header("Accept", "application/ld+json")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(requirement["@type"], json!(["oslc_rm:Requirement"]));
+    assert_eq!(requirement["dcterms:title"], "Braking distance");
+
+    let query = |params: &'static str| {
+        let client = client.clone();
+        let url = format!(
+            "{}/oslc/projects/{project_id}/requirements?{params}",
+            api(&base)
+        );
+        async move {
+            client
+                .get(url)
+                .header("Accept", "application/ld+json")
+                .send()
+                .await
+                .unwrap()
+        }
+    };
+    let res =
+        query(r#"oslc.where=dcterms:title="Braking distance"&oslc.select=dcterms:title"#).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let doc: Value = res.json().await.unwrap();
+    let graph = doc["@graph"].as_array().unwrap();
+    let info = graph
+        .iter()
+        .find(|n| n["@type"] == json!(["oslc:ResponseInfo"]))
+        .unwrap();
+    assert_eq!(info["oslc:totalCount"]["@value"], "1");
+    let members: Vec<&Value> = graph
+        .iter()
+        .filter(|n| n["@type"] == json!(["oslc_rm:Requirement"]))
+        .collect();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0]["dcterms:title"], "Braking distance");
+    assert!(members[0].get("dcterms:description").is_none());
+    assert!(members[0].get("req1:classification").is_none());
+
+    let doc: Value = query("oslc.pageSize=1").await.json().await.unwrap();
+    let info = doc["@graph"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["@type"] == json!(["oslc:ResponseInfo"]))
+        .unwrap()
+        .clone();
+    assert_eq!(info["oslc:totalCount"]["@value"], "2");
+    assert!(
+        info["oslc:nextPage"]["@id"]
+            .as_str()
+            .unwrap()
+            .contains("page=2")
+    );
+
+    let res = query("oslc.where=dcterms:title").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // Creation factory takes JSON-LD and answers with the new resource's URI
+    let res = client
+        .post(format!("{}/oslc/modules/{mod_id}/requirements", api(&base)))
+        .header("Content-Type", "application/ld+json")
+        .header("Accept", "application/ld+json")
+        .body(
+            json!({
+                "@type": "oslc_rm:Requirement",
+                "dcterms:title": "Created via OSLC",
+                "dcterms:description": "From the ALM tool",
+            })
+            .to_string(),
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let location = res.headers()["location"].to_str().unwrap().to_owned();
+    let created: Value = res.json().await.unwrap();
+    assert_eq!(created["@id"], location.as_str());
+    let objects: Value = client
+        .get(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(objects["total"], 3);
+
+    // Dialog pages are plain HTML served without a token
+    let res = reqwest::get(format!(
+        "{}/oslc/projects/{project_id}/selector",
+        api(&base)
+    ))
+    .await
+    .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let page = res.text().await.unwrap();
+    assert!(page.contains("oslc-response:"));
+    // Answers go only to configured consumers, never to any origin
+    assert!(page.contains(r#"const CONSUMER_ORIGINS = ["https://consumer.example"];"#));
+    assert!(!page.contains(r#""*")"#));
+}
+
+#[tokio::test]
+async fn test_oslc_provider_lists_only_readable_modules() {
+    let base = spawn_server().await;
+    let owner = authed_client(&base).await;
+    let (ws, project, open) = create_module(&owner, &base).await;
+    let ws_id = ws["id"].as_str().unwrap();
+    let project_id = project["id"].as_str().unwrap();
+    let open_id = open["id"].as_str().unwrap();
+    let restricted: Value = owner
+        .post(format!("{}/modules", api(&base)))
+        .json(&json!({"name": format!("mod-{}", uuid::Uuid::now_v7()), "project_id": project_id}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let restricted_id = restricted["id"].as_str().unwrap();
+
+    let email = format!("oslc-viewer-{}@test.com", uuid::Uuid::now_v7());
+    let viewer = authed_client_as(&base, &email).await;
+    let me: Value = viewer
+        .get(format!("{}/auth/me", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let res = owner
+        .post(format!("{}/workspaces/{ws_id}/members", api(&base)))
+        .json(&json!({"user_id": me["id"], "role": "viewer"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    // A grant to someone else restricts the module to its grantees
+    let owner_me: Value = owner
+        .get(format!("{}/auth/me", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let res = owner
+        .post(format!(
+            "{}/modules/{restricted_id}/permissions",
+            api(&base)
+        ))
+        .json(&json!({"user_id": owner_me["id"], "permission": "write"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let provider = viewer
+        .get(format!("{}/oslc/projects/{project_id}", api(&base)))
+        .header("Accept", "text/turtle")
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(provider.contains(&format!("/oslc/modules/{open_id}/creator")));
+    assert!(!provider.contains(restricted_id));
+}
@@ -0,0 +1,337 @@
+use axum::http::StatusCode;
+use reqwest::Client;
+use serde_json::{Value, json};
+
+use super::common::{
+    api, authed_client, authed_client_as, create_module, create_object, spawn_server,
+};
+
+async fn other_user(base: &str) -> (Client, String) {
+    let email = format!("member-{}@test.com", uuid::Uuid::now_v7());
+    let client = authed_client_as(base, &email).await;
+    let me: Value = client
+        .get(format!("{}/auth/me", api(base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    (client, me["id"].as_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn test_non_member_is_forbidden() {
+    let base = spawn_server().await;
+    let owner = authed_client(&base).await;
+    let (ws, _proj, module) = create_module(&owner, &base).await;
+    let ws_id = ws["id"].as_str().unwrap();
+    let mod_id = module["id"].as_str().unwrap();
+
+    let (stranger, _) = other_user(&base).await;
+
+    let res = stranger
+        .get(format!("{}/workspaces/{ws_id}", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = stranger
+        .get(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = stranger
+        .get(format!("{}/workspaces?limit=500", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let list: Value = res.json().await.unwrap();
+    assert!(
+        !list["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|i| i["id"] == ws_id)
+    );
+}
+
+#[tokio::test]
+async fn test_viewer_member_is_read_only() {
+    let base = spawn_server().await;
+    let owner = authed_client(&base).await;
+    let (ws, _proj, module) = create_module(&owner, &base).await;
+    let ws_id = ws["id"].as_str().unwrap();
+    let mod_id = module["id"].as_str().unwrap();
+
+    let (viewer, viewer_id) = other_user(&base).await;
+    let res = owner
+        .post(format!("{}/workspaces/{ws_id}/members", api(&base)))
+        .json(&json!({"user_id": viewer_id, "role": "viewer"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = viewer
+        .get(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = viewer
+        .post(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .json(&json!({"heading": "Not allowed"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = viewer
+        .post(format!("{}/workspaces/{ws_id}/members", api(&base)))
+        .json(&json!({"user_id": viewer_id, "role": "admin"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_module_grant_restricts_access() {
+    let base = spawn_server().await;
+    let owner = authed_client(&base).await;
+    let (ws, _proj, module) = create_module(&owner, &base).await;
+    let ws_id = ws["id"].as_str().unwrap();
+    let mod_id = module["id"].as_str().unwrap();
+
+    let (editor, editor_id) = other_user(&base).await;
+    let (supplier, supplier_id) = other_user(&base).await;
+    for (user_id, role) in [(&editor_id, "editor"), (&supplier_id, "viewer")] {
+        let res = owner
+            .post(format!("{}/workspaces/{ws_id}/members", api(&base)))
+            .json(&json!({"user_id": user_id, "role": role}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+    }
+
+    // Before any grant, the module inherits workspace roles
+    let res = editor
+        .get(format!("{}/modules/{mod_id}", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = owner
+        .post(format!("{}/modules/{mod_id}/permissions", api(&base)))
+        .json(&json!({"user_id": supplier_id, "permission": "write"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let grant: Value = res.json().await.unwrap();
+    assert_eq!(grant["permission"], "write");
+
+    let res = editor
+        .get(format!("{}/modules/{mod_id}", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = supplier
+        .post(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .json(&json!({"heading": "Supplier requirement"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = supplier
+        .get(format!("{}/modules?limit=500", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    let list: Value = res.json().await.unwrap();
+    assert!(
+        list["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|i| i["id"] == mod_id)
+    );
+}
+
+#[tokio::test]
+async fn test_workspace_member_crud() {
+    let base = spawn_server().await;
+    let owner = authed_client(&base).await;
+    let (ws, _proj, _module) = create_module(&owner, &base).await;
+    let ws_id = ws["id"].as_str().unwrap();
+    let url = format!("{}/workspaces/{ws_id}/members", api(&base));
+
+    let (_member, member_id) = other_user(&base).await;
+    let res = owner
+        .post(&url)
+        .json(&json!({"user_id": member_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let member: Value = res.json().await.unwrap();
+    let id = member["id"].as_str().unwrap();
+    assert_eq!(member["role"], "viewer");
+
+    let res = owner
+        .post(&url)
+        .json(&json!({"user_id": member_id}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let res = owner
+        .patch(format!("{url}/{id}"))
+        .json(&json!({"role": "editor"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let updated: Value = res.json().await.unwrap();
+    assert_eq!(updated["role"], "editor");
+
+    let res = owner
+        .patch(format!("{url}/{id}"))
+        .json(&json!({"role": "owner"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = owner.get(&url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let list: Value = res.json().await.unwrap();
+    assert_eq!(list["items"].as_array().unwrap().len(), 2);
+
+    let res = owner.delete(format!("{url}/{id}")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let res = owner.get(format!("{url}/{id}")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_nested_id_from_other_module_not_found() {
+    let base = spawn_server().await;
+    let owner = authed_client(&base).await;
+    let (_ws, _proj, module_a) = create_module(&owner, &base).await;
+    let (_ws, _proj, module_b) = create_module(&owner, &base).await;
+    let mod_a = module_a["id"].as_str().unwrap();
+    let mod_b = module_b["id"].as_str().unwrap();
+
+    let obj: Value = owner
+        .post(format!("{}/modules/{mod_b}/objects", api(&base)))
+        .json(&json!({"heading": "In B"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let obj_id = obj["id"].as_str().unwrap();
+
+    let res = owner
+        .get(format!("{}/modules/{mod_a}/objects/{obj_id}", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_object_types_and_signatures_follow_module_access() {
+    let base = spawn_server().await;
+    let owner = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&owner, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj = create_object(&owner, &base, mod_id, "Signed requirement").await;
+    let obj_id = obj["id"].as_str().unwrap();
+
+    let res = owner
+        .post(format!("{}/object-types", api(&base)))
+        .json(&json!({"module_id": mod_id, "name": "Requirement"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let ot: Value = res.json().await.unwrap();
+    let ot_url = format!("{}/object-types/{}", api(&base), ot["id"].as_str().unwrap());
+
+    let res = owner
+        .post(format!("{}/e-signatures", api(&base)))
+        .json(&json!({
+            "entity_type": "object",
+            "entity_id": obj_id,
+            "password": "password123",
+            "meaning": "approved",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let (stranger, _) = other_user(&base).await;
+    let res = stranger
+        .get(format!("{}/object-types?module_id={mod_id}", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = stranger
+        .post(format!("{}/object-types", api(&base)))
+        .json(&json!({"module_id": mod_id, "name": "Intruder"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = stranger.get(&ot_url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = stranger
+        .patch(&ot_url)
+        .json(&json!({"fingerprint_policy": {"exclude": ["Status"]}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = stranger.delete(&ot_url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = stranger
+        .post(format!("{}/e-signatures", api(&base)))
+        .json(&json!({
+            "entity_type": "object",
+            "entity_id": obj_id,
+            "password": "password123",
+            "meaning": "approved",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = stranger
+        .get(format!(
+            "{}/e-signatures/entity/object/{obj_id}",
+            api(&base)
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,404 @@
+use axum::http::StatusCode;
+use reqwest::Client;
+use serde_json::{Value, json};
+
+use super::common::{
+    admin_client, api, authed_client, authed_client_as, create_module, create_object,
+    create_project, spawn_server,
+};
+
+/// Minimal ReqIF document: flat objects `(identifier, heading, description,
+/// supplier status)` and relations `(identifier, source, target)`.
+fn reqif(objects: &[(&str, &str, &str, &str)], relations: &[(&str, &str, &str)]) -> String {
+    let spec_objects: String = objects
+        .iter()
+        .map(|(id, heading, desc, status)| {
+            format!(
+                r#"<SPEC-OBJECT IDENTIFIER="{id}" LONG-NAME="{heading}">
+          <TYPE><SPEC-OBJECT-TYPE-REF>sot-1</SPEC-OBJECT-TYPE-REF></TYPE>
+          <VALUES>
+            <ATTRIBUTE-VALUE-STRING THE-VALUE="{desc}">
+              <DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>ad-desc</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>
+            <ATTRIBUTE-VALUE-STRING THE-VALUE="{status}">
+              <DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>ad-status</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>
+          </VALUES>
+        </SPEC-OBJECT>"#
+            )
+        })
+        .collect();
+    let spec_relations: String = relations
+        .iter()
+        .map(|(id, source, target)| {
+            format!(
+                r#"<SPEC-RELATION IDENTIFIER="{id}">
+          <TYPE><SPEC-RELATION-TYPE-REF>srt-1</SPEC-RELATION-TYPE-REF></TYPE>
+          <SOURCE><SPEC-OBJECT-REF>{source}</SPEC-OBJECT-REF></SOURCE>
+          <TARGET><SPEC-OBJECT-REF>{target}</SPEC-OBJECT-REF></TARGET>
+        </SPEC-RELATION>"#
+            )
+        })
+        .collect();
+    let hierarchy: String = objects
+        .iter()
+        .map(|(id, ..)| {
+            format!(
+                r#"<SPEC-HIERARCHY IDENTIFIER="sh-{id}"><OBJECT><SPEC-OBJECT-REF>{id}</SPEC-OBJECT-REF></OBJECT></SPEC-HIERARCHY>"#
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="header-1">
+      <REQ-IF-TOOL-ID>other-tool</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.2</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>other-tool</SOURCE-TOOL-ID>
+      <TITLE>Supplier spec</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <DATATYPES>
+        <DATATYPE-DEFINITION-STRING IDENTIFIER="dt-string" LONG-NAME="Text" MAX-LENGTH="4096"/>
+      </DATATYPES>
+      <SPEC-TYPES>
+        <SPEC-OBJECT-TYPE IDENTIFIER="sot-1" LONG-NAME="Requirement">
+          <SPEC-ATTRIBUTES>
+            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="ad-desc" LONG-NAME="Description">
+              <TYPE><DATATYPE-DEFINITION-STRING-REF>dt-string</DATATYPE-DEFINITION-STRING-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-STRING>
+            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="ad-status" LONG-NAME="Supplier Status">
+              <TYPE><DATATYPE-DEFINITION-STRING-REF>dt-string</DATATYPE-DEFINITION-STRING-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-STRING>
+          </SPEC-ATTRIBUTES>
+        </SPEC-OBJECT-TYPE>
+        <SPEC-RELATION-TYPE IDENTIFIER="srt-1" LONG-NAME="refines-{suffix}"/>
+        <SPECIFICATION-TYPE IDENTIFIER="spec-type-1" LONG-NAME="Document"/>
+      </SPEC-TYPES>
+      <SPEC-OBJECTS>{spec_objects}</SPEC-OBJECTS>
+      <SPEC-RELATIONS>{spec_relations}</SPEC-RELATIONS>
+      <SPECIFICATIONS>
+        <SPECIFICATION IDENTIFIER="spec-1" LONG-NAME="Supplier spec">
+          <TYPE><SPECIFICATION-TYPE-REF>spec-type-1</SPECIFICATION-TYPE-REF></TYPE>
+          <CHILDREN>{hierarchy}</CHILDREN>
+        </SPECIFICATION>
+      </SPECIFICATIONS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>"#,
+        suffix = "merge-test",
+    )
+}
+
+fn upload(xml: String) -> reqwest::multipart::Form {
+    reqwest::multipart::Form::new().part(
+        "file",
+        reqwest::multipart::Part::bytes(xml.into_bytes()).file_name("spec.reqif"),
+    )
+}
+
+async fn objects_by_heading(client: &Client, base: &str, mod_id: &str) -> Vec<Value> {
+    let objects: Value = client
+        .get(format!("{}/modules/{mod_id}/objects", api(base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let mut items = objects["items"].as_array().unwrap().clone();
+    items.sort_by_key(|o| o["heading"].as_str().unwrap_or_default().to_owned());
+    items
+}
+
+#[tokio::test]
+async fn test_reqif_merge_updates_module_in_place() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, proj) = create_project(&client, &base).await;
+    let proj_id = proj["id"].as_str().unwrap();
+
+    let res = client
+        .post(format!("{}/projects/{proj_id}/reqif/import", api(&base)))
+        .multipart(upload(reqif(
+            &[
+                ("so-1", "Alpha", "first", "open"),
+                ("so-2", "Beta", "second", "open"),
+                ("so-3", "Gamma", "third", "open"),
+            ],
+            &[("sr-1", "so-1", "so-2")],
+        )))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let imported: Value = res.json().await.unwrap();
+    let mod_id = imported["module_id"].as_str().unwrap().to_owned();
+
+    // Local edit to Beta's description
+    let items = objects_by_heading(&client, &base, &mod_id).await;
+    let beta_id = items[1]["id"].as_str().unwrap();
+    let res = client
+        .patch(format!("{}/modules/{mod_id}/objects/{beta_id}", api(&base)))
+        .json(&serde_json::json!({"attributes": {"Description": "second, edited locally"}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Supplier renames Alpha, edits Beta too, drops Gamma, adds Delta and moves the relation
+    let next = || {
+        upload(reqif(
+            &[
+                ("so-1", "Alpha v2", "first", "open"),
+                ("so-2", "Beta", "second, edited by supplier", "open"),
+                ("so-4", "Delta", "fourth", "open"),
+            ],
+            &[("sr-2", "so-1", "so-4")],
+        ))
+    };
+
+    let res = client
+        .post(format!(
+            "{}/modules/{mod_id}/reqif/merge/preview",
+            api(&base)
+        ))
+        .multipart(next())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let preview: Value = res.json().await.unwrap();
+    assert_eq!(preview["objects_created"], 1);
+    assert_eq!(preview["objects_updated"], 1);
+    assert_eq!(preview["objects_deleted"], 1);
+    let conflicts = preview["conflicts"].as_array().unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0]["field"], "attributes.Description");
+    assert_eq!(conflicts[0]["kind"], "modified");
+    assert_eq!(conflicts[0]["base"], "second");
+    assert_eq!(conflicts[0]["local"], "second, edited locally");
+    assert_eq!(conflicts[0]["incoming"], "second, edited by supplier");
+
+    // Preview wrote nothing
+    assert_eq!(objects_by_heading(&client, &base, &mod_id).await.len(), 3);
+
+    let merge_url = format!("{}/modules/{mod_id}/reqif/merge", api(&base));
+    let res = client
+        .post(&merge_url)
+        .multipart(next())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let res = client
+        .post(format!("{merge_url}?on_conflict=keep_local"))
+        .multipart(next())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let merged: Value = res.json().await.unwrap();
+    assert_eq!(merged["objects_created"], 1);
+    assert_eq!(merged["objects_deleted"], 1);
+    assert_eq!(merged["links_created"], 1);
+    assert_eq!(merged["links_deleted"], 1);
+
+    let items = objects_by_heading(&client, &base, &mod_id).await;
+    let headings: Vec<&str> = items
+        .iter()
+        .map(|o| o["heading"].as_str().unwrap())
+        .collect();
+    assert_eq!(headings, ["Alpha v2", "Beta", "Delta"]);
+    assert_eq!(
+        items[1]["attributes"]["Description"],
+        "second, edited locally"
+    );
+
+    // The export keeps the supplier's identifiers, so merging it back is a no-op
+    let exported = client
+        .get(format!("{}/modules/{mod_id}/reqif/export", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(exported.contains(r#"IDENTIFIER="so-4""#));
+    assert!(exported.contains(r#"IDENTIFIER="spec-1""#));
+    let res = client
+        .post(&merge_url)
+        .multipart(upload(exported))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let again: Value = res.json().await.unwrap();
+    assert_eq!(again["objects_created"], 0);
+    assert_eq!(again["objects_deleted"], 0);
+    assert_eq!(again["links_created"], 0);
+    assert_eq!(again["links_deleted"], 0);
+}
+
+#[tokio::test]
+async fn test_reqif_exchange_profile_limits_partner_changes() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, proj) = create_project(&client, &base).await;
+    let proj_id = proj["id"].as_str().unwrap();
+
+    let imported: Value = client
+        .post(format!("{}/projects/{proj_id}/reqif/import", api(&base)))
+        .multipart(upload(reqif(
+            &[("so-1", "Braking", "Shall stop within 40 m", "open")],
+            &[],
+        )))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let mod_id = imported["module_id"].as_str().unwrap().to_owned();
+    let set_profile = |profile: Value| {
+        client
+            .patch(format!("{}/modules/{mod_id}", api(&base)))
+            .json(&json!({ "reqif_profile": profile }))
+            .send()
+    };
+    let res = set_profile(json!({"accept": ["Supplier Status"]}))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // The supplier answers, but also rewrites the normative text
+    let returned = || {
+        upload(reqif(
+            &[("so-1", "Braking", "Shall stop within 60 m", "accepted")],
+            &[],
+        ))
+    };
+    let merge_url = format!("{}/modules/{mod_id}/reqif/merge", api(&base));
+    let res = client
+        .post(&merge_url)
+        .multipart(returned())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = set_profile(json!({"accept": ["Supplier Status"], "on_violation": "report"}))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = client
+        .post(&merge_url)
+        .multipart(returned())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let merged: Value = res.json().await.unwrap();
+    let violations = merged["ownership_violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0]["field"], "attributes.Description");
+    assert_eq!(violations[0]["kind"], "not_owned");
+    assert_eq!(violations[0]["incoming"], "Shall stop within 60 m");
+
+    let items = objects_by_heading(&client, &base, &mod_id).await;
+    assert_eq!(
+        items[0]["attributes"]["Description"],
+        "Shall stop within 40 m"
+    );
+    assert_eq!(items[0]["attributes"]["Supplier Status"], "accepted");
+
+    // Attributes left out of the profile's export list are not sent
+    let res = set_profile(json!({"export": ["Description"], "accept": ["Supplier Status"]}))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let exported = client
+        .get(format!("{}/modules/{mod_id}/reqif/export", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(exported.contains("Shall stop within 40 m"));
+    assert!(!exported.contains("Supplier Status"));
+}
+
+#[tokio::test]
+async fn test_reqif_merge_skips_relations_the_caller_may_not_link() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let merge_url = format!("{}/modules/{mod_id}/reqif/merge", api(&base));
+    let relation_type = format!("refines-{}", uuid::Uuid::now_v7());
+    let doc = |relations: &[(&str, &str, &str)]| {
+        upload(
+            reqif(
+                &[
+                    ("so-1", "Alpha", "first", "open"),
+                    ("so-2", "Beta", "second", "open"),
+                ],
+                relations,
+            )
+            .replace("refines-merge-test", &relation_type),
+        )
+    };
+
+    // Link types are a global catalog, so an editor's merge does not add one
+    let res = client
+        .post(&merge_url)
+        .multipart(doc(&[("sr-1", "so-1", "so-2")]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let merged: Value = res.json().await.unwrap();
+    assert_eq!(merged["objects_created"], 2);
+    assert_eq!(merged["link_types_created"], 0);
+    assert_eq!(merged["links_created"], 0);
+    assert_eq!(merged["links_skipped"], 1);
+
+    let res = admin_client(&base)
+        .await
+        .post(&merge_url)
+        .multipart(doc(&[("sr-1", "so-1", "so-2")]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let merged: Value = res.json().await.unwrap();
+    assert_eq!(merged["link_types_created"], 1);
+    assert_eq!(merged["links_created"], 1);
+
+    // Relations to a module the caller cannot access are skipped in both directions
+    let stranger = authed_client_as(&base, "reqif-stranger@example.com").await;
+    let (_ws, _proj, other) = create_module(&stranger, &base).await;
+    let other_id = other["id"].as_str().unwrap();
+    let foreign = create_object(&stranger, &base, other_id, "Foreign").await;
+    let foreign_ref = format!("req1-{}", foreign["id"].as_str().unwrap());
+    let res = client
+        .post(&merge_url)
+        .multipart(doc(&[
+            ("sr-1", "so-1", "so-2"),
+            ("sr-2", &foreign_ref, "so-1"),
+            ("sr-3", "so-1", &foreign_ref),
+        ]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let merged: Value = res.json().await.unwrap();
+    assert_eq!(merged["links_unchanged"], 1);
+    assert_eq!(merged["links_created"], 0);
+    assert_eq!(merged["links_skipped"], 2);
+}
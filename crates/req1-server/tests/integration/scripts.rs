@@ -1,7 +1,11 @@
 use axum::http::StatusCode;
+use sea_orm::ConnectionTrait;
 use serde_json::{Value, json};
 
-use super::common::{api, authed_client, create_module, create_object, spawn_server};
+use super::common::{
+    admin_client, api, authed_client, authed_client_as, connect_db, create_link_type,
+    create_module, create_object, spawn_server,
+};
 
 #[tokio::test]
 async fn test_script_crud() {
@@ -207,3 +211,919 @@ async fn test_script_batch_layout() {
     let result: Value = res.json().await.unwrap();
     assert_eq!(result["results"].as_array().unwrap().len(), 2);
 }
+
+#[tokio::test]
+async fn test_script_action_history_actor() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj = create_object(&client, &base, mod_id, "Tagged").await;
+    let obj_id = obj["id"].as_str().unwrap();
+    let _ = client
+        .post(format!(
+            "{}/modules/{mod_id}/attribute-definitions",
+            api(&base)
+        ))
+        .json(&json!({"name": "tag", "data_type": "string"}))
+        .send()
+        .await
+        .unwrap();
+    let url = format!("{}/modules/{mod_id}/scripts", api(&base));
+
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Tagger",
+            "script_type": "action",
+            "source_code": format!("req1.set('{obj_id}', 'tag', 'auto')"),
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let s_id = s["id"].as_str().unwrap();
+
+    let res = client
+        .post(format!("{url}/{s_id}/execute"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let history: Value = client
+        .get(format!(
+            "{}/modules/{mod_id}/objects/{obj_id}/history?actor=script:Tagger",
+            api(&base)
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = history["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["version"], 2);
+    assert_eq!(items[0]["attribute_values"]["tag"], "auto");
+    assert!(items[0]["changed_by"].is_string());
+}
+
+#[tokio::test]
+async fn test_script_timeout() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let url = format!("{}/modules/{mod_id}/scripts", api(&base));
+
+    // A runaway pre_save trigger fails the save instead of hanging it
+    let res = client
+        .post(&url)
+        .json(&json!({
+            "name": "Spin",
+            "script_type": "trigger",
+            "hook_point": "pre_save",
+            "source_code": "while (true) {}",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let trigger: Value = res.json().await.unwrap();
+
+    let res = client
+        .post(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .json(&json!({"heading": "Never saved"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = res.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "SCRIPT_TIMEOUT");
+
+    // The timeout is recorded although the save was rolled back
+    let executions: Value = client
+        .get(format!(
+            "{url}/{}/executions",
+            trigger["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(executions["items"].as_array().unwrap().len(), 1);
+    assert_eq!(executions["items"][0]["status"], "timeout");
+
+    // Action runs that time out are recorded as such
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Spin action",
+            "script_type": "action",
+            "source_code": "for (;;) {}",
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let s_id = s["id"].as_str().unwrap();
+
+    let res = client
+        .post(format!("{url}/{s_id}/execute"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let executions: Value = client
+        .get(format!("{url}/{s_id}/executions"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let execution = &executions["items"][0];
+    assert_eq!(execution["status"], "timeout");
+    assert!(
+        execution["error_message"]
+            .as_str()
+            .unwrap()
+            .starts_with("script_timeout")
+    );
+}
+
+#[tokio::test]
+async fn test_script_heap_limit() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let url = format!("{}/modules/{mod_id}/scripts", api(&base));
+
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Hoard",
+            "script_type": "action",
+            "source_code": "const hoard = [];\nfor (;;) hoard.push(new Array(1e6).fill(1));",
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let s_id = s["id"].as_str().unwrap();
+
+    let res = client
+        .post(format!("{url}/{s_id}/execute"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = res.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "SCRIPT_MEMORY_LIMIT");
+
+    let executions: Value = client
+        .get(format!("{url}/{s_id}/executions"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(executions["items"][0]["status"], "memory_limit");
+}
+
+#[tokio::test]
+async fn test_script_action_restructures_module() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let lt_id = create_link_type(&base).await;
+    let old = create_object(&client, &base, mod_id, "Old").await;
+    let old_id = old["id"].as_str().unwrap();
+    let doomed = create_object(&client, &base, mod_id, "Doomed").await;
+    let doomed_id = doomed["id"].as_str().unwrap();
+    let url = format!("{}/modules/{mod_id}/scripts", api(&base));
+
+    let source = format!(
+        "const section = req1.create({{ heading: 'Section' }});
+         const child = req1.create({{ heading: 'Child', parent: section }});
+         req1.setBody(child, 'Created by script');
+         req1.move('{old_id}', section);
+         req1.setHeading('{old_id}', 'Renamed');
+         req1.link(child, '{old_id}', '{lt_id}');
+         req1.delete('{doomed_id}');"
+    );
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Restructure",
+            "script_type": "action",
+            "source_code": source,
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let s_id = s["id"].as_str().unwrap();
+
+    let res = client
+        .post(format!("{url}/{s_id}/execute"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let result: Value = res.json().await.unwrap();
+    assert_eq!(result["mutations_applied"], 7);
+
+    let objects: Value = client
+        .get(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = objects["items"].as_array().unwrap();
+    let by_heading = |h: &str| items.iter().find(|o| o["heading"] == h).cloned();
+    assert!(by_heading("Doomed").is_none());
+    let section = by_heading("Section").unwrap();
+    let child = by_heading("Child").unwrap();
+    let renamed = by_heading("Renamed").unwrap();
+    assert_eq!(child["parent_id"], section["id"]);
+    assert_eq!(child["body"], "Created by script");
+    assert_eq!(renamed["id"], old_id);
+    assert_eq!(renamed["parent_id"], section["id"]);
+
+    let links: Value = client
+        .get(format!(
+            "{}/links?source_object_id={}",
+            api(&base),
+            child["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(links["items"][0]["target_object_id"], old_id);
+
+    // The edits are attributed to the script in history
+    let history: Value = client
+        .get(format!(
+            "{}/modules/{mod_id}/objects/{old_id}/history?actor=script:Restructure",
+            api(&base)
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(history["items"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_script_reads_other_modules() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, system) = create_module(&client, &base).await;
+    let sys_id = system["id"].as_str().unwrap();
+    let (_ws, _proj, software) = create_module(&client, &base).await;
+    let sw_id = software["id"].as_str().unwrap();
+    let lt_id = create_link_type(&base).await;
+
+    let sys_req = create_object(&client, &base, sys_id, "Approved system requirement").await;
+    let traced = create_object(&client, &base, sw_id, "Traced").await;
+    let untraced = create_object(&client, &base, sw_id, "Untraced").await;
+    let res = client
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": traced["id"],
+            "target_object_id": sys_req["id"],
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    // Every SW requirement must trace to a system requirement in another module
+    let url = format!("{}/modules/{sw_id}/scripts", api(&base));
+    let res = client
+        .post(&url)
+        .json(&json!({
+            "name": "Traces upstream",
+            "script_type": "trigger",
+            "hook_point": "validate",
+            "source_code": format!(
+                "const up = req1.follow(context.object.id, '{lt_id}', 'out');
+                 if (!up.some(o => o.heading.startsWith('Approved'))) req1.reject('no upstream');"
+            ),
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let report: Value = client
+        .get(format!("{}/modules/{sw_id}/validate", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let failing: Vec<&str> = report["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|i| i["rule"] == "script:Traces upstream")
+        .map(|i| i["object_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(failing, vec![untraced["id"].as_str().unwrap()]);
+
+    // Whole modules and single objects resolve across modules
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Count system",
+            "script_type": "action",
+            "source_code": format!(
+                "req1.print(req1.module('{}').objects().length);
+                 req1.print(req1.get_object('{}').heading);",
+                system["name"].as_str().unwrap(),
+                sys_req["id"].as_str().unwrap()
+            ),
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let res = client
+        .post(format!("{url}/{}/execute", s["id"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let result: Value = res.json().await.unwrap();
+    assert_eq!(
+        result["output"],
+        json!(["1", "Approved system requirement"])
+    );
+
+    // Modules the caller cannot read stay closed
+    let other = authed_client_as(&base, "script-outsider@example.com").await;
+    let (_ws, _proj, private) = create_module(&other, &base).await;
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Peek",
+            "script_type": "action",
+            "source_code": format!("req1.module('{}')", private["id"].as_str().unwrap()),
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let res = client
+        .post(format!("{url}/{}/execute", s["id"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: Value = res.json().await.unwrap();
+    assert!(
+        body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("read access")
+    );
+
+    // Links to objects in unreadable modules are left out, as are deleted objects
+    let hidden = create_object(&other, &base, private["id"].as_str().unwrap(), "Hidden").await;
+    let res = admin_client(&base)
+        .await
+        .post(format!("{}/links", api(&base)))
+        .json(&json!({
+            "source_object_id": traced["id"],
+            "target_object_id": hidden["id"],
+            "link_type_id": lt_id,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let res = client
+        .delete(format!(
+            "{}/modules/{sw_id}/objects/{}",
+            api(&base),
+            untraced["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Neighbours",
+            "script_type": "action",
+            "source_code": format!(
+                "req1.print(req1.links('{}').length);
+                 req1.print(req1.links().length);
+                 req1.print(req1.get_object('{}') === null);",
+                traced["id"].as_str().unwrap(),
+                untraced["id"].as_str().unwrap()
+            ),
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let res = client
+        .post(format!("{url}/{}/execute", s["id"].as_str().unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let result: Value = res.json().await.unwrap();
+    assert_eq!(result["output"], json!(["1", "1", "true"]));
+}
+
+#[tokio::test]
+async fn test_script_trigger_on_csv_import() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let _ = client
+        .post(format!(
+            "{}/modules/{mod_id}/attribute-definitions",
+            api(&base)
+        ))
+        .json(&json!({"name": "seq", "data_type": "string"}))
+        .send()
+        .await
+        .unwrap();
+
+    // Each row sees the rows imported before it, although the script stays warm
+    let res = client
+        .post(format!("{}/modules/{mod_id}/scripts", api(&base)))
+        .json(&json!({
+            "name": "Sequencer",
+            "script_type": "trigger",
+            "hook_point": "pre_save",
+            "source_code": "const n = req1.objects().length;\nreq1.set(context.object.id, 'seq', String(n));",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .post(format!("{}/modules/{mod_id}/import/csv", api(&base)))
+        .header("content-type", "text/csv")
+        .body("level,heading\n1,A\n2,B\n3,C\n")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let objects: Value = client
+        .get(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = objects["items"].as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    for (heading, seq) in [("A", "0"), ("B", "1"), ("C", "2")] {
+        let obj = items.iter().find(|o| o["heading"] == heading).unwrap();
+        assert_eq!(obj["attributes"]["seq"], seq, "row {heading}");
+    }
+}
+
+#[tokio::test]
+async fn test_script_globals_reset_between_objects() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let _ = client
+        .post(format!(
+            "{}/modules/{mod_id}/attribute-definitions",
+            api(&base)
+        ))
+        .json(&json!({"name": "seen", "data_type": "string"}))
+        .send()
+        .await
+        .unwrap();
+
+    // Neither a plain global nor an overwritten built-in survives into the next row
+    let res = client
+        .post(format!("{}/modules/{mod_id}/scripts", api(&base)))
+        .json(&json!({
+            "name": "Leaky",
+            "script_type": "trigger",
+            "hook_point": "pre_save",
+            "source_code": "globalThis.seen = (globalThis.seen ?? 0) + 1;\nconst leaked = typeof JSON.leak;\nJSON = { leak: true };\nreq1.set(context.object.id, 'seen', seen + ' ' + leaked);",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let res = client
+        .post(format!("{}/modules/{mod_id}/import/csv", api(&base)))
+        .header("content-type", "text/csv")
+        .body("level,heading\n1,A\n2,B\n")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let objects: Value = client
+        .get(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = objects["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    for obj in items {
+        assert_eq!(
+            obj["attributes"]["seen"], "1 undefined",
+            "{}",
+            obj["heading"]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_script_library_import_and_rollback() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (ws, _proj, module) = create_module(&client, &base).await;
+    let ws_id = ws["id"].as_str().unwrap();
+    let mod_id = module["id"].as_str().unwrap();
+
+    let res = client
+        .post(format!(
+            "{}/workspaces/{ws_id}/script-libraries",
+            api(&base)
+        ))
+        .json(&json!({
+            "name": "greet",
+            "source_code": "export function greet(name) { return `v1 ${name}`; }",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let lib: Value = res.json().await.unwrap();
+    let lib_url = format!(
+        "{}/script-libraries/{}",
+        api(&base),
+        lib["id"].as_str().unwrap()
+    );
+    assert_eq!(lib["active_version"], 1);
+
+    let url = format!("{}/modules/{mod_id}/scripts", api(&base));
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Greeter",
+            "script_type": "action",
+            "source_code": "import { greet } from 'req1:lib/greet';\nimport { greet as pinned } from 'req1:lib/greet@1';\nreq1.print(greet('a'));\nreq1.print(pinned('b'));",
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let execute_url = format!("{url}/{}/execute", s["id"].as_str().unwrap());
+    let run = || async {
+        let res = client.post(&execute_url).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let result: Value = res.json().await.unwrap();
+        result["output"].clone()
+    };
+    assert_eq!(run().await, json!(["v1 a", "v1 b"]));
+
+    // A new version is rolled out to unpinned imports only
+    let res = client
+        .patch(&lib_url)
+        .json(&json!({"source_code": "export function greet(name) { return `v2 ${name}`; }"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(run().await, json!(["v2 a", "v1 b"]));
+
+    let res = client
+        .post(format!("{lib_url}/activate"))
+        .json(&json!({"version": 1}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(run().await, json!(["v1 a", "v1 b"]));
+
+    let versions: Value = client
+        .get(format!("{lib_url}/versions"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(versions.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_script_library_writes_require_workspace_admin() {
+    let base = spawn_server().await;
+    let owner = authed_client(&base).await;
+    let (ws, proj, _module) = create_module(&owner, &base).await;
+    let ws_id = ws["id"].as_str().unwrap();
+    let proj_id = proj["id"].as_str().unwrap();
+
+    let editor = authed_client_as(
+        &base,
+        &format!("lib-editor-{}@test.com", uuid::Uuid::now_v7()),
+    )
+    .await;
+    let me: Value = editor
+        .get(format!("{}/auth/me", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let res = owner
+        .post(format!("{}/workspaces/{ws_id}/members", api(&base)))
+        .json(&json!({"user_id": me["id"], "role": "editor"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let body = json!({"name": "shared", "source_code": "export const x = 1;"});
+    for url in [
+        format!("{}/workspaces/{ws_id}/script-libraries", api(&base)),
+        format!("{}/projects/{proj_id}/script-libraries", api(&base)),
+    ] {
+        let res = editor.post(&url).json(&body).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN, "{url}");
+    }
+
+    let res = owner
+        .post(format!(
+            "{}/projects/{proj_id}/script-libraries",
+            api(&base)
+        ))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let lib: Value = res.json().await.unwrap();
+    let lib_url = format!(
+        "{}/script-libraries/{}",
+        api(&base),
+        lib["id"].as_str().unwrap()
+    );
+
+    // Editors can still read and import libraries
+    let res = editor.get(&lib_url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = editor
+        .patch(&lib_url)
+        .json(&json!({"source_code": "export const x = 2;"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = editor
+        .post(format!("{lib_url}/activate"))
+        .json(&json!({"version": 1}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = editor.delete(&lib_url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_script_link_requires_access_to_other_module() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let obj = create_object(&client, &base, mod_id, "Mine").await;
+    let lt_id = create_link_type(&base).await;
+
+    let other = authed_client_as(
+        &base,
+        &format!("link-outsider-{}@test.com", uuid::Uuid::now_v7()),
+    )
+    .await;
+    let (_ws, _proj, private) = create_module(&other, &base).await;
+    let foreign = create_object(&other, &base, private["id"].as_str().unwrap(), "Theirs").await;
+
+    let url = format!("{}/modules/{mod_id}/scripts", api(&base));
+    let s: Value = client
+        .post(&url)
+        .json(&json!({
+            "name": "Reach out",
+            "script_type": "action",
+            "source_code": format!(
+                "req1.link('{}', '{}', '{lt_id}');",
+                obj["id"].as_str().unwrap(),
+                foreign["id"].as_str().unwrap()
+            ),
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let s_id = s["id"].as_str().unwrap();
+
+    let res = client
+        .post(format!("{url}/{s_id}/execute"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let links: Value = client
+        .get(format!(
+            "{}/links?source_object_id={}",
+            api(&base),
+            obj["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(links["items"].as_array().unwrap().len(), 0);
+
+    // The run is recorded as failed, not as a success
+    let executions: Value = client
+        .get(format!("{url}/{s_id}/executions"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(executions["items"].as_array().unwrap().len(), 1);
+    assert_eq!(executions["items"][0]["status"], "error");
+}
+
+#[tokio::test]
+async fn test_scheduled_script_is_confined_to_its_module() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, proj, home) = create_module(&client, &base).await;
+    let home_id = home["id"].as_str().unwrap();
+    let other: Value = client
+        .post(format!("{}/modules", api(&base)))
+        .json(&json!({
+            "name": format!("mod-{}", uuid::Uuid::now_v7()),
+            "project_id": proj["id"],
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let other_id = other["id"].as_str().unwrap();
+    let own_obj = create_object(&client, &base, home_id, "Own").await;
+    let other_obj = create_object(&client, &base, other_id, "Neighbour").await;
+
+    // Scheduled runs act for no user, so even a module of the same workspace is off limits
+    let url = format!("{}/modules/{home_id}/scripts", api(&base));
+    let mut ids = Vec::new();
+    for (name, source) in [
+        ("Read neighbour", format!("req1.module('{other_id}')")),
+        (
+            "Write neighbour",
+            format!(
+                "req1.setHeading('{}', 'Overwritten')",
+                other_obj["id"].as_str().unwrap()
+            ),
+        ),
+        (
+            "Write own",
+            format!(
+                "req1.setHeading('{}', 'Checked')",
+                own_obj["id"].as_str().unwrap()
+            ),
+        ),
+    ] {
+        let res = client
+            .post(&url)
+            .json(&json!({
+                "name": name,
+                "script_type": "action",
+                "source_code": source,
+                "cron_expression": "0 0 * * * *",
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let s: Value = res.json().await.unwrap();
+        ids.push(s["id"].as_str().unwrap().to_owned());
+    }
+
+    let db = connect_db().await;
+    let _ = db
+        .execute_unprepared(&format!(
+            "UPDATE script SET next_run_at = now() - interval '1 minute' WHERE module_id = '{home_id}'"
+        ))
+        .await
+        .unwrap();
+    req1_server::scheduler::run_due_scripts(&db).await.unwrap();
+
+    let mut statuses = Vec::new();
+    for id in &ids {
+        let executions: Value = client
+            .get(format!("{url}/{id}/executions"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        statuses.push(
+            executions["items"][0]["status"]
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        );
+    }
+    assert_eq!(statuses, ["error", "error", "success"]);
+
+    let neighbour: Value = client
+        .get(format!(
+            "{}/modules/{other_id}/objects/{}",
+            api(&base),
+            other_obj["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(neighbour["heading"], "Neighbour");
+    let own: Value = client
+        .get(format!(
+            "{}/modules/{home_id}/objects/{}",
+            api(&base),
+            own_obj["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(own["heading"], "Checked");
+}
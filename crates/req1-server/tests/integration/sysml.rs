@@ -0,0 +1,149 @@
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use super::common::{api, authed_client, create_module, spawn_server};
+
+fn model(braking_text: &str) -> Value {
+    json!({"elements": [
+        {"@id": "def-perf", "@type": "RequirementDefinition", "declaredName": "PerformanceRequirement"},
+        {"@id": "req-vehicle", "@type": "RequirementUsage", "declaredName": "Vehicle",
+         "declaredShortName": "R1", "text": ["Top-level vehicle requirements"]},
+        {"@id": "req-braking", "@type": "RequirementUsage", "declaredName": "Braking distance",
+         "declaredShortName": "R1.1", "owner": {"@id": "req-vehicle"},
+         "requirementDefinition": {"@id": "def-perf"},
+         "documentation": [{"@id": "doc-braking"}]},
+        {"@id": "doc-braking", "@type": "Documentation", "body": braking_text,
+         "annotatedElement": [{"@id": "req-braking"}]},
+        {"@id": "req-abs", "@type": "RequirementUsage", "declaredName": "ABS controller"},
+        {"@id": "sat-1", "@type": "SatisfyRequirementUsage",
+         "satisfyingFeature": {"@id": "req-abs"}, "satisfiedRequirement": {"@id": "req-braking"}},
+        {"@id": "sat-2", "@type": "SatisfyRequirementUsage",
+         "satisfyingFeature": {"@id": "unknown"}, "satisfiedRequirement": {"@id": "req-braking"}},
+    ]})
+}
+
+#[tokio::test]
+async fn test_sysml_import_reimport_and_export() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+    let (_ws, _proj, module) = create_module(&client, &base).await;
+    let mod_id = module["id"].as_str().unwrap();
+    let import_url = format!("{}/modules/{mod_id}/sysml/import", api(&base));
+
+    let res = client
+        .post(&import_url)
+        .json(&model("Stop within 40 m from 100 km/h"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let result: Value = res.json().await.unwrap();
+    assert_eq!(result["objects_created"], 3);
+    assert_eq!(result["object_types_created"], 1);
+    assert_eq!(result["links_created"], 1);
+    assert_eq!(result["links_skipped"], 1);
+
+    let objects: Value = client
+        .get(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = objects["items"].as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    let find = |items: &[Value], heading: &str| {
+        items
+            .iter()
+            .find(|o| o["heading"] == heading)
+            .cloned()
+            .unwrap()
+    };
+    let vehicle = find(items, "Vehicle");
+    let braking = find(items, "Braking distance");
+    assert_eq!(braking["parent_id"], vehicle["id"]);
+    assert_eq!(braking["body"], "Stop within 40 m from 100 km/h");
+    assert!(braking["object_type_id"].is_string());
+    assert_eq!(braking["references_"][0]["type"], "sysml");
+    assert_eq!(braking["references_"][0]["path"], "req-braking");
+
+    // Re-import with changed text updates in place
+    let result: Value = client
+        .post(&import_url)
+        .json(&model("Stop within 35 m from 100 km/h"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(result["objects_created"], 0);
+    assert_eq!(result["objects_updated"], 1);
+    assert_eq!(result["objects_unchanged"], 2);
+    assert_eq!(result["object_types_created"], 0);
+    assert_eq!(result["links_created"], 0);
+
+    let objects: Value = client
+        .get(format!("{}/modules/{mod_id}/objects", api(&base)))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let items = objects["items"].as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(
+        find(items, "Braking distance")["body"],
+        "Stop within 35 m from 100 km/h"
+    );
+
+    let res = client
+        .get(format!("{}/modules/{mod_id}/sysml/export", api(&base)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let elements: Vec<Value> = res.json().await.unwrap();
+    let usage = elements.iter().find(|e| e["@id"] == "req-braking").unwrap();
+    assert_eq!(usage["@type"], "RequirementUsage");
+    assert_eq!(usage["declaredShortName"], "R1.1");
+    assert_eq!(usage["owner"]["@id"], "req-vehicle");
+    assert_eq!(usage["text"], json!(["Stop within 35 m from 100 km/h"]));
+    assert!(
+        elements
+            .iter()
+            .any(|e| e["@type"] == "RequirementDefinition"
+                && e["declaredName"] == "PerformanceRequirement")
+    );
+    let satisfy = elements
+        .iter()
+        .find(|e| e["@type"] == "SatisfyRequirementUsage")
+        .unwrap();
+    assert_eq!(satisfy["@id"], "sat-1");
+    assert_eq!(satisfy["satisfyingFeature"]["@id"], "req-abs");
+    assert_eq!(satisfy["satisfiedRequirement"]["@id"], "req-braking");
+
+    // The exported model re-imports as a no-op
+    let result: Value = client
+        .post(&import_url)
+        .json(&elements)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(result["objects_created"], 0);
+    assert_eq!(result["objects_updated"], 0);
+    assert_eq!(result["links_created"], 0);
+
+    let res = client
+        .post(&import_url)
+        .json(&json!([{"@id": "x", "@type": "PartUsage"}]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
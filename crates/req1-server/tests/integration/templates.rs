@@ -1,14 +1,24 @@
 use axum::http::StatusCode;
 use serde_json::{Value, json};
 
-use super::common::{api, authed_client, create_workspace, spawn_server};
+use super::common::{admin_client, api, authed_client, create_workspace, spawn_server};
 
 #[tokio::test]
 async fn test_project_template_crud() {
     let base = spawn_server().await;
-    let client = authed_client(&base).await;
+    let client = admin_client(&base).await;
     let url = format!("{}/project-templates", api(&base));
 
+    // Templates are shared by all workspaces, so only admins change them
+    let res = authed_client(&base)
+        .await
+        .post(&url)
+        .json(&json!({"name": "Editor Template", "template_data": {"modules": []}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
     // Create
     let res = client
         .post(&url)
@@ -57,6 +67,14 @@ async fn test_project_template_crud() {
     let list: Vec<Value> = res.json().await.unwrap();
     assert!(list.iter().any(|i| i["id"] == tmpl_id));
 
+    let res = authed_client(&base)
+        .await
+        .delete(format!("{url}/{tmpl_id}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
     // Delete
     let res = client
         .delete(format!("{url}/{tmpl_id}"))
@@ -99,7 +117,7 @@ async fn test_builtin_templates_exist() {
 #[tokio::test]
 async fn test_builtin_template_cannot_be_deleted() {
     let base = spawn_server().await;
-    let client = authed_client(&base).await;
+    let client = admin_client(&base).await;
 
     let res = client
         .get(format!("{}/project-templates", api(&base)))
@@ -1,12 +1,12 @@
 use axum::http::StatusCode;
 use serde_json::{Value, json};
 
-use super::common::{api, authed_client, spawn_server};
+use super::common::{admin_client, api, authed_client, spawn_server};
 
 #[tokio::test]
 async fn test_app_user_crud() {
     let base = spawn_server().await;
-    let client = authed_client(&base).await;
+    let client = admin_client(&base).await;
     let url = format!("{}/users", api(&base));
 
     let email = format!("user-{}@test.com", uuid::Uuid::now_v7());
@@ -64,7 +64,7 @@ async fn test_app_user_crud() {
 #[tokio::test]
 async fn test_app_user_invalid_role() {
     let base = spawn_server().await;
-    let client = authed_client(&base).await;
+    let client = admin_client(&base).await;
 
     let res = client
         .post(format!("{}/users", api(&base)))
@@ -82,7 +82,7 @@ async fn test_app_user_invalid_role() {
 #[tokio::test]
 async fn test_app_user_not_found() {
     let base = spawn_server().await;
-    let client = authed_client(&base).await;
+    let client = admin_client(&base).await;
     let fake_id = uuid::Uuid::now_v7();
 
     let res = client
@@ -92,3 +92,21 @@ async fn test_app_user_not_found() {
         .unwrap();
     assert_eq!(res.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_app_user_create_requires_admin() {
+    let base = spawn_server().await;
+    let client = authed_client(&base).await;
+
+    let res = client
+        .post(format!("{}/users", api(&base)))
+        .json(&json!({
+            "email": format!("user-{}@test.com", uuid::Uuid::now_v7()),
+            "display_name": "Not Allowed",
+            "role": "editor",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
@@ -12,6 +12,9 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub object_id: Uuid,
     pub version: i32,
+    pub parent_id: Option<Uuid>,
+    pub position: Option<i32>,
+    pub level: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, utoipa::ToSchema)]
+#[schema(as = BaselineLink)]
+#[sea_orm(table_name = "baseline_link")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub baseline_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub link_id: Uuid,
+    pub source_object_id: Uuid,
+    pub target_object_id: Uuid,
+    pub link_type_id: Uuid,
+    pub suspect: bool,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub attributes: Option<serde_json::Value>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, utoipa::ToSchema)]
+#[schema(as = ExternalLink)]
+#[sea_orm(table_name = "external_link")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub source_object_id: Uuid,
+    pub link_type_id: Uuid,
+    pub uri: String,
+    pub label: Option<String>,
+    pub content_hash: Option<String>,
+    pub source_fingerprint: String,
+    pub suspect: bool,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub suspect_reason: Option<serde_json::Value>,
+    #[schema(value_type = Option<String>)]
+    pub suspected_at: Option<DateTimeWithTimeZone>,
+    #[schema(value_type = Option<String>)]
+    pub last_checked_at: Option<DateTimeWithTimeZone>,
+    pub check_error: Option<String>,
+    #[schema(value_type = String)]
+    pub created_at: DateTimeWithTimeZone,
+    #[schema(value_type = String)]
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
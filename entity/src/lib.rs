@@ -4,6 +4,7 @@ pub mod attribute_definition;
 pub mod audit_log;
 pub mod baseline;
 pub mod baseline_entry;
+pub mod baseline_link;
 pub mod baseline_set;
 pub mod change_proposal;
 pub mod comment;
@@ -11,8 +12,10 @@ pub mod dashboard;
 pub mod dashboard_widget;
 pub mod diagram;
 pub mod e_signature;
+pub mod external_link;
 pub mod lifecycle_model;
 pub mod link;
+pub mod link_history;
 pub mod link_type;
 pub mod module;
 pub mod module_permission;
@@ -27,6 +30,8 @@ pub mod review_comment;
 pub mod review_package;
 pub mod script;
 pub mod script_execution;
+pub mod script_library;
+pub mod script_library_version;
 pub mod test_case;
 pub mod test_execution;
 pub mod view;
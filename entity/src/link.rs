@@ -20,6 +20,14 @@ pub struct Model {
     pub created_at: DateTimeWithTimeZone,
     #[schema(value_type = String)]
     pub updated_at: DateTimeWithTimeZone,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub suspect_reason: Option<serde_json::Value>,
+    #[schema(value_type = Option<String>)]
+    pub suspected_at: Option<DateTimeWithTimeZone>,
+    pub version: i32,
+    /// `ReqIF` `SPEC-RELATION` identifier, set on import and emitted on export.
+    pub reqif_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, utoipa::ToSchema)]
+#[schema(as = LinkHistory)]
+#[sea_orm(table_name = "link_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub link_id: Uuid,
+    pub version: i32,
+    pub change_type: String,
+    pub changed_by: Option<Uuid>,
+    pub actor: Option<String>,
+    #[schema(value_type = String)]
+    pub changed_at: DateTimeWithTimeZone,
+    pub justification: Option<String>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub suspect_reason: Option<serde_json::Value>,
+    pub source_fingerprint: Option<String>,
+    pub target_fingerprint: Option<String>,
+    pub source_object_id: Option<Uuid>,
+    pub target_object_id: Option<Uuid>,
+    pub link_type_id: Option<Uuid>,
+    pub old_link_type_id: Option<Uuid>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub old_attributes: Option<serde_json::Value>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub new_attributes: Option<serde_json::Value>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
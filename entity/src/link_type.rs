@@ -13,6 +13,13 @@ pub struct Model {
     pub description: Option<String>,
     #[schema(value_type = String)]
     pub created_at: DateTimeWithTimeZone,
+    pub suspect_direction: String,
+    pub suspect_cascade: bool,
+    pub suspect_skip_informative: bool,
+    pub inverse_name: Option<String>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub rules: Option<serde_json::Value>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
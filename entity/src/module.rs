@@ -24,6 +24,15 @@ pub struct Model {
     #[sea_orm(column_type = "JsonBinary")]
     #[schema(value_type = Object)]
     pub signature_config: serde_json::Value,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub fingerprint_policy: Option<serde_json::Value>,
+    /// `ReqIF` specification identifier this module is exchanged under.
+    pub reqif_id: Option<String>,
+    /// Attributes shared with and owned by the `ReqIF` exchange partner.
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub reqif_profile: Option<serde_json::Value>,
     #[schema(value_type = String)]
     pub created_at: DateTimeWithTimeZone,
     #[schema(value_type = String)]
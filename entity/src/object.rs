@@ -33,6 +33,12 @@ pub struct Model {
     pub source_module_id: Option<Uuid>,
     pub is_placeholder: bool,
     pub docx_source_id: Option<String>,
+    /// `ReqIF` `SPEC-OBJECT` identifier, set on import and emitted on export.
+    pub reqif_id: Option<String>,
+    /// Heading and attributes as of the last `ReqIF` exchange; the base for merge conflicts.
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub reqif_base: Option<serde_json::Value>,
     #[schema(value_type = Option<String>)]
     pub deleted_at: Option<DateTimeWithTimeZone>,
     #[schema(value_type = String)]
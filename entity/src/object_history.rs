@@ -16,9 +16,17 @@ pub struct Model {
     pub heading: Option<String>,
     pub body: Option<String>,
     pub changed_by: Option<Uuid>,
+    pub actor: Option<String>,
     #[schema(value_type = String)]
     pub changed_at: DateTimeWithTimeZone,
     pub change_type: String,
+    pub from_state: Option<String>,
+    pub to_state: Option<String>,
+    pub lifecycle_model_id: Option<Uuid>,
+    pub e_signature_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub position: Option<i32>,
+    pub level: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -40,6 +40,22 @@ mod m20260221_000037_docx_import_tracking;
 mod m20260221_000038_dashboard_tables;
 mod m20260221_000039_project_template;
 mod m20260221_000040_seed_admin_user;
+mod m20260221_000041_object_history_actor;
+mod m20260221_000042_state_transition_history;
+mod m20260221_000043_baseline_structure_links;
+mod m20260221_000044_object_history_structure;
+mod m20260221_000045_canonical_fingerprint;
+mod m20260221_000046_suspect_propagation_rules;
+mod m20260221_000047_link_history;
+mod m20260221_000048_link_versioning;
+mod m20260221_000049_link_type_semantics;
+mod m20260221_000050_external_link;
+mod m20260221_000051_reqif_identifiers;
+mod m20260221_000052_reqif_profile;
+mod m20260222_000053_script_library;
+
+/// After this migration the server recomputes every content fingerprint in Rust.
+pub const CANONICAL_FINGERPRINT: &str = "m20260221_000045_canonical_fingerprint";
 
 pub struct Migrator;
 
@@ -87,6 +103,19 @@ impl MigratorTrait for Migrator {
             Box::new(m20260221_000038_dashboard_tables::Migration),
             Box::new(m20260221_000039_project_template::Migration),
             Box::new(m20260221_000040_seed_admin_user::Migration),
+            Box::new(m20260221_000041_object_history_actor::Migration),
+            Box::new(m20260221_000042_state_transition_history::Migration),
+            Box::new(m20260221_000043_baseline_structure_links::Migration),
+            Box::new(m20260221_000044_object_history_structure::Migration),
+            Box::new(m20260221_000045_canonical_fingerprint::Migration),
+            Box::new(m20260221_000046_suspect_propagation_rules::Migration),
+            Box::new(m20260221_000047_link_history::Migration),
+            Box::new(m20260221_000048_link_versioning::Migration),
+            Box::new(m20260221_000049_link_type_semantics::Migration),
+            Box::new(m20260221_000050_external_link::Migration),
+            Box::new(m20260221_000051_reqif_identifiers::Migration),
+            Box::new(m20260221_000052_reqif_profile::Migration),
+            Box::new(m20260222_000053_script_library::Migration),
         ]
     }
 }
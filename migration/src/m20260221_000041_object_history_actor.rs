@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // "<user uuid>" for user edits, "script:<name>" for script-originated changes
+        let _ = db
+            .execute_unprepared("ALTER TABLE object_history ADD COLUMN actor VARCHAR")
+            .await?;
+
+        let _ = db
+            .execute_unprepared(
+                "UPDATE object_history SET actor = changed_by::text WHERE changed_by IS NOT NULL",
+            )
+            .await?;
+
+        let _ = db
+            .execute_unprepared(
+                "CREATE INDEX idx_object_history_object_changed_at ON object_history(object_id, changed_at)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let _ = db
+            .execute_unprepared("DROP INDEX IF EXISTS idx_object_history_object_changed_at")
+            .await?;
+        let _ = db
+            .execute_unprepared("ALTER TABLE object_history DROP COLUMN IF EXISTS actor")
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE object_history
+                    ADD COLUMN from_state VARCHAR,
+                    ADD COLUMN to_state VARCHAR,
+                    ADD COLUMN lifecycle_model_id UUID,
+                    ADD COLUMN e_signature_id UUID REFERENCES e_signature(id) ON DELETE SET NULL",
+            )
+            .await?;
+
+        let _ = db
+            .execute_unprepared(
+                "CREATE INDEX idx_object_history_state_timeline ON object_history(module_id, changed_at) WHERE to_state IS NOT NULL",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let _ = db
+            .execute_unprepared("DROP INDEX IF EXISTS idx_object_history_state_timeline")
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE object_history
+                    DROP COLUMN IF EXISTS e_signature_id,
+                    DROP COLUMN IF EXISTS lifecycle_model_id,
+                    DROP COLUMN IF EXISTS to_state,
+                    DROP COLUMN IF EXISTS from_state",
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Tree structure at capture time (NULL for baselines taken before this migration)
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE baseline_entry
+                    ADD COLUMN parent_id UUID,
+                    ADD COLUMN position INTEGER,
+                    ADD COLUMN level VARCHAR",
+            )
+            .await?;
+
+        // Link graph at capture time; no FKs to link/object so the snapshot outlives them
+        let _ = db
+            .execute_unprepared(
+                "CREATE TABLE baseline_link (
+                    baseline_id UUID NOT NULL REFERENCES baseline(id) ON DELETE CASCADE,
+                    link_id UUID NOT NULL,
+                    source_object_id UUID NOT NULL,
+                    target_object_id UUID NOT NULL,
+                    link_type_id UUID NOT NULL,
+                    suspect BOOLEAN NOT NULL,
+                    attributes JSONB,
+                    PRIMARY KEY (baseline_id, link_id)
+                )",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let _ = db
+            .execute_unprepared("DROP TABLE IF EXISTS baseline_link")
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE baseline_entry
+                    DROP COLUMN IF EXISTS level,
+                    DROP COLUMN IF EXISTS position,
+                    DROP COLUMN IF EXISTS parent_id",
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Outline position of the object when the history row was written
+        // (NULL for rows recorded before this migration)
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE object_history
+                    ADD COLUMN parent_id UUID,
+                    ADD COLUMN position INTEGER,
+                    ADD COLUMN level VARCHAR",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE object_history
+                    DROP COLUMN IF EXISTS level,
+                    DROP COLUMN IF EXISTS position,
+                    DROP COLUMN IF EXISTS parent_id",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Per-module / per-object-type choice of which attributes count as content
+        let _ = db
+            .execute_unprepared("ALTER TABLE module ADD COLUMN fingerprint_policy JSONB")
+            .await?;
+        let _ = db
+            .execute_unprepared("ALTER TABLE object_type ADD COLUMN fingerprint_policy JSONB")
+            .await?;
+
+        // Existing fingerprints are recomputed by the server in Rust once this migration
+        // has run (`fingerprint::refingerprint_all`), so numbers hash exactly as on save
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Fingerprints stay canonical; they remain valid under the old hashing rules
+        let _ = db
+            .execute_unprepared("ALTER TABLE object_type DROP COLUMN IF EXISTS fingerprint_policy")
+            .await?;
+        let _ = db
+            .execute_unprepared("ALTER TABLE module DROP COLUMN IF EXISTS fingerprint_policy")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Per link type: which end's changes flag the link ('forward' = source changes,
+        // 'backward' = target changes, 'both', 'none'), whether flags cascade along
+        // further links, and whether edits to informative objects are exempt
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE link_type
+                    ADD COLUMN suspect_direction VARCHAR NOT NULL DEFAULT 'both',
+                    ADD COLUMN suspect_cascade BOOLEAN NOT NULL DEFAULT false,
+                    ADD COLUMN suspect_skip_informative BOOLEAN NOT NULL DEFAULT false",
+            )
+            .await?;
+
+        // Why the link went suspect: triggering object version and changed fields
+        let _ = db
+            .execute_unprepared("ALTER TABLE link ADD COLUMN suspect_reason JSONB")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let _ = db
+            .execute_unprepared("ALTER TABLE link DROP COLUMN IF EXISTS suspect_reason")
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE link_type
+                    DROP COLUMN IF EXISTS suspect_skip_informative,
+                    DROP COLUMN IF EXISTS suspect_cascade,
+                    DROP COLUMN IF EXISTS suspect_direction",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // When the link last went suspect, for ageing the review queue
+        let _ = db
+            .execute_unprepared("ALTER TABLE link ADD COLUMN suspected_at TIMESTAMPTZ")
+            .await?;
+        let _ = db
+            .execute_unprepared("UPDATE link SET suspected_at = updated_at WHERE suspect")
+            .await?;
+
+        // Link events such as suspect clearances; no FK to link so records outlive it
+        let _ = db
+            .execute_unprepared(
+                "CREATE TABLE link_history (
+                    id BIGSERIAL PRIMARY KEY,
+                    link_id UUID NOT NULL,
+                    change_type VARCHAR NOT NULL,
+                    changed_by UUID,
+                    actor VARCHAR,
+                    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    justification TEXT,
+                    suspect_reason JSONB,
+                    source_fingerprint VARCHAR,
+                    target_fingerprint VARCHAR
+                )",
+            )
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "CREATE INDEX idx_link_history_link ON link_history (link_id, changed_at)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let _ = db
+            .execute_unprepared("DROP TABLE IF EXISTS link_history")
+            .await?;
+        let _ = db
+            .execute_unprepared("ALTER TABLE link DROP COLUMN IF EXISTS suspected_at")
+            .await?;
+        Ok(())
+    }
+}
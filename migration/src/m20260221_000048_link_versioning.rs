@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let _ = db
+            .execute_unprepared("ALTER TABLE link ADD COLUMN version INTEGER NOT NULL DEFAULT 1")
+            .await?;
+
+        // Each history row is a full picture of the link after the change, so records of
+        // deleted links stay readable
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE link_history
+                    ADD COLUMN version INTEGER NOT NULL DEFAULT 1,
+                    ADD COLUMN source_object_id UUID,
+                    ADD COLUMN target_object_id UUID,
+                    ADD COLUMN link_type_id UUID,
+                    ADD COLUMN old_link_type_id UUID,
+                    ADD COLUMN old_attributes JSONB,
+                    ADD COLUMN new_attributes JSONB",
+            )
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "UPDATE link_history h
+                 SET source_object_id = l.source_object_id,
+                     target_object_id = l.target_object_id,
+                     link_type_id = l.link_type_id,
+                     new_attributes = l.attributes
+                 FROM link l WHERE l.id = h.link_id",
+            )
+            .await?;
+
+        // Existing links start their history with a create record
+        let _ = db
+            .execute_unprepared(
+                "INSERT INTO link_history (link_id, version, change_type, changed_at,
+                    source_object_id, target_object_id, link_type_id, new_attributes,
+                    source_fingerprint, target_fingerprint)
+                 SELECT id, 1, 'create', created_at, source_object_id, target_object_id,
+                    link_type_id, attributes, source_fingerprint, target_fingerprint
+                 FROM link",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let _ = db
+            .execute_unprepared("DELETE FROM link_history WHERE change_type <> 'resolve'")
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE link_history
+                    DROP COLUMN IF EXISTS new_attributes,
+                    DROP COLUMN IF EXISTS old_attributes,
+                    DROP COLUMN IF EXISTS old_link_type_id,
+                    DROP COLUMN IF EXISTS link_type_id,
+                    DROP COLUMN IF EXISTS target_object_id,
+                    DROP COLUMN IF EXISTS source_object_id,
+                    DROP COLUMN IF EXISTS version",
+            )
+            .await?;
+        let _ = db
+            .execute_unprepared("ALTER TABLE link DROP COLUMN IF EXISTS version")
+            .await?;
+
+        Ok(())
+    }
+}
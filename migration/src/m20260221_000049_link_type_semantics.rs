@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Display name read from the target side ("satisfied by"), and the allowed
+        // endpoints, cardinality and cycle policy (NULL = unrestricted)
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE link_type
+                    ADD COLUMN inverse_name VARCHAR,
+                    ADD COLUMN rules JSONB",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE link_type
+                    DROP COLUMN IF EXISTS rules,
+                    DROP COLUMN IF EXISTS inverse_name",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Links from an object to a resource outside req1 (commit, ticket, model element,
+        // test report). content_hash is the last accepted hash of the resource
+        let _ = db
+            .execute_unprepared(
+                "CREATE TABLE external_link (
+                    id UUID PRIMARY KEY,
+                    source_object_id UUID NOT NULL REFERENCES object(id) ON DELETE CASCADE,
+                    link_type_id UUID NOT NULL REFERENCES link_type(id),
+                    uri TEXT NOT NULL,
+                    label VARCHAR,
+                    content_hash VARCHAR,
+                    source_fingerprint VARCHAR NOT NULL,
+                    suspect BOOLEAN NOT NULL DEFAULT false,
+                    suspect_reason JSONB,
+                    suspected_at TIMESTAMPTZ,
+                    last_checked_at TIMESTAMPTZ,
+                    check_error TEXT,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    UNIQUE (source_object_id, link_type_id, uri)
+                )",
+            )
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "CREATE INDEX idx_external_link_source ON external_link (source_object_id)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let _ = db
+            .execute_unprepared("DROP TABLE IF EXISTS external_link")
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let _ = db
+            .execute_unprepared("ALTER TABLE module ADD COLUMN reqif_id VARCHAR")
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE object ADD COLUMN reqif_id VARCHAR, ADD COLUMN reqif_base JSONB",
+            )
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "CREATE INDEX idx_object_reqif ON object(module_id, reqif_id) WHERE reqif_id IS NOT NULL",
+            )
+            .await?;
+        let _ = db
+            .execute_unprepared("ALTER TABLE link ADD COLUMN reqif_id VARCHAR")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let _ = db
+            .execute_unprepared("ALTER TABLE link DROP COLUMN IF EXISTS reqif_id")
+            .await?;
+        let _ = db
+            .execute_unprepared("DROP INDEX IF EXISTS idx_object_reqif")
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "ALTER TABLE object DROP COLUMN IF EXISTS reqif_base, DROP COLUMN IF EXISTS reqif_id",
+            )
+            .await?;
+        let _ = db
+            .execute_unprepared("ALTER TABLE module DROP COLUMN IF EXISTS reqif_id")
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Shared helper modules scripts import as `req1:lib/<name>`, owned by a
+        // workspace or by a project. Imports get active_version unless they pin one
+        let _ = db
+            .execute_unprepared(
+                "CREATE TABLE script_library (
+                    id UUID PRIMARY KEY,
+                    workspace_id UUID REFERENCES workspace(id) ON DELETE CASCADE,
+                    project_id UUID REFERENCES project(id) ON DELETE CASCADE,
+                    name VARCHAR NOT NULL,
+                    description TEXT,
+                    active_version INTEGER NOT NULL DEFAULT 1,
+                    latest_version INTEGER NOT NULL DEFAULT 1,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    CHECK ((workspace_id IS NULL) <> (project_id IS NULL)),
+                    UNIQUE (workspace_id, name),
+                    UNIQUE (project_id, name)
+                )",
+            )
+            .await?;
+        let _ = db
+            .execute_unprepared(
+                "CREATE TABLE script_library_version (
+                    id UUID PRIMARY KEY,
+                    library_id UUID NOT NULL REFERENCES script_library(id) ON DELETE CASCADE,
+                    version INTEGER NOT NULL,
+                    source_code TEXT NOT NULL,
+                    created_by UUID REFERENCES app_user(id) ON DELETE SET NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    UNIQUE (library_id, version)
+                )",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let _ = db
+            .execute_unprepared("DROP TABLE IF EXISTS script_library_version")
+            .await?;
+        let _ = db
+            .execute_unprepared("DROP TABLE IF EXISTS script_library")
+            .await?;
+        Ok(())
+    }
+}